@@ -0,0 +1,161 @@
+use serde::Serialize;
+
+use crate::effect::{mean, stddev};
+use crate::error::{BenchmarkError, Result};
+use crate::export;
+use crate::types::BenchmarkResult;
+
+/// Run means spread less than this (relative standard deviation) are
+/// consistent, however many iterations make the difference detectable.
+const NEGLIGIBLE_SPREAD: f64 = 0.02;
+
+/// Between-run variance may exceed what within-run noise predicts by this
+/// factor (the one-way ANOVA F ratio) before runs count as inconsistent.
+const MAX_VARIANCE_RATIO: f64 = 4.0;
+
+/// One model's tokens/s pooled across repeated runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelAggregate {
+    pub model: String,
+    pub runs: u64,
+    pub iterations: u64,
+    pub avg_tokens_per_second: f64,
+    pub stddev_tokens_per_second: f64,
+    pub avg_ttft_ms: f64,
+    /// Mean tokens/s of each run the model appeared in, in input order
+    pub run_means: Vec<f64>,
+    /// Standard deviation of the run means
+    pub between_run_stddev: f64,
+    /// Pooled standard deviation of iterations around their own run's mean
+    pub within_run_stddev: f64,
+    /// Between-run variance relative to what within-run noise predicts;
+    /// `None` without within-run variation to compare against
+    pub variance_ratio: Option<f64>,
+    pub consistent: bool,
+}
+
+/// Loads the iterations of a raw JSON export (`--export-raw runs.json`,
+/// optionally compressed).
+pub fn load_iterations(path: &str) -> Result<Vec<BenchmarkResult>> {
+    let content = export::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| {
+        BenchmarkError::ConfigError(format!(
+            "{} is not a raw JSON export from --export-raw ({})",
+            path, e
+        ))
+    })
+}
+
+/// Pools every model with successful iterations in at least two runs.
+/// Failed, anomalous and warm-up iterations are left out, as in a single run.
+pub fn aggregate(runs: &[Vec<BenchmarkResult>]) -> Vec<ModelAggregate> {
+    let mut models: Vec<&str> = Vec::new();
+    for result in runs.iter().flatten() {
+        if !models.contains(&result.model.as_str()) {
+            models.push(&result.model);
+        }
+    }
+
+    models
+        .into_iter()
+        .filter_map(|model| {
+            let per_run: Vec<Vec<&BenchmarkResult>> = runs
+                .iter()
+                .map(|run| {
+                    run.iter()
+                        .filter(|r| r.model == model && r.is_measured())
+                        .collect::<Vec<_>>()
+                })
+                .filter(|run| !run.is_empty())
+                .collect();
+            (per_run.len() >= 2).then(|| pool(model, &per_run))
+        })
+        .collect()
+}
+
+fn pool(model: &str, runs: &[Vec<&BenchmarkResult>]) -> ModelAggregate {
+    let speeds: Vec<Vec<f64>> = runs
+        .iter()
+        .map(|run| run.iter().map(|r| r.tokens_per_second).collect())
+        .collect();
+    let all: Vec<f64> = speeds.iter().flatten().copied().collect();
+    let (n, k) = (all.len() as f64, speeds.len() as f64);
+    let grand_mean = mean(all.iter().copied());
+    let run_means: Vec<f64> = speeds.iter().map(|run| mean(run.iter().copied())).collect();
+
+    // One-way ANOVA: variation of run means vs variation inside runs
+    let between_ss: f64 = speeds
+        .iter()
+        .zip(&run_means)
+        .map(|(run, m)| run.len() as f64 * (m - grand_mean).powi(2))
+        .sum();
+    let within_ss: f64 = speeds
+        .iter()
+        .zip(&run_means)
+        .map(|(run, m)| run.iter().map(|x| (x - m).powi(2)).sum::<f64>())
+        .sum();
+    let within_var = if n > k { within_ss / (n - k) } else { 0.0 };
+    let variance_ratio = (within_var > 0.0).then(|| between_ss / (k - 1.0) / within_var);
+
+    let between_run_stddev = stddev(&run_means).unwrap_or(0.0);
+    let spread = if grand_mean > 0.0 { between_run_stddev / grand_mean } else { 0.0 };
+    let consistent = spread <= NEGLIGIBLE_SPREAD || variance_ratio.is_some_and(|f| f <= MAX_VARIANCE_RATIO);
+
+    ModelAggregate {
+        model: model.to_string(),
+        runs: runs.len() as u64,
+        iterations: all.len() as u64,
+        avg_tokens_per_second: grand_mean,
+        stddev_tokens_per_second: stddev(&all).unwrap_or(0.0),
+        avg_ttft_ms: mean(runs.iter().flatten().map(|r| r.time_to_first_token_ms as f64)),
+        run_means,
+        between_run_stddev,
+        within_run_stddev: within_var.sqrt(),
+        variance_ratio,
+        consistent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(model: &str, speeds: &[f64]) -> Vec<BenchmarkResult> {
+        speeds
+            .iter()
+            .map(|&tps| BenchmarkResult::ok(model, tps))
+            .collect()
+    }
+
+    #[test]
+    fn test_consistent_runs() {
+        let runs = vec![run("a", &[49.0, 50.0, 51.0]), run("a", &[50.0, 51.0, 52.0]), run("b", &[10.0])];
+
+        let report = aggregate(&runs);
+        assert_eq!(report.len(), 1, "b ran only once");
+        let a = &report[0];
+        assert_eq!((a.runs, a.iterations), (2, 6));
+        assert_eq!(a.run_means, vec![50.0, 51.0]);
+        assert_eq!(a.avg_tokens_per_second, 50.5);
+        assert_eq!(a.within_run_stddev, 1.0);
+        assert!(a.consistent);
+    }
+
+    #[test]
+    fn test_inconsistent_runs() {
+        // Tight runs whose means differ by 20%: something changed between them
+        let runs = vec![run("a", &[50.0, 50.5, 49.5]), run("a", &[40.0, 40.5, 39.5])];
+
+        let a = &aggregate(&runs)[0];
+        assert!(a.variance_ratio.unwrap() > MAX_VARIANCE_RATIO);
+        assert!(!a.consistent);
+    }
+
+    #[test]
+    fn test_load_iterations_rejects_summaries() {
+        let path = std::env::temp_dir().join(format!("ollama-bench-aggregate-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"models": []}"#).unwrap();
+        assert!(matches!(load_iterations(path.to_str().unwrap()), Err(BenchmarkError::ConfigError(_))));
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,95 @@
+use std::process::Command;
+
+use crate::types::BenchmarkResult;
+
+/// The wall clock jumping ahead of the monotonic clock by this much during a
+/// request means the machine was suspended (the monotonic clock stops while
+/// asleep). Smaller or backward steps are NTP corrections: recorded on the
+/// result but harmless, since durations are monotonic.
+const SUSPEND_GAP_MS: i64 = 2000;
+
+/// Watches for system events that invalidate an iteration's timing.
+pub struct AnomalyMonitor {
+    /// OOM-killer lines in the kernel log so far; `None` if dmesg is not readable
+    oom_kills: Option<usize>,
+}
+
+impl Default for AnomalyMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnomalyMonitor {
+    pub fn new() -> Self {
+        Self { oom_kills: count_oom_kills() }
+    }
+
+    /// Annotates `result` with the first anomaly detected during it, if any.
+    pub fn check(&mut self, result: &mut BenchmarkResult) {
+        if let Some(gap_ms) = suspend_gap_ms(result) {
+            result.anomaly = Some(format!("suspend/resume: wall clock ran {:.1}s ahead", gap_ms as f64 / 1000.0));
+            return;
+        }
+
+        // Only re-read the kernel log when the baseline could be read at all
+        if let Some(before) = self.oom_kills {
+            let now = count_oom_kills().unwrap_or(before);
+            if now > before {
+                result.anomaly = Some(format!("OOM killer ran {} time(s) (dmesg)", now - before));
+            }
+            self.oom_kills = Some(now);
+        }
+    }
+}
+
+fn suspend_gap_ms(result: &BenchmarkResult) -> Option<i64> {
+    result.clock_jump_ms.filter(|jump| *jump >= SUSPEND_GAP_MS)
+}
+
+fn count_oom_kills() -> Option<usize> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let output = Command::new("dmesg").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(count_oom_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn count_oom_lines(log: &str) -> usize {
+    log.lines()
+        .filter(|line| line.contains("Killed process"))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_suspend_gap() {
+        let mut result = BenchmarkResult::failed("m", "p", Utc::now(), 1000, String::new());
+        result.clock_jump_ms = Some(-1500);
+        assert_eq!(suspend_gap_ms(&result), None);
+
+        // Ten minutes of sleep in the middle of a one-second request
+        result.clock_jump_ms = Some(600_000);
+        assert_eq!(suspend_gap_ms(&result), Some(600_000));
+
+        let mut monitor = AnomalyMonitor { oom_kills: None };
+        monitor.check(&mut result);
+        assert_eq!(result.anomaly.as_deref(), Some("suspend/resume: wall clock ran 600.0s ahead"));
+    }
+
+    #[test]
+    fn test_count_oom_lines() {
+        let log = "[  1.0] usb 1-1: new device\n\
+                   [ 99.2] Out of memory: Killed process 4242 (ollama_llama_se)\n\
+                   [ 99.3] oom-kill:constraint=CONSTRAINT_NONE,task=ollama\n";
+        assert_eq!(count_oom_lines(log), 1);
+        assert_eq!(count_oom_lines(""), 0);
+    }
+}
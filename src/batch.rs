@@ -0,0 +1,113 @@
+use serde::Serialize;
+
+use crate::effect::mean;
+use crate::types::{BatchMode, BenchmarkResult, ModelSummary};
+
+/// One way of requesting a model's `--batch-n` completions.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchStats {
+    pub iterations: u64,
+    pub avg_latency_ms: f64,
+    /// Completions returned per iteration
+    pub avg_completions: f64,
+    /// Completion tokens over every choice per second of wall clock
+    pub tokens_per_second: f64,
+}
+
+/// Separate requests vs one `n` request for one model.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    pub model: String,
+    /// Completions asked for per iteration
+    pub n: u32,
+    pub separate: BatchStats,
+    pub combined: BatchStats,
+    /// The server returned `n` choices; otherwise it ignored `n` and the
+    /// combined side produced fewer completions
+    pub n_supported: bool,
+    /// Relative change in throughput; positive means one `n` request is faster
+    pub throughput_change_percent: f64,
+}
+
+fn stats(results: &[&BenchmarkResult]) -> BatchStats {
+    let count = results.len() as f64;
+    let total_ms: u64 = results.iter().map(|r| r.total_duration_ms).sum();
+    let tokens: u64 = results.iter().map(|r| r.completion_tokens as u64).sum();
+    BatchStats {
+        iterations: results.len() as u64,
+        avg_latency_ms: total_ms as f64 / count,
+        avg_completions: mean(results.iter().map(|r| r.completions.unwrap_or(0) as f64)),
+        tokens_per_second: if total_ms > 0 { tokens as f64 * 1000.0 / total_ms as f64 } else { 0.0 },
+    }
+}
+
+/// Separate against combined measured iterations, for each summary that
+/// has both.
+pub fn build_report(summaries: &[ModelSummary], n: u32, results: &[BenchmarkResult]) -> Vec<BatchReport> {
+    summaries
+        .iter()
+        .filter_map(|summary| {
+            let through = |mode: BatchMode| -> Vec<&BenchmarkResult> {
+                results
+                    .iter()
+                    .filter(|r| summary.owns(r) && r.is_measured() && r.batch == Some(mode))
+                    .collect()
+            };
+            let (separate, combined) = (through(BatchMode::Separate), through(BatchMode::Combined));
+            if separate.is_empty() || combined.is_empty() {
+                return None;
+            }
+
+            let (separate, combined) = (stats(&separate), stats(&combined));
+            Some(BatchReport {
+                model: summary.label(),
+                n,
+                n_supported: combined.avg_completions >= n as f64,
+                throughput_change_percent: if separate.tokens_per_second > 0.0 {
+                    (combined.tokens_per_second - separate.tokens_per_second) / separate.tokens_per_second * 100.0
+                } else {
+                    0.0
+                },
+                separate,
+                combined,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, mode: BatchMode, total_ms: u64, completion_tokens: u32, completions: u32) -> BenchmarkResult {
+        BenchmarkResult {
+            total_duration_ms: total_ms,
+            completion_tokens,
+            batch: Some(mode),
+            completions: Some(completions),
+            ..BenchmarkResult::ok(model, 0.0)
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let summaries = ["a", "b", "c"].map(|model| ModelSummary::from_results(model.to_string(), &[]));
+        let results = vec![
+            result("a", BatchMode::Separate, 2000, 400, 4),
+            result("a", BatchMode::Combined, 1000, 300, 4),
+            result("a", BatchMode::Combined, 1000, 300, 4),
+            // Server ignoring `n`: one choice per combined request
+            result("b", BatchMode::Separate, 2000, 400, 4),
+            result("b", BatchMode::Combined, 500, 100, 1),
+            result("c", BatchMode::Separate, 2000, 400, 4),
+        ];
+
+        let report = build_report(&summaries, 4, &results);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].separate.tokens_per_second, 200.0);
+        assert_eq!(report[0].combined.tokens_per_second, 300.0);
+        assert_eq!(report[0].throughput_change_percent, 50.0);
+        assert!(report[0].n_supported);
+        assert!(!report[1].n_supported);
+    }
+}
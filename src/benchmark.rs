@@ -1,15 +1,56 @@
-use std::time::Duration;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
 use tokio::time::sleep;
+use tokio_stream::{wrappers::UnboundedReceiverStream, Stream};
+use tokio_util::sync::CancellationToken;
 
 use crate::types::*;
+use crate::anomaly::AnomalyMonitor;
+use crate::config::{CI_MIN_ITERATIONS, PATTERN_TICK_MS, TOKENIZER_RATIO_THRESHOLD, WINNER_THRESHOLD_PERCENT};
+use crate::effect::{self, Moments};
 use crate::error::Result;
-use crate::ollama::OllamaClient;
+use crate::eval::{EvalScore, EvalTask};
+use crate::exclusions::ExcludedModel;
+use crate::gpu;
+use crate::histogram::TokenHistogram;
+use crate::host;
+use crate::keepalive::IdleSample;
+use crate::load::LoadStats;
+use crate::pattern::LoadPattern;
+use crate::ollama::{self, OllamaClient};
+use crate::plan::{self, Cell};
+use crate::outliers;
+use crate::warmup;
 use crate::progress::ProgressReporter;
+use crate::telemetry::{GpuSource, Sampler};
+
+/// Live events emitted by [`Benchmarker::run_stream`]. `model` is the
+/// bare model name and `variant` the sweep variant, as in
+/// [`ModelSummary`], so iterations match the run they belong to.
+#[derive(Debug, Clone)]
+pub enum BenchmarkEvent {
+    ModelStarted { model: String, variant: Option<String>, index: u32, total: u32 },
+    IterationFinished { model: String, variant: Option<String>, iteration: u32, result: Box<BenchmarkResult> },
+    ModelFinished(Box<ModelSummary>),
+    RunComplete(Vec<ModelSummary>),
+    RunFailed(String),
+}
 
 pub struct Benchmarker {
     client: OllamaClient,
     config: BenchmarkConfig,
     progress: Box<dyn ProgressReporter>,
+    events: Option<mpsc::UnboundedSender<BenchmarkEvent>>,
+    results: Vec<BenchmarkResult>,
+    anomalies: AnomalyMonitor,
+    /// Name results and summaries are labelled with, when several servers are benchmarked
+    server: Option<String>,
+    /// Models that never got a summary, with the reason
+    skipped: Vec<ExcludedModel>,
+    /// Resource sampling around each sequential iteration
+    telemetry: Option<GpuSource>,
 }
 
 impl Benchmarker {
@@ -22,77 +63,883 @@ impl Benchmarker {
             client,
             config,
             progress,
+            events: None,
+            results: Vec::new(),
+            anomalies: AnomalyMonitor::new(),
+            server: None,
+            skipped: Vec::new(),
+            telemetry: None,
         }
     }
     
-    pub async fn benchmark_models(&mut self, models: Vec<String>) -> Result<Vec<ModelSummary>> {
-        let total_models = models.len() as u32;
-        let mut all_results = Vec::new();
+    /// Points later runs at another server; their results and summaries
+    /// are labelled with `name`.
+    pub fn use_server(&mut self, client: OllamaClient, name: String) {
+        self.client = client;
+        self.server = Some(name);
+    }
+    
+    /// Samples resource usage during every sequential iteration from now on.
+    pub fn enable_telemetry(&mut self, source: GpuSource) {
+        self.telemetry = Some(source);
+    }
+    
+    /// Models left out so far: missing on the server or never reached.
+    pub fn skipped(&self) -> &[ExcludedModel] {
+        &self.skipped
+    }
+    
+    /// Every individual iteration result from the last run, in execution order.
+    pub fn raw_results(&self) -> &[BenchmarkResult] {
+        &self.results
+    }
+    
+    /// Runs the benchmark in a background task and yields events as they
+    /// happen, ending with `RunComplete` or `RunFailed`.
+    pub fn run_stream(
+        mut self,
+        models: Vec<String>,
+        cancel: CancellationToken,
+    ) -> impl Stream<Item = BenchmarkEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.events = Some(tx.clone());
         
-        // First, validate all models exist
-        self.progress.print_info("Validating models...");
-        for model in &models {
-            if !self.client.validate_model(model).await? {
-                return Err(crate::error::BenchmarkError::ModelNotFound(model.clone()));
-            }
+        tokio::spawn(async move {
+            let event = match self.benchmark_models(models, &cancel).await {
+                Ok(summaries) => BenchmarkEvent::RunComplete(summaries),
+                Err(e) => BenchmarkEvent::RunFailed(e.to_string()),
+            };
+            tx.send(event).ok();
+        });
+        
+        UnboundedReceiverStream::new(rx)
+    }
+    
+    fn emit(&self, event: BenchmarkEvent) {
+        if let Some(events) = &self.events {
+            // A dropped receiver just means nobody is listening anymore
+            events.send(event).ok();
         }
+    }
+    
+    /// `ModelStarted` for the `index`th of `total` runs; every mode sends
+    /// it before the run's first `IterationFinished`.
+    fn emit_started(&self, model: &str, variant: Option<&str>, index: u32, total: u32) {
+        self.emit(BenchmarkEvent::ModelStarted {
+            model: model.to_string(),
+            variant: variant.map(str::to_string),
+            index,
+            total,
+        });
+    }
+    
+    /// Benchmarks every model × variant cell in the configured sweep order.
+    /// Cancelling `cancel` stops the run after the in-flight request is
+    /// abandoned and returns summaries for whatever iterations completed so far.
+    pub async fn benchmark_models(
+        &mut self,
+        models: Vec<String>,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<ModelSummary>> {
+        let mut summaries: Vec<ModelSummary> = Vec::new();
         
-        // Benchmark each model
-        for (idx, model) in models.iter().enumerate() {
-            let model_results = self.benchmark_single_model(
-                model,
-                idx as u32,
-                total_models
-            ).await?;
+        // First, check which models exist; missing ones are skipped
+        let models = self.validate_models(models).await?;
+        
+        let cells = plan::plan(&models, &self.config, self.config.sweep_order);
+        let total_runs = cells.len() as u32;
+        
+        // Per-model facts, gathered the first time each model comes up
+        let mut eval_scores: HashMap<String, Option<EvalScore>> = HashMap::new();
+        let mut default_threads: HashMap<String, Option<u32>> = HashMap::new();
+        
+        // Observed cost of an iteration, for fitting the run into its time budget
+        let run_start = Instant::now();
+        let mut measured_time = Duration::ZERO;
+        let mut measured_iterations = 0u64;
+        
+        let mut interleaved = if self.config.interleave {
+            Some(self.benchmark_interleaved(&cells, cancel).await?)
+        } else {
+            None
+        };
+        
+        for (run_index, cell) in cells.iter().enumerate() {
+            // Interleaved results are already in; summarize what finished
+            if cancel.is_cancelled() && interleaved.is_none() {
+                break;
+            }
+            
+            let model = &cell.model;
+            if !default_threads.contains_key(model) {
+                let threads = if self.config.num_thread.is_none() && self.config.thread_sweep.is_empty() {
+                    configured_num_thread(&self.client, model).await
+                } else {
+                    None
+                };
+                default_threads.insert(model.clone(), threads);
+            }
             
-            all_results.push((model.clone(), model_results));
+            let mut config = cell.config.clone();
+            if let Some(budget) = self.config.time_budget {
+                let per_iteration = (measured_iterations > 0).then(|| measured_time.div_f64(measured_iterations as f64));
+                config.iterations = plan::budget_iterations(
+                    config.iterations,
+                    cells.len() - run_index,
+                    budget.saturating_sub(run_start.elapsed()),
+                    per_iteration,
+                );
+            }
+            
+            let cell_start = Instant::now();
+            let mut model_results = if let Some(interleaved) = &mut interleaved {
+                std::mem::take(&mut interleaved[run_index])
+            } else if config.concurrency > 1 || config.load_pattern.is_some() {
+                self.benchmark_concurrent(cell, &config, run_index as u32, total_runs, cancel).await?
+            } else {
+                self.benchmark_single_model(cell, &config, run_index as u32, total_runs, cancel).await?
+            };
+            let cell_time = cell_start.elapsed();
+            measured_time += cell_time;
+            measured_iterations += model_results.len() as u64;
+            if let Some(count) = config.warmup {
+                warmup::mark_first(&mut model_results, count as usize);
+            } else if !config.keep_warmup {
+                warmup::mark(&mut model_results);
+            }
+            if let Some(rule) = config.trim_outliers {
+                outliers::mark(&mut model_results, rule);
+            }
             
-            // Small delay between models
-            if idx < models.len() - 1 {
+            // Runs cancelled before any iteration finished get no summary
+            if !model_results.is_empty() {
+                let mut summary = ModelSummary::from_results(model.clone(), &model_results);
+                summary.completion_histogram = TokenHistogram::of(&model_results, cell.config.max_tokens);
+                if config.iterations < cell.config.iterations {
+                    summary.planned_iterations = Some(cell.config.iterations);
+                }
+                summary.variant = cell.variant.clone();
+                summary.server = self.server.clone();
+                if config.concurrency > 1 || config.load_pattern.is_some() {
+                    // A pattern varies the load; report the most it reached
+                    let concurrency = match config.load_pattern {
+                        Some(_) => model_results.iter().filter_map(|r| r.in_flight).max().unwrap_or(config.concurrency),
+                        None => config.concurrency,
+                    };
+                    summary.load = Some(LoadStats::new(concurrency, &model_results, cell_time));
+                }
+                summary.num_thread = cell.config.num_thread.or(default_threads[model]);
+                if !self.config.history_sweep.is_empty() {
+                    summary.history_messages = Some(cell.config.history_messages);
+                }
+                summary.context_tokens = cell.config.context_tokens;
+                
+                // Accuracy is a property of the model, so score it once
+                if let Some(task) = self.config.eval {
+                    if !eval_scores.contains_key(model) {
+                        let score = match self.run_eval(model, task, cancel).await {
+                            Err(e) if self.config.keep_going => {
+                                self.progress.print_info(&format!("Skipping eval of {}: {}", model, e));
+                                None
+                            }
+                            score => score?,
+                        };
+                        eval_scores.insert(model.clone(), score);
+                    }
+                    summary.eval = eval_scores[model].clone();
+                }
+                
+                if self.config.gpu_filter.is_some() {
+                    summary.gpu_devices = match detect_gpu_devices(&self.client, model).await {
+                        Err(e) if self.config.keep_going => {
+                            self.progress.print_info(&format!("Could not detect GPU placement of {}: {}", model, e));
+                            None
+                        }
+                        devices => Some(devices?),
+                    };
+                }
+                summary.vram_fraction = vram_fraction(&self.client, model).await;
+                
+                self.emit(BenchmarkEvent::ModelFinished(Box::new(summary.clone())));
+                summaries.push(summary);
+            }
+            for result in &mut model_results {
+                result.server = self.server.clone();
+                result.variant = cell.variant.clone();
+            }
+            self.results.extend(model_results);
+            
+            // Small delay when switching models
+            let next_model = cells.get(run_index + 1).map(|next| &next.model);
+            if next_model.is_some_and(|next| next != model) && !cancel.is_cancelled() && interleaved.is_none() {
                 sleep(Duration::from_millis(500)).await;
             }
         }
         
-        // Generate summaries
-        let summaries: Vec<ModelSummary> = all_results
+        for model in &models {
+            if cancel.is_cancelled() && !summaries.iter().any(|s| &s.model == model) {
+                self.skip(model, "run cancelled before its first iteration finished".to_string());
+            }
+        }
+        
+        Ok(summaries)
+    }
+    
+    /// Loads every model up front, then alternates requests among them so
+    /// each request measures latency including any swap/eviction penalty.
+    /// Returns the summaries plus the models still resident after preloading.
+    pub async fn benchmark_warm_pool(
+        &mut self,
+        models: Vec<String>,
+        cancel: &CancellationToken,
+    ) -> Result<(Vec<ModelSummary>, Vec<String>)> {
+        let models = self.validate_models(models).await?;
+        
+        self.progress.print_info(&format!("Preloading {} models...", models.len()));
+        for model in &models {
+            self.client.load_model(model).await?;
+        }
+        
+        let resident: Vec<String> = self.client
+            .running_models()
+            .await?
             .into_iter()
-            .map(|(model, results)| ModelSummary::from_results(model, &results))
+            .map(|m| m.name)
+            .filter(|name| models.contains(name))
             .collect();
+        self.progress.print_info(&format!("{}/{} models resident after preload", resident.len(), models.len()));
+        
+        let label = format!("warm pool ({} models)", models.len());
+        let total_requests = self.config.iterations * models.len() as u32;
+        let mut per_model: Vec<Vec<BenchmarkResult>> = vec![Vec::new(); models.len()];
+        
+        self.progress.start_model(&label, 1, 1);
+        // Every model is in play from the first round
+        for (idx, model) in models.iter().enumerate() {
+            self.emit_started(model, None, idx as u32 + 1, models.len() as u32);
+        }
+        'rounds: for iteration in 0..self.config.iterations {
+            for (idx, model) in models.iter().enumerate() {
+                let request = iteration * models.len() as u32 + idx as u32 + 1;
+                self.progress.update_progress(&label, request, total_requests);
+                
+                let prompt = self.config.iteration_prompt(model, iteration);
+                let mut result = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => break 'rounds,
+                    result = attempt(self.config.keep_going, model, &prompt, self.client.generate(model, &prompt, &self.config)) => result?,
+                };
+                result.category = self.config.iteration_category(iteration);
+                self.anomalies.check(&mut result);
+                
+                self.emit(BenchmarkEvent::IterationFinished {
+                    model: model.clone(),
+                    variant: None,
+                    iteration: iteration + 1,
+                    result: Box::new(result.clone()),
+                });
+                per_model[idx].push(result);
+            }
+        }
+        self.progress.complete_model(&label);
+        
+        let mut summaries = Vec::new();
+        for (model, results) in models.iter().zip(per_model) {
+            if !results.is_empty() {
+                let mut summary = ModelSummary::from_results(model.clone(), &results);
+                summary.completion_histogram = TokenHistogram::of(&results, self.config.max_tokens);
+                self.emit(BenchmarkEvent::ModelFinished(Box::new(summary.clone())));
+                summaries.push(summary);
+            }
+            self.results.extend(results);
+        }
+        
+        Ok((summaries, resident))
+    }
+    
+    /// `--ab-hosts`: sends each model's iterations to both servers back to
+    /// back, swapping which goes first every iteration, so drift over the
+    /// run hits both alike. Returns one summary per model and server.
+    pub async fn benchmark_ab(
+        &mut self,
+        models: Vec<String>,
+        hosts: &[(OllamaClient, String)],
+        cancel: &CancellationToken,
+    ) -> Result<Vec<ModelSummary>> {
+        // Only models present on both servers can be paired
+        let mut models = models;
+        for (client, name) in hosts {
+            self.use_server(client.clone(), name.clone());
+            models = self.validate_models(models).await?;
+        }
+        
+        let config = self.config.clone();
+        let mut summaries = Vec::new();
+        for (idx, model) in models.iter().enumerate() {
+            self.progress.start_model(model, idx as u32 + 1, models.len() as u32);
+            self.emit_started(model, None, idx as u32 + 1, models.len() as u32);
+            let mut per_host: Vec<Vec<BenchmarkResult>> = vec![Vec::new(); hosts.len()];
+            
+            'iterations: for iteration in 0..config.iterations {
+                self.progress.update_progress(model, iteration + 1, config.iterations);
+                let mut order: Vec<usize> = (0..hosts.len()).collect();
+                if iteration % 2 == 1 {
+                    order.reverse();
+                }
+                for host in order {
+                    let (client, name) = &hosts[host];
+                    self.use_server(client.clone(), name.clone());
+                    let Some(mut result) = self.run_iteration(model, None, &config, iteration, cancel).await? else {
+                        break 'iterations;
+                    };
+                    result.server = Some(name.clone());
+                    per_host[host].push(result);
+                    sleep(Duration::from_millis(100)).await;
+                }
+            }
+            self.progress.complete_model(model);
+            self.finish_ab_model(model, hosts, per_host, &mut summaries);
+            if cancel.is_cancelled() {
+                break;
+            }
+        }
         
         Ok(summaries)
     }
     
-    async fn benchmark_single_model(
+    /// Marks warm-up and summarizes one model's results on each server.
+    fn finish_ab_model(
         &mut self,
         model: &str,
-        model_index: u32,
-        total_models: u32,
+        hosts: &[(OllamaClient, String)],
+        per_host: Vec<Vec<BenchmarkResult>>,
+        summaries: &mut Vec<ModelSummary>,
+    ) {
+        for ((_, name), mut results) in hosts.iter().zip(per_host) {
+            if let Some(count) = self.config.warmup {
+                warmup::mark_first(&mut results, count as usize);
+            } else if !self.config.keep_warmup {
+                warmup::mark(&mut results);
+            }
+            if let Some(rule) = self.config.trim_outliers {
+                outliers::mark(&mut results, rule);
+            }
+            if !results.is_empty() {
+                let mut summary = ModelSummary::from_results(model.to_string(), &results);
+                summary.completion_histogram = TokenHistogram::of(&results, self.config.max_tokens);
+                summary.server = Some(name.clone());
+                self.emit(BenchmarkEvent::ModelFinished(Box::new(summary.clone())));
+                summaries.push(summary);
+            }
+            self.results.extend(results);
+        }
+    }
+    
+    /// For each model: a warm-up request, then one request after each idle
+    /// period, measuring whether Ollama kept the model loaded in between.
+    /// Each probe reloads the model if needed, so idle periods are independent.
+    pub async fn benchmark_idle_probe(
+        &mut self,
+        models: Vec<String>,
+        idle_periods: &[Duration],
+        cancel: &CancellationToken,
+    ) -> Result<(Vec<ModelSummary>, Vec<IdleSample>)> {
+        let models = self.validate_models(models).await?;
+        
+        let mut summaries = Vec::new();
+        let mut samples = Vec::new();
+        
+        'models: for (idx, model) in models.iter().enumerate() {
+            self.progress.start_model(model, idx as u32 + 1, models.len() as u32);
+            self.emit_started(model, None, idx as u32 + 1, models.len() as u32);
+            self.client.load_model(model).await?;
+            
+            let mut model_results = Vec::new();
+            for (probe, idle) in idle_periods.iter().enumerate() {
+                self.progress.print_info(&format!("Idling {}s before the next request...", idle.as_secs()));
+                tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => break 'models,
+                    _ = sleep(*idle) => {}
+                }
+                
+                self.progress.update_progress(model, probe as u32 + 1, idle_periods.len() as u32);
+                let prompt = self.config.iteration_prompt(model, probe as u32);
+                let mut result = tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => break 'models,
+                    result = attempt(self.config.keep_going, model, &prompt, self.client.generate(model, &prompt, &self.config)) => result?,
+                };
+                result.category = self.config.iteration_category(probe as u32);
+                self.anomalies.check(&mut result);
+                
+                samples.push(IdleSample::new(idle.as_secs(), &result));
+                self.emit(BenchmarkEvent::IterationFinished {
+                    model: model.clone(),
+                    variant: None,
+                    iteration: probe as u32 + 1,
+                    result: Box::new(result.clone()),
+                });
+                model_results.push(result);
+            }
+            self.progress.complete_model(model);
+            
+            if !model_results.is_empty() {
+                let summary = ModelSummary::from_results(model.clone(), &model_results);
+                self.emit(BenchmarkEvent::ModelFinished(Box::new(summary.clone())));
+                summaries.push(summary);
+            }
+            self.results.extend(model_results);
+        }
+        
+        Ok((summaries, samples))
+    }
+    
+    /// Returns the models present on the server, recording the rest as
+    /// skipped. Fails only when none of them is there.
+    async fn validate_models(&mut self, models: Vec<String>) -> Result<Vec<String>> {
+        self.progress.print_info("Validating models...");
+        let mut present = Vec::new();
+        let mut missing = Vec::new();
+        for model in models {
+            if self.client.validate_model(&model).await? {
+                present.push(model);
+            } else if self.config.pull_missing {
+                match self.pull(&model).await {
+                    Ok(()) => present.push(model),
+                    Err(e) => {
+                        self.skip(&model, format!("pull failed: {}", e));
+                        missing.push(model);
+                    }
+                }
+            } else {
+                self.skip(&model, "not found on the server (ollama pull it first, or pass --pull-missing)".to_string());
+                missing.push(model);
+            }
+        }
+        if present.is_empty() {
+            if let Some(model) = missing.first() {
+                return Err(crate::error::BenchmarkError::ModelNotFound(model.clone()));
+            }
+        }
+        for model in missing {
+            self.progress.print_info(&format!("Skipping {}: not available on the server", model));
+        }
+        Ok(present)
+    }
+    
+    /// Pulls `model`, reporting each status change and whole percent.
+    async fn pull(&mut self, model: &str) -> Result<()> {
+        self.progress.print_info(&format!("Pulling missing model {}...", model));
+        let progress = &mut self.progress;
+        let mut last: Option<(String, u64)> = None;
+        self.client
+            .pull_model(model, |status| {
+                let (completed, total) = (status.completed.unwrap_or(0), status.total.unwrap_or(0));
+                let step = (status.status.clone(), (completed * 100).checked_div(total).unwrap_or(0));
+                if last.as_ref() != Some(&step) {
+                    progress.pull_progress(model, &status.status, completed, total);
+                    last = Some(step);
+                }
+            })
+            .await
+    }
+    
+    fn skip(&mut self, model: &str, reason: String) {
+        self.skipped.push(ExcludedModel { model: model.to_string(), server: self.server.clone(), variant: None, reason });
+    }
+    
+    async fn benchmark_single_model(
+        &mut self,
+        cell: &Cell,
+        config: &BenchmarkConfig,
+        run_index: u32,
+        total_runs: u32,
+        cancel: &CancellationToken,
     ) -> Result<Vec<BenchmarkResult>> {
         let mut results = Vec::new();
+        let label = &cell.label();
         
-        self.progress.start_model(model, model_index + 1, total_models);
+        self.progress.start_model(label, run_index + 1, total_runs);
+        self.emit_started(&cell.model, cell.variant.as_deref(), run_index + 1, total_runs);
         
-        for iteration in 0..self.config.iterations {
-            self.progress.update_progress(model, iteration + 1, self.config.iterations);
-            
-            let result = self.client.generate(
-                model,
-                &self.config.prompt,
-                &self.config
-            ).await?;
+        for iteration in 0..config.iterations {
+            self.progress.update_progress(label, iteration + 1, config.iterations);
             
+            let Some(result) = self.run_iteration(&cell.model, cell.variant.as_deref(), config, iteration, cancel).await? else {
+                break;
+            };
             results.push(result);
             
+            if config.until_ci.is_some_and(|margin| ci_reached(&results, config, margin)) {
+                break;
+            }
+            
             // Small delay between iterations to avoid overwhelming the server
-            if iteration < self.config.iterations - 1 {
+            if iteration < config.iterations - 1 {
                 sleep(Duration::from_millis(100)).await;
             }
         }
         
-        self.progress.complete_model(model);
+        self.progress.complete_model(label);
+        
+        Ok(results)
+    }
+    
+    /// Runs every cell's iterations round-robin, each round starting one
+    /// cell later (A1, B1, B2, A2, ...), so slow drift such as thermal
+    /// throttling or background load spreads evenly over the models instead
+    /// of penalizing whichever runs last, and no cell always goes first.
+    /// Returns each cell's results, in cell order.
+    async fn benchmark_interleaved(&mut self, cells: &[Cell], cancel: &CancellationToken) -> Result<Vec<Vec<BenchmarkResult>>> {
+        let mut results = vec![Vec::new(); cells.len()];
+        let rounds = cells.iter().map(|cell| cell.config.iterations).max().unwrap_or(0);
+        let total_requests: u32 = cells.iter().map(|cell| cell.config.iterations).sum();
+        let label = format!("interleaved ({} runs)", cells.len());
+        let mut sent = 0;
+        
+        self.progress.start_model(&label, 1, 1);
+        // Every cell is in play from the first round
+        for (index, cell) in cells.iter().enumerate() {
+            self.emit_started(&cell.model, cell.variant.as_deref(), index as u32 + 1, cells.len() as u32);
+        }
+        'rounds: for iteration in 0..rounds {
+            for index in (0..cells.len()).map(|offset| (iteration as usize + offset) % cells.len()) {
+                let cell = &cells[index];
+                if iteration >= cell.config.iterations {
+                    continue;
+                }
+                sent += 1;
+                self.progress.update_progress(&label, sent, total_requests);
+                
+                let Some(result) = self.run_iteration(&cell.model, cell.variant.as_deref(), &cell.config, iteration, cancel).await? else {
+                    break 'rounds;
+                };
+                results[index].push(result);
+                
+                if sent < total_requests {
+                    sleep(Duration::from_millis(100)).await;
+                }
+            }
+        }
+        self.progress.complete_model(&label);
         
         Ok(results)
     }
+    
+    /// One sequential iteration, with telemetry, category and anomaly
+    /// checks; `None` if `cancel` fired while it was in flight.
+    async fn run_iteration(
+        &mut self,
+        model: &str,
+        variant: Option<&str>,
+        config: &BenchmarkConfig,
+        iteration: u32,
+        cancel: &CancellationToken,
+    ) -> Result<Option<BenchmarkResult>> {
+        let prompt = config.iteration_prompt(model, iteration);
+        let category = config.iteration_category(iteration);
+        let config = &config.for_iteration(iteration);
+        let sampler = self.telemetry.map(Sampler::start);
+        let mut result = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => return Ok(None),
+            result = attempt(config.keep_going, model, &prompt, generate_iteration(&self.client, model, &prompt, config)) => result?,
+        };
+        result.telemetry = sampler.and_then(Sampler::finish);
+        result.category = category;
+        result.image = config.image.as_ref().map(|image| image.name.clone());
+        self.anomalies.check(&mut result);
+        
+        self.emit(BenchmarkEvent::IterationFinished {
+            model: model.to_string(),
+            variant: variant.map(str::to_string),
+            iteration: iteration + 1,
+            result: Box::new(result.clone()),
+        });
+        Ok(Some(result))
+    }
+    
+    /// Keeps `config.concurrency` requests in flight until `config.iterations`
+    /// have been sent, like `ab -c`, varied over time by `config.load_pattern`.
+    /// Results are in completion order.
+    async fn benchmark_concurrent(
+        &mut self,
+        cell: &Cell,
+        config: &BenchmarkConfig,
+        run_index: u32,
+        total_runs: u32,
+        cancel: &CancellationToken,
+    ) -> Result<Vec<BenchmarkResult>> {
+        let (model, label) = (cell.model.as_str(), &cell.label());
+        let mut results = Vec::new();
+        let mut in_flight = JoinSet::new();
+        let mut sent = 0;
+        let started = Instant::now();
+        let mut next_burst = match config.load_pattern {
+            Some(LoadPattern::Burst { every, .. }) => Some(every),
+            _ => None,
+        };
+        
+        self.progress.start_model(label, run_index + 1, total_runs);
+        self.emit_started(model, cell.variant.as_deref(), run_index + 1, total_runs);
+        
+        loop {
+            let elapsed = started.elapsed();
+            let target = match config.load_pattern {
+                Some(pattern) => pattern.target(config.concurrency, elapsed),
+                None => config.concurrency,
+            };
+            let mut to_send = (target as usize).saturating_sub(in_flight.len());
+            if let (Some(at), Some(LoadPattern::Burst { size, every })) = (next_burst, config.load_pattern) {
+                if elapsed >= at {
+                    to_send += size as usize;
+                    next_burst = Some(at + every);
+                }
+            }
+            let to_send = to_send.min((config.iterations - sent) as usize);
+            let level = (in_flight.len() + to_send) as u32;
+            
+            for _ in 0..to_send {
+                let (client, config, model) = (self.client.clone(), config.for_iteration(sent), model.to_string());
+                let prompt = config.iteration_prompt(&model, sent);
+                let category = config.iteration_category(sent);
+                let image = config.image.as_ref().map(|image| image.name.clone());
+                let in_flight_after = config.load_pattern.is_some().then_some(level);
+                in_flight.spawn(async move {
+                    attempt(config.keep_going, &model, &prompt, client.generate(&model, &prompt, &config)).await.map(|mut result| {
+                        result.category = category;
+                        result.image = image;
+                        result.in_flight = in_flight_after;
+                        result
+                    })
+                });
+                sent += 1;
+            }
+            
+            // Dropping the set on cancel aborts the requests still in flight;
+            // a pattern also wakes up on its own to follow the load curve
+            let joined = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => break,
+                joined = in_flight.join_next(), if !in_flight.is_empty() => joined,
+                _ = sleep(Duration::from_millis(PATTERN_TICK_MS)), if config.load_pattern.is_some() && sent < config.iterations => continue,
+                else => break,
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+            let mut result = joined??;
+            self.anomalies.check(&mut result);
+            
+            self.progress.update_progress(label, results.len() as u32 + 1, config.iterations);
+            self.emit(BenchmarkEvent::IterationFinished {
+                model: model.to_string(),
+                variant: cell.variant.clone(),
+                iteration: results.len() as u32 + 1,
+                result: Box::new(result.clone()),
+            });
+            results.push(result);
+        }
+        
+        self.progress.complete_model(label);
+        
+        Ok(results)
+    }
+    
+    /// Scores `model` on every item of `task`; returns `None` if cancelled midway.
+    async fn run_eval(
+        &mut self,
+        model: &str,
+        task: EvalTask,
+        cancel: &CancellationToken,
+    ) -> Result<Option<EvalScore>> {
+        let items = task.items();
+        let total = items.len() as u32;
+        let mut correct = 0;
+        
+        self.progress.print_info(&format!("Evaluating {} on {} ({} items)...", model, task.name(), total));
+        
+        for (idx, item) in items.iter().enumerate() {
+            self.progress.update_progress(model, idx as u32 + 1, total);
+            
+            let result = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Ok(None),
+                result = self.client.generate(model, &item.prompt, &self.config) => result?,
+            };
+            
+            if result.response.as_deref().is_some_and(|r| task.is_correct(r, &item.answer)) {
+                correct += 1;
+            }
+        }
+        
+        self.progress.complete_model(model);
+        
+        Ok(Some(EvalScore {
+            task: task.name().to_string(),
+            correct,
+            total,
+        }))
+    }
+}
+
+/// Awaits one iteration's request. With `--keep-going`, an error that would
+/// end the run (e.g. the model disappearing mid-run) becomes a failed
+/// iteration instead, so the remaining models still run.
+async fn attempt(
+    keep_going: bool,
+    model: &str,
+    prompt: &str,
+    request: impl std::future::Future<Output = Result<BenchmarkResult>>,
+) -> Result<BenchmarkResult> {
+    let timestamp = chrono::Utc::now();
+    let start_time = Instant::now();
+    match request.await {
+        Err(e) if keep_going => Ok(BenchmarkResult::failed(
+            model,
+            prompt,
+            timestamp,
+            start_time.elapsed().as_millis() as u64,
+            e.to_string(),
+        )),
+        outcome => outcome,
+    }
+}
+
+/// One iteration's request(s): batched, hedged or plain.
+async fn generate_iteration(client: &OllamaClient, model: &str, prompt: &str, config: &BenchmarkConfig) -> Result<BenchmarkResult> {
+    match config.batch_mode {
+        Some(mode) => generate_batch(client, model, prompt, config, mode).await,
+        None => generate_hedged(client, model, prompt, config).await,
+    }
+}
+
+/// Requests `config.batch_n` completions, either as one request with `n`
+/// set or as that many concurrent single-completion requests. Speed counts
+/// every completion over the iteration's wall clock either way.
+async fn generate_batch(client: &OllamaClient, model: &str, prompt: &str, config: &BenchmarkConfig, mode: BatchMode) -> Result<BenchmarkResult> {
+    let n = config.batch_n.unwrap_or(1);
+    let mut result = match mode {
+        BatchMode::Combined => client.chat_completions(model, prompt, config, n).await?,
+        BatchMode::Separate => {
+            let start_time = Instant::now();
+            let mut in_flight = JoinSet::new();
+            for _ in 0..n {
+                let (client, config, model, prompt) = (client.clone(), config.clone(), model.to_string(), prompt.to_string());
+                in_flight.spawn(async move { client.chat_completions(&model, &prompt, &config, 1).await });
+            }
+            
+            let mut parts = Vec::new();
+            while let Some(joined) = in_flight.join_next().await {
+                parts.push(joined??);
+            }
+            merge_batch(parts, start_time.elapsed().as_millis() as u64)
+        }
+    };
+    result.batch = Some(mode);
+    Ok(result)
+}
+
+/// Folds concurrent single-completion requests into one iteration. Any
+/// failed request fails the iteration.
+fn merge_batch(mut parts: Vec<BenchmarkResult>, total_duration_ms: u64) -> BenchmarkResult {
+    parts.sort_by_key(|part| part.timestamp);
+    if let Some(failed) = parts.iter().find(|part| !part.success) {
+        return BenchmarkResult { total_duration_ms, ..failed.clone() };
+    }
+    
+    let completion_tokens = parts.iter().map(|part| part.completion_tokens).sum();
+    BenchmarkResult {
+        finished_at: parts.iter().filter_map(|part| part.finished_at).max(),
+        total_duration_ms,
+        prompt_tokens: parts.iter().map(|part| part.prompt_tokens).sum(),
+        completion_tokens,
+        tokens_per_second: ollama::per_second(completion_tokens, total_duration_ms),
+        completions: Some(parts.iter().filter_map(|part| part.completions).sum()),
+        ..parts.swap_remove(0)
+    }
+}
+
+/// Sends `config.hedge_copies` identical requests at once and keeps the
+/// first to succeed; the rest are aborted, which closes their connections
+/// so the server stops generating. All failing returns the last failure.
+async fn generate_hedged(client: &OllamaClient, model: &str, prompt: &str, config: &BenchmarkConfig) -> Result<BenchmarkResult> {
+    if config.hedge_copies <= 1 {
+        return client.generate(model, prompt, config).await;
+    }
+    
+    let mut in_flight = JoinSet::new();
+    for _ in 0..config.hedge_copies {
+        let (client, config, model, prompt) = (client.clone(), config.clone(), model.to_string(), prompt.to_string());
+        in_flight.spawn(async move { client.generate(&model, &prompt, &config).await });
+    }
+    
+    let mut last = None;
+    while let Some(joined) = in_flight.join_next().await {
+        let result = joined??;
+        if result.success {
+            in_flight.abort_all();
+            last = Some(result);
+            break;
+        }
+        last = Some(result);
+    }
+    
+    let mut result = last.expect("at least two hedged copies were sent");
+    result.hedged_copies = Some(config.hedge_copies);
+    Ok(result)
+}
+
+/// `--until-ci`: the measured iterations so far put tok/s within `margin`
+/// of its mean at 95% confidence. Warm-up is judged as it will be for the
+/// summary, so a cold first request does not keep the run going.
+fn ci_reached(results: &[BenchmarkResult], config: &BenchmarkConfig, margin: f64) -> bool {
+    let warmup = match config.warmup {
+        Some(count) => warmup::first(results, count as usize),
+        None if !config.keep_warmup => warmup::detect(results),
+        None => Vec::new(),
+    };
+    let speeds: Vec<f64> = results
+        .iter()
+        .enumerate()
+        .filter(|(i, r)| r.is_measured() && !warmup.contains(i))
+        .map(|(_, r)| r.tokens_per_second)
+        .collect();
+    speeds.len() >= CI_MIN_ITERATIONS as usize
+        && Moments::of(&speeds).and_then(effect::relative_margin).is_some_and(|m| m <= margin)
+}
+
+/// `num_thread` baked into the model's parameters, if any. Metadata only, so
+/// lookup failures are not fatal.
+async fn configured_num_thread(client: &OllamaClient, model: &str) -> Option<u32> {
+    let show = client.show_model(model).await.ok()?;
+    host::parse_num_thread(show.parameters.as_deref()?)
+}
+
+/// Share of the loaded model held in VRAM, from `/api/ps`. Metadata only,
+/// so lookup failures are not fatal.
+async fn vram_fraction(client: &OllamaClient, model: &str) -> Option<f64> {
+    let running = client.running_models().await.ok()?;
+    running
+        .iter()
+        .find(|m| m.name == model && m.size > 0)
+        .map(|m| m.size_vram as f64 / m.size as f64)
+}
+
+/// GPUs the freshly benchmarked model is resident on; empty if Ollama
+/// reports no VRAM use for it (CPU inference).
+async fn detect_gpu_devices(client: &OllamaClient, model: &str) -> Result<Vec<u32>> {
+    let running = client.running_models().await?;
+    let on_gpu = running
+        .iter()
+        .any(|m| m.name == model && m.size_vram > 0);
+    
+    if !on_gpu {
+        return Ok(Vec::new());
+    }
+    
+    Ok(gpu::ollama_gpu_indices(&gpu::list_gpus()))
 }
 
 pub fn calculate_winner(summaries: &[ModelSummary]) -> Option<&ModelSummary> {
@@ -127,10 +974,79 @@ pub fn calculate_performance_difference(winner: &ModelSummary, other: &ModelSumm
     (speed_diff, ttft_diff)
 }
 
+/// Whether `winner`'s token-speed lead over `other` is mostly a tokenizer
+/// artifact: their chars-per-token ratios differ materially, and measured in
+/// chars/s the lead shrinks by more than half or reverses.
+pub fn is_tokenizer_artifact(winner: &ModelSummary, other: &ModelSummary) -> bool {
+    let (Some(winner_cpt), Some(other_cpt)) = (winner.chars_per_token, other.chars_per_token) else {
+        return false;
+    };
+    if other_cpt <= 0.0 || (winner_cpt / other_cpt - 1.0).abs() < TOKENIZER_RATIO_THRESHOLD {
+        return false;
+    }
+    
+    let (token_diff, _) = calculate_performance_difference(winner, other);
+    if token_diff < WINNER_THRESHOLD_PERCENT {
+        return false;
+    }
+    
+    let other_chars = other.avg_tokens_per_second * other_cpt;
+    let char_diff = if other_chars > 0.0 {
+        (winner.avg_tokens_per_second * winner_cpt - other_chars) / other_chars * 100.0
+    } else {
+        0.0
+    };
+    char_diff < token_diff / 2.0
+}
+
+/// Models with eval scores that no other model beats on both speed and
+/// accuracy (the Pareto-efficient set), fastest first.
+pub fn efficient_frontier(summaries: &[ModelSummary]) -> Vec<&ModelSummary> {
+    let scored: Vec<(&ModelSummary, f64)> = summaries
+        .iter()
+        .filter(|s| s.success_rate > 0.0)
+        .filter_map(|s| s.eval.as_ref().map(|e| (s, e.accuracy())))
+        .collect();
+    
+    let mut frontier: Vec<&ModelSummary> = scored
+        .iter()
+        .filter(|(candidate, accuracy)| {
+            !scored.iter().any(|(other, other_accuracy)| {
+                other.avg_tokens_per_second >= candidate.avg_tokens_per_second
+                    && other_accuracy >= accuracy
+                    && (other.avg_tokens_per_second > candidate.avg_tokens_per_second
+                        || other_accuracy > accuracy)
+            })
+        })
+        .map(|(s, _)| *s)
+        .collect();
+    
+    frontier.sort_by(|a, b| {
+        b.avg_tokens_per_second
+            .partial_cmp(&a.avg_tokens_per_second)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    frontier
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::progress::QuietProgress;
+    use tokio_stream::StreamExt;
 
+    #[test]
+    fn test_ci_reached() {
+        let results: Vec<BenchmarkResult> = [20.0, 20.2, 19.8, 20.1]
+            .iter()
+            .map(|&speed| BenchmarkResult::ok("m", speed))
+            .collect();
+        let config = BenchmarkConfig { keep_warmup: true, ..BenchmarkConfig::default() };
+        assert!(ci_reached(&results, &config, 0.05));
+        assert!(!ci_reached(&results, &config, 0.001));
+        assert!(!ci_reached(&results[..2], &config, 0.05), "too few iterations to trust the spread");
+    }
+    
     #[test]
     fn test_calculate_winner() {
         let summaries = vec![
@@ -142,6 +1058,7 @@ mod tests {
                 min_tokens_per_second: 20.0,
                 max_tokens_per_second: 30.0,
                 avg_ttft_ms: 200.0,
+                ..Default::default()
             },
             ModelSummary {
                 model: "model2".to_string(),
@@ -151,6 +1068,7 @@ mod tests {
                 min_tokens_per_second: 25.0,
                 max_tokens_per_second: 35.0,
                 avg_ttft_ms: 150.0,
+                ..Default::default()
             },
         ];
         
@@ -169,6 +1087,7 @@ mod tests {
             min_tokens_per_second: 25.0,
             max_tokens_per_second: 35.0,
             avg_ttft_ms: 150.0,
+            ..Default::default()
         };
         
         let other = ModelSummary {
@@ -179,10 +1098,134 @@ mod tests {
             min_tokens_per_second: 20.0,
             max_tokens_per_second: 30.0,
             avg_ttft_ms: 200.0,
+            ..Default::default()
         };
         
         let (speed_diff, ttft_diff) = calculate_performance_difference(&winner, &other);
         assert_eq!(speed_diff, 20.0); // 30 is 20% faster than 25
         assert_eq!(ttft_diff, 25.0); // 150ms is 25% lower than 200ms
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_is_tokenizer_artifact() {
+        let summary = |speed: f64, chars_per_token: f64| ModelSummary {
+            success_rate: 1.0,
+            avg_tokens_per_second: speed,
+            chars_per_token: Some(chars_per_token),
+            ..Default::default()
+        };
+        
+        // 50% more tokens/s, but each token carries less text: same chars/s
+        assert!(is_tokenizer_artifact(&summary(30.0, 2.0), &summary(20.0, 3.0)));
+        // Similar tokenizers: the lead is real
+        assert!(!is_tokenizer_artifact(&summary(30.0, 3.0), &summary(20.0, 3.1)));
+        // Different tokenizers, but the lead survives in chars/s
+        assert!(!is_tokenizer_artifact(&summary(30.0, 2.5), &summary(10.0, 3.0)));
+    }
+    
+    #[tokio::test]
+    async fn test_cancelled_run_returns_no_results() {
+        let client = OllamaClient::new(
+            "http://127.0.0.1:9".to_string(),
+            Duration::from_secs(1)
+        );
+        let mut benchmarker = Benchmarker::new(client, BenchmarkConfig::default(), Box::new(QuietProgress));
+        
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        
+        let config = BenchmarkConfig::default();
+        let cell = &plan::plan(&["test-model".to_string()], &config, config.sweep_order)[0];
+        let results = benchmarker.benchmark_single_model(cell, &config, 0, 1, &cancel).await.unwrap();
+        assert!(results.is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_interleaved_announces_every_cell() {
+        let client = OllamaClient::new("http://127.0.0.1:9".to_string(), Duration::from_secs(1));
+        let config = BenchmarkConfig::default();
+        let mut benchmarker = Benchmarker::new(client, config.clone(), Box::new(QuietProgress));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        benchmarker.events = Some(tx);
+        
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let cells = plan::plan(&["a".to_string(), "b".to_string()], &config, config.sweep_order);
+        benchmarker.benchmark_interleaved(&cells, &cancel).await.unwrap();
+        
+        let mut started = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let BenchmarkEvent::ModelStarted { model, variant, index, total } = event {
+                started.push((model, variant, index, total));
+            }
+        }
+        assert_eq!(started, [("a".to_string(), None, 1, 2), ("b".to_string(), None, 2, 2)]);
+    }
+    
+    #[tokio::test]
+    async fn test_run_stream_reports_failure() {
+        let client = OllamaClient::new(
+            "http://127.0.0.1:9".to_string(),
+            Duration::from_secs(1)
+        );
+        let benchmarker = Benchmarker::new(client, BenchmarkConfig::default(), Box::new(QuietProgress));
+        
+        let events: Vec<BenchmarkEvent> = benchmarker
+            .run_stream(vec!["test-model".to_string()], CancellationToken::new())
+            .collect()
+            .await;
+        
+        assert!(matches!(events.last(), Some(BenchmarkEvent::RunFailed(_))));
+    }
+    
+    #[test]
+    fn test_efficient_frontier() {
+        let summary = |model: &str, speed: f64, correct: u32| ModelSummary {
+            model: model.to_string(),
+            success_rate: 1.0,
+            avg_tokens_per_second: speed,
+            eval: Some(EvalScore { task: "arithmetic".to_string(), correct, total: 10 }),
+            ..Default::default()
+        };
+        let summaries = vec![
+            summary("fast-wrong", 50.0, 3),
+            summary("slow-right", 10.0, 9),
+            summary("dominated", 9.0, 8),
+            summary("balanced", 30.0, 7),
+        ];
+        
+        let frontier: Vec<&str> = efficient_frontier(&summaries).iter().map(|s| s.model.as_str()).collect();
+        assert_eq!(frontier, vec!["fast-wrong", "balanced", "slow-right"]);
+    }
+    
+    #[tokio::test]
+    async fn test_attempt_keep_going() {
+        let vanished = || async { Err(crate::error::BenchmarkError::ModelNotFound("m".to_string())) };
+        
+        let result = attempt(true, "m", "p", vanished()).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("m"));
+        assert!(attempt(false, "m", "p", vanished()).await.is_err());
+    }
+    
+    #[test]
+    fn test_merge_batch() {
+        let part = |completion_tokens: u32| BenchmarkResult {
+            total_duration_ms: 900,
+            prompt_tokens: 10,
+            completion_tokens,
+            completions: Some(1),
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        
+        let merged = merge_batch(vec![part(40), part(60)], 1000);
+        assert!(merged.success);
+        assert_eq!((merged.prompt_tokens, merged.completion_tokens, merged.completions), (20, 100, Some(2)));
+        assert_eq!(merged.tokens_per_second, 100.0);
+        
+        let failed = BenchmarkResult::failed("m", "p", chrono::Utc::now(), 500, "timeout".to_string());
+        let merged = merge_batch(vec![part(40), failed], 1000);
+        assert!(!merged.success);
+        assert_eq!(merged.total_duration_ms, 1000);
+    }
+}
@@ -0,0 +1,145 @@
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::CANCEL_SAMPLES;
+use crate::effect::Distribution;
+use crate::net::{probe_client, ProbeTarget};
+use crate::types::{BenchmarkConfig, ModelSummary};
+
+/// Prompt of the abandoned stream; long enough that the model is still
+/// generating when it is cancelled.
+const LONG_ANSWER_PROMPT: &str = "Write a detailed, multi-section essay on the history of computing, \
+    from mechanical calculators to modern processors.";
+
+/// Prompt of the follow-up request whose first token is timed.
+const FOLLOW_UP_PROMPT: &str = "Name a prime number.";
+
+/// How quickly one model's server is ready for the next request after a
+/// stream is abandoned.
+#[derive(Debug, Clone, Serialize)]
+pub struct CancelStats {
+    pub model: String,
+    /// Server of a multi-server run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Tokens received before each stream was cancelled
+    pub cancel_after_tokens: u32,
+    /// Streams cancelled; ones that finished before `cancel_after_tokens`
+    /// are not counted
+    pub cancelled: u64,
+    /// Follow-up TTFT on an idle server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline_ttft_ms: Option<Distribution>,
+    /// Follow-up TTFT sent right after a cancellation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_cancel_ttft_ms: Option<Distribution>,
+    /// Median extra wait caused by the cancelled stream
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cost_ms: Option<f64>,
+}
+
+/// `--cancel-after`: per model and server, times a one-token streamed
+/// follow-up on an idle server, then again right after abandoning a stream
+/// at `tokens` tokens. Runs after the benchmark, so models are loaded.
+/// Stops after `cancel` with the models it finished.
+pub async fn measure(
+    targets: &[ProbeTarget],
+    config: &BenchmarkConfig,
+    summaries: &[ModelSummary],
+    tokens: u32,
+    cancel: &CancellationToken,
+) -> Vec<CancelStats> {
+    let follow_up = BenchmarkConfig {
+        max_tokens: 1,
+        stream: true,
+        history_messages: 0,
+        ..config.clone()
+    };
+    // Room to keep generating well past the cancellation point
+    let abandoned = BenchmarkConfig {
+        max_tokens: config.max_tokens.max(tokens as i32 * 4),
+        history_messages: 0,
+        ..config.clone()
+    };
+
+    let mut stats = Vec::new();
+    for summary in summaries.iter().filter(|s| s.variant.is_none() && s.success_rate > 0.0) {
+        let Some(client) = probe_client(targets, summary.server.as_deref()) else {
+            continue;
+        };
+        let model = &summary.model;
+        let (mut baseline, mut after_cancel) = (Vec::new(), Vec::new());
+        let mut cancelled = 0;
+        for sample in 0..CANCEL_SAMPLES {
+            // Numbered prompts keep the prompt cache from favouring either side
+            let follow_up_prompt = |side: &str| format!("[{}{}] {}", side, sample, FOLLOW_UP_PROMPT);
+            let (before, after) = (follow_up_prompt("a"), follow_up_prompt("b"));
+            let response = tokio::select! {
+                response = client.generate(model, &before, &follow_up) => response,
+                _ = cancel.cancelled() => return stats,
+            };
+            if let Ok(result) = response {
+                if result.success {
+                    baseline.push(result.time_to_first_token_ms as f64);
+                }
+            }
+
+            let prompt = format!("[{}] {}", sample, LONG_ANSWER_PROMPT);
+            let received = tokio::select! {
+                received = client.stream_and_cancel(model, &prompt, &abandoned, tokens) => received,
+                _ = cancel.cancelled() => return stats,
+            };
+            match received {
+                Ok(received) if received >= tokens => cancelled += 1,
+                _ => continue,
+            }
+            let response = tokio::select! {
+                response = client.generate(model, &after, &follow_up) => response,
+                _ = cancel.cancelled() => return stats,
+            };
+            if let Ok(result) = response {
+                if result.success {
+                    after_cancel.push(result.time_to_first_token_ms as f64);
+                }
+            }
+        }
+        let mut entry = build_stats(model, tokens, cancelled, &baseline, &after_cancel);
+        entry.server = summary.server.clone();
+        stats.push(entry);
+    }
+    stats
+}
+
+fn build_stats(model: &str, tokens: u32, cancelled: u64, baseline: &[f64], after_cancel: &[f64]) -> CancelStats {
+    let baseline_ttft_ms = Distribution::of(baseline);
+    let after_cancel_ttft_ms = Distribution::of(after_cancel);
+    let cost_ms = match (&baseline_ttft_ms, &after_cancel_ttft_ms) {
+        (Some(baseline), Some(after)) => Some(after.median - baseline.median),
+        _ => None,
+    };
+    CancelStats {
+        model: model.to_string(),
+        server: None,
+        cancel_after_tokens: tokens,
+        cancelled,
+        baseline_ttft_ms,
+        after_cancel_ttft_ms,
+        cost_ms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_stats() {
+        let stats = build_stats("m", 20, 3, &[40.0, 50.0, 45.0], &[140.0, 160.0, 150.0]);
+        assert_eq!(stats.cancelled, 3);
+        assert_eq!(stats.cost_ms, Some(105.0));
+
+        let stats = build_stats("m", 20, 0, &[40.0], &[]);
+        assert!(stats.after_cancel_ttft_ms.is_none());
+        assert_eq!(stats.cost_ms, None);
+    }
+}
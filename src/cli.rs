@@ -1,5 +1,22 @@
-use clap::{Parser, ValueEnum};
+use std::ffi::OsString;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, Parser, Subcommand, ValueEnum};
 use crate::config::*;
+use crate::context::parse_context_size;
+use crate::eval::EvalTask;
+use crate::export::format_extension;
+use crate::filter::{parse_filter, Filter, SummaryFilter};
+use crate::i18n::Lang;
+use crate::inventory::{self, SizeClass};
+use crate::plan::{parse_duration, parse_option_sweep, OptionSweep, SweepOrder};
+use crate::outliers::OutlierRule;
+use crate::pattern::{parse_pattern, LoadPattern};
+use crate::preset::Preset;
+use crate::redact::RedactField;
+use crate::rundb::parse_since;
+use crate::types::{Endpoint, SpeedUnit};
 
 #[derive(Parser)]
 #[command(name = APP_NAME)]
@@ -31,15 +48,79 @@ EXAMPLES:
     {bin} --prompt \"Explain quantum computing\" llama2:7b
 "
 )]
+#[command(subcommand_negates_reqs = true)]
 pub struct Cli {
-    /// Models to benchmark (e.g., llama2:7b mistral:7b)
-    #[arg(required = true, value_name = "MODEL")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    
+    /// Models to benchmark (e.g., llama2:7b mistral:7b); `*` patterns like llama3* or *:7b match installed models
+    #[arg(required_unless_present_any = ["verify", "self_check_format", "size_class", "all"], value_name = "MODEL")]
     pub models: Vec<String>,
     
+    /// The `*` MODEL arguments, kept once the runner has replaced them with the models they match
+    #[arg(skip)]
+    pub model_patterns: Vec<String>,
+    
+    /// Benchmark every installed model of this parameter count instead of naming them (7b takes 5-10B, 13b 10-20B, 70b 60-90B)
+    #[arg(long, value_name = "CLASS", conflicts_with = "models")]
+    pub size_class: Option<SizeClass>,
+    
+    /// Benchmark every model installed on the server
+    #[arg(long, conflicts_with_all = ["models", "size_class"])]
+    pub all: bool,
+    
+    /// Leave out installed models matching PATTERN with --all, --size-class or MODEL patterns (`*` matches anything; no tag matches every tag)
+    #[arg(long, value_name = "PATTERN")]
+    pub exclude: Vec<String>,
+    
+    /// Download models missing on the server (like ollama pull) instead of skipping them
+    #[arg(long)]
+    pub pull_missing: bool,
+    
+    /// Record request errors (including a model vanishing mid-run) as failed iterations and carry on with the other models; a missing model no longer fails the run
+    #[arg(long)]
+    pub keep_going: bool,
+    
+    /// Retry a request up to N times when it fails transiently (timeout, connection reset, HTTP 5xx)
+    #[arg(long, default_value_t = 0, value_name = "N")]
+    pub retries: u32,
+    
+    /// Wait before the first retry, doubling for each retry after
+    #[arg(long, default_value_t = DEFAULT_RETRY_BACKOFF_MS, value_name = "MS")]
+    pub retry_backoff_ms: u64,
+    
     /// Number of test iterations per model
     #[arg(short = 'n', long, default_value_t = DEFAULT_ITERATIONS, value_name = "COUNT")]
     pub iterations: u32,
     
+    /// Instead of a fixed count, iterate each model until the 95% confidence interval of tok/s is within ±PCT percent of the mean
+    #[arg(long, value_name = "PCT", conflicts_with_all = ["interleave", "ab_hosts", "pattern", "max_total_time"])]
+    pub until_ci: Option<f64>,
+    
+    /// Give up on --until-ci after N iterations of a model
+    #[arg(long, default_value_t = DEFAULT_CI_MAX_ITERATIONS, value_name = "N", requires = "until_ci")]
+    pub max_iterations: u32,
+    
+    /// Keep slow leading iterations in the statistics instead of excluding them as warm-up
+    #[arg(long)]
+    pub keep_warmup: bool,
+    
+    /// Treat the first N iterations of each model as warm-up instead of detecting it
+    #[arg(long, value_name = "N", conflicts_with = "keep_warmup")]
+    pub warmup: Option<u32>,
+    
+    /// Leave iterations with outlying speed or TTFT out of the statistics: iqr (beyond 1.5 IQR, the default) or zscore (a median/MAD-based z-score beyond 3.5)
+    #[arg(long, value_name = "RULE", num_args = 0..=1, require_equals = true, default_missing_value = "iqr", conflicts_with_all = ["warm_pool", "idle_probe"])]
+    pub trim_outliers: Option<OutlierRule>,
+    
+    /// Requests to keep in flight at once per model (like ab -c)
+    #[arg(short = 'c', long, default_value_t = 1, value_name = "N", conflicts_with_all = ["warm_pool", "idle_probe"])]
+    pub concurrency: u32,
+    
+    /// Vary the load over time on top of --concurrency: burst:10x5s, ramp:1-8/30s or sine:1-8/60s
+    #[arg(long, value_name = "PATTERN", value_parser = parse_pattern, conflicts_with_all = ["warm_pool", "idle_probe", "interleave", "hedged", "batch_n"])]
+    pub pattern: Option<LoadPattern>,
+    
     /// Output format
     #[arg(short, long, default_value = "table", value_name = "FORMAT")]
     pub output: OutputFormat,
@@ -48,26 +129,250 @@ pub struct Cli {
     #[arg(short, long, value_name = "TEXT")]
     pub prompt: Option<String>,
     
+    /// Workload shape replacing the default prompt and output length (summarize: ~8k tokens in, 128 out)
+    #[arg(long, value_name = "PRESET", conflicts_with_all = ["prompt", "max_tokens"])]
+    pub preset: Option<Preset>,
+    
+    /// Cycle iterations through the prompts in a file: one per line ('# ' starts a comment), or .jsonl with "prompt" and optional "category"
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["prompt", "preset", "model_prompt"])]
+    pub prompts_file: Option<String>,
+    
+    /// Attach the PNG, JPEG or WebP images in DIR to the prompt, one per iteration in turn after the warm-up (vision models; one warm-up request unless --warmup or --keep-warmup)
+    #[arg(long, value_name = "DIR", conflicts_with = "batch_n")]
+    pub image_dir: Option<String>,
+    
+    /// Use N images from --image-dir, spread evenly over the set; raises -n so each is sent once
+    #[arg(long, value_name = "N", requires = "image_dir", value_parser = clap::value_parser!(u32).range(1..))]
+    pub samples: Option<u32>,
+    
+    /// Break speed and TTFT down by the --prompts-file category of each prompt
+    #[arg(long, requires = "prompts_file")]
+    pub by_category: bool,
+    
+    /// Prompt for one model instead of --prompt (e.g. 'codellama=Write a quicksort in Rust'); repeatable
+    #[arg(long, value_name = "MODEL=TEXT")]
+    pub model_prompt: Vec<String>,
+    
+    /// System prompt sent with every request
+    #[arg(long, value_name = "TEXT", conflicts_with = "raw")]
+    pub system: Option<String>,
+    
     /// Maximum tokens to generate
     #[arg(short = 'm', long, default_value_t = DEFAULT_MAX_TOKENS, value_name = "COUNT")]
     pub max_tokens: i32,
     
+    /// After benchmarking, time /api/version and a one-token request per model to show how much of TTFT is not the model
+    #[arg(long)]
+    pub overhead_check: bool,
+    
+    /// After benchmarking, abandon streams after TOKENS tokens and time how soon a follow-up request gets its first token
+    #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub cancel_after: Option<u32>,
+    
+    /// After benchmarking, start N requests per model --stagger-ms apart and report how queue position affects TTFT
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..))]
+    pub queue_depth: Option<u32>,
+    
+    /// Gap between arrivals in --queue-depth
+    #[arg(long, default_value_t = DEFAULT_STAGGER_MS, value_name = "MS", requires = "queue_depth")]
+    pub stagger_ms: u64,
+    
+    /// Context window (num_ctx) for every request, overriding the Modelfile
+    #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u32).range(1..))]
+    pub num_ctx: Option<u32>,
+    
+    /// Fail instead of warning when the prompt plus --max-tokens may not fit a model's context window
+    #[arg(long)]
+    pub strict_context: bool,
+    
     /// Temperature for generation
     #[arg(short = 't', long, default_value_t = DEFAULT_TEMPERATURE, value_name = "FLOAT")]
     pub temperature: f32,
     
+    /// Random seed for reproducible generations
+    #[arg(long, value_name = "INT")]
+    pub seed: Option<i64>,
+    
+    /// Nucleus sampling: sample from the smallest token set whose probability reaches P
+    #[arg(long, value_name = "P")]
+    pub top_p: Option<f64>,
+    
+    /// Sample from the K most likely tokens only
+    #[arg(long, value_name = "K", value_parser = clap::value_parser!(u32).range(1..))]
+    pub top_k: Option<u32>,
+    
+    /// Penalty for repeating recent tokens (1.0 disables it)
+    #[arg(long, value_name = "FLOAT")]
+    pub repeat_penalty: Option<f64>,
+    
+    /// Request token logprobs and report average logprob as a rough quality score
+    #[arg(long)]
+    pub logprobs: bool,
+    
+    /// Stream responses and measure TTFT from the first token's arrival instead of prompt-eval time
+    #[arg(long)]
+    pub stream: bool,
+    
+    /// Also score exact-match accuracy on a built-in eval task
+    #[arg(long, value_name = "TASK")]
+    pub eval: Option<EvalTask>,
+    
+    /// Recommend the fastest model that meets --min-accuracy and --max-ttft
+    #[arg(long, conflicts_with = "warm_pool")]
+    pub select: bool,
+    
+    /// Minimum eval accuracy (0-1) for --select
+    #[arg(long, value_name = "FRACTION", requires_all = ["select", "eval"])]
+    pub min_accuracy: Option<f64>,
+    
+    /// Maximum average time to first token for --select (e.g. 400ms)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, requires = "select")]
+    pub max_ttft: Option<Duration>,
+    
+    /// Write the --select result to a file (.json, otherwise KEY='value' lines to source in a shell)
+    #[arg(long, value_name = "PATH", requires = "select")]
+    pub select_output: Option<String>,
+    
+    /// Expect models on this GPU index and verify placement after each model
+    #[arg(long, value_name = "INDEX")]
+    pub gpu_filter: Option<u32>,
+    
+    /// CPU threads for inference (Ollama num_thread option)
+    #[arg(long, value_name = "COUNT")]
+    pub num_thread: Option<u32>,
+    
+    /// Sweep num_thread values and report tok/s per thread count (e.g. 4,8,16)
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    pub sweep_threads: Vec<u32>,
+    
+    /// Sweep any Ollama option and report per-value results (e.g. 'temperature=0,0.7,1.2')
+    #[arg(long, value_name = "NAME=VALUES", value_parser = parse_option_sweep, conflicts_with = "warm_pool")]
+    pub sweep_option: Option<OptionSweep>,
+    
+    /// Order of sweep cells: grouped (fewest model loads) or interleaved (spreads drift)
+    #[arg(long, default_value = "grouped", value_name = "ORDER")]
+    pub sweep_order: SweepOrder,
+    
+    /// Alternate models every iteration (A,B,A,B...) so thermal throttling and background load hit all of them alike
+    #[arg(long, conflicts_with_all = ["concurrency", "warm_pool", "idle_probe", "max_total_time"])]
+    pub interleave: bool,
+    
+    /// Sweep the length of a synthetic chat history (messages before the prompt) and report latency growth (e.g. 0,5,20,50)
+    #[arg(long, value_name = "LIST", value_delimiter = ',', conflicts_with = "warm_pool")]
+    pub sweep_history: Vec<u32>,
+    
+    /// Pad prompts to each input size in tokens (e.g. 1k,4k,8k,16k) and report prefill and decode speed per size
+    #[arg(long, value_name = "SIZES", value_delimiter = ',', value_parser = parse_context_size, conflicts_with_all = ["prompt", "preset", "prompts_file", "model_prompt", "warm_pool"])]
+    pub context_sweep: Vec<u32>,
+    
+    /// Finish within this wall-clock budget (e.g. 45m, 2h) by running fewer iterations per cell
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration, conflicts_with = "warm_pool")]
+    pub max_total_time: Option<Duration>,
+    
+    /// Compare against a previous JSON export and include per-metric deltas
+    #[arg(long, value_name = "PATH")]
+    pub baseline: Option<String>,
+    
+    /// Exit non-zero if any model's avg tokens/s dropped more than PCT percent below --baseline, or a baseline model has no result
+    #[arg(long, value_name = "PCT", requires = "baseline")]
+    pub fail_on_regression: Option<f64>,
+    
+    /// Compare speeds with the --reference-file results for this hardware (e.g. --compare-reference="M2 Pro"); detected when no value is given and the server is local
+    #[arg(long, value_name = "HARDWARE", num_args = 0..=1, require_equals = true, default_missing_value = "auto", requires = "reference_file")]
+    pub compare_reference: Option<String>,
+    
+    /// Reference results to compare with: JSON with a `source` naming where the numbers come from, and `results` of {model, hardware, tokens_per_second}
+    #[arg(long, value_name = "PATH", requires = "compare_reference")]
+    pub reference_file: Option<String>,
+    
+    /// Keep all models loaded and alternate requests among them to measure swap penalties
+    #[arg(long, conflicts_with = "sweep_threads")]
+    pub warm_pool: bool,
+    
+    /// Measure latency after each idle period (e.g. 30s,2m,6m) to find where keep-alive expires
+    #[arg(long, value_name = "LIST", value_delimiter = ',', value_parser = parse_duration, conflicts_with_all = ["warm_pool", "sweep_threads", "max_total_time"])]
+    pub idle_probe: Vec<Duration>,
+    
+    /// Ollama API to benchmark: generate (prompt), chat (messages through the chat template) or embed (embedding models)
+    #[arg(long, default_value = "generate", value_name = "API", conflicts_with = "compare_endpoints")]
+    pub endpoint: Endpoint,
+    
+    /// Also run every iteration as COPIES identical requests, keep the first to finish, and report the tail-latency difference
+    #[arg(long, value_name = "COPIES", conflicts_with_all = ["concurrency", "warm_pool", "idle_probe"])]
+    pub hedged: Option<u32>,
+    
+    /// Also request N completions per iteration through /v1/chat/completions, as N separate requests and as one request with n=N, and compare throughput
+    #[arg(long, value_name = "N", conflicts_with_all = ["concurrency", "warm_pool", "idle_probe", "hedged", "compare_endpoints", "sweep_history"])]
+    pub batch_n: Option<u32>,
+    
+    /// Sample GPU utilization/VRAM (nvidia-smi, rocm-smi or powermetrics) and CPU/RAM during each iteration; skipped when --ollama-url is not on this machine
+    #[arg(long, conflicts_with_all = ["concurrency", "warm_pool", "idle_probe"])]
+    pub telemetry: bool,
+    
+    /// Run the prompt through both /api/generate and /api/chat and report the overhead difference
+    #[arg(long, conflicts_with = "warm_pool")]
+    pub compare_endpoints: bool,
+    
+    /// Send the prompt verbatim (Ollama raw mode), bypassing the model's prompt template
+    #[arg(long, conflicts_with = "compare_endpoints")]
+    pub raw: bool,
+    
+    /// Run the prompt with and without the model's template and report the template's cost
+    #[arg(long, conflicts_with_all = ["raw", "compare_endpoints", "warm_pool"])]
+    pub compare_template: bool,
+    
+    /// Declare a server setting to record in the report (e.g. OLLAMA_NUM_PARALLEL=4); repeatable
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub server_env: Vec<String>,
+    
+    /// Record first-run responses in DIR and report output drift on later runs
+    #[arg(long, value_name = "DIR")]
+    pub golden: Option<String>,
+    
     /// Request timeout in seconds
     #[arg(long, default_value_t = DEFAULT_TIMEOUT_SECONDS, value_name = "SECONDS")]
     pub timeout: u64,
     
-    /// Ollama API base URL
-    #[arg(long, default_value = DEFAULT_OLLAMA_BASE_URL, value_name = "URL")]
-    pub ollama_url: String,
+    /// Ollama API base URL; repeat or comma-separate to run the same models on several servers
+    #[arg(long, default_value = DEFAULT_OLLAMA_BASE_URL, value_name = "URL", value_delimiter = ',')]
+    pub ollama_url: Vec<String>,
+    
+    /// Alternate identical requests between two servers and compare them pair by pair
+    #[arg(long, num_args = 2, value_names = ["URL_A", "URL_B"], conflicts_with_all = ["ollama_url", "concurrency", "warm_pool", "idle_probe", "interleave", "pattern"])]
+    pub ab_hosts: Vec<String>,
+    
+    /// JSON list of servers with per-host API keys, headers and TLS settings, instead of --ollama-url
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["ollama_url", "ab_hosts"])]
+    pub hosts_file: Option<String>,
+    
+    /// User-Agent for requests (default: ollama-bench/<version>), for gateways that fingerprint clients
+    #[arg(long, value_name = "STRING")]
+    pub user_agent: Option<String>,
+    
+    /// Send an X-Bench-Client header with this value, to find benchmark traffic in proxy logs
+    #[arg(long, value_name = "ID")]
+    pub bench_client: Option<String>,
+    
+    /// Connect over IPv4 first when the Ollama host name has both IPv4 and IPv6 addresses
+    #[arg(long, conflicts_with = "prefer_ipv6")]
+    pub prefer_ipv4: bool,
+    
+    /// Connect over IPv6 first when the Ollama host name has both IPv4 and IPv6 addresses
+    #[arg(long)]
+    pub prefer_ipv6: bool,
+    
+    /// Ollama or this tool runs in Docker/Podman: try container-to-host URLs and inspect containers on failure
+    #[arg(long)]
+    pub docker: bool,
     
     /// Quiet mode (no progress indicators)
     #[arg(short, long)]
     pub quiet: bool,
     
+    /// Progress display style (auto picks plain when stdout is not a terminal)
+    #[arg(long, default_value = "auto", value_name = "MODE")]
+    pub progress: ProgressMode,
+    
     /// Verbose output
     #[arg(short, long)]
     pub verbose: bool,
@@ -75,6 +380,241 @@ pub struct Cli {
     /// Export results to file
     #[arg(short = 'e', long, value_name = "PATH")]
     pub export: Option<String>,
+    
+    /// JSON layout: 1 is a bare array of summaries; 2 is a report object with run totals, server info and every extra section
+    #[arg(long, default_value = "1", value_name = "VERSION")]
+    pub json_schema: JsonSchema,
+    
+    /// Leave results with any failed iteration out of the output and export
+    #[arg(long)]
+    pub only_successful: bool,
+    
+    /// Leave results with fewer than N counted iterations out of the output and export
+    #[arg(long, value_name = "N")]
+    pub min_tests: Option<u64>,
+    
+    /// Keep only results matching FIELD OP VALUE (tps, ttft, success, tests; e.g. 'tps>20'); repeatable
+    #[arg(long, value_name = "EXPR", value_parser = parse_filter)]
+    pub filter: Vec<Filter>,
+    
+    /// Export every iteration with start/end timestamps (.json or .csv)
+    #[arg(long, value_name = "PATH")]
+    pub export_raw: Option<String>,
+    
+    /// Show when each request ran, to spot gaps and interference
+    #[arg(long)]
+    pub timeline: bool,
+    
+    /// Unit for reported speeds (chars or words compare better across tokenizers)
+    #[arg(long, default_value = "tokens", value_name = "UNIT")]
+    pub speed_unit: SpeedUnit,
+    
+    /// Add a SHA-256 integrity hash to JSON reports (a .sha256 file for other exports)
+    #[arg(long)]
+    pub sign: bool,
+    
+    /// Also sign exported files with this minisign secret key
+    #[arg(long, value_name = "KEY", requires = "sign")]
+    pub sign_key: Option<String>,
+    
+    /// Check that a signed JSON report has not been modified, then exit
+    #[arg(long, value_name = "PATH")]
+    pub verify: Option<String>,
+    
+    /// Render built-in fixture results in every output format, check that they agree, then exit
+    #[arg(long)]
+    pub self_check_format: bool,
+    
+    /// Language for human-readable output
+    #[arg(long, default_value = "en", value_name = "LANG")]
+    pub lang: Lang,
+    
+    /// Significant digits for numbers in every report (JSON, OpenMetrics and raw exports keep full precision)
+    #[arg(long, value_name = "DIGITS", value_parser = clap::value_parser!(u32).range(1..=15))]
+    pub precision: Option<u32>,
+    
+    /// Strip sensitive data from output and exports (prompts, hostname, paths)
+    #[arg(long, value_name = "LIST", value_delimiter = ',')]
+    pub redact: Vec<RedactField>,
+    
+    /// Results history database every run is appended to [default: ~/.ollama-bench/history.db]
+    #[arg(long, value_name = "PATH")]
+    pub history_db: Option<String>,
+    
+    /// Do not record this run in the results history database
+    #[arg(long)]
+    pub no_history: bool,
+    
+    /// Record this run under experiment NAME in the results history database, to pool with `history show --experiment NAME`
+    #[arg(long, value_name = "NAME", conflicts_with = "no_history")]
+    pub experiment: Option<String>,
+}
+
+/// Index of the first argument that is neither a flag nor a flag's value:
+/// where a subcommand or the first model sits.
+fn first_operand(args: &[OsString]) -> Option<usize> {
+    let mut command = Cli::command();
+    command.build();
+    let takes_value = |arg: Option<&clap::Arg>| {
+        arg.and_then(clap::Arg::get_num_args).is_some_and(|range| range.min_values() > 0)
+    };
+    
+    let mut index = 1;
+    while let Some(arg) = args.get(index) {
+        let Some(text) = arg.to_str() else {
+            return Some(index);
+        };
+        let value_follows = if let Some(long) = text.strip_prefix("--") {
+            !long.contains('=') && takes_value(command.get_arguments().find(|a| a.get_long() == Some(long)))
+        } else if let Some(short) = text.strip_prefix('-').filter(|s| !s.is_empty()) {
+            let mut chars = short.chars();
+            let flag = chars.next();
+            chars.next().is_none() && takes_value(command.get_arguments().find(|a| a.get_short() == flag))
+        } else {
+            return Some(index);
+        };
+        index += if value_follows { 2 } else { 1 };
+    }
+    None
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum Command {
+    /// Benchmark models; the default, so `run MODEL...` is the same as `MODEL...`
+    Run,
+    /// List the models installed on the server with their size, parameters and quantization
+    List {
+        /// Print the models as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Compare two saved runs (-o json or -e FILE.json), e.g. before and after an upgrade
+    Compare {
+        /// The earlier run
+        #[arg(value_name = "BASELINE")]
+        baseline: String,
+        
+        /// The run to compare with it
+        #[arg(value_name = "CURRENT")]
+        current: String,
+        
+        /// Exit with an error when a model's speed dropped more than PCT percent, or a baseline model is missing
+        #[arg(long, value_name = "PCT")]
+        fail_on_regression: Option<f64>,
+        
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Race two models: alternate their iterations (taking turns to go first) with the same prompts and seed, then compare them pair by pair; run flags may follow, as in `duel A B -n 20`
+    Duel {
+        #[arg(value_name = "MODEL_A")]
+        model_a: String,
+        
+        #[arg(value_name = "MODEL_B")]
+        model_b: String,
+    },
+    /// Check connectivity, server version, GPUs, models and disk space
+    Doctor {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Pool raw iterations from repeated runs (--export-raw *.json) and check they agree
+    Aggregate {
+        /// Raw JSON exports of identical runs, optionally .gz or .zst
+        #[arg(required = true, num_args = 2.., value_name = "FILE")]
+        files: Vec<String>,
+        
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Browse past runs recorded in the results history database
+    History {
+        #[command(subcommand)]
+        action: HistoryAction,
+    },
+    /// Manage persistent defaults, e.g. `config set default.output json`
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum HistoryAction {
+    /// List recorded runs, newest first
+    List {
+        /// Only runs that benchmarked this model, with its tok/s against the rolling p10–p90 band of earlier runs
+        #[arg(long, value_name = "MODEL")]
+        model: Option<String>,
+        
+        /// Only runs recorded with --experiment NAME
+        #[arg(long, value_name = "NAME")]
+        experiment: Option<String>,
+        
+        /// Only runs recorded on or after this date (e.g. 2026-10-01)
+        #[arg(long, value_name = "DATE", value_parser = parse_since)]
+        since: Option<DateTime<Utc>>,
+        
+        /// Maximum number of runs to list
+        #[arg(long, default_value_t = 20, value_name = "COUNT")]
+        limit: usize,
+        
+        /// Print the runs as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show one run's results; any unique prefix of the run id works
+    Show {
+        #[arg(value_name = "RUN_ID", required_unless_present = "experiment")]
+        run_id: Option<String>,
+        
+        /// Pool the iterations of every run recorded with --experiment NAME instead
+        #[arg(long, value_name = "NAME", conflicts_with = "run_id")]
+        experiment: Option<String>,
+        
+        /// Print the run and its iterations as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete old runs and compact the database
+    Vacuum {
+        /// Delete runs recorded more than DAYS days ago
+        #[arg(long, value_name = "DAYS")]
+        keep_days: Option<u32>,
+        
+        /// Delete all but the newest COUNT runs
+        #[arg(long, value_name = "COUNT")]
+        keep_runs: Option<usize>,
+    },
+}
+
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum ConfigAction {
+    /// Store a default (keys: default.url, default.output, default.iterations, default.export_dir)
+    Set { key: String, value: String },
+    /// Print a stored default
+    Get { key: String },
+    /// Remove a stored default
+    Unset { key: String },
+    /// Print every stored default
+    List,
+    /// Print the config file location
+    Path,
+}
+
+/// Shape of the JSON written by `-o json` and `-e FILE.json`.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum JsonSchema {
+    /// A bare array of model summaries, as in earlier releases
+    #[default]
+    #[value(name = "1")]
+    V1,
+    /// An object with `schema_version`, `models`, `totals` and every report section
+    #[value(name = "2")]
+    V2,
 }
 
 #[derive(ValueEnum, Clone, Debug, PartialEq)]
@@ -87,17 +627,60 @@ pub enum OutputFormat {
     Csv,
     /// Markdown table output
     Markdown,
+    /// Self-contained HTML report with charts
+    Html,
+}
+
+#[derive(ValueEnum, Clone, Debug, PartialEq)]
+pub enum ProgressMode {
+    /// Progress bar on a terminal, plain lines otherwise (default)
+    Auto,
+    /// Animated progress bar
+    Bar,
+    /// One line per iteration, no cursor movement (CI-friendly)
+    Plain,
 }
 
 impl Cli {
+    /// Conflicts that depend on where a value came from: `-n` on the
+    /// command line fights `--until-ci`, but an `OLLAMA_BENCH_ITERATIONS`
+    /// or preferences default is just ignored.
+    pub fn check_sources(&self, matches: &ArgMatches) -> Result<(), String> {
+        if self.until_ci.is_some() && matches.value_source("iterations") == Some(ValueSource::CommandLine) {
+            return Err("--until-ci decides the iteration count; drop -n (--max-iterations caps it)".to_string());
+        }
+        Ok(())
+    }
+    
     pub fn validate(&self) -> Result<(), String> {
         // Validate iterations
         if self.iterations == 0 {
             return Err("Iterations must be greater than 0".to_string());
         }
         
-        if self.iterations > 1000 {
-            return Err("Iterations must be 1000 or less".to_string());
+        if self.iterations > MAX_ITERATIONS {
+            return Err(format!("Iterations must be {} or less", MAX_ITERATIONS));
+        }
+        
+        let patterns = !self.model_patterns.is_empty() || self.models.iter().any(|model| inventory::is_pattern(model));
+        if !self.exclude.is_empty() && !self.all && self.size_class.is_none() && !patterns {
+            return Err("--exclude needs --all, --size-class or a `*` MODEL pattern; leave unwanted models off the command line instead".to_string());
+        }
+        
+        if self.until_ci.is_some_and(|pct| !(pct > 0.0 && pct < 100.0)) {
+            return Err("--until-ci must be a percentage between 0 and 100".to_string());
+        }
+        
+        if self.until_ci.is_some() && !(CI_MIN_ITERATIONS..=MAX_ITERATIONS).contains(&self.max_iterations) {
+            return Err(format!("--max-iterations must be between {} and {}", CI_MIN_ITERATIONS, MAX_ITERATIONS));
+        }
+        
+        if self.until_ci.is_some() && self.concurrency > 1 {
+            return Err("--until-ci decides after each iteration, so it needs --concurrency 1".to_string());
+        }
+        
+        if self.warmup.is_some_and(|n| n >= self.iteration_cap()) {
+            return Err("--warmup must leave at least one measured iteration".to_string());
         }
         
         // Validate temperature
@@ -105,6 +688,14 @@ impl Cli {
             return Err("Temperature must be between 0.0 and 2.0".to_string());
         }
         
+        if self.top_p.is_some_and(|p| p <= 0.0 || p > 1.0) {
+            return Err("--top-p must be greater than 0.0 and at most 1.0".to_string());
+        }
+        
+        if self.repeat_penalty.is_some_and(|penalty| penalty < 0.0) {
+            return Err("--repeat-penalty must not be negative".to_string());
+        }
+        
         // Validate max_tokens
         if self.max_tokens <= 0 {
             return Err("Max tokens must be greater than 0".to_string());
@@ -124,59 +715,246 @@ impl Cli {
             return Err("At least one model must be specified".to_string());
         }
         
+        // Chat requests always go through the template
+        if self.endpoint != Endpoint::Generate && (self.raw || self.compare_template) {
+            return Err("--raw and --compare-template need --endpoint generate".to_string());
+        }
+        
+        let generation_only = self.stream || self.system.is_some() || self.eval.is_some() || self.logprobs || self.golden.is_some();
+        let generation_only = generation_only || self.cancel_after.is_some() || self.image_dir.is_some();
+        if self.endpoint == Endpoint::Embed && generation_only {
+            return Err("--stream, --system, --eval, --logprobs, --golden, --cancel-after and --image-dir need a generating endpoint, not --endpoint embed".to_string());
+        }
+        
+        if !self.sweep_history.is_empty() && self.endpoint != Endpoint::Chat {
+            return Err("--sweep-history sends chat histories; add --endpoint chat".to_string());
+        }
+        
+        if self.concurrency == 0 {
+            return Err("Concurrency must be greater than 0".to_string());
+        }
+        
+        if self.hedged.is_some_and(|copies| !(2..=8).contains(&copies)) {
+            return Err("--hedged takes 2 to 8 copies".to_string());
+        }
+        
+        if self.batch_n.is_some_and(|n| !(2..=16).contains(&n)) {
+            return Err("--batch-n takes 2 to 16 completions".to_string());
+        }
+        
+        if self.batch_n.is_some() && self.endpoint == Endpoint::Embed {
+            return Err("--batch-n generates completions; it cannot be combined with --endpoint embed".to_string());
+        }
+        
+        if self.num_thread == Some(0) || self.sweep_threads.contains(&0) {
+            return Err("Thread counts must be greater than 0".to_string());
+        }
+        
+        if self.warm_pool && self.models.len() < 2 {
+            return Err("Warm-pool mode needs at least two models".to_string());
+        }
+        
+        let writes_json = self.output == OutputFormat::Json || self.export.as_deref().is_some_and(|path| format_extension(path) == Some("json"));
+        if self.sign && writes_json && self.json_schema == JsonSchema::V1 {
+            return Err("--sign seals the JSON report object; add --json-schema 2".to_string());
+        }
+        
+        if self.sign_key.is_some() && self.export.is_none() && self.export_raw.is_none() {
+            return Err("--sign-key signs exported files; add --export or --export-raw".to_string());
+        }
+        
+        if self.fail_on_regression.is_some_and(|pct| !(pct >= 0.0 && pct.is_finite())) {
+            return Err("--fail-on-regression must be a percentage of 0 or more".to_string());
+        }
+        
+        if self.min_accuracy.is_some_and(|min| !(0.0..=1.0).contains(&min)) {
+            return Err("--min-accuracy must be between 0 and 1".to_string());
+        }
+        
+        // Header values must be visible ASCII (or spaces/tabs)
+        let header_safe = |value: &String| value.bytes().all(|b| b == b'\t' || (b' '..=b'~').contains(&b));
+        if !self.user_agent.iter().chain(&self.bench_client).all(header_safe) {
+            return Err("--user-agent and --bench-client must be printable ASCII".to_string());
+        }
+        
+        if let Some(entry) = self.model_prompt.iter().find(|e| !e.contains('=')) {
+            return Err(format!("--model-prompt expects MODEL=TEXT, got '{}'", entry));
+        }
+        if let Some(model) = self.model_prompt_map().into_keys().find(|m| !self.models.contains(m)) {
+            return Err(format!("--model-prompt names {}, which is not being benchmarked", model));
+        }
+        
+        if let Some(entry) = self.server_env.iter().find(|e| !e.contains('=')) {
+            return Err(format!("--server-env expects KEY=VALUE, got '{}'", entry));
+        }
+        
         // Validate Ollama URL
-        if !self.ollama_url.starts_with("http://") && !self.ollama_url.starts_with("https://") {
+        if self.server_urls().iter().any(|url| !url.starts_with("http://") && !url.starts_with("https://")) {
             return Err("Ollama URL must start with http:// or https://".to_string());
         }
         
+        if self.ollama_url.len() > 1 && (self.warm_pool || !self.idle_probe.is_empty()) {
+            return Err("--warm-pool and --idle-probe measure one server; pass a single --ollama-url".to_string());
+        }
+        
         Ok(())
     }
     
+    /// The command line with an `OLLAMA_BENCH_*` environment variable behind
+    /// every flag; flags given on the command line win, and `--help` lists
+    /// each variable.
+    pub fn command_with_env() -> clap::Command {
+        Cli::command().mut_args(|arg| {
+            if arg.is_positional() {
+                return arg;
+            }
+            let name = format!("{}{}", ENV_PREFIX, arg.get_id().as_str().to_uppercase());
+            arg.env(name)
+        })
+    }
+    
+    /// Drops a `run` in the command position, after any flags: benchmarking
+    /// is the default command, so its flags stay on the top level and
+    /// `-n 3 run MODEL...` parses as `-n 3 MODEL...`. `duel A B` moves
+    /// behind the run flags that follow it, so `duel A B -n 20` parses as
+    /// `-n 20 duel A B`.
+    pub fn default_command(args: impl IntoIterator<Item = OsString>) -> Vec<OsString> {
+        let mut args: Vec<OsString> = args.into_iter().collect();
+        match first_operand(&args) {
+            Some(index) if args[index] == "run" => {
+                args.remove(index);
+            }
+            Some(index) if args[index] == "duel" => {
+                let duel: Vec<OsString> = args.drain(index..args.len().min(index + 3)).collect();
+                args.extend(duel);
+            }
+            _ => {}
+        }
+        args
+    }
+    
+    /// Turns `duel A B` into an interleaved run of the two models sharing one
+    /// seed, rejecting flags that would stop each round being a fair pair.
+    pub fn start_duel(&mut self, model_a: &str, model_b: &str) -> Result<(), String> {
+        if model_a == model_b {
+            return Err("duel needs two different models".to_string());
+        }
+        let unfair = [
+            (self.concurrency > 1, "--concurrency"),
+            (self.warm_pool, "--warm-pool"),
+            (!self.idle_probe.is_empty(), "--idle-probe"),
+            (self.max_total_time.is_some(), "--max-total-time"),
+            (!self.ab_hosts.is_empty(), "--ab-hosts"),
+            (self.pattern.is_some(), "--pattern"),
+            (self.until_ci.is_some(), "--until-ci"),
+            (!self.model_prompt.is_empty(), "--model-prompt"),
+        ];
+        if let Some((_, flag)) = unfair.iter().find(|(set, _)| *set) {
+            return Err(format!("duel alternates single requests with identical prompts; drop {}", flag));
+        }
+        self.models = vec![model_a.to_string(), model_b.to_string()];
+        self.interleave = true;
+        self.seed.get_or_insert(DUEL_SEED);
+        Ok(())
+    }
+    
+    /// Iterations per model: `-n`, or the `--until-ci` cap.
+    pub fn iteration_cap(&self) -> u32 {
+        if self.until_ci.is_some() {
+            self.max_iterations
+        } else {
+            self.iterations
+        }
+    }
+    
+    pub fn history_db_path(&self) -> Option<std::path::PathBuf> {
+        self.history_db.as_ref().map(Into::into).or_else(crate::rundb::default_path)
+    }
+    
+    pub fn is_config_command(&self) -> bool {
+        matches!(self.command, Some(Command::Config { .. }))
+    }
+    
+    /// The first of `server_urls`, for commands that talk to one server.
+    pub fn primary_url(&self) -> &str {
+        &self.server_urls()[0]
+    }
+    
+    /// The servers to benchmark: the `--ab-hosts` pair, else every `--ollama-url`.
+    pub fn server_urls(&self) -> &[String] {
+        if self.ab_hosts.is_empty() {
+            &self.ollama_url
+        } else {
+            &self.ab_hosts
+        }
+    }
+    
+    pub fn server_env_map(&self) -> std::collections::BTreeMap<String, String> {
+        self.server_env
+            .iter()
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+            .collect()
+    }
+    
+    /// Per-model prompts; the text after the first `=` is kept verbatim.
+    pub fn model_prompt_map(&self) -> std::collections::BTreeMap<String, String> {
+        self.model_prompt
+            .iter()
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(model, prompt)| (model.trim().to_string(), prompt.to_string()))
+            .collect()
+    }
+    
+    /// Ollama options for the sampling flags that were given.
+    pub fn sampling_options(&self) -> std::collections::BTreeMap<String, serde_json::Value> {
+        let mut options = std::collections::BTreeMap::new();
+        if let Some(top_p) = self.top_p {
+            options.insert("top_p".to_string(), serde_json::json!(top_p));
+        }
+        if let Some(top_k) = self.top_k {
+            options.insert("top_k".to_string(), serde_json::json!(top_k));
+        }
+        if let Some(penalty) = self.repeat_penalty {
+            options.insert("repeat_penalty".to_string(), serde_json::json!(penalty));
+        }
+        options
+    }
+    
+    pub fn summary_filter(&self) -> SummaryFilter {
+        SummaryFilter {
+            only_successful: self.only_successful,
+            min_tests: self.min_tests,
+            filters: self.filter.clone(),
+        }
+    }
+    
     pub fn get_prompt(&self) -> String {
         self.prompt.as_ref()
             .map(|s| s.to_string())
-            .unwrap_or_else(|| DEFAULT_PROMPT.to_string())
+            .unwrap_or_else(|| if self.image_dir.is_some() { VISION_PROMPT } else { DEFAULT_PROMPT }.to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use clap::FromArgMatches;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(std::iter::once(APP_NAME).chain(args.iter().copied())).unwrap()
+    }
 
     #[test]
     fn test_cli_validation_valid() {
-        let cli = Cli {
-            models: vec!["llama2:7b".to_string()],
-            iterations: 5,
-            output: OutputFormat::Table,
-            prompt: None,
-            max_tokens: 100,
-            temperature: 0.7,
-            timeout: 120,
-            ollama_url: "http://localhost:11434".to_string(),
-            quiet: false,
-            verbose: false,
-            export: None,
-        };
+        let cli = parse(&["llama2:7b"]);
         
         assert!(cli.validate().is_ok());
     }
     
     #[test]
     fn test_cli_validation_invalid_iterations() {
-        let mut cli = Cli {
-            models: vec!["llama2:7b".to_string()],
-            iterations: 0,
-            output: OutputFormat::Table,
-            prompt: None,
-            max_tokens: 100,
-            temperature: 0.7,
-            timeout: 120,
-            ollama_url: "http://localhost:11434".to_string(),
-            quiet: false,
-            verbose: false,
-            export: None,
-        };
+        let mut cli = parse(&["-n", "0", "llama2:7b"]);
         
         assert!(cli.validate().is_err());
         
@@ -186,19 +964,8 @@ mod tests {
     
     #[test]
     fn test_cli_validation_invalid_temperature() {
-        let mut cli = Cli {
-            models: vec!["llama2:7b".to_string()],
-            iterations: 5,
-            output: OutputFormat::Table,
-            prompt: None,
-            max_tokens: 100,
-            temperature: -0.1,
-            timeout: 120,
-            ollama_url: "http://localhost:11434".to_string(),
-            quiet: false,
-            verbose: false,
-            export: None,
-        };
+        let mut cli = parse(&["llama2:7b"]);
+        cli.temperature = -0.1;
         
         assert!(cli.validate().is_err());
         
@@ -208,23 +975,535 @@ mod tests {
     
     #[test]
     fn test_get_prompt() {
-        let mut cli = Cli {
-            models: vec!["llama2:7b".to_string()],
-            iterations: 5,
-            output: OutputFormat::Table,
-            prompt: None,
-            max_tokens: 100,
-            temperature: 0.7,
-            timeout: 120,
-            ollama_url: "http://localhost:11434".to_string(),
-            quiet: false,
-            verbose: false,
-            export: None,
-        };
+        let mut cli = parse(&["llama2:7b"]);
         
         assert_eq!(cli.get_prompt(), DEFAULT_PROMPT);
         
         cli.prompt = Some("Custom prompt".to_string());
         assert_eq!(cli.get_prompt(), "Custom prompt");
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_sweep_threads() {
+        let cli = parse(&["--sweep-threads", "4,8,16", "llama2:7b"]);
+        assert_eq!(cli.sweep_threads, vec![4, 8, 16]);
+        assert!(cli.validate().is_ok());
+        
+        let cli = parse(&["--sweep-threads", "0,8", "llama2:7b"]);
+        assert!(cli.validate().is_err());
+    }
+    
+    #[test]
+    fn test_redact_list() {
+        let cli = parse(&["--redact", "prompts,hostname", "llama2:7b"]);
+        assert_eq!(cli.redact, vec![RedactField::Prompts, RedactField::Hostname]);
+        assert!(Cli::try_parse_from([APP_NAME, "--redact", "everything", "llama2:7b"]).is_err());
+    }
+    
+    #[test]
+    fn test_sign_options() {
+        assert!(Cli::try_parse_from([APP_NAME, "--verify", "results.json"]).is_ok());
+        assert!(Cli::try_parse_from([APP_NAME, "--self-check-format"]).is_ok());
+        assert!(Cli::try_parse_from([APP_NAME, "--sign-key", "k.key", "-e", "r.json", "llama2:7b"]).is_err());
+        
+        let cli = parse(&["--sign", "--sign-key", "k.key", "llama2:7b"]);
+        assert!(cli.validate().is_err());
+        
+        // Only the schema 2 object has room for the hash
+        assert!(parse(&["--sign", "-e", "r.json", "llama2:7b"]).validate().is_err());
+        assert!(parse(&["--sign", "--json-schema", "2", "-e", "r.json", "llama2:7b"]).validate().is_ok());
+        assert!(parse(&["--sign", "-e", "r.json.gz", "llama2:7b"]).validate().is_err());
+        // Only the extension picks the format
+        assert!(parse(&["--sign", "-e", "out.jsonl.csv", "llama2:7b"]).validate().is_ok());
+        assert!(parse(&["--sign", "-e", "runs.json/summary.md", "llama2:7b"]).validate().is_ok());
+    }
+    
+    #[test]
+    fn test_max_total_time() {
+        let cli = parse(&["--max-total-time", "1h30m", "llama2:7b"]);
+        assert_eq!(cli.max_total_time, Some(Duration::from_secs(5400)));
+        assert!(Cli::try_parse_from([APP_NAME, "--max-total-time", "soon", "llama2:7b"]).is_err());
+    }
+    
+    #[test]
+    fn test_compare_endpoints() {
+        assert!(parse(&["--compare-endpoints", "llama2:7b"]).compare_endpoints);
+        assert!(parse(&["--stream", "llama2:7b"]).stream);
+        assert_eq!(parse(&["-c", "4", "llama2:7b"]).concurrency, 4);
+        assert!(Cli::try_parse_from([APP_NAME, "-c", "4", "--warm-pool", "a", "b"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--compare-endpoints", "--warm-pool", "a", "b"]).is_err());
+    }
+    
+    #[test]
+    fn test_endpoint_chat() {
+        let cli = parse(&["--endpoint", "chat", "--system", "Answer briefly.", "llama2:7b"]);
+        assert_eq!(cli.endpoint, Endpoint::Chat);
+        assert_eq!(cli.system.as_deref(), Some("Answer briefly."));
+        assert!(cli.validate().is_ok());
+        assert_eq!(parse(&["llama2:7b"]).endpoint, Endpoint::Generate);
+        
+        assert!(parse(&["--endpoint", "chat", "--compare-template", "llama2:7b"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--endpoint", "chat", "--compare-endpoints", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--system", "s", "--raw", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_endpoint_embed() {
+        let cli = parse(&["--endpoint", "embed", "nomic-embed-text"]);
+        assert_eq!(cli.endpoint, Endpoint::Embed);
+        assert!(cli.validate().is_ok());
+        assert!(parse(&["--endpoint", "embed", "--stream", "nomic-embed-text"]).validate().is_err());
+        assert!(parse(&["--endpoint", "embed", "--raw", "nomic-embed-text"]).validate().is_err());
+    }
+    
+    #[test]
+    fn test_fail_on_regression() {
+        let cli = parse(&["--baseline", "main.json", "--fail-on-regression", "5", "llama2:7b"]);
+        assert_eq!(cli.fail_on_regression, Some(5.0));
+        assert!(cli.validate().is_ok());
+        assert!(parse(&["--baseline", "main.json", "--fail-on-regression=-5", "llama2:7b"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--fail-on-regression", "5", "llama2:7b"]).is_err());
+    }
+    
+    #[test]
+    fn test_multiple_servers() {
+        let cli = parse(&["--ollama-url", "http://desktop:11434,http://mac-mini:11434", "llama2:7b"]);
+        assert_eq!(cli.ollama_url, vec!["http://desktop:11434", "http://mac-mini:11434"]);
+        assert!(cli.validate().is_ok());
+        
+        let repeated = parse(&["--ollama-url", "http://a:11434", "--ollama-url", "b:11434", "llama2:7b"]);
+        assert!(repeated.validate().is_err(), "every URL needs a scheme");
+        assert_eq!(parse(&["llama2:7b"]).primary_url(), "http://localhost:11434");
+    }
+    
+    #[test]
+    fn test_ab_hosts() {
+        let cli = parse(&["--ab-hosts", "http://a:11434", "http://b:11434", "llama2:7b"]);
+        assert_eq!(cli.server_urls(), ["http://a:11434", "http://b:11434"]);
+        assert_eq!(cli.primary_url(), "http://a:11434");
+        assert!(cli.validate().is_ok());
+        assert!(Cli::try_parse_from([APP_NAME, "--ab-hosts", "http://a:11434", "llama2:7b"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--ab-hosts", "http://a:11434", "http://b:11434", "-c", "4", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_sampling_options() {
+        let cli = parse(&["--top-p", "0.9", "--top-k", "40", "--repeat-penalty", "1.1", "--seed", "7", "a"]);
+        let options = cli.sampling_options();
+        assert_eq!(options.keys().collect::<Vec<_>>(), ["repeat_penalty", "top_k", "top_p"]);
+        assert_eq!(options["top_k"], 40);
+        assert!(cli.validate().is_ok());
+        assert!(parse(&["a"]).sampling_options().is_empty());
+        assert!(parse(&["--top-p", "1.5", "a"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--top-k", "0", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_queue_depth() {
+        let cli = parse(&["--queue-depth", "4", "a"]);
+        assert_eq!((cli.queue_depth, cli.stagger_ms), (Some(4), DEFAULT_STAGGER_MS));
+        assert_eq!(parse(&["--queue-depth", "4", "--stagger-ms", "250", "a"]).stagger_ms, 250);
+        assert!(Cli::try_parse_from([APP_NAME, "--queue-depth", "1", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--stagger-ms", "250", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_num_ctx() {
+        assert_eq!(parse(&["--num-ctx", "8192", "a"]).num_ctx, Some(8192));
+        assert!(Cli::try_parse_from([APP_NAME, "--num-ctx", "0", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_hosts_file() {
+        assert_eq!(parse(&["--hosts-file", "hosts.json", "a"]).hosts_file.as_deref(), Some("hosts.json"));
+        assert!(Cli::try_parse_from([APP_NAME, "--hosts-file", "hosts.json", "--ollama-url", "http://a:11434", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_image_dir() {
+        let cli = parse(&["--image-dir", "photos", "--samples", "20", "llava:7b"]);
+        assert_eq!(cli.samples, Some(20));
+        assert_eq!(cli.get_prompt(), VISION_PROMPT);
+        assert!(Cli::try_parse_from([APP_NAME, "--samples", "20", "llava:7b"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--image-dir", "photos", "--samples", "0", "llava:7b"]).is_err());
+    }
+    
+    #[test]
+    fn test_result_filters() {
+        let cli = parse(&["--only-successful", "--min-tests", "3", "--filter", "tps>20", "--filter", "ttft<500", "a"]);
+        let filter = cli.summary_filter();
+        assert!(filter.only_successful);
+        assert_eq!(filter.min_tests, Some(3));
+        assert_eq!(filter.filters.len(), 2);
+        assert!(Cli::try_parse_from([APP_NAME, "--filter", "speed>20", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_hedged() {
+        let cli = parse(&["--hedged", "2", "llama2:7b"]);
+        assert_eq!(cli.hedged, Some(2));
+        assert!(cli.validate().is_ok());
+        assert!(parse(&["--hedged", "1", "llama2:7b"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--hedged", "2", "-c", "4", "llama2:7b"]).is_err());
+    }
+    
+    #[test]
+    fn test_warmup() {
+        let cli = parse(&["-n", "5", "--warmup", "2", "llama2:7b"]);
+        assert_eq!(cli.warmup, Some(2));
+        assert!(cli.validate().is_ok());
+        assert!(parse(&["-n", "2", "--warmup", "2", "llama2:7b"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--warmup", "1", "--keep-warmup", "a"]).is_err());
+    }
+    
+    /// Sets environment variables for one test and removes them when dropped,
+    /// even if the test fails.
+    struct EnvGuard(Vec<&'static str>);
+    
+    impl EnvGuard {
+        fn set(vars: &[(&'static str, &str)]) -> Self {
+            for (name, value) in vars {
+                std::env::set_var(name, value);
+            }
+            Self(vars.iter().map(|(name, _)| *name).collect())
+        }
+    }
+    
+    impl Drop for EnvGuard {
+        fn drop(&mut self) {
+            for name in &self.0 {
+                std::env::remove_var(name);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_env_overrides() {
+        // Only this test parses with environment variables
+        let _env = EnvGuard::set(&[
+            ("OLLAMA_BENCH_MAX_TOKENS", "256"),
+            ("OLLAMA_BENCH_STRICT_CONTEXT", "true"),
+            ("OLLAMA_BENCH_ITERATIONS", "10"),
+        ]);
+        let command = Cli::command_with_env();
+        
+        let matches = command.clone().get_matches_from([APP_NAME, "llama2:7b"]);
+        let cli = Cli::from_arg_matches(&matches).unwrap();
+        assert_eq!(cli.max_tokens, 256);
+        assert!(cli.strict_context);
+        
+        let matches = command.clone().get_matches_from([APP_NAME, "-m", "64", "llama2:7b"]);
+        assert_eq!(Cli::from_arg_matches(&matches).unwrap().max_tokens, 64);
+        
+        // An environment default for -n does not fight --until-ci; the command line does
+        let matches = command.clone().get_matches_from([APP_NAME, "--until-ci", "3", "llama2:7b"]);
+        assert!(Cli::from_arg_matches(&matches).unwrap().check_sources(&matches).is_ok());
+        let matches = command.clone().get_matches_from([APP_NAME, "--until-ci", "3", "-n", "5", "llama2:7b"]);
+        assert!(Cli::from_arg_matches(&matches).unwrap().check_sources(&matches).is_err());
+        
+        let help = command.clone().render_long_help().to_string();
+        assert!(help.contains("OLLAMA_BENCH_ITERATIONS"), "{}", help);
+    }
+    
+    #[test]
+    fn test_prompts_file() {
+        let cli = parse(&["--prompts-file", "suite.jsonl", "--by-category", "llama2:7b"]);
+        assert_eq!(cli.prompts_file.as_deref(), Some("suite.jsonl"));
+        assert!(cli.by_category);
+        assert!(Cli::try_parse_from([APP_NAME, "--prompts-file", "s.txt", "-p", "hi", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--by-category", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_pattern() {
+        assert!(parse(&["--pattern", "burst:10x5s", "-n", "100", "a"]).pattern.is_some());
+        assert!(Cli::try_parse_from([APP_NAME, "--pattern", "burst:10", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--pattern", "ramp:1-8/30s", "--interleave", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_trim_outliers() {
+        assert_eq!(parse(&["a"]).trim_outliers, None);
+        assert_eq!(parse(&["--trim-outliers", "a"]).trim_outliers, Some(OutlierRule::Iqr));
+        assert_eq!(parse(&["--trim-outliers=zscore", "a"]).trim_outliers, Some(OutlierRule::Zscore));
+        assert!(Cli::try_parse_from([APP_NAME, "--trim-outliers", "--warm-pool", "a", "b"]).is_err());
+    }
+    
+    #[test]
+    fn test_interleave() {
+        assert!(parse(&["--interleave", "a", "b"]).interleave);
+        assert!(Cli::try_parse_from([APP_NAME, "--interleave", "-c", "4", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_cancel_after() {
+        assert_eq!(parse(&["--cancel-after", "20", "a"]).cancel_after, Some(20));
+        assert!(Cli::try_parse_from([APP_NAME, "--cancel-after", "0", "a"]).is_err());
+        assert!(parse(&["--cancel-after", "20", "--endpoint", "embed", "a"]).validate().is_err());
+    }
+    
+    #[test]
+    fn test_compare_reference() {
+        let cli = parse(&["--compare-reference", "--reference-file", "ref.json", "llama2:7b"]);
+        assert_eq!(cli.compare_reference.as_deref(), Some("auto"));
+        assert_eq!(cli.models, vec!["llama2:7b"]);
+        assert_eq!(parse(&["--compare-reference=M2 Pro", "--reference-file", "ref.json", "a"]).compare_reference.as_deref(), Some("M2 Pro"));
+        assert!(Cli::try_parse_from([APP_NAME, "--reference-file", "ref.json", "a"]).is_err());
+        // Nothing is bundled to compare with
+        assert!(Cli::try_parse_from([APP_NAME, "--compare-reference", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_batch_n() {
+        assert_eq!(parse(&["--batch-n", "4", "llama2:7b"]).batch_n, Some(4));
+        assert!(parse(&["--batch-n", "1", "llama2:7b"]).validate().is_err());
+        assert!(parse(&["--batch-n", "4", "--endpoint", "embed", "nomic-embed-text"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--batch-n", "4", "--hedged", "2", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_telemetry() {
+        assert!(parse(&["--telemetry", "llama2:7b"]).telemetry);
+        assert!(Cli::try_parse_from([APP_NAME, "--telemetry", "-c", "4", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_precision() {
+        assert_eq!(parse(&["--precision", "3", "llama2:7b"]).precision, Some(3));
+        assert!(Cli::try_parse_from([APP_NAME, "--precision", "0", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--precision", "16", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_sweep_history() {
+        let cli = parse(&["--endpoint", "chat", "--sweep-history", "0,5,20,50", "llama2:7b"]);
+        assert_eq!(cli.sweep_history, vec![0, 5, 20, 50]);
+        assert!(cli.validate().is_ok());
+        assert!(parse(&["--sweep-history", "0,5", "llama2:7b"]).validate().is_err());
+    }
+    
+    #[test]
+    fn test_context_sweep() {
+        assert_eq!(parse(&["--context-sweep", "1k,4k,8k,16k", "a"]).context_sweep, vec![1024, 4096, 8192, 16384]);
+        assert!(Cli::try_parse_from([APP_NAME, "--context-sweep", "4x", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--context-sweep", "4k", "--preset", "summarize", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_raw() {
+        assert!(parse(&["--raw", "llama2:7b"]).raw);
+        assert!(!parse(&["llama2:7b"]).raw);
+        assert!(Cli::try_parse_from([APP_NAME, "--raw", "--compare-endpoints", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_compare_template() {
+        assert!(parse(&["--compare-template", "llama2:7b"]).compare_template);
+        assert!(Cli::try_parse_from([APP_NAME, "--compare-template", "--raw", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_sweep_option() {
+        let cli = parse(&["--sweep-option", "mirostat=0,1,2", "llama2:7b"]);
+        let sweep = cli.sweep_option.unwrap();
+        assert_eq!(sweep.name, "mirostat");
+        assert_eq!(sweep.values.len(), 3);
+        assert!(Cli::try_parse_from([APP_NAME, "--sweep-option", "mirostat", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_select() {
+        let cli = parse(&["--select", "--eval", "arithmetic", "--min-accuracy", "0.8", "--max-ttft", "400ms", "a", "b"]);
+        assert!(cli.select);
+        assert_eq!(cli.min_accuracy, Some(0.8));
+        assert_eq!(cli.max_ttft, Some(Duration::from_millis(400)));
+        
+        // Accuracy needs an eval task to score against
+        assert!(Cli::try_parse_from([APP_NAME, "--select", "--min-accuracy", "0.8", "a"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--max-ttft", "400ms", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_client_identity() {
+        let cli = parse(&["--user-agent", "curl/8.0", "--bench-client", "nightly-42", "llama2:7b"]);
+        assert_eq!(cli.user_agent.as_deref(), Some("curl/8.0"));
+        assert_eq!(cli.bench_client.as_deref(), Some("nightly-42"));
+        assert!(cli.validate().is_ok());
+        
+        assert!(parse(&["--bench-client", "line\nbreak", "llama2:7b"]).validate().is_err());
+    }
+    
+    #[test]
+    fn test_prefer_ip_family() {
+        assert!(parse(&["--prefer-ipv6", "llama2:7b"]).prefer_ipv6);
+        assert!(Cli::try_parse_from([APP_NAME, "--prefer-ipv4", "--prefer-ipv6", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_idle_probe() {
+        let cli = parse(&["--idle-probe", "30s,2m,6m", "llama2:7b"]);
+        assert_eq!(cli.idle_probe, vec![Duration::from_secs(30), Duration::from_secs(120), Duration::from_secs(360)]);
+        assert!(Cli::try_parse_from([APP_NAME, "--idle-probe", "30s", "--warm-pool", "a", "b"]).is_err());
+    }
+    
+    #[test]
+    fn test_doctor_subcommand() {
+        let cli = parse(&["doctor", "--json"]);
+        assert_eq!(cli.command, Some(Command::Doctor { json: true }));
+        assert!(cli.models.is_empty());
+        
+        let cli = parse(&["llama2:7b"]);
+        assert_eq!(cli.command, None);
+    }
+    
+    #[test]
+    fn test_run_subcommand() {
+        let args = Cli::default_command(["ollama-bench", "run", "-n", "3", "llama2:7b"].map(OsString::from));
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!(cli.command, None);
+        assert_eq!(cli.iterations, 3);
+        assert_eq!(cli.models, vec!["llama2:7b"]);
+        
+        // Flags may come first; a flag's value is not the command position
+        let args = Cli::default_command(["ollama-bench", "-n", "3", "run", "llama2:7b"].map(OsString::from));
+        let cli = Cli::try_parse_from(args).unwrap();
+        assert_eq!((cli.iterations, cli.models), (3, vec!["llama2:7b".to_string()]));
+        let args = Cli::default_command(["ollama-bench", "-p", "run", "llama2:7b"].map(OsString::from));
+        assert_eq!(Cli::try_parse_from(args).unwrap().prompt.as_deref(), Some("run"));
+        
+        // Only the command position is special
+        let args = Cli::default_command(["ollama-bench", "llama2:7b", "run"].map(OsString::from));
+        assert_eq!(Cli::try_parse_from(args).unwrap().models, vec!["llama2:7b", "run"]);
+    }
+    
+    #[test]
+    fn test_until_ci() {
+        let cli = parse(&["--until-ci", "3", "llama2:7b"]);
+        assert_eq!((cli.until_ci, cli.iteration_cap()), (Some(3.0), DEFAULT_CI_MAX_ITERATIONS));
+        assert!(cli.validate().is_ok());
+        assert_eq!(parse(&["--until-ci", "3", "--max-iterations", "20", "a"]).iteration_cap(), 20);
+        
+        assert!(parse(&["--until-ci", "0", "a"]).validate().is_err());
+        assert!(parse(&["--until-ci", "3", "-c", "4", "a"]).validate().is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--max-iterations", "10", "a"]).is_err());
+    }
+    
+    #[test]
+    fn test_size_class() {
+        let cli = parse(&["--size-class", "7b"]);
+        assert_eq!(cli.size_class, Some(SizeClass::Small));
+        assert!(cli.models.is_empty());
+        assert!(Cli::try_parse_from([APP_NAME, "--size-class", "7b", "llama2:7b"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--size-class", "30b"]).is_err());
+    }
+    
+    #[test]
+    fn test_all_and_exclude() {
+        let mut cli = parse(&["--all", "--exclude", "*:70b", "--exclude", "nomic-embed-text"]);
+        assert!(cli.all);
+        assert_eq!(cli.exclude, vec!["*:70b", "nomic-embed-text"]);
+        // The runner fills the models in from the server before validating
+        cli.models = vec!["llama3:8b".to_string()];
+        assert!(cli.validate().is_ok());
+        
+        assert!(Cli::try_parse_from([APP_NAME, "--all", "llama2:7b"]).is_err());
+        assert!(parse(&["--exclude", "mistral", "llama2:7b"]).validate().is_err());
+        assert!(parse(&["--exclude", "*:70b", "llama3*"]).validate().is_ok());
+    }
+    
+    #[test]
+    fn test_list_and_compare_subcommands() {
+        assert_eq!(parse(&["list"]).command, Some(Command::List { json: false }));
+        
+        let cli = parse(&["compare", "before.json", "after.json", "--fail-on-regression", "5"]);
+        let compare = Command::Compare {
+            baseline: "before.json".to_string(),
+            current: "after.json".to_string(),
+            fail_on_regression: Some(5.0),
+            json: false,
+        };
+        assert_eq!(cli.command, Some(compare));
+        assert!(Cli::try_parse_from([APP_NAME, "compare", "before.json"]).is_err());
+    }
+    
+    #[test]
+    fn test_duel_subcommand() {
+        // Run flags may come before or after the models
+        let args = Cli::default_command([APP_NAME, "duel", "llama2:7b", "mistral:7b", "-n", "20"].map(OsString::from));
+        assert_eq!(Cli::try_parse_from(args).unwrap().iterations, 20);
+        let args = Cli::default_command([APP_NAME, "--seed", "7", "duel", "a", "b", "-p", "hi"].map(OsString::from));
+        assert_eq!(Cli::try_parse_from(args).unwrap().prompt.as_deref(), Some("hi"));
+        
+        let mut cli = parse(&["-n", "20", "duel", "llama2:7b", "mistral:7b"]);
+        let duel = Command::Duel { model_a: "llama2:7b".to_string(), model_b: "mistral:7b".to_string() };
+        assert_eq!(cli.command, Some(duel));
+        cli.start_duel("llama2:7b", "mistral:7b").unwrap();
+        assert_eq!(cli.models, vec!["llama2:7b", "mistral:7b"]);
+        assert!(cli.interleave);
+        assert_eq!(cli.seed, Some(DUEL_SEED));
+        assert!(cli.validate().is_ok());
+        
+        let mut seeded = parse(&["--seed", "7", "duel", "a", "b"]);
+        seeded.start_duel("a", "b").unwrap();
+        assert_eq!(seeded.seed, Some(7));
+        
+        assert!(parse(&["-c", "2", "duel", "a", "b"]).start_duel("a", "b").is_err());
+        assert!(parse(&["duel", "a", "a"]).start_duel("a", "a").is_err());
+    }
+    
+    #[test]
+    fn test_aggregate_subcommand() {
+        let cli = parse(&["aggregate", "run1.json", "run2.json.gz"]);
+        let files = vec!["run1.json".to_string(), "run2.json.gz".to_string()];
+        assert_eq!(cli.command, Some(Command::Aggregate { files, json: false }));
+        
+        // Replication needs at least two runs
+        assert!(Cli::try_parse_from([APP_NAME, "aggregate", "run1.json"]).is_err());
+    }
+    
+    #[test]
+    fn test_history_vacuum() {
+        let cli = parse(&["history", "vacuum", "--keep-days", "90"]);
+        let action = HistoryAction::Vacuum { keep_days: Some(90), keep_runs: None };
+        assert_eq!(cli.command, Some(Command::History { action }));
+    }
+    
+    #[test]
+    fn test_history_show_experiment() {
+        let cli = parse(&["history", "show", "--experiment", "undervolt"]);
+        let action = HistoryAction::Show { run_id: None, experiment: Some("undervolt".to_string()), json: false };
+        assert_eq!(cli.command, Some(Command::History { action }));
+        
+        assert!(Cli::try_parse_from([APP_NAME, "history", "show"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "history", "show", "2026", "--experiment", "undervolt"]).is_err());
+        assert!(Cli::try_parse_from([APP_NAME, "--experiment", "undervolt", "--no-history", "llama2:7b"]).is_err());
+    }
+    
+    #[test]
+    fn test_model_prompt() {
+        let cli = parse(&["--model-prompt", "codellama:7b=fn quicksort(v: &mut [i32]) {", "--model-prompt", "llama2:7b=Write x = y", "codellama:7b", "llama2:7b"]);
+        let prompts = cli.model_prompt_map();
+        assert_eq!(prompts["codellama:7b"], "fn quicksort(v: &mut [i32]) {");
+        assert_eq!(prompts["llama2:7b"], "Write x = y");
+        assert!(cli.validate().is_ok());
+        
+        assert!(parse(&["--model-prompt", "mistral=Hi", "llama2:7b"]).validate().is_err());
+        assert!(parse(&["--model-prompt", "no separator", "llama2:7b"]).validate().is_err());
+    }
+    
+    #[test]
+    fn test_server_env() {
+        let cli = parse(&["--server-env", "OLLAMA_NUM_PARALLEL=4", "--server-env", "OLLAMA_FLASH_ATTENTION=1", "llama2:7b"]);
+        assert!(cli.validate().is_ok());
+        assert_eq!(cli.server_env_map().get("OLLAMA_NUM_PARALLEL").map(String::as_str), Some("4"));
+        
+        let cli = parse(&["--server-env", "OLLAMA_NUM_PARALLEL", "llama2:7b"]);
+        assert!(cli.validate().is_err());
+    }
+    
+    #[test]
+    fn test_progress_mode() {
+        assert_eq!(parse(&["llama2:7b"]).progress, ProgressMode::Auto);
+        assert_eq!(parse(&["--progress", "plain", "llama2:7b"]).progress, ProgressMode::Plain);
+    }
+}
@@ -0,0 +1,73 @@
+use std::sync::OnceLock;
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+
+/// Wall-clock steps at least this large (NTP corrections, manual changes,
+/// suspend) are recorded on the iteration they happened in.
+pub const CLOCK_JUMP_THRESHOLD_MS: i64 = 1000;
+
+/// Monotonic reference point for the whole process.
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+/// A moment read from both clocks: wall time for display and correlation
+/// with other logs, monotonic time for every duration.
+#[derive(Debug, Clone, Copy)]
+pub struct Stamp {
+    pub wall: DateTime<Utc>,
+    pub mono: Instant,
+}
+
+impl Stamp {
+    pub fn now() -> Self {
+        // Pin the epoch first so `mono` is never earlier than it
+        epoch();
+        Self {
+            wall: Utc::now(),
+            mono: Instant::now(),
+        }
+    }
+
+    /// Monotonic milliseconds since the process started timing.
+    pub fn mono_ms(&self) -> u64 {
+        self.mono.saturating_duration_since(epoch()).as_millis() as u64
+    }
+}
+
+/// How far the wall clock moved beyond (positive) or behind (negative) the
+/// monotonic clock between two stamps; `None` when within the threshold.
+pub fn jump_ms(start: &Stamp, end: &Stamp) -> Option<i64> {
+    let wall_ms = (end.wall - start.wall).num_milliseconds();
+    let mono_ms = end.mono.saturating_duration_since(start.mono).as_millis() as i64;
+    let jump = wall_ms - mono_ms;
+    (jump.abs() >= CLOCK_JUMP_THRESHOLD_MS).then_some(jump)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_jump_ms() {
+        let start = Stamp::now();
+        let steady = Stamp {
+            wall: start.wall + chrono::Duration::milliseconds(500),
+            mono: start.mono + Duration::from_millis(500),
+        };
+        assert_eq!(jump_ms(&start, &steady), None);
+
+        // NTP stepped the clock back 3s during a 500ms request
+        let stepped_back = Stamp { wall: steady.wall - chrono::Duration::seconds(3), ..steady };
+        assert_eq!(jump_ms(&start, &stepped_back), Some(-3000));
+    }
+
+    #[test]
+    fn test_mono_ms_is_monotonic() {
+        let first = Stamp::now();
+        let second = Stamp::now();
+        assert!(second.mono_ms() >= first.mono_ms());
+    }
+}
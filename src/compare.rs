@@ -0,0 +1,265 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+use crate::config::WINNER_THRESHOLD_PERCENT;
+use crate::effect::{self, Moments};
+use crate::error::{BenchmarkError, Result};
+use crate::precision::num;
+use crate::types::{ModelSummary, ServerInfo};
+
+/// Change in one metric between a baseline run and the current run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricDelta {
+    pub metric: String,
+    pub baseline: f64,
+    pub current: f64,
+    pub absolute: f64,
+    /// Relative change in percent; `None` when the baseline value is zero
+    pub percent: Option<f64>,
+    /// Cohen's d of current over baseline, when both runs recorded a spread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect_size: Option<f64>,
+    /// Whether the change exceeds the noise threshold and, when the effect
+    /// size is known, is more than negligible
+    pub significant: bool,
+    /// Whether the change is in the metric's "better" direction
+    pub improved: bool,
+}
+
+/// All metric changes for one model present in both runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelDelta {
+    pub model: String,
+    pub metrics: Vec<MetricDelta>,
+}
+
+impl ModelDelta {
+    pub fn metric(&self, name: &str) -> Option<&MetricDelta> {
+        self.metrics.iter().find(|m| m.metric == name)
+    }
+}
+
+/// A previously exported run: the summaries plus any recorded server info.
+pub struct SavedRun {
+    pub models: Vec<ModelSummary>,
+    pub server: Option<ServerInfo>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SavedResults {
+    Report {
+        models: Vec<ModelSummary>,
+        #[serde(default)]
+        server: Option<ServerInfo>,
+    },
+    Summaries(Vec<ModelSummary>),
+}
+
+/// Loads a run from a JSON file written by `-o json` or `-e file.json`.
+pub fn load_run(path: &str) -> Result<SavedRun> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| BenchmarkError::IoError(format!("{}: {}", path, e)))?;
+
+    match serde_json::from_str(&content) {
+        Ok(SavedResults::Report { models, server }) => Ok(SavedRun { models, server }),
+        Ok(SavedResults::Summaries(models)) => Ok(SavedRun { models, server: None }),
+        Err(e) => Err(BenchmarkError::ConfigError(format!(
+            "{} is not an ollama-bench JSON export ({})",
+            path, e
+        ))),
+    }
+}
+
+/// Human-readable differences in server configuration between two runs.
+pub fn server_differences(baseline: &ServerInfo, current: &ServerInfo) -> Vec<String> {
+    let mut differences = Vec::new();
+
+    if baseline.version != current.version {
+        differences.push(format!(
+            "Ollama version: {} → {}",
+            baseline.version.as_deref().unwrap_or("unknown"),
+            current.version.as_deref().unwrap_or("unknown")
+        ));
+    }
+
+    let keys: std::collections::BTreeSet<&String> = baseline.env.keys().chain(current.env.keys()).collect();
+    for key in keys {
+        let before = baseline.env.get(key);
+        let after = current.env.get(key);
+        if before != after {
+            differences.push(format!(
+                "{}: {} → {}",
+                key,
+                before.map(String::as_str).unwrap_or("unset"),
+                after.map(String::as_str).unwrap_or("unset")
+            ));
+        }
+    }
+
+    differences
+}
+
+/// Computes deltas for every model label present in both runs.
+pub fn compute_deltas(baseline: &[ModelSummary], current: &[ModelSummary]) -> Vec<ModelDelta> {
+    current
+        .iter()
+        .filter_map(|cur| {
+            let base = baseline.iter().find(|b| b.label() == cur.label())?;
+            Some(ModelDelta {
+                model: cur.label(),
+                metrics: vec![
+                    metric_delta("avg_tokens_per_second", base.avg_tokens_per_second, cur.avg_tokens_per_second, true)
+                        .with_effect(Moments::speed(base), Moments::speed(cur)),
+                    metric_delta("min_tokens_per_second", base.min_tokens_per_second, cur.min_tokens_per_second, true),
+                    metric_delta("max_tokens_per_second", base.max_tokens_per_second, cur.max_tokens_per_second, true),
+                    metric_delta("avg_ttft_ms", base.avg_ttft_ms, cur.avg_ttft_ms, false)
+                        .with_effect(Moments::ttft(base), Moments::ttft(cur)),
+                    metric_delta("success_rate", base.success_rate, cur.success_rate, true),
+                ],
+            })
+        })
+        .collect()
+}
+
+/// Models whose average tokens/s fell by more than `threshold_percent`,
+/// and baseline models the current run has no result for, described for
+/// the error message.
+pub fn regressions(baseline: &[ModelSummary], current: &[ModelSummary], threshold_percent: f64) -> Vec<String> {
+    let slower = compute_deltas(baseline, current).into_iter().filter_map(|delta| {
+        let speed = delta.metric("avg_tokens_per_second")?;
+        let percent = speed.percent?;
+        (percent < -threshold_percent).then(|| {
+            format!("{} {}% ({} → {} tok/s)", delta.model, num(percent, 1), num(speed.baseline, 1), num(speed.current, 1))
+        })
+    });
+    let missing = baseline
+        .iter()
+        .map(ModelSummary::label)
+        .filter(|label| !current.iter().any(|summary| &summary.label() == label))
+        .map(|label| format!("{} missing from the current run", label));
+    slower.chain(missing).collect()
+}
+
+fn metric_delta(metric: &str, baseline: f64, current: f64, higher_is_better: bool) -> MetricDelta {
+    let absolute = current - baseline;
+    let percent = (baseline != 0.0).then(|| absolute / baseline * 100.0);
+
+    MetricDelta {
+        metric: metric.to_string(),
+        baseline,
+        current,
+        absolute,
+        percent,
+        effect_size: None,
+        significant: percent.map_or(absolute != 0.0, |p| p.abs() >= WINNER_THRESHOLD_PERCENT),
+        improved: if higher_is_better { absolute > 0.0 } else { absolute < 0.0 },
+    }
+}
+
+impl MetricDelta {
+    /// Adds the effect size; a negligible one overrules the percent threshold,
+    /// since the change is then within the runs' own noise.
+    fn with_effect(mut self, baseline: Option<Moments>, current: Option<Moments>) -> Self {
+        self.effect_size = current.zip(baseline).and_then(|(current, baseline)| effect::cohens_d(current, baseline));
+        if self.effect_size.is_some_and(effect::is_negligible) {
+            self.significant = false;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(model: &str, speed: f64, ttft: f64) -> ModelSummary {
+        ModelSummary {
+            model: model.to_string(),
+            success_rate: 1.0,
+            avg_tokens_per_second: speed,
+            avg_ttft_ms: ttft,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_compute_deltas() {
+        let baseline = vec![summary("a", 20.0, 200.0), summary("gone", 10.0, 100.0)];
+        let current = vec![summary("a", 25.0, 198.0), summary("new", 10.0, 100.0)];
+
+        let deltas = compute_deltas(&baseline, &current);
+        assert_eq!(deltas.len(), 1);
+
+        let speed = deltas[0].metric("avg_tokens_per_second").unwrap();
+        assert_eq!(speed.absolute, 5.0);
+        assert_eq!(speed.percent, Some(25.0));
+        assert!(speed.significant);
+        assert!(speed.improved);
+
+        let ttft = deltas[0].metric("avg_ttft_ms").unwrap();
+        assert!(!ttft.significant);
+        assert!(ttft.improved);
+    }
+
+    #[test]
+    fn test_effect_size_overrules_noisy_percent() {
+        let with_spread = |speed: f64, stddev: f64| ModelSummary {
+            total_tests: 10,
+            stddev_tokens_per_second: Some(stddev),
+            ..summary("a", speed, 200.0)
+        };
+
+        // +10%, but the iterations scatter over ±20 tok/s
+        let noisy = compute_deltas(&[with_spread(20.0, 20.0)], &[with_spread(22.0, 20.0)]);
+        let speed = noisy[0].metric("avg_tokens_per_second").unwrap();
+        assert_eq!(speed.effect_size, Some(0.1));
+        assert!(!speed.significant);
+
+        let tight = compute_deltas(&[with_spread(20.0, 0.5)], &[with_spread(22.0, 0.5)]);
+        let speed = tight[0].metric("avg_tokens_per_second").unwrap();
+        assert_eq!(speed.effect_size, Some(4.0));
+        assert!(speed.significant);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = vec![summary("a", 50.0, 200.0), summary("b", 40.0, 200.0), summary("c", 0.0, 200.0)];
+        let current = vec![summary("a", 44.0, 200.0), summary("b", 39.0, 200.0), summary("c", 10.0, 200.0)];
+
+        assert_eq!(regressions(&baseline, &current, 5.0), vec!["a -12.0% (50.0 → 44.0 tok/s)".to_string()]);
+        assert!(regressions(&baseline, &current, 15.0).is_empty());
+        assert_eq!(regressions(&baseline, &current[1..], 15.0), vec!["a missing from the current run".to_string()]);
+    }
+
+    #[test]
+    fn test_server_differences() {
+        let mut baseline = ServerInfo { version: Some("0.5.1".to_string()), ..Default::default() };
+        baseline.env.insert("OLLAMA_NUM_PARALLEL".to_string(), "1".to_string());
+        let mut current = ServerInfo { version: Some("0.5.7".to_string()), ..Default::default() };
+        current.env.insert("OLLAMA_NUM_PARALLEL".to_string(), "4".to_string());
+
+        let differences = server_differences(&baseline, &current);
+        assert_eq!(differences, vec![
+            "Ollama version: 0.5.1 → 0.5.7".to_string(),
+            "OLLAMA_NUM_PARALLEL: 1 → 4".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn test_load_run_accepts_both_shapes() {
+        let dir = std::env::temp_dir();
+        let array_path = dir.join(format!("ollama-bench-baseline-array-{}.json", std::process::id()));
+        let report_path = dir.join(format!("ollama-bench-baseline-report-{}.json", std::process::id()));
+
+        let summaries = vec![summary("a", 20.0, 200.0)];
+        fs::write(&array_path, serde_json::to_string(&summaries).unwrap()).unwrap();
+        fs::write(&report_path, serde_json::json!({ "models": summaries, "deltas": [] }).to_string()).unwrap();
+
+        assert_eq!(load_run(array_path.to_str().unwrap()).unwrap().models.len(), 1);
+        assert_eq!(load_run(report_path.to_str().unwrap()).unwrap().models.len(), 1);
+
+        fs::remove_file(array_path).ok();
+        fs::remove_file(report_path).ok();
+    }
+}
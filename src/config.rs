@@ -2,13 +2,26 @@ pub const APP_NAME: &str = "ollama-bench";
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
 pub const APP_DESCRIPTION: &str = "⚡ Apache Bench-style Ollama LLM performance benchmarking";
 
+/// Prefix of the environment variables mirroring every flag, e.g.
+/// `OLLAMA_BENCH_ITERATIONS` for `--iterations`.
+pub const ENV_PREFIX: &str = "OLLAMA_BENCH_";
+
 pub const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
 pub const DEFAULT_ITERATIONS: u32 = 5;
 pub const DEFAULT_TIMEOUT_SECONDS: u64 = 120;
+pub const DEFAULT_RETRY_BACKOFF_MS: u64 = 500;
+/// Longest wait between retries, however many have failed
+pub const MAX_RETRY_BACKOFF_MS: u64 = 30_000;
+pub const DEFAULT_STAGGER_MS: u64 = 100;
+pub const DEFAULT_CI_MAX_ITERATIONS: u32 = 50;
+/// Seed both models share in `duel` unless `--seed` is given
+pub const DUEL_SEED: i64 = 42;
 pub const DEFAULT_TEMPERATURE: f32 = 0.7;
 pub const DEFAULT_MAX_TOKENS: i32 = 100;
 
 pub const DEFAULT_PROMPT: &str = "Write a haiku about benchmarking language models.";
+/// Default prompt with `--image-dir`
+pub const VISION_PROMPT: &str = "Describe this image in one sentence.";
 
 pub const PROGRESS_BAR_WIDTH: usize = 32;
 #[allow(dead_code)]
@@ -29,9 +42,71 @@ pub struct TableWidths {
     pub success_rate: usize,
 }
 
-#[allow(dead_code)]
 pub const WINNER_THRESHOLD_PERCENT: f64 = 5.0;
 
+/// Speeds this far below the `--compare-reference` figure suggest a
+/// misconfiguration rather than normal variation.
+pub const REFERENCE_BELOW_THRESHOLD_PERCENT: f64 = 20.0;
+
+/// Jain's fairness index under `--concurrency` below which some requests
+/// are flagged as starved.
+pub const FAIRNESS_WARN_THRESHOLD: f64 = 0.9;
+
+/// Chars-per-token ratios differing by at least this fraction mean two
+/// models' token counts are not directly comparable.
+pub const TOKENIZER_RATIO_THRESHOLD: f64 = 0.15;
+
+/// Requests per probe of `--overhead-check`.
+pub const OVERHEAD_SAMPLES: u32 = 10;
+
+/// How often a `--pattern` run re-checks its load curve between completions.
+pub const PATTERN_TICK_MS: u64 = 50;
+
+/// Cancelled streams per model in `--cancel-after`.
+pub const CANCEL_SAMPLES: u32 = 5;
+
+/// Staggered starts per model in `--queue-depth`.
+pub const QUEUE_ROUNDS: u32 = 3;
+
+/// A request whose model load took at least this long counts as a swap-in.
+pub const SWAP_LOAD_THRESHOLD_MS: u64 = 250;
+
+/// Context window Ollama allocates when neither the request nor the
+/// Modelfile sets `num_ctx`.
+pub const DEFAULT_NUM_CTX: u32 = 4096;
+
+/// A request using this share of its context window is close enough to
+/// the limit that a slightly longer prompt would be truncated.
+pub const CONTEXT_NEAR_FRACTION: f64 = 0.9;
+
+/// How long a history database write waits for another process's write.
+pub const HISTORY_BUSY_TIMEOUT_MS: u64 = 5_000;
+
+/// Earlier runs the `history list --model` trend band is taken over.
+pub const TREND_WINDOW: usize = 10;
+
+/// Earlier runs needed before a run gets a trend band.
+pub const TREND_MIN_RUNS: usize = 3;
+
+/// Most iterations per model a run may request.
+pub const MAX_ITERATIONS: u32 = 1000;
+
+/// Half-width of the 95% confidence interval, as a fraction of mean tok/s,
+/// that the sample-size recommendation aims for.
+pub const SAMPLE_MARGIN_FRACTION: f64 = 0.05;
+
+/// Successful iterations `--until-ci` waits for before trusting the spread.
+pub const CI_MIN_ITERATIONS: u32 = 3;
+
+/// Upper bounds of the OpenMetrics latency histogram buckets, in seconds.
+pub const LATENCY_BUCKETS_SECONDS: [f64; 10] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Equal-width bins of the completion token histogram.
+pub const TOKEN_HISTOGRAM_BINS: usize = 8;
+
+/// Downloads for `--pull-missing` run far longer than a benchmark request.
+pub const PULL_TIMEOUT_SECONDS: u64 = 6 * 60 * 60;
+
 #[allow(dead_code)]
 pub const TERMINAL_COLORS: TerminalColors = TerminalColors {
     success: "\x1b[32m",   // Green
@@ -0,0 +1,146 @@
+use std::path::Path;
+use std::process::Command;
+use reqwest::Url;
+
+use crate::i18n::{tf, Msg};
+
+/// Container runtime this process appears to be running under.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    /// Host name the runtime provides for reaching the host machine.
+    fn host_gateway(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "host.docker.internal",
+            ContainerRuntime::Podman => "host.containers.internal",
+        }
+    }
+}
+
+/// Detects whether ollama-bench itself runs inside a container, where
+/// `localhost` means the container rather than the machine running Ollama.
+pub fn detect_runtime() -> Option<ContainerRuntime> {
+    if Path::new("/run/.containerenv").exists() {
+        return Some(ContainerRuntime::Podman);
+    }
+    if Path::new("/.dockerenv").exists() {
+        return Some(ContainerRuntime::Docker);
+    }
+    let cgroup = std::fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    runtime_from_cgroup(&cgroup)
+}
+
+fn runtime_from_cgroup(cgroup: &str) -> Option<ContainerRuntime> {
+    if cgroup.contains("libpod") {
+        Some(ContainerRuntime::Podman)
+    } else if cgroup.contains("docker") || cgroup.contains("kubepods") {
+        Some(ContainerRuntime::Docker)
+    } else {
+        None
+    }
+}
+
+pub fn is_loopback(base_url: &str) -> bool {
+    Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .is_some_and(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]" | "::1"))
+}
+
+/// Common ways to reach Ollama when a loopback URL fails across a container
+/// boundary: the host gateway names, Docker's default bridge, and the usual
+/// compose service name.
+pub fn alternative_urls(base_url: &str) -> Vec<String> {
+    let Ok(url) = Url::parse(base_url) else {
+        return Vec::new();
+    };
+    if !is_loopback(base_url) {
+        return Vec::new();
+    }
+
+    let hosts = [
+        ContainerRuntime::Docker.host_gateway(),
+        ContainerRuntime::Podman.host_gateway(),
+        "172.17.0.1",
+        "ollama",
+    ];
+    hosts
+        .iter()
+        .filter_map(|host| {
+            let mut candidate = url.clone();
+            candidate.set_host(Some(host)).ok()?;
+            Some(candidate.as_str().trim_end_matches('/').to_string())
+        })
+        .collect()
+}
+
+/// Published ports of running Ollama containers, e.g. `0.0.0.0:11500->11434/tcp`,
+/// asking whichever of `docker` or `podman` is installed.
+pub fn ollama_container_ports() -> Option<String> {
+    ["docker", "podman"].iter().find_map(|cli| {
+        let output = Command::new(cli)
+            .args(["ps", "--format", "{{.Image}}\t{{.Ports}}"])
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        parse_ollama_ports(&String::from_utf8_lossy(&output.stdout))
+    })
+}
+
+fn parse_ollama_ports(ps_output: &str) -> Option<String> {
+    let ports: Vec<&str> = ps_output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter(|(image, _)| image.contains("ollama"))
+        .map(|(_, ports)| ports.trim())
+        .filter(|ports| !ports.is_empty())
+        .collect();
+    (!ports.is_empty()).then(|| ports.join(", "))
+}
+
+/// Extra guidance for connection failures that look container-related.
+/// `probe_cli` also asks docker/podman for Ollama's published ports.
+pub fn hint(base_url: &str, probe_cli: bool) -> Option<String> {
+    if let Some(runtime) = detect_runtime().filter(|_| is_loopback(base_url)) {
+        let mut suggestion = Url::parse(base_url).ok()?;
+        suggestion.set_host(Some(runtime.host_gateway())).ok()?;
+        return Some(tf(Msg::HintInsideContainer, suggestion.as_str().trim_end_matches('/')));
+    }
+    if probe_cli {
+        return ollama_container_ports().map(|ports| tf(Msg::HintOllamaContainer, ports));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alternative_urls() {
+        let urls = alternative_urls("http://localhost:11434");
+        assert_eq!(urls[0], "http://host.docker.internal:11434");
+        assert!(urls.contains(&"http://ollama:11434".to_string()));
+        assert!(alternative_urls("http://gpu-box:11434").is_empty());
+    }
+
+    #[test]
+    fn test_runtime_from_cgroup() {
+        assert_eq!(runtime_from_cgroup("0::/system.slice/docker-3f2a.scope"), Some(ContainerRuntime::Docker));
+        assert_eq!(runtime_from_cgroup("0::/machine.slice/libpod-9c1b.scope"), Some(ContainerRuntime::Podman));
+        assert_eq!(runtime_from_cgroup("0::/init.scope"), None);
+    }
+
+    #[test]
+    fn test_parse_ollama_ports() {
+        let ps = "postgres:16\t0.0.0.0:5432->5432/tcp\nollama/ollama:latest\t0.0.0.0:11500->11434/tcp\n";
+        assert_eq!(parse_ollama_ports(ps), Some("0.0.0.0:11500->11434/tcp".to_string()));
+        assert_eq!(parse_ollama_ports("redis:7\t6379/tcp\n"), None);
+    }
+}
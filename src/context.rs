@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{CONTEXT_NEAR_FRACTION, DEFAULT_NUM_CTX};
+use crate::history;
+use crate::preset;
+use crate::types::{BenchmarkConfig, ModelSummary};
+
+/// Task after the padding of a `--context-sweep` prompt; its answer is what
+/// decode speed is measured on.
+const PADDING_TASK: &str = "Summarize the report above.";
+
+/// Rough token count for budget checks. Four characters per token is close
+/// for English text across common tokenizers; it does not need to be exact
+/// to catch a prompt that is far too long.
+pub fn estimate_tokens(text: &str) -> u32 {
+    ((text.chars().count() + 3) / 4) as u32
+}
+
+/// Context window of one request: its `num_ctx` option (a sweep variant's
+/// own value included), then the model's Modelfile on `server`, then
+/// Ollama's default.
+pub fn request_num_ctx(config: &BenchmarkConfig, server: &str, model: &str) -> u32 {
+    config
+        .options
+        .get("num_ctx")
+        .and_then(|value| value.as_u64())
+        .map(|n| n as u32)
+        .or_else(|| config.model_num_ctx.get(&(server.to_string(), model.to_string())).copied())
+        .unwrap_or(DEFAULT_NUM_CTX)
+}
+
+/// How close a finished request came to its context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextLimit {
+    /// At least `CONTEXT_NEAR_FRACTION` of the window was used
+    Near,
+    /// The window was full: Ollama truncated the prompt or shifted context
+    Reached,
+}
+
+/// Flags a request whose prompt and output used most or all of `num_ctx`.
+pub fn context_limit(prompt_tokens: u32, completion_tokens: u32, num_ctx: u32) -> Option<ContextLimit> {
+    let used = (prompt_tokens + completion_tokens) as f64;
+    if used >= num_ctx as f64 {
+        Some(ContextLimit::Reached)
+    } else if used >= num_ctx as f64 * CONTEXT_NEAR_FRACTION {
+        Some(ContextLimit::Near)
+    } else {
+        None
+    }
+}
+
+/// Estimated tokens of the largest request a model will receive against
+/// the context window it runs with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Budget {
+    pub prompt_tokens: u32,
+    pub max_tokens: u32,
+    pub num_ctx: u32,
+}
+
+impl Budget {
+    /// Budget for `model` on `server` in one sweep variant: system prompt,
+    /// chat history and the (longest suite) prompt, plus `max_tokens` of
+    /// output, against the window its requests run with.
+    pub fn new(config: &BenchmarkConfig, server: &str, model: &str) -> Self {
+        let history: u32 = history::messages(config.history_messages)
+            .iter()
+            .filter_map(|message| message["content"].as_str())
+            .map(estimate_tokens)
+            .sum();
+        let system = config.system.as_deref().map_or(0, estimate_tokens);
+        let prompt = match config.prompt_suite.iter().map(|entry| estimate_tokens(&entry.prompt)).max() {
+            Some(longest) => longest,
+            None => estimate_tokens(config.prompt_for(model)),
+        };
+        let prompt = prompt.max(config.context_tokens.unwrap_or(0));
+        Self {
+            prompt_tokens: system + history + prompt,
+            max_tokens: config.max_tokens.max(0) as u32,
+            num_ctx: request_num_ctx(config, server, model),
+        }
+    }
+
+    /// Ollama silently drops the start of a prompt longer than the window.
+    pub fn prompt_overflows(&self) -> bool {
+        self.prompt_tokens > self.num_ctx
+    }
+
+    /// Generation runs out of window before reaching `max_tokens`.
+    pub fn overflows(&self) -> bool {
+        self.prompt_tokens + self.max_tokens > self.num_ctx
+    }
+
+    pub fn describe(&self, model: &str) -> String {
+        if self.prompt_overflows() {
+            format!(
+                "{}: prompt (~{} tokens) exceeds num_ctx {} and would be truncated",
+                model, self.prompt_tokens, self.num_ctx
+            )
+        } else {
+            format!(
+                "{}: prompt (~{} tokens) + max_tokens {} exceeds num_ctx {}",
+                model, self.prompt_tokens, self.max_tokens, self.num_ctx
+            )
+        }
+    }
+}
+
+/// Parses a `--context-sweep` size: a token count, or `k` thousands of
+/// 1024 tokens (`4k` is 4096).
+pub fn parse_context_size(text: &str) -> Result<u32, String> {
+    let text = text.trim();
+    let invalid = || format!("invalid context size '{}' (use e.g. 512, 4k, 16k)", text);
+    let (number, scale) = match text.strip_suffix(['k', 'K']) {
+        Some(thousands) => (thousands, 1024),
+        None => (text, 1),
+    };
+    number
+        .parse::<u32>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+        .filter(|&tokens| tokens > 0)
+        .ok_or_else(invalid)
+}
+
+/// `4k` for whole multiples of 1024, else the plain count.
+pub fn context_label(tokens: u32) -> String {
+    if tokens % 1024 == 0 {
+        format!("{}k", tokens / 1024)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// A prompt of about `tokens` tokens (by `estimate_tokens`): synthetic
+/// report sections cut at a word boundary, followed by a short task.
+pub fn padded_prompt(tokens: u32) -> String {
+    let target_chars = (tokens as usize * 4).saturating_sub(PADDING_TASK.len() + 2);
+    let mut document = String::from(preset::REPORT_TITLE);
+    let mut section = 1;
+    while document.len() < target_chars {
+        document.push_str(&preset::report_section(section));
+        section += 1;
+    }
+    // The report is ASCII, so byte offsets are character offsets
+    document.truncate(target_chars);
+    if let Some(end) = document.trim_end().rfind(char::is_whitespace) {
+        document.truncate(end);
+    }
+    document.push_str("\n\n");
+    document.push_str(PADDING_TASK);
+    document
+}
+
+/// Context window for a `--context-sweep` run: the largest prompt plus
+/// `max_tokens`, with a quarter extra for tokenizers that split the text
+/// finer, in multiples of 1024. One window for every size, so the model is
+/// loaded once and only the prompt changes.
+pub fn sweep_num_ctx(sizes: &[u32], max_tokens: i32) -> u32 {
+    let largest = sizes.iter().copied().max().unwrap_or(0) + max_tokens.max(0) as u32;
+    let with_headroom = largest + largest / 4;
+    (with_headroom + 1023) / 1024 * 1024
+}
+
+/// One prompt size of a model's `--context-sweep` run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextPoint {
+    /// Requested size
+    pub tokens: u32,
+    /// Prompt tokens as counted by the server
+    pub avg_prompt_tokens: f64,
+    pub prefill_tokens_per_second: Option<f64>,
+    pub decode_tokens_per_second: f64,
+}
+
+/// How a model's prefill and decode speed change as the prompt grows.
+#[derive(Debug, Clone)]
+pub struct ContextCurve {
+    pub model: String,
+    /// Points by increasing prompt size
+    pub points: Vec<ContextPoint>,
+    /// Decode speed at the largest size relative to the smallest;
+    /// `None` with fewer than two sizes
+    pub decode_change_percent: Option<f64>,
+}
+
+/// Prefill and decode speed against `--context-sweep` size, one curve per
+/// model, server and remaining sweep variant. Sizes that never succeeded
+/// are skipped.
+pub fn curves(summaries: &[ModelSummary]) -> Vec<ContextCurve> {
+    let mut labels: Vec<String> = Vec::new();
+    for summary in summaries.iter().filter(|s| s.context_tokens.is_some()) {
+        let label = summary.label_without("context");
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+
+    labels
+        .into_iter()
+        .map(|label| {
+            let mut points: Vec<ContextPoint> = summaries
+                .iter()
+                .filter(|s| s.label_without("context") == label && s.success_rate > 0.0)
+                .filter_map(|s| {
+                    let successful = (s.total_tests as f64 * s.success_rate).round();
+                    Some(ContextPoint {
+                        tokens: s.context_tokens?,
+                        avg_prompt_tokens: s.total_prompt_tokens as f64 / successful,
+                        prefill_tokens_per_second: s.prompt_eval_tokens_per_second,
+                        decode_tokens_per_second: s.avg_tokens_per_second,
+                    })
+                })
+                .collect();
+            points.sort_by_key(|p| p.tokens);
+
+            let decode_change_percent = match (points.first(), points.last()) {
+                (Some(first), Some(last)) if points.len() > 1 && first.decode_tokens_per_second > 0.0 => Some(
+                    (last.decode_tokens_per_second - first.decode_tokens_per_second) / first.decode_tokens_per_second * 100.0,
+                ),
+                _ => None,
+            };
+            ContextCurve {
+                model: label,
+                points,
+                decode_change_percent,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::OptionSweep;
+
+    #[test]
+    fn test_context_limit() {
+        let mut config = BenchmarkConfig::default();
+        config.model_num_ctx.insert(("http://a".to_string(), "m".to_string()), 8192);
+        assert_eq!(request_num_ctx(&config, "http://a", "m"), 8192);
+        assert_eq!(request_num_ctx(&config, "http://b", "m"), DEFAULT_NUM_CTX);
+        assert_eq!(request_num_ctx(&config, "http://a", "other"), DEFAULT_NUM_CTX);
+        config.options.insert("num_ctx".to_string(), 2048.into());
+        assert_eq!(request_num_ctx(&config, "http://a", "m"), 2048);
+
+        assert_eq!(context_limit(1000, 100, 2048), None);
+        assert_eq!(context_limit(1900, 50, 2048), Some(ContextLimit::Near));
+        assert_eq!(context_limit(1948, 100, 2048), Some(ContextLimit::Reached));
+    }
+
+    #[test]
+    fn test_budget() {
+        let mut config = BenchmarkConfig {
+            prompt: "x".repeat(4000),
+            max_tokens: 100,
+            ..Default::default()
+        };
+        config.model_num_ctx.insert(("http://a".to_string(), "m".to_string()), 1024);
+        let budget = Budget::new(&config, "http://a", "m");
+        assert_eq!(budget.prompt_tokens, 1000);
+        assert!(budget.overflows());
+        assert!(!budget.prompt_overflows());
+
+        let budget = Budget { num_ctx: 512, ..budget };
+        assert!(budget.prompt_overflows());
+        assert!(budget.describe("m").contains("truncated"));
+        assert!(!Budget::new(&config, "http://b", "m").overflows());
+
+        // Each variant is checked against its own window
+        config.option_sweep = Some(OptionSweep { name: "num_ctx".to_string(), values: vec![1024.into(), 4096.into()] });
+        let overflows: Vec<bool> = config.variants().iter().map(|(_, variant)| Budget::new(variant, "http://a", "m").overflows()).collect();
+        assert_eq!(overflows, vec![true, false]);
+    }
+
+    #[test]
+    fn test_context_sizes() {
+        assert_eq!(parse_context_size("4k"), Ok(4096));
+        assert_eq!(parse_context_size(" 16K "), Ok(16384));
+        assert_eq!(parse_context_size("1500"), Ok(1500));
+        assert!(parse_context_size("0").is_err());
+        assert!(parse_context_size("4kb").is_err());
+        assert_eq!(context_label(8192), "8k");
+        assert_eq!(context_label(1500), "1500");
+        assert_eq!(sweep_num_ctx(&[1024, 16384], 100), 21504);
+
+        for tokens in [256, 4096] {
+            let prompt = padded_prompt(tokens);
+            assert!(estimate_tokens(&prompt).abs_diff(tokens) < 8, "{} for {}", estimate_tokens(&prompt), tokens);
+            assert!(prompt.ends_with(PADDING_TASK));
+        }
+    }
+
+    #[test]
+    fn test_curves() {
+        let cell = |model: &str, tokens: u32, decode: f64| ModelSummary {
+            model: model.to_string(),
+            total_tests: 2,
+            success_rate: 1.0,
+            total_prompt_tokens: 2 * tokens as u64,
+            avg_tokens_per_second: decode,
+            prompt_eval_tokens_per_second: Some(500.0),
+            context_tokens: Some(tokens),
+            ..Default::default()
+        };
+        let variant = |summary: ModelSummary, variant: &str| ModelSummary { variant: Some(variant.to_string()), ..summary };
+        let summaries = vec![
+            variant(cell("a", 8192, 30.0), "context=8k"),
+            cell("b", 1024, 50.0),
+            variant(cell("a", 1024, 40.0), "context=1k"),
+            variant(cell("a", 1024, 45.0), "num_thread=8, context=1k"),
+        ];
+
+        let curves = curves(&summaries);
+        assert_eq!(curves.len(), 3);
+        assert_eq!((curves[0].model.as_str(), curves[2].model.as_str()), ("a", "a [num_thread=8]"));
+        assert_eq!(curves[0].points.iter().map(|p| p.tokens).collect::<Vec<_>>(), vec![1024, 8192]);
+        assert_eq!(curves[0].points[1].avg_prompt_tokens, 8192.0);
+        assert_eq!(curves[0].decode_change_percent, Some(-25.0));
+        assert_eq!(curves[1].decode_change_percent, None);
+    }
+}
@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::config::{APP_NAME, APP_VERSION};
+use crate::error::Result;
+use crate::exclusions::Exclusions;
+use crate::types::{BenchmarkConfig, SpeedUnit};
+use crate::warnings::Warnings;
+
+/// Version of the CSV column layouts below. Columns are append-only: new
+/// ones go at the end and leave the version alone; renaming, removing or
+/// reordering a column bumps it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Columns of `--export-raw *.csv`, one row per iteration.
+pub const RAW_COLUMNS: &[&str] = &[
+    "model",
+    "started_at",
+    "finished_at",
+    "success",
+    "tokens_per_second",
+    "ttft_ms",
+    "total_ms",
+    "load_ms",
+    "prompt_tokens",
+    "completion_tokens",
+    "error",
+    "remote_addr",
+    "anomaly",
+    "clock_jump_ms",
+    "reported_ttft_ms",
+    "gpu_util_avg",
+    "gpu_util_peak",
+    "vram_peak_mb",
+    "cpu_percent_avg",
+    "ram_peak_mb",
+    "first_sentence_ms",
+];
+
+/// Columns of `--export *.csv`, one row per model (or sweep variant). Speed
+/// headings follow `--speed-unit`.
+pub fn summary_columns(unit: SpeedUnit) -> Vec<String> {
+    let heading = unit.heading();
+    vec![
+        "Model".to_string(),
+        "Success Rate".to_string(),
+        format!("Avg {}", heading),
+        format!("Min {}", heading),
+        format!("Max {}", heading),
+        "Avg TTFT (ms)".to_string(),
+        "Avg First Sentence (ms)".to_string(),
+        format!("Median {}", heading),
+        format!("P90 {}", heading),
+        format!("P95 {}", heading),
+        format!("P99 {}", heading),
+        "Median TTFT (ms)".to_string(),
+        "P90 TTFT (ms)".to_string(),
+        "P95 TTFT (ms)".to_string(),
+        "P99 TTFT (ms)".to_string(),
+    ]
+}
+
+/// What a run's CSV exports have in common: an id tying the files of one
+/// run together, and the settings it ran with.
+pub struct RunMeta {
+    pub run_id: String,
+    pub config: Value,
+    /// The run is in the history database under `run_id`
+    pub recorded: bool,
+}
+
+impl RunMeta {
+    /// `prompt` is passed separately so redaction applies to it.
+    pub fn new(config: &BenchmarkConfig, models: &[String], prompt: String) -> Self {
+        Self {
+            run_id: run_id(Utc::now()),
+            config: json!({
+                "models": models,
+                "iterations": config.iterations,
+                "prompt": prompt,
+                "temperature": config.temperature,
+                "max_tokens": config.max_tokens,
+                "seed": config.seed,
+                "endpoint": config.endpoint,
+                "stream": config.stream,
+                "raw": config.raw,
+                "concurrency": config.concurrency,
+                "num_thread": config.num_thread,
+                "options": config.options,
+            }),
+            recorded: false,
+        }
+    }
+}
+
+/// Sortable and unique per process: `20261016T093012Z-1f2e`.
+fn run_id(now: DateTime<Utc>) -> String {
+    format!("{}-{:04x}", now.format("%Y%m%dT%H%M%SZ"), std::process::id() & 0xffff)
+}
+
+#[derive(Serialize)]
+struct Sidecar<'a> {
+    run_id: &'a str,
+    generator: String,
+    created_at: DateTime<Utc>,
+    schema_version: u32,
+    /// `summary` or `raw`
+    kind: &'a str,
+    /// Column layout of a CSV export; empty for other formats
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    columns: &'a [String],
+    config: &'a Value,
+    /// Models the export has no entry for, and why
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    exclusions: Option<&'a Exclusions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    warnings: Option<&'a Warnings>,
+}
+
+/// `results.csv.gz` → `results.meta.json`; other formats keep their
+/// extension (`results.html` → `results.html.meta.json`) so a summary and a
+/// raw export of the same name stay apart.
+pub fn sidecar_path(export_path: &str) -> String {
+    let path = export_path
+        .strip_suffix(".gz")
+        .or_else(|| export_path.strip_suffix(".zst"))
+        .unwrap_or(export_path);
+    let stem = path.strip_suffix(".csv").unwrap_or(path);
+    format!("{}.meta.json", stem)
+}
+
+/// Writes the `.meta.json` sidecar describing an export and returns its
+/// path. A CSV itself stays plain, so its exclusions and warnings go here.
+pub fn write_sidecar(
+    export_path: &str,
+    kind: &str,
+    columns: &[String],
+    meta: &RunMeta,
+    exclusions: Option<&Exclusions>,
+    warnings: Option<&Warnings>,
+) -> Result<String> {
+    let sidecar = Sidecar {
+        run_id: &meta.run_id,
+        generator: format!("{} {}", APP_NAME, APP_VERSION),
+        created_at: Utc::now(),
+        schema_version: SCHEMA_VERSION,
+        kind,
+        columns,
+        config: &meta.config,
+        exclusions,
+        warnings,
+    };
+    let path = sidecar_path(export_path);
+    std::fs::write(&path, serde_json::to_string_pretty(&sidecar)? + "\n")?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_columns_are_append_only() {
+        // Schema v1; extend these lists, never edit them
+        let v1_raw = [
+            "model", "started_at", "finished_at", "success", "tokens_per_second", "ttft_ms", "total_ms", "load_ms",
+            "prompt_tokens", "completion_tokens", "error",
+        ];
+        assert_eq!(&RAW_COLUMNS[..v1_raw.len()], &v1_raw);
+
+        let v1_summary = ["Model", "Success Rate", "Avg Tokens/s", "Min Tokens/s", "Max Tokens/s", "Avg TTFT (ms)"];
+        assert_eq!(&summary_columns(SpeedUnit::Tokens)[..v1_summary.len()], &v1_summary);
+    }
+
+    #[test]
+    fn test_sidecar_path_and_run_id() {
+        assert_eq!(sidecar_path("out/results.csv"), "out/results.meta.json");
+        assert_eq!(sidecar_path("results.csv.zst"), "results.meta.json");
+        assert_eq!(sidecar_path("raw.json.gz"), "raw.json.meta.json");
+
+        let id = run_id(Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 12).unwrap());
+        assert!(id.starts_with("20261016T093012Z-"), "{}", id);
+    }
+}
@@ -0,0 +1,145 @@
+use std::process::Command;
+use std::time::Duration;
+use serde::Serialize;
+
+use crate::container;
+use crate::error::{self, BenchmarkError};
+use crate::gpu;
+use crate::ollama::OllamaClient;
+
+/// Free space below which exports of long runs may fail.
+const MIN_FREE_DISK_MB: u64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+/// One line of the doctor report.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name, status, detail: detail.into() }
+    }
+}
+
+/// Runs every setup check against the Ollama server at `base_url`.
+pub async fn run(base_url: &str, timeout: Duration) -> Vec<Check> {
+    let client = OllamaClient::new(base_url.to_string(), timeout);
+    let mut checks = Vec::new();
+
+    let connected = matches!(client.health_check().await, Ok(true));
+    if connected {
+        checks.push(Check::new("connectivity", CheckStatus::Pass, format!("Ollama reachable at {}", base_url)));
+    } else {
+        let err = BenchmarkError::Unreachable {
+            url: base_url.to_string(),
+            diagnosis: error::diagnose(base_url).await,
+            container_hint: None,
+        };
+        checks.push(Check::new("connectivity", CheckStatus::Fail, first_line(&err.to_string())));
+    }
+
+    if let Some(runtime) = container::detect_runtime() {
+        let status = if container::is_loopback(base_url) { CheckStatus::Warn } else { CheckStatus::Pass };
+        checks.push(Check::new("container", status, format!("running under {:?}", runtime)));
+    }
+
+    if connected {
+        checks.push(match client.version().await {
+            Ok(version) => Check::new("server version", CheckStatus::Pass, version),
+            Err(_) => Check::new("server version", CheckStatus::Warn, "/api/version not available"),
+        });
+
+        checks.push(match client.list_models().await {
+            Ok(models) if models.is_empty() => {
+                Check::new("models", CheckStatus::Warn, "no models installed (ollama pull <model>)")
+            }
+            Ok(models) => Check::new("models", CheckStatus::Pass, format!("{} installed: {}", models.len(), models.join(", "))),
+            Err(e) => Check::new("models", CheckStatus::Fail, first_line(&e.to_string())),
+        });
+    }
+
+    let gpus = gpu::list_gpus();
+    checks.push(if gpus.is_empty() {
+        Check::new("gpu", CheckStatus::Warn, "no NVIDIA GPU visible (CPU-only or non-NVIDIA)")
+    } else {
+        let names: Vec<String> = gpus
+            .iter()
+            .map(|g| format!("#{} {} ({} MiB)", g.index, g.name, g.memory_total_mb))
+            .collect();
+        Check::new("gpu", CheckStatus::Pass, names.join(", "))
+    });
+
+    checks.push(match free_disk_mb() {
+        Some(mb) if mb < MIN_FREE_DISK_MB => {
+            Check::new("disk space", CheckStatus::Warn, format!("{} MB free in the current directory", mb))
+        }
+        Some(mb) => Check::new("disk space", CheckStatus::Pass, format!("{} MB free in the current directory", mb)),
+        None => Check::new("disk space", CheckStatus::Warn, "could not determine free space"),
+    });
+
+    checks
+}
+
+pub fn failures(checks: &[Check]) -> usize {
+    checks.iter().filter(|c| c.status == CheckStatus::Fail).count()
+}
+
+pub fn print_report(checks: &[Check]) {
+    println!("🩺 ollama-bench doctor\n");
+    for check in checks {
+        let icon = match check.status {
+            CheckStatus::Pass => "✅",
+            CheckStatus::Warn => "⚠️ ",
+            CheckStatus::Fail => "❌",
+        };
+        println!("{} {:15} {}", icon, check.name, check.detail);
+    }
+}
+
+/// The message line of a user-facing error, without its emoji and hint.
+fn first_line(message: &str) -> String {
+    message.lines().next().unwrap_or_default().trim_start_matches("❌ ").to_string()
+}
+
+fn free_disk_mb() -> Option<u64> {
+    let output = Command::new("df").args(["-Pk", "."]).output().ok()?;
+    parse_df_available_kb(&String::from_utf8_lossy(&output.stdout)).map(|kb| kb / 1024)
+}
+
+/// Reads the "Available" column from POSIX `df -Pk` output.
+fn parse_df_available_kb(output: &str) -> Option<u64> {
+    output.lines().nth(1)?.split_whitespace().nth(3)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_df_available() {
+        let output = "Filesystem     1024-blocks     Used Available Capacity Mounted on\n\
+                      /dev/vda         264212084 19443544  81358732      20% /\n";
+        assert_eq!(parse_df_available_kb(output), Some(81358732));
+        assert_eq!(parse_df_available_kb(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_fails() {
+        let checks = run("http://127.0.0.1:9", Duration::from_secs(1)).await;
+        assert_eq!(checks[0].name, "connectivity");
+        assert_eq!(checks[0].status, CheckStatus::Fail);
+        assert!(failures(&checks) >= 1);
+        assert!(!checks.iter().any(|c| c.name == "models"));
+    }
+}
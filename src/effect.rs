@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::MAX_ITERATIONS;
+use crate::types::ModelSummary;
+
+/// Two-sided 95% critical values of Student's t for 1 to 30 degrees of
+/// freedom; beyond that the normal 1.96 is close enough.
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145, 2.131,
+    2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+];
+
+pub fn t_critical_95(degrees_of_freedom: usize) -> f64 {
+    degrees_of_freedom
+        .checked_sub(1)
+        .and_then(|index| T_CRITICAL_95.get(index))
+        .copied()
+        .unwrap_or(1.96)
+}
+
+/// Arithmetic mean; zero for no values.
+pub fn mean(values: impl IntoIterator<Item = f64>) -> f64 {
+    let (sum, count) = values.into_iter().fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count > 0 {
+        sum / count as f64
+    } else {
+        0.0
+    }
+}
+
+/// Sample standard deviation; `None` for fewer than two values.
+pub fn stddev(values: &[f64]) -> Option<f64> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = mean(values.iter().copied());
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+/// Percentiles and relative spread of one per-iteration metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub median: f64,
+    pub p90: f64,
+    pub p95: f64,
+    pub p99: f64,
+    /// Coefficient of variation (standard deviation over the mean); `None`
+    /// for fewer than two values or a zero mean
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cv: Option<f64>,
+}
+
+impl Distribution {
+    /// `None` for no values.
+    pub fn of(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let median = if sorted.len() % 2 == 0 {
+            (sorted[sorted.len() / 2 - 1] + sorted[sorted.len() / 2]) / 2.0
+        } else {
+            sorted[sorted.len() / 2]
+        };
+
+        Some(Self {
+            median,
+            p90: percentile(&sorted, 90),
+            p95: percentile(&sorted, 95),
+            p99: percentile(&sorted, 99),
+            cv: stddev(values).filter(|_| mean > 0.0).map(|s| s / mean),
+        })
+    }
+}
+
+/// Nearest-rank percentile of already sorted values.
+pub fn percentile(sorted: &[f64], pct: usize) -> f64 {
+    let rank = (pct * sorted.len() + 99) / 100;
+    sorted[rank.saturating_sub(1)]
+}
+
+/// Mean, standard deviation and sample count of one metric.
+#[derive(Debug, Clone, Copy)]
+pub struct Moments {
+    pub mean: f64,
+    pub stddev: f64,
+    pub n: f64,
+}
+
+impl Moments {
+    /// `None` for fewer than two values.
+    pub fn of(values: &[f64]) -> Option<Self> {
+        Some(Self {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            stddev: stddev(values)?,
+            n: values.len() as f64,
+        })
+    }
+
+    /// Tokens/s moments of a summary's successful iterations, if it recorded a spread.
+    pub fn speed(summary: &ModelSummary) -> Option<Self> {
+        Some(Self {
+            mean: summary.avg_tokens_per_second,
+            stddev: summary.stddev_tokens_per_second?,
+            n: successful(summary),
+        })
+    }
+
+    pub fn ttft(summary: &ModelSummary) -> Option<Self> {
+        Some(Self {
+            mean: summary.avg_ttft_ms,
+            stddev: summary.stddev_ttft_ms?,
+            n: successful(summary),
+        })
+    }
+}
+
+fn successful(summary: &ModelSummary) -> f64 {
+    (summary.total_tests as f64 * summary.success_rate).round()
+}
+
+/// Cohen's d of `a` over `b`: the mean difference in units of the pooled
+/// standard deviation. `None` when there is no spread to measure it against.
+pub fn cohens_d(a: Moments, b: Moments) -> Option<f64> {
+    if a.n < 2.0 || b.n < 2.0 {
+        return None;
+    }
+    let pooled_variance = ((a.n - 1.0) * a.stddev.powi(2) + (b.n - 1.0) * b.stddev.powi(2)) / (a.n + b.n - 2.0);
+    (pooled_variance > 0.0).then(|| (a.mean - b.mean) / pooled_variance.sqrt())
+}
+
+/// Iterations for the 95% confidence interval of the mean to reach
+/// ±`margin` (a fraction of the mean) at the observed spread; `None` when
+/// there is no spread yet or it would take more than `MAX_ITERATIONS`.
+pub fn iterations_for_margin(moments: Moments, margin: f64) -> Option<u32> {
+    if moments.n < 2.0 || moments.mean <= 0.0 {
+        return None;
+    }
+    let relative = moments.stddev / moments.mean;
+    // t shrinks as n grows, so search upward rather than solve for n
+    (2..=MAX_ITERATIONS).find(|&n| t_critical_95(n as usize - 1) * relative / (n as f64).sqrt() <= margin)
+}
+
+/// Iterations one model needs for a 95% CI of tok/s within a margin.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SampleSize {
+    pub model: String,
+    /// Measured iterations this run
+    pub ran: u64,
+    /// Measured iterations needed; `None` when the spread is too wide to
+    /// narrow by repetition
+    pub needed: Option<u32>,
+    /// `-n` for a rerun: `needed` plus the warm-up iterations this run dropped
+    pub recommended_iterations: Option<u32>,
+}
+
+impl SampleSize {
+    pub fn is_enough(&self) -> bool {
+        self.needed.is_some_and(|n| n as u64 <= self.ran)
+    }
+}
+
+/// [`SampleSize`] of every summary with at least two measured iterations.
+pub fn sample_sizes(summaries: &[ModelSummary], margin: f64) -> Vec<SampleSize> {
+    summaries
+        .iter()
+        .filter_map(|summary| {
+            let moments = Moments::speed(summary).filter(|m| m.n >= 2.0 && m.mean > 0.0)?;
+            let needed = iterations_for_margin(moments, margin);
+            Some(SampleSize {
+                model: summary.label(),
+                ran: moments.n as u64,
+                needed,
+                recommended_iterations: needed.map(|n| n + summary.warmup_iterations as u32),
+            })
+        })
+        .collect()
+}
+
+/// Half-width of the 95% confidence interval of the mean, as a fraction
+/// of the mean; `None` below two samples or for a zero mean.
+pub fn relative_margin(moments: Moments) -> Option<f64> {
+    if moments.n < 2.0 || moments.mean <= 0.0 {
+        return None;
+    }
+    Some(t_critical_95(moments.n as usize - 1) * moments.stddev / moments.n.sqrt() / moments.mean)
+}
+
+/// Conventional reading of |d| (Cohen, 1988).
+pub fn magnitude(d: f64) -> &'static str {
+    match d.abs() {
+        d if d < 0.2 => "negligible",
+        d if d < 0.5 => "small",
+        d if d < 0.8 => "medium",
+        _ => "large",
+    }
+}
+
+pub fn is_negligible(d: f64) -> bool {
+    d.abs() < 0.2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iterations_for_margin() {
+        let moments = |stddev: f64, n: f64| Moments { mean: 20.0, stddev, n };
+        // 5% CV: t(n-1) * 0.05 / sqrt(n) <= 0.05 first holds at n = 7 (2.447 / 2.646)
+        assert_eq!(iterations_for_margin(moments(1.0, 5.0), 0.05), Some(7));
+        assert_eq!(iterations_for_margin(moments(0.0, 5.0), 0.05), Some(2));
+        assert_eq!(iterations_for_margin(moments(20.0, 5.0), 0.05), None);
+        assert_eq!(iterations_for_margin(moments(1.0, 1.0), 0.05), None);
+        assert!((relative_margin(moments(1.0, 7.0)).unwrap() - 2.447 * 0.05 / 7f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sample_sizes_add_warmup() {
+        let summary = ModelSummary {
+            model: "m".to_string(),
+            total_tests: 5,
+            success_rate: 1.0,
+            avg_tokens_per_second: 20.0,
+            stddev_tokens_per_second: Some(1.0),
+            warmup_iterations: 1,
+            ..Default::default()
+        };
+        let size = &sample_sizes(&[summary], 0.05)[0];
+        assert_eq!((size.ran, size.needed, size.recommended_iterations), (5, Some(7), Some(8)));
+        assert!(!size.is_enough());
+    }
+
+    #[test]
+    fn test_stddev() {
+        assert_eq!(stddev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]), Some((32.0f64 / 7.0).sqrt()));
+        assert_eq!(stddev(&[3.0]), None);
+    }
+
+    #[test]
+    fn test_distribution() {
+        let values: Vec<f64> = (1..=20).rev().map(f64::from).collect();
+        let dist = Distribution::of(&values).unwrap();
+        assert_eq!((dist.median, dist.p90, dist.p95, dist.p99), (10.5, 18.0, 19.0, 20.0));
+        assert_eq!(dist.cv, stddev(&values).map(|s| s / 10.5));
+
+        let single = Distribution::of(&[42.0]).unwrap();
+        assert_eq!((single.median, single.p99, single.cv), (42.0, 42.0, None));
+        assert_eq!(Distribution::of(&[]), None);
+    }
+
+    #[test]
+    fn test_cohens_d() {
+        let a = Moments { mean: 52.0, stddev: 2.0, n: 10.0 };
+        let b = Moments { mean: 50.0, stddev: 2.0, n: 10.0 };
+        assert_eq!(cohens_d(a, b), Some(1.0));
+        assert_eq!(magnitude(1.0), "large");
+
+        // 5% faster, but the runs are far noisier than that
+        let noisy = |mean| Moments { mean, stddev: 20.0, n: 10.0 };
+        let d = cohens_d(noisy(52.5), noisy(50.0)).unwrap();
+        assert_eq!(magnitude(d), "negligible");
+
+        assert_eq!(cohens_d(Moments { n: 1.0, ..a }, b), None);
+        assert_eq!(cohens_d(Moments { stddev: 0.0, ..a }, Moments { stddev: 0.0, ..b }), None);
+    }
+}
@@ -0,0 +1,128 @@
+use serde::Serialize;
+
+use crate::effect::mean;
+use crate::types::{BenchmarkResult, Endpoint, ModelSummary};
+
+/// Averages for one model's requests through one endpoint.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EndpointStats {
+    pub requests: u64,
+    pub avg_latency_ms: f64,
+    /// Wall-clock time not spent loading, evaluating the prompt or generating:
+    /// HTTP, templating and (de)serialization
+    pub avg_overhead_ms: f64,
+    pub avg_ttft_ms: f64,
+    /// Prompt tokens after templating; chat wraps the prompt in the chat template
+    pub avg_prompt_tokens: f64,
+}
+
+/// Paired generate-vs-chat comparison for one model.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointComparison {
+    pub model: String,
+    pub generate: EndpointStats,
+    pub chat: EndpointStats,
+    /// Chat overhead minus generate overhead; positive means chat costs more
+    pub overhead_difference_ms: f64,
+}
+
+/// Time of a request spent outside the model itself.
+pub fn overhead_ms(result: &BenchmarkResult) -> f64 {
+    let generation_ms = if result.tokens_per_second > 0.0 {
+        result.completion_tokens as f64 / result.tokens_per_second * 1000.0
+    } else {
+        0.0
+    };
+    let compute_ms = result.load_duration_ms as f64 + result.time_to_first_token_ms as f64 + generation_ms;
+    (result.total_duration_ms as f64 - compute_ms).max(0.0)
+}
+
+fn stats(results: &[&BenchmarkResult]) -> EndpointStats {
+    if results.is_empty() {
+        return EndpointStats::default();
+    }
+    let avg = |value: fn(&BenchmarkResult) -> f64| mean(results.iter().map(|r| value(r)));
+
+    EndpointStats {
+        requests: results.len() as u64,
+        avg_latency_ms: avg(|r| r.total_duration_ms as f64),
+        avg_overhead_ms: avg(overhead_ms),
+        avg_ttft_ms: avg(|r| r.time_to_first_token_ms as f64),
+        avg_prompt_tokens: avg(|r| r.prompt_tokens as f64),
+    }
+}
+
+/// One comparison per summary whose successful requests went through both
+/// endpoints; a cancelled run can leave chat without any.
+pub fn build_report(summaries: &[ModelSummary], results: &[BenchmarkResult]) -> Vec<EndpointComparison> {
+    summaries
+        .iter()
+        .filter_map(|summary| {
+            let through = |endpoint: Endpoint| -> Vec<&BenchmarkResult> {
+                results
+                    .iter()
+                    .filter(|r| summary.owns(r) && r.success && r.endpoint == endpoint)
+                    .collect()
+            };
+            let generate = through(Endpoint::Generate);
+            let chat = through(Endpoint::Chat);
+            if generate.is_empty() || chat.is_empty() {
+                return None;
+            }
+
+            let (generate, chat) = (stats(&generate), stats(&chat));
+            Some(EndpointComparison {
+                model: summary.label(),
+                overhead_difference_ms: chat.avg_overhead_ms - generate.avg_overhead_ms,
+                generate,
+                chat,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, endpoint: Endpoint, total_ms: u64, ttft_ms: u64, prompt_tokens: u32) -> BenchmarkResult {
+        BenchmarkResult {
+            endpoint,
+            total_duration_ms: total_ms,
+            time_to_first_token_ms: ttft_ms,
+            prompt_tokens,
+            // 50 tokens at 50 tok/s: one second of generation
+            completion_tokens: 50,
+            ..BenchmarkResult::ok(model, 50.0)
+        }
+    }
+
+    #[test]
+    fn test_overhead_excludes_model_time() {
+        let mut r = result("a", Endpoint::Chat, 1300, 200, 10);
+        r.load_duration_ms = 50;
+        assert_eq!(overhead_ms(&r), 50.0);
+
+        // Server timings larger than the wall clock never go negative
+        assert_eq!(overhead_ms(&result("a", Endpoint::Chat, 900, 200, 10)), 0.0);
+    }
+
+    #[test]
+    fn test_build_report_pairs_endpoints() {
+        let summaries = ["a", "b"].map(|model| ModelSummary::from_results(model.to_string(), &[]));
+        let results = vec![
+            result("a", Endpoint::Generate, 1220, 200, 10),
+            result("a", Endpoint::Generate, 1240, 200, 10),
+            result("a", Endpoint::Chat, 1260, 210, 24),
+            result("b", Endpoint::Generate, 1300, 200, 10),
+        ];
+
+        let report = build_report(&summaries, &results);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].generate.requests, 2);
+        assert_eq!(report[0].generate.avg_overhead_ms, 30.0);
+        assert_eq!(report[0].chat.avg_overhead_ms, 50.0);
+        assert_eq!(report[0].chat.avg_prompt_tokens, 24.0);
+        assert_eq!(report[0].overhead_difference_ms, 20.0);
+    }
+}
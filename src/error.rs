@@ -1,4 +1,9 @@
 use std::fmt;
+use std::time::Duration;
+use tokio::net::{lookup_host, TcpStream};
+use tokio::time::timeout;
+
+use crate::i18n::{t, tf, Msg};
 
 #[derive(Debug)]
 pub enum BenchmarkError {
@@ -10,36 +15,113 @@ pub enum BenchmarkError {
     ParseError(String),
     IoError(String),
     ConfigError(String),
+    /// Connection failure explained by active probes of the server URL
+    Unreachable {
+        url: String,
+        diagnosis: Diagnosis,
+        /// Container-specific advice, when the setup looks containerized
+        container_hint: Option<String>,
+    },
+    /// `doctor` found this many failing checks
+    DoctorFailed(usize),
+    /// `--select` found no model meeting the criteria
+    NoModelSelected,
+    /// Models whose speed dropped past `--fail-on-regression`, described
+    SpeedRegressed(Vec<String>),
+    /// `duel A B` ended without a round both models completed; holds "A vs B"
+    DuelIncomplete(String),
+    /// A spawned request task panicked or was aborted
+    TaskFailed(String),
+}
+
+/// What the connectivity probes found at the configured Ollama URL.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Diagnosis {
+    /// The host name does not resolve
+    UnknownHost { host: String },
+    /// The host resolves but nothing accepts connections on the port
+    PortClosed { host: String, port: u16 },
+    /// Connections are accepted but no HTTP response arrives
+    Unresponsive,
+    /// An HTTP server answers, but it is not Ollama
+    NotOllama { server: Option<String> },
+    /// Ollama answers but its API returns errors
+    OllamaUnhealthy { version: Option<String> },
 }
 
 impl fmt::Display for BenchmarkError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
+        let (message, hint) = match self {
             BenchmarkError::OllamaNotRunning => {
-                write!(f, "❌ Ollama is not running\n💡 Start with: ollama serve")
+                (t(Msg::OllamaNotRunning).to_string(), t(Msg::HintStartOllama).to_string())
             }
             BenchmarkError::ModelNotFound(model) => {
-                write!(f, "❌ Model '{}' not found\n💡 Install with: ollama pull {}", model, model)
+                (tf(Msg::ModelNotFound, model), tf(Msg::HintPullModel, model))
             }
             BenchmarkError::NetworkTimeout(seconds) => {
-                write!(f, "❌ Network timeout after {}s\n💡 Try increasing --timeout", seconds)
+                (tf(Msg::NetworkTimeout, seconds), t(Msg::HintIncreaseTimeout).to_string())
             }
             BenchmarkError::InvalidModel(model) => {
-                write!(f, "❌ Invalid model name: '{}'\n💡 Model names should be in format: model:tag (e.g., llama2:7b)", model)
+                (tf(Msg::InvalidModel, model), t(Msg::HintModelFormat).to_string())
             }
             BenchmarkError::ConnectionFailed(url) => {
-                write!(f, "❌ Failed to connect to Ollama at {}\n💡 Check if Ollama is running and accessible", url)
+                (tf(Msg::ConnectionFailed, url), t(Msg::HintCheckOllama).to_string())
             }
             BenchmarkError::ParseError(msg) => {
-                write!(f, "❌ Failed to parse response: {}\n💡 This might be a compatibility issue with your Ollama version", msg)
+                (tf(Msg::ParseError, msg), t(Msg::HintVersionMismatch).to_string())
             }
             BenchmarkError::IoError(msg) => {
-                write!(f, "❌ I/O error: {}\n💡 Check file permissions and disk space", msg)
+                (tf(Msg::IoError, msg), t(Msg::HintPermissions).to_string())
             }
             BenchmarkError::ConfigError(msg) => {
-                write!(f, "❌ Configuration error: {}\n💡 {}", msg, msg)
+                (tf(Msg::ConfigError, msg), msg.clone())
+            }
+            BenchmarkError::DoctorFailed(count) => {
+                (tf(Msg::DoctorFailed, count), t(Msg::HintDoctorFailed).to_string())
+            }
+            BenchmarkError::NoModelSelected => {
+                (t(Msg::NoModelSelected).to_string(), t(Msg::HintNoModelSelected).to_string())
             }
+            BenchmarkError::SpeedRegressed(regressions) => {
+                (tf(Msg::SpeedRegressed, regressions.join("; ")), t(Msg::HintSpeedRegressed).to_string())
+            }
+            BenchmarkError::DuelIncomplete(pair) => {
+                (tf(Msg::DuelIncomplete, pair), t(Msg::HintDuelIncomplete).to_string())
+            }
+            BenchmarkError::TaskFailed(msg) => {
+                (tf(Msg::TaskFailed, msg), t(Msg::HintTaskFailed).to_string())
+            }
+            BenchmarkError::Unreachable { url, diagnosis, .. } => match diagnosis {
+                Diagnosis::UnknownHost { host } => {
+                    (tf(Msg::UnknownHost, host), t(Msg::HintUnknownHost).to_string())
+                }
+                Diagnosis::PortClosed { host, port } => {
+                    (tf(Msg::PortClosed, format!("{}:{}", host, port)), tf(Msg::HintPortClosed, port))
+                }
+                Diagnosis::Unresponsive => {
+                    (tf(Msg::Unresponsive, url), t(Msg::HintUnresponsive).to_string())
+                }
+                Diagnosis::NotOllama { server } => {
+                    let found = match server {
+                        Some(server) => format!("{} ({})", url, server),
+                        None => url.clone(),
+                    };
+                    (tf(Msg::NotOllama, found), t(Msg::HintNotOllama).to_string())
+                }
+                Diagnosis::OllamaUnhealthy { version } => {
+                    let found = match version {
+                        Some(version) => format!("{} (v{})", url, version),
+                        None => url.clone(),
+                    };
+                    (tf(Msg::OllamaUnhealthy, found), t(Msg::HintOllamaUnhealthy).to_string())
+                }
+            },
+        };
+        write!(f, "❌ {}\n💡 {}", message, hint)?;
+        if let BenchmarkError::Unreachable { container_hint: Some(extra), .. } = self {
+            write!(f, "\n💡 {}", extra)?;
         }
+        Ok(())
     }
 }
 
@@ -63,6 +145,18 @@ impl From<reqwest::Error> for BenchmarkError {
     }
 }
 
+impl From<rusqlite::Error> for BenchmarkError {
+    fn from(error: rusqlite::Error) -> Self {
+        BenchmarkError::IoError(format!("history database: {}", error))
+    }
+}
+
+impl From<tokio::task::JoinError> for BenchmarkError {
+    fn from(error: tokio::task::JoinError) -> Self {
+        BenchmarkError::TaskFailed(error.to_string())
+    }
+}
+
 impl From<serde_json::Error> for BenchmarkError {
     fn from(error: serde_json::Error) -> Self {
         BenchmarkError::ParseError(error.to_string())
@@ -71,6 +165,70 @@ impl From<serde_json::Error> for BenchmarkError {
 
 pub type Result<T> = std::result::Result<T, BenchmarkError>;
 
+/// Time allowed for each connectivity probe.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Probes `base_url` step by step (DNS, TCP, HTTP, Ollama API) to explain
+/// why the health check failed, so hints match the actual problem.
+pub async fn diagnose(base_url: &str) -> Diagnosis {
+    let Ok(url) = reqwest::Url::parse(base_url) else {
+        return Diagnosis::UnknownHost { host: base_url.to_string() };
+    };
+    let host = url.host_str().unwrap_or_default().to_string();
+    let port = url.port_or_known_default().unwrap_or(11434);
+    
+    let addrs: Vec<_> = match timeout(PROBE_TIMEOUT, lookup_host((host.as_str(), port))).await {
+        Ok(Ok(addrs)) => addrs.collect(),
+        _ => Vec::new(),
+    };
+    if addrs.is_empty() {
+        return Diagnosis::UnknownHost { host };
+    }
+    
+    let mut port_open = false;
+    for addr in addrs {
+        if matches!(timeout(PROBE_TIMEOUT, TcpStream::connect(addr)).await, Ok(Ok(_))) {
+            port_open = true;
+            break;
+        }
+    }
+    if !port_open {
+        return Diagnosis::PortClosed { host, port };
+    }
+    
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+    let base = base_url.trim_end_matches('/');
+    
+    // Ollama answers `GET /` with "Ollama is running"
+    let Ok(root) = client.get(base).send().await else {
+        return Diagnosis::Unresponsive;
+    };
+    let server = root
+        .headers()
+        .get(reqwest::header::SERVER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let body = root.text().await.unwrap_or_default();
+    
+    let version = match client.get(format!("{}/api/version", base)).send().await {
+        Ok(response) if response.status().is_success() => response
+            .json::<serde_json::Value>()
+            .await
+            .ok()
+            .and_then(|v| v["version"].as_str().map(str::to_string)),
+        _ => None,
+    };
+    
+    if body.contains("Ollama") || version.is_some() {
+        Diagnosis::OllamaUnhealthy { version }
+    } else {
+        Diagnosis::NotOllama { server }
+    }
+}
+
 pub fn validate_model_name(model: &str) -> Result<()> {
     if model.is_empty() {
         return Err(BenchmarkError::InvalidModel("empty model name".to_string()));
@@ -100,6 +258,37 @@ mod tests {
         assert!(err.to_string().contains("60s"));
     }
     
+    #[test]
+    fn test_unreachable_display() {
+        let err = BenchmarkError::Unreachable {
+            url: "http://localhost:8080".to_string(),
+            diagnosis: Diagnosis::PortClosed { host: "localhost".to_string(), port: 8080 },
+            container_hint: None,
+        };
+        assert!(err.to_string().contains("localhost:8080"));
+        assert!(err.to_string().contains("-p 8080:11434"));
+        
+        let err = BenchmarkError::Unreachable {
+            url: "http://localhost:8080".to_string(),
+            diagnosis: Diagnosis::NotOllama { server: Some("nginx".to_string()) },
+            container_hint: Some("check the port mapping".to_string()),
+        };
+        assert!(err.to_string().contains("nginx"));
+        assert!(!err.to_string().contains("ollama serve"));
+        assert!(err.to_string().ends_with("\n💡 check the port mapping"));
+    }
+    
+    #[tokio::test]
+    async fn test_diagnose_closed_port() {
+        // Bind then drop a listener to get a local port nobody is using
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        
+        let diagnosis = diagnose(&format!("http://127.0.0.1:{}", port)).await;
+        assert_eq!(diagnosis, Diagnosis::PortClosed { host: "127.0.0.1".to_string(), port });
+    }
+    
     #[test]
     fn test_validate_model_name() {
         assert!(validate_model_name("llama2:7b").is_ok());
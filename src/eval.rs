@@ -0,0 +1,227 @@
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+/// Built-in accuracy tasks, each a few dozen prompt/answer pairs scored by
+/// exact match after task-specific answer extraction.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum EvalTask {
+    /// Two-operand integer arithmetic
+    Arithmetic,
+    /// Multiple-choice general knowledge questions (A-D)
+    MmluMini,
+    /// Extract name/age/city from a sentence as JSON
+    JsonExtract,
+}
+
+pub struct EvalItem {
+    pub prompt: String,
+    pub answer: String,
+}
+
+/// Accuracy of one model on one eval task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalScore {
+    pub task: String,
+    pub correct: u32,
+    pub total: u32,
+}
+
+impl EvalScore {
+    pub fn accuracy(&self) -> f64 {
+        if self.total > 0 {
+            self.correct as f64 / self.total as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+const ARITHMETIC: &[(i64, char, i64)] = &[
+    (17, '+', 25), (48, '+', 97), (123, '+', 456), (999, '+', 1), (305, '+', 788),
+    (64, '-', 29), (500, '-', 137), (81, '-', 94), (1000, '-', 1), (732, '-', 268),
+    (12, '*', 13), (7, '*', 48), (25, '*', 25), (36, '*', 19), (101, '*', 11),
+    (144, '/', 12), (625, '/', 25), (96, '/', 8), (1001, '/', 7), (378, '/', 14),
+    (58, '+', 67), (420, '-', 69), (15, '*', 16), (512, '/', 16), (243, '+', 758),
+    (88, '*', 9), (1024, '-', 512), (729, '/', 27), (333, '+', 667), (45, '*', 22),
+];
+
+const MMLU_MINI: &[(&str, [&str; 4], char)] = &[
+    ("What is the chemical symbol for gold?", ["Ag", "Au", "Gd", "Go"], 'B'),
+    ("Which planet is known as the Red Planet?", ["Venus", "Jupiter", "Mars", "Mercury"], 'C'),
+    ("What is the largest ocean on Earth?", ["Atlantic", "Indian", "Arctic", "Pacific"], 'D'),
+    ("Who wrote 'Romeo and Juliet'?", ["William Shakespeare", "Charles Dickens", "Jane Austen", "Mark Twain"], 'A'),
+    ("What is the boiling point of water at sea level in Celsius?", ["90", "100", "110", "120"], 'B'),
+    ("Which gas do plants primarily absorb for photosynthesis?", ["Oxygen", "Nitrogen", "Carbon dioxide", "Helium"], 'C'),
+    ("How many sides does a hexagon have?", ["5", "6", "7", "8"], 'B'),
+    ("What is the powerhouse of the cell?", ["Nucleus", "Ribosome", "Golgi apparatus", "Mitochondrion"], 'D'),
+    ("Which data structure uses first-in, first-out ordering?", ["Queue", "Stack", "Tree", "Heap"], 'A'),
+    ("What is the capital of Japan?", ["Osaka", "Kyoto", "Tokyo", "Nagoya"], 'C'),
+    ("What is the square root of 144?", ["10", "11", "12", "14"], 'C'),
+    ("Which element has atomic number 1?", ["Hydrogen", "Helium", "Lithium", "Carbon"], 'A'),
+    ("What is the time complexity of binary search?", ["O(n)", "O(log n)", "O(n log n)", "O(1)"], 'B'),
+    ("Which organ pumps blood through the human body?", ["Lungs", "Liver", "Kidney", "Heart"], 'D'),
+    ("In which year did World War II end?", ["1943", "1944", "1945", "1946"], 'C'),
+    ("What is the freezing point of water in Fahrenheit?", ["0", "32", "100", "212"], 'B'),
+    ("Which protocol is used to securely browse the web?", ["HTTP", "FTP", "HTTPS", "SMTP"], 'C'),
+    ("What is the smallest prime number?", ["0", "1", "2", "3"], 'C'),
+    ("Which continent is the Sahara Desert located on?", ["Asia", "Africa", "Australia", "South America"], 'B'),
+    ("What is the hardest natural substance?", ["Diamond", "Quartz", "Iron", "Granite"], 'A'),
+    ("Who developed the theory of general relativity?", ["Isaac Newton", "Niels Bohr", "Albert Einstein", "Max Planck"], 'C'),
+    ("What does CPU stand for?", ["Central Processing Unit", "Computer Power Unit", "Core Program Utility", "Central Peripheral Unit"], 'A'),
+    ("How many bits are in a byte?", ["4", "8", "16", "32"], 'B'),
+    ("Which is the longest river in South America?", ["Orinoco", "Paraná", "Magdalena", "Amazon"], 'D'),
+];
+
+const JSON_NAMES: &[&str] = &["Alice", "Kenji", "Maria", "Tomasz", "Aisha", "Lukas", "Priya", "Diego"];
+const JSON_CITIES: &[&str] = &["Berlin", "Osaka", "Lisbon", "Toronto", "Nairobi", "Seoul"];
+const JSON_TEMPLATES: &[&str] = &[
+    "{name} is {age} years old and lives in {city}.",
+    "Last year {name} moved to {city}; {name} just turned {age}.",
+    "Meet {name}, a {age}-year-old engineer based in {city}.",
+];
+const JSON_ITEM_COUNT: usize = 24;
+
+impl EvalTask {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EvalTask::Arithmetic => "arithmetic",
+            EvalTask::MmluMini => "mmlu-mini",
+            EvalTask::JsonExtract => "json-extract",
+        }
+    }
+
+    pub fn items(&self) -> Vec<EvalItem> {
+        match self {
+            EvalTask::Arithmetic => ARITHMETIC
+                .iter()
+                .map(|&(a, op, b)| EvalItem {
+                    prompt: format!(
+                        "What is {} {} {}? Reply with only the integer result.",
+                        a, op, b
+                    ),
+                    answer: match op {
+                        '+' => a + b,
+                        '-' => a - b,
+                        '*' => a * b,
+                        _ => a / b,
+                    }
+                    .to_string(),
+                })
+                .collect(),
+            EvalTask::MmluMini => MMLU_MINI
+                .iter()
+                .map(|(question, choices, answer)| EvalItem {
+                    prompt: format!(
+                        "{}\nA) {}\nB) {}\nC) {}\nD) {}\nReply with only the letter of the correct answer.",
+                        question, choices[0], choices[1], choices[2], choices[3]
+                    ),
+                    answer: answer.to_string(),
+                })
+                .collect(),
+            EvalTask::JsonExtract => (0..JSON_ITEM_COUNT)
+                .map(|i| {
+                    let name = JSON_NAMES[i % JSON_NAMES.len()];
+                    let city = JSON_CITIES[(i * 5) % JSON_CITIES.len()];
+                    let age = 21 + (i * 7) % 50;
+                    let sentence = JSON_TEMPLATES[i % JSON_TEMPLATES.len()]
+                        .replace("{name}", name)
+                        .replace("{city}", city)
+                        .replace("{age}", &age.to_string());
+                    EvalItem {
+                        prompt: format!(
+                            "Extract the person's name, age and city from the text below. Reply with only a JSON object with keys \"name\", \"age\" (integer) and \"city\".\n\nText: {}",
+                            sentence
+                        ),
+                        answer: format!("{}|{}|{}", name, age, city),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    /// Extracts the task's answer from a model response and compares it to `expected`.
+    pub fn is_correct(&self, response: &str, expected: &str) -> bool {
+        match self {
+            EvalTask::Arithmetic => extract_last_integer(response).as_deref() == Some(expected),
+            EvalTask::MmluMini => extract_choice(response) == expected.chars().next(),
+            EvalTask::JsonExtract => extract_person(response).as_deref() == Some(expected),
+        }
+    }
+}
+
+fn extract_last_integer(response: &str) -> Option<String> {
+    let cleaned = response.replace(',', "");
+    let mut last = None;
+    let mut current = String::new();
+
+    for c in cleaned.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_digit() || (c == '-' && current.is_empty()) {
+            current.push(c);
+        } else {
+            if current.chars().any(|c| c.is_ascii_digit()) {
+                last = Some(current.clone());
+            }
+            current.clear();
+        }
+    }
+
+    last
+}
+
+fn extract_choice(response: &str) -> Option<char> {
+    // Accept "B", "B)", "(B)", "Answer: B" but not letters inside words
+    response
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.eq_ignore_ascii_case("answer"))
+        .find(|word| matches!(*word, "A" | "B" | "C" | "D"))
+        .and_then(|word| word.chars().next())
+}
+
+fn extract_person(response: &str) -> Option<String> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    let value: serde_json::Value = serde_json::from_str(response.get(start..=end)?).ok()?;
+
+    let age = match &value["age"] {
+        serde_json::Value::Number(n) => n.as_i64()?,
+        serde_json::Value::String(s) => s.trim().parse().ok()?,
+        _ => return None,
+    };
+
+    Some(format!(
+        "{}|{}|{}",
+        value["name"].as_str()?.trim(),
+        age,
+        value["city"].as_str()?.trim()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_counts() {
+        assert_eq!(EvalTask::Arithmetic.items().len(), 30);
+        assert_eq!(EvalTask::MmluMini.items().len(), 24);
+        assert_eq!(EvalTask::JsonExtract.items().len(), 24);
+        assert_eq!(EvalTask::Arithmetic.items()[0].answer, "42");
+    }
+
+    #[test]
+    fn test_scoring() {
+        assert!(EvalTask::Arithmetic.is_correct("The answer is 42.", "42"));
+        assert!(EvalTask::Arithmetic.is_correct("1,000", "1000"));
+        assert!(!EvalTask::Arithmetic.is_correct("43", "42"));
+
+        assert!(EvalTask::MmluMini.is_correct("B) Au", "B"));
+        assert!(EvalTask::MmluMini.is_correct("Answer: C", "C"));
+        assert!(!EvalTask::MmluMini.is_correct("A", "B"));
+
+        assert!(EvalTask::JsonExtract.is_correct(
+            "```json\n{\"name\": \"Alice\", \"age\": 21, \"city\": \"Berlin\"}\n```",
+            "Alice|21|Berlin"
+        ));
+        assert!(!EvalTask::JsonExtract.is_correct("Alice, 21, Berlin", "Alice|21|Berlin"));
+    }
+}
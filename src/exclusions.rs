@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// A model without a usable row in the results, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExcludedModel {
+    pub model: String,
+    /// Server the model was excluded on, when several were benchmarked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Sweep variant whose iterations all failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    pub reason: String,
+}
+
+impl ExcludedModel {
+    /// Display name, matching [`ModelSummary::label`].
+    pub fn label(&self) -> String {
+        let model = match &self.server {
+            Some(server) => format!("{} @ {}", self.model, server),
+            None => self.model.clone(),
+        };
+        match &self.variant {
+            Some(variant) => format!("{} [{}]", model, variant),
+            None => model,
+        }
+    }
+}
+
+/// Models every output format lists separately instead of dropping them.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Exclusions {
+    /// Never benchmarked: missing on the server, or the run was cancelled first
+    pub skipped: Vec<ExcludedModel>,
+    /// Benchmarked, but every iteration failed
+    pub failed: Vec<ExcludedModel>,
+}
+
+impl Exclusions {
+    /// Pairs the skipped models with summaries whose iterations all failed;
+    /// the reason given for a failure is its most frequent error.
+    pub fn new(skipped: Vec<ExcludedModel>, summaries: &[ModelSummary], results: &[BenchmarkResult]) -> Self {
+        let failed = summaries
+            .iter()
+            .filter(|s| s.total_tests > 0 && s.success_rate == 0.0)
+            .map(|summary| {
+                let errors = results
+                    .iter()
+                    .filter(|r| r.model == summary.model && r.server == summary.server && !r.success)
+                    .filter_map(|r| r.error.as_deref());
+                ExcludedModel {
+                    model: summary.model.clone(),
+                    server: summary.server.clone(),
+                    variant: summary.variant.clone(),
+                    reason: most_common(errors).unwrap_or_else(|| "every iteration failed".to_string()),
+                }
+            })
+            .collect();
+        Self { skipped, failed }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.skipped.is_empty() && self.failed.is_empty()
+    }
+
+    fn sections(&self) -> [(&'static str, &[ExcludedModel]); 2] {
+        [("Skipped", &self.skipped), ("Failed", &self.failed)]
+    }
+
+    /// Terminal listing printed below the results table.
+    pub fn listing(&self) -> String {
+        let mut content = String::new();
+        for (title, models) in self.sections() {
+            if models.is_empty() {
+                continue;
+            }
+            let icon = if title == "Skipped" { "⏭️ " } else { "❌" };
+            let _ = writeln!(content, "\n{} {}:", icon, title);
+            for model in models {
+                let _ = writeln!(content, "   {:11} {}", model.label(), model.reason);
+            }
+        }
+        content
+    }
+
+    /// `### Skipped` / `### Failed` sections for Markdown reports.
+    pub fn markdown(&self) -> String {
+        let mut content = String::new();
+        for (title, models) in self.sections() {
+            if models.is_empty() {
+                continue;
+            }
+            let _ = writeln!(content, "### {}\n", title);
+            for model in models {
+                let _ = writeln!(content, "- {}: {}", model.label(), model.reason);
+            }
+            content.push('\n');
+        }
+        content
+    }
+
+}
+
+fn most_common<'a>(errors: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for error in errors {
+        *counts.entry(error).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(error, _)| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn skipped(model: &str) -> ExcludedModel {
+        ExcludedModel { model: model.to_string(), server: None, variant: None, reason: "not found".to_string() }
+    }
+
+    #[test]
+    fn test_failed_models_take_most_common_error() {
+        let failed = |error: &str| BenchmarkResult::failed("b", "p", Utc::now(), 10, error.to_string());
+        let results = vec![failed("timeout"), failed("connection refused"), failed("timeout")];
+        let summaries = vec![
+            ModelSummary { model: "a".to_string(), total_tests: 3, success_rate: 1.0, ..Default::default() },
+            ModelSummary::from_results("b".to_string(), &results),
+        ];
+
+        let exclusions = Exclusions::new(vec![skipped("c")], &summaries, &results);
+        assert_eq!(exclusions.skipped, vec![skipped("c")]);
+        assert_eq!(exclusions.failed.len(), 1);
+        assert_eq!((exclusions.failed[0].model.as_str(), exclusions.failed[0].reason.as_str()), ("b", "timeout"));
+        assert!(Exclusions::new(Vec::new(), &summaries[..1], &results).is_empty());
+    }
+
+    #[test]
+    fn test_markdown_and_listing() {
+        let exclusions = Exclusions {
+            skipped: vec![ExcludedModel { server: Some("mac-mini:11434".to_string()), ..skipped("c") }],
+            failed: vec![ExcludedModel { variant: Some("temp=0.2".to_string()), ..skipped("d") }],
+        };
+        assert_eq!(
+            exclusions.markdown(),
+            "### Skipped\n\n- c @ mac-mini:11434: not found\n\n### Failed\n\n- d [temp=0.2]: not found\n\n"
+        );
+        assert!(exclusions.listing().contains("   d [temp=0.2] not found\n"));
+    }
+}
@@ -0,0 +1,117 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+
+use crate::error::{BenchmarkError, Result};
+
+/// Export destination, compressed according to the file's final extension
+/// (`.gz` or `.zst`). Data is written through a buffer as it is produced, so
+/// large exports never need to exist as one string in memory.
+pub enum ExportWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::Encoder<'static, BufWriter<File>>),
+}
+
+impl ExportWriter {
+    pub fn create(path: &str) -> Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(if path.ends_with(".gz") {
+            ExportWriter::Gzip(GzEncoder::new(file, flate2::Compression::default()))
+        } else if path.ends_with(".zst") {
+            ExportWriter::Zstd(zstd::Encoder::new(file, 0)?)
+        } else {
+            ExportWriter::Plain(file)
+        })
+    }
+
+    /// Flushes buffers and writes the compression trailer, if any.
+    pub fn finish(self) -> Result<()> {
+        let mut file = match self {
+            ExportWriter::Plain(file) => file,
+            ExportWriter::Gzip(encoder) => encoder.finish()?,
+            ExportWriter::Zstd(encoder) => encoder.finish()?,
+        };
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl Write for ExportWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            ExportWriter::Plain(w) => w.write(buf),
+            ExportWriter::Gzip(w) => w.write(buf),
+            ExportWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            ExportWriter::Plain(w) => w.flush(),
+            ExportWriter::Gzip(w) => w.flush(),
+            ExportWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+/// Reads an export back, decompressing `.gz` and `.zst` files.
+pub fn read_to_string(path: &str) -> Result<String> {
+    let read = || -> io::Result<String> {
+        let file = File::open(path)?;
+        let mut text = String::new();
+        if path.ends_with(".gz") {
+            GzDecoder::new(file).read_to_string(&mut text)?;
+        } else if path.ends_with(".zst") {
+            zstd::Decoder::new(file)?.read_to_string(&mut text)?;
+        } else {
+            io::BufReader::new(file).read_to_string(&mut text)?;
+        }
+        Ok(text)
+    };
+    read().map_err(|e| BenchmarkError::IoError(format!("{}: {}", path, e)))
+}
+
+/// The format extension of an export path, ignoring a compression suffix:
+/// `runs.json.gz` → `json`.
+pub fn format_extension(path: &str) -> Option<&str> {
+    let path = path
+        .strip_suffix(".gz")
+        .or_else(|| path.strip_suffix(".zst"))
+        .unwrap_or(path);
+    path.rsplit_once('.').map(|(_, ext)| ext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_extension() {
+        assert_eq!(format_extension("runs.json"), Some("json"));
+        assert_eq!(format_extension("runs.json.gz"), Some("json"));
+        assert_eq!(format_extension("out/runs.csv.zst"), Some("csv"));
+        assert_eq!(format_extension("runs"), None);
+    }
+
+    #[test]
+    fn test_compressed_round_trip() {
+        let dir = std::env::temp_dir();
+        let gz = dir.join(format!("ollama-bench-export-{}.csv.gz", std::process::id()));
+        let zst = dir.join(format!("ollama-bench-export-{}.csv.zst", std::process::id()));
+
+        for path in [&gz, &zst] {
+            let mut writer = ExportWriter::create(path.to_str().unwrap()).unwrap();
+            writer.write_all(b"model,success\nm,true\n").unwrap();
+            writer.finish().unwrap();
+        }
+
+        for path in [&gz, &zst] {
+            assert_eq!(read_to_string(path.to_str().unwrap()).unwrap(), "model,success\nm,true\n");
+        }
+
+        std::fs::remove_file(gz).ok();
+        std::fs::remove_file(zst).ok();
+    }
+}
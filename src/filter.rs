@@ -0,0 +1,133 @@
+use crate::types::ModelSummary;
+
+/// Summary metric a `--filter` expression can test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    /// Average tokens/s
+    Tps,
+    /// Average time to first token, ms
+    Ttft,
+    /// Success rate in percent
+    Success,
+    /// Iterations counted in the summary
+    Tests,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Op {
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+}
+
+/// One `--filter` expression, e.g. `tps>20` or `ttft<=500`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    field: Field,
+    op: Op,
+    value: f64,
+}
+
+impl Filter {
+    pub fn matches(&self, summary: &ModelSummary) -> bool {
+        let actual = match self.field {
+            Field::Tps => summary.avg_tokens_per_second,
+            Field::Ttft => summary.avg_ttft_ms,
+            Field::Success => summary.success_rate * 100.0,
+            Field::Tests => summary.total_tests as f64,
+        };
+        match self.op {
+            Op::Gt => actual > self.value,
+            Op::Ge => actual >= self.value,
+            Op::Lt => actual < self.value,
+            Op::Le => actual <= self.value,
+            Op::Eq => actual == self.value,
+            Op::Ne => actual != self.value,
+        }
+    }
+}
+
+/// Parses `FIELD OP VALUE` with FIELD one of tps, ttft, success, tests and
+/// OP one of > >= < <= == !=.
+pub fn parse_filter(text: &str) -> Result<Filter, String> {
+    let invalid = || format!("invalid filter '{}' (use e.g. 'tps>20', 'ttft<=500', 'success==100')", text);
+
+    // Two-character operators first, so `>=` is not read as `>`
+    let (field, op, value) = [("!=", Op::Ne), ("==", Op::Eq), (">=", Op::Ge), ("<=", Op::Le), (">", Op::Gt), ("<", Op::Lt)]
+        .iter()
+        .find_map(|(token, op)| {
+            let (field, value) = text.split_once(token)?;
+            Some((field, *op, value))
+        })
+        .ok_or_else(invalid)?;
+
+    let field = match field.trim().to_ascii_lowercase().as_str() {
+        "tps" => Field::Tps,
+        "ttft" => Field::Ttft,
+        "success" => Field::Success,
+        "tests" => Field::Tests,
+        _ => return Err(invalid()),
+    };
+    let value = value.trim().parse::<f64>().map_err(|_| invalid())?;
+    Ok(Filter { field, op, value })
+}
+
+/// What `--only-successful`, `--min-tests` and `--filter` keep.
+#[derive(Debug, Clone, Default)]
+pub struct SummaryFilter {
+    /// Drop summaries with any failed iteration
+    pub only_successful: bool,
+    pub min_tests: Option<u64>,
+    pub filters: Vec<Filter>,
+}
+
+impl SummaryFilter {
+    pub fn is_active(&self) -> bool {
+        self.only_successful || self.min_tests.is_some() || !self.filters.is_empty()
+    }
+
+    pub fn keeps(&self, summary: &ModelSummary) -> bool {
+        (!self.only_successful || summary.success_rate >= 1.0)
+            && self.min_tests.map_or(true, |min| summary.total_tests >= min)
+            && self.filters.iter().all(|filter| filter.matches(summary))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(parse_filter("tps>20").unwrap(), Filter { field: Field::Tps, op: Op::Gt, value: 20.0 });
+        assert_eq!(parse_filter(" ttft <= 500 ").unwrap(), Filter { field: Field::Ttft, op: Op::Le, value: 500.0 });
+        assert_eq!(parse_filter("success!=100").unwrap().op, Op::Ne);
+        assert!(parse_filter("tps~20").is_err());
+        assert!(parse_filter("speed>20").is_err());
+        assert!(parse_filter("tps>fast").is_err());
+    }
+
+    #[test]
+    fn test_summary_filter() {
+        let summary = |speed: f64, success_rate: f64, total_tests: u64| ModelSummary {
+            avg_tokens_per_second: speed,
+            success_rate,
+            total_tests,
+            ..Default::default()
+        };
+        let filter = SummaryFilter {
+            only_successful: true,
+            min_tests: Some(5),
+            filters: vec![parse_filter("tps>20").unwrap()],
+        };
+
+        assert!(filter.keeps(&summary(30.0, 1.0, 5)));
+        assert!(!filter.keeps(&summary(30.0, 0.8, 5)));
+        assert!(!filter.keeps(&summary(30.0, 1.0, 4)));
+        assert!(!filter.keeps(&summary(20.0, 1.0, 5)));
+        assert!(!SummaryFilter::default().is_active());
+    }
+}
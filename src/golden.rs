@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+use crate::types::BenchmarkResult;
+
+/// A recorded reference response for one (model, prompt, seed) combination.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GoldenRecord {
+    pub model: String,
+    pub prompt: String,
+    pub seed: Option<i64>,
+    pub response: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum GoldenOutcome {
+    /// No golden existed yet; this run's response was stored.
+    Recorded,
+    /// The response matches the stored golden exactly.
+    Matched,
+    /// The response differs; `similarity` is the word-level LCS ratio (0.0-1.0).
+    Drifted { similarity: f64 },
+}
+
+/// Compares `response` against the stored golden, recording it on first use.
+pub fn check(dir: &Path, model: &str, prompt: &str, seed: Option<i64>, response: &str) -> Result<GoldenOutcome> {
+    let path = golden_path(dir, model, prompt, seed);
+
+    if !path.exists() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let record = GoldenRecord {
+            model: model.to_string(),
+            prompt: prompt.to_string(),
+            seed,
+            response: response.to_string(),
+        };
+        fs::write(&path, serde_json::to_string_pretty(&record)?)?;
+        return Ok(GoldenOutcome::Recorded);
+    }
+
+    let record: GoldenRecord = serde_json::from_str(&fs::read_to_string(&path)?)?;
+    if record.response == response {
+        Ok(GoldenOutcome::Matched)
+    } else {
+        Ok(GoldenOutcome::Drifted {
+            similarity: word_similarity(&record.response, response),
+        })
+    }
+}
+
+/// One comparison made by `check_results`.
+#[derive(Debug)]
+pub struct GoldenCheck {
+    /// The model plus its sweep variant, if any
+    pub model: String,
+    pub prompt: String,
+    pub outcome: GoldenOutcome,
+}
+
+/// Checks the first successful response of each (model, prompt, sweep
+/// variant) in `results` against its golden; later iterations of the same
+/// combination share the seed.
+pub fn check_results(dir: &Path, results: &[BenchmarkResult], seed: Option<i64>) -> Result<Vec<GoldenCheck>> {
+    let mut seen: Vec<(&str, &str, Option<&str>)> = Vec::new();
+    let mut checks = Vec::new();
+    for result in results.iter().filter(|r| r.success) {
+        let Some(response) = result.response.as_deref() else {
+            continue;
+        };
+        let key = (result.model.as_str(), result.prompt.as_str(), result.variant.as_deref());
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+
+        let model = match &result.variant {
+            Some(variant) => format!("{} [{}]", result.model, variant),
+            None => result.model.clone(),
+        };
+        let outcome = check(dir, &model, &result.prompt, seed, response)?;
+        checks.push(GoldenCheck { model, prompt: result.prompt.clone(), outcome });
+    }
+    Ok(checks)
+}
+
+fn golden_path(dir: &Path, model: &str, prompt: &str, seed: Option<i64>) -> PathBuf {
+    let model_dir: String = model
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect();
+    let seed_part = seed.map(|s| s.to_string()).unwrap_or_else(|| "noseed".to_string());
+
+    dir.join(model_dir)
+        .join(format!("{:016x}-{}.json", fnv1a(prompt.as_bytes()), seed_part))
+}
+
+/// Stable 64-bit FNV-1a, so golden file names survive toolchain upgrades.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Length of the longest common word subsequence relative to the longer text.
+pub fn word_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<&str> = a.split_whitespace().collect();
+    let b: Vec<&str> = b.split_whitespace().collect();
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut row = vec![0usize; b.len() + 1];
+    for word_a in &a {
+        let mut diagonal = 0;
+        for (j, word_b) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if word_a == word_b {
+                diagonal + 1
+            } else {
+                row[j + 1].max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+
+    row[b.len()] as f64 / longest as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_similarity() {
+        assert_eq!(word_similarity("a b c", "a b c"), 1.0);
+        assert_eq!(word_similarity("a b c d", "a x c d"), 0.75);
+        assert_eq!(word_similarity("", ""), 1.0);
+        assert_eq!(word_similarity("a b", ""), 0.0);
+    }
+
+    #[test]
+    fn test_check_records_then_compares() {
+        let dir = std::env::temp_dir().join(format!("ollama-bench-golden-{}", std::process::id()));
+
+        let first = check(&dir, "llama2:7b", "prompt", Some(42), "hello world").unwrap();
+        assert_eq!(first, GoldenOutcome::Recorded);
+
+        let same = check(&dir, "llama2:7b", "prompt", Some(42), "hello world").unwrap();
+        assert_eq!(same, GoldenOutcome::Matched);
+
+        let drifted = check(&dir, "llama2:7b", "prompt", Some(42), "hello there").unwrap();
+        assert_eq!(drifted, GoldenOutcome::Drifted { similarity: 0.5 });
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_check_results_per_prompt() {
+        let dir = std::env::temp_dir().join(format!("ollama-bench-golden-prompts-{}", std::process::id()));
+        let result = |prompt: &str, response: &str| BenchmarkResult {
+            prompt: prompt.to_string(),
+            response: Some(response.to_string()),
+            ..BenchmarkResult::ok("llama2:7b", 20.0)
+        };
+
+        let first = [result("one", "a b"), result("two", "c d"), result("one", "ignored")];
+        let checks = check_results(&dir, &first, Some(42)).unwrap();
+        let prompts: Vec<&str> = checks.iter().map(|c| c.prompt.as_str()).collect();
+        assert_eq!(prompts, ["one", "two"]);
+        assert!(checks.iter().all(|c| c.outcome == GoldenOutcome::Recorded));
+
+        // Each prompt is compared with its own golden
+        let checks = check_results(&dir, &[result("one", "a b"), result("two", "c x")], Some(42)).unwrap();
+        assert_eq!(checks[0].outcome, GoldenOutcome::Matched);
+        assert_eq!(checks[1].outcome, GoldenOutcome::Drifted { similarity: 0.5 });
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
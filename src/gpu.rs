@@ -0,0 +1,111 @@
+use std::process::Command;
+
+/// Share of GPU memory a model's weights may use before we predict CPU
+/// spill; the rest is reserved for KV cache and runtime overhead.
+const VRAM_FIT_RATIO: f64 = 0.9;
+
+/// One GPU as reported by `nvidia-smi`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpuInfo {
+    pub index: u32,
+    pub uuid: String,
+    pub name: String,
+    pub memory_total_mb: u64,
+}
+
+/// Lists NVIDIA GPUs, or an empty list when `nvidia-smi` is unavailable.
+pub fn list_gpus() -> Vec<GpuInfo> {
+    run_nvidia_smi(&["--query-gpu=index,uuid,name,memory.total", "--format=csv,noheader,nounits"])
+        .map(|out| parse_gpu_list(&out))
+        .unwrap_or_default()
+}
+
+/// Whether a model of `model_bytes` is expected to fit entirely in the
+/// combined memory of `gpus`.
+pub fn fits_in_vram(model_bytes: u64, gpus: &[GpuInfo]) -> bool {
+    let total_bytes: u64 = gpus.iter().map(|g| g.memory_total_mb * 1024 * 1024).sum();
+    model_bytes as f64 <= total_bytes as f64 * VRAM_FIT_RATIO
+}
+
+/// Indices of GPUs currently hosting an Ollama runner process.
+pub fn ollama_gpu_indices(gpus: &[GpuInfo]) -> Vec<u32> {
+    let Some(apps) = run_nvidia_smi(&["--query-compute-apps=gpu_uuid,process_name", "--format=csv,noheader"]) else {
+        return Vec::new();
+    };
+
+    let mut indices: Vec<u32> = parse_ollama_apps(&apps)
+        .iter()
+        .filter_map(|uuid| gpus.iter().find(|g| &g.uuid == uuid).map(|g| g.index))
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+pub fn run_nvidia_smi(args: &[&str]) -> Option<String> {
+    let output = Command::new("nvidia-smi").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+fn parse_gpu_list(output: &str) -> Vec<GpuInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() < 4 {
+                return None;
+            }
+            Some(GpuInfo {
+                index: fields[0].parse().ok()?,
+                uuid: fields[1].to_string(),
+                name: fields[2].to_string(),
+                memory_total_mb: fields[3].parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn parse_ollama_apps(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (uuid, process) = line.split_once(',')?;
+            process
+                .trim()
+                .to_lowercase()
+                .contains("ollama")
+                .then(|| uuid.trim().to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpu_list() {
+        let gpus = parse_gpu_list("0, GPU-aaa, NVIDIA GeForce RTX 4090, 24564\n1, GPU-bbb, NVIDIA RTX A4000, 16376\n");
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[1].index, 1);
+        assert_eq!(gpus[1].uuid, "GPU-bbb");
+        assert_eq!(gpus[0].memory_total_mb, 24564);
+    }
+
+    #[test]
+    fn test_fits_in_vram() {
+        let gpus = parse_gpu_list("0, GPU-aaa, RTX, 8192\n");
+        assert!(fits_in_vram(4 * 1024 * 1024 * 1024, &gpus));
+        assert!(!fits_in_vram(8 * 1024 * 1024 * 1024, &gpus));
+        assert!(!fits_in_vram(1, &[]));
+    }
+
+    #[test]
+    fn test_parse_ollama_apps() {
+        let apps = parse_ollama_apps("GPU-aaa, /usr/bin/python3\nGPU-bbb, /usr/lib/ollama/runners/cuda_v12/ollama_llama_server\n");
+        assert_eq!(apps, vec!["GPU-bbb".to_string()]);
+    }
+}
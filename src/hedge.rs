@@ -0,0 +1,91 @@
+use serde::Serialize;
+
+use crate::effect::Distribution;
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// One model's request latency with and without hedging (`--hedged`).
+#[derive(Debug, Clone, Serialize)]
+pub struct HedgeReport {
+    pub model: String,
+    /// Identical requests raced per hedged iteration
+    pub copies: u32,
+    pub plain_requests: u64,
+    pub hedged_requests: u64,
+    /// Latency (ms) of plain requests
+    pub plain: Distribution,
+    /// Latency (ms) of the first copy to finish
+    pub hedged: Distribution,
+    /// Relative change in p95 latency; negative means hedging helped
+    pub p95_change_percent: f64,
+}
+
+/// Latency of plain against hedged measured iterations, for each summary
+/// that ran both.
+pub fn build_report(summaries: &[ModelSummary], results: &[BenchmarkResult]) -> Vec<HedgeReport> {
+    summaries
+        .iter()
+        .filter_map(|summary| {
+            let measured: Vec<&BenchmarkResult> = results
+                .iter()
+                .filter(|r| summary.owns(r) && r.is_measured())
+                .collect();
+            let latencies = |hedged: bool| -> Vec<f64> {
+                measured
+                    .iter()
+                    .filter(|r| r.hedged_copies.is_some() == hedged)
+                    .map(|r| r.total_duration_ms as f64)
+                    .collect()
+            };
+            let (plain_latencies, hedged_latencies) = (latencies(false), latencies(true));
+            let plain = Distribution::of(&plain_latencies)?;
+            let hedged = Distribution::of(&hedged_latencies)?;
+
+            Some(HedgeReport {
+                model: summary.label(),
+                copies: measured.iter().find_map(|r| r.hedged_copies).unwrap_or(0),
+                plain_requests: plain_latencies.len() as u64,
+                hedged_requests: hedged_latencies.len() as u64,
+                p95_change_percent: if plain.p95 > 0.0 { (hedged.p95 - plain.p95) / plain.p95 * 100.0 } else { 0.0 },
+                plain,
+                hedged,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn result(model: &str, hedged: bool, total_ms: u64) -> BenchmarkResult {
+        BenchmarkResult {
+            total_duration_ms: total_ms,
+            hedged_copies: hedged.then_some(2),
+            ..BenchmarkResult::ok(model, 0.0)
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let summaries = ["a", "b"].map(|model| ModelSummary::from_results(model.to_string(), &[]));
+        let mut results = vec![
+            result("a", false, 1000),
+            result("a", false, 1100),
+            result("a", false, 4000),
+            result("a", true, 950),
+            result("a", true, 1000),
+            result("a", true, 1200),
+            result("b", false, 1000),
+        ];
+        // A failed hedge does not count as a fast one
+        results.push(BenchmarkResult::failed("a", "p", Utc::now(), 10, "refused".to_string()));
+
+        let report = build_report(&summaries, &results);
+        assert_eq!(report.len(), 1, "b has no hedged iterations");
+        let a = &report[0];
+        assert_eq!((a.copies, a.plain_requests, a.hedged_requests), (2, 3, 3));
+        assert_eq!((a.plain.p95, a.hedged.p95), (4000.0, 1200.0));
+        assert_eq!(a.p95_change_percent, -70.0);
+    }
+}
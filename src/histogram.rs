@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::TOKEN_HISTOGRAM_BINS;
+use crate::types::BenchmarkResult;
+
+/// Block characters for a one-line histogram, lowest first.
+const SPARK: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// How many tokens a model's successful iterations generated: whether it
+/// tends to stop early or runs into `max_tokens` every time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenHistogram {
+    pub min: u32,
+    pub median: u32,
+    pub max: u32,
+    /// `max_tokens` of the requests, when there was one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cap: Option<u32>,
+    /// Iterations cut off at the cap rather than finishing on their own
+    pub at_cap: u64,
+    /// Equal-width bins from zero to the cap (the longest response when
+    /// uncapped), so histograms of one run line up
+    pub bins: Vec<u64>,
+}
+
+impl TokenHistogram {
+    /// Over successful, measured iterations; `None` below two of them.
+    pub fn of(results: &[BenchmarkResult], max_tokens: i32) -> Option<Self> {
+        let mut counts: Vec<u32> = results
+            .iter()
+            .filter(|r| r.is_measured())
+            .map(|r| r.completion_tokens)
+            .collect();
+        if counts.len() < 2 {
+            return None;
+        }
+        counts.sort_unstable();
+
+        let cap = u32::try_from(max_tokens).ok().filter(|&cap| cap > 0);
+        let max = counts[counts.len() - 1];
+        let top = cap.unwrap_or(max).max(1) as u64;
+        let mut bins = vec![0; TOKEN_HISTOGRAM_BINS];
+        for &count in &counts {
+            let bin = (count as u64 * TOKEN_HISTOGRAM_BINS as u64 / (top + 1)) as usize;
+            bins[bin.min(TOKEN_HISTOGRAM_BINS - 1)] += 1;
+        }
+
+        Some(Self {
+            min: counts[0],
+            median: counts[counts.len() / 2],
+            max,
+            cap,
+            at_cap: cap.map_or(0, |cap| counts.iter().filter(|&&count| count >= cap).count() as u64),
+            bins,
+        })
+    }
+
+    /// The bins as block characters scaled to the fullest one, e.g. `▁▁▂▁▁▁▃█`.
+    pub fn sparkline(&self) -> String {
+        let fullest = self.bins.iter().copied().max().unwrap_or(0).max(1);
+        self.bins
+            .iter()
+            // Rounded up, so a single iteration still shows
+            .map(|&n| if n == 0 { ' ' } else { SPARK[((n * (SPARK.len() as u64 - 1) + fullest - 1) / fullest) as usize] })
+            .collect()
+    }
+
+    pub fn iterations(&self) -> u64 {
+        self.bins.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(counts: &[u32]) -> Vec<BenchmarkResult> {
+        counts
+            .iter()
+            .map(|&completion_tokens| BenchmarkResult { completion_tokens, ..BenchmarkResult::ok("m", 0.0) })
+            .collect()
+    }
+
+    #[test]
+    fn test_histogram() {
+        let histogram = TokenHistogram::of(&results(&[10, 40, 128, 128, 128, 127]), 128).unwrap();
+        assert_eq!((histogram.min, histogram.median, histogram.max), (10, 128, 128));
+        assert_eq!(histogram.at_cap, 3);
+        assert_eq!(histogram.bins, vec![1, 0, 1, 0, 0, 0, 0, 4]);
+        assert_eq!(histogram.sparkline(), "▃ ▃    █");
+        assert_eq!(histogram.iterations(), 6);
+
+        assert!(TokenHistogram::of(&results(&[10]), 128).is_none());
+        assert_eq!(TokenHistogram::of(&results(&[10, 20]), -1).unwrap().cap, None);
+    }
+}
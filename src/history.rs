@@ -0,0 +1,145 @@
+use serde_json::{json, Value};
+
+use crate::types::ModelSummary;
+
+const USER_TURN: &str = "Can you go over the trade-offs once more, this time focusing on \
+    memory use and how the approach behaves when the input keeps growing?";
+
+const ASSISTANT_TURN: &str = "Sure. The main trade-off is between keeping everything in memory, \
+    which is fast but grows with the input, and streaming it in chunks, which bounds memory at \
+    the cost of extra passes. For small inputs the difference is negligible; past a few hundred \
+    megabytes the chunked approach is usually the safer default.";
+
+/// A synthetic conversation of `count` messages, alternating user and
+/// assistant turns and ending with an assistant turn so the benchmark prompt
+/// follows as the next user message.
+pub fn messages(count: u32) -> Vec<Value> {
+    (0..count)
+        .map(|i| {
+            let turn = i / 2 + 1;
+            if (count - i) % 2 == 0 {
+                json!({ "role": "user", "content": format!("({}) {}", turn, USER_TURN) })
+            } else {
+                json!({ "role": "assistant", "content": format!("({}) {}", turn, ASSISTANT_TURN) })
+            }
+        })
+        .collect()
+}
+
+/// One history length of a model's `--sweep-history` run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPoint {
+    pub messages: u32,
+    pub avg_prompt_tokens: f64,
+    pub avg_latency_ms: f64,
+    pub avg_ttft_ms: f64,
+}
+
+/// How a model's latency grows with the length of the conversation.
+#[derive(Debug, Clone)]
+pub struct HistoryCurve {
+    pub model: String,
+    /// Points by increasing history length
+    pub points: Vec<HistoryPoint>,
+    /// Least-squares slope of request latency over history length;
+    /// `None` with fewer than two history lengths
+    pub ms_per_message: Option<f64>,
+}
+
+/// One curve per model, server and other sweep variant, in the order they
+/// first appear, over the `--history-sweep` lengths. Lengths where every
+/// iteration failed have no point.
+pub fn curves(summaries: &[ModelSummary]) -> Vec<HistoryCurve> {
+    let mut labels: Vec<String> = Vec::new();
+    for summary in summaries.iter().filter(|s| s.history_messages.is_some()) {
+        let label = summary.label_without("history");
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+
+    labels
+        .into_iter()
+        .map(|label| {
+            let mut points: Vec<HistoryPoint> = summaries
+                .iter()
+                .filter(|s| s.label_without("history") == label && s.success_rate > 0.0)
+                .filter_map(|s| {
+                    let successful = (s.total_tests as f64 * s.success_rate).round();
+                    Some(HistoryPoint {
+                        messages: s.history_messages?,
+                        avg_prompt_tokens: s.total_prompt_tokens as f64 / successful,
+                        avg_latency_ms: s.total_request_ms as f64 / s.total_tests as f64,
+                        avg_ttft_ms: s.avg_ttft_ms,
+                    })
+                })
+                .collect();
+            points.sort_by_key(|p| p.messages);
+
+            HistoryCurve {
+                model: label,
+                ms_per_message: slope(&points),
+                points,
+            }
+        })
+        .collect()
+}
+
+fn slope(points: &[HistoryPoint]) -> Option<f64> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.messages as f64).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.avg_latency_ms).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.messages as f64 - mean_x).powi(2)).sum();
+    let sxy: f64 = points
+        .iter()
+        .map(|p| (p.messages as f64 - mean_x) * (p.avg_latency_ms - mean_y))
+        .sum();
+    (sxx > 0.0).then(|| sxy / sxx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_alternate_and_end_with_assistant() {
+        assert!(messages(0).is_empty());
+        for count in [1, 4, 5] {
+            let history = messages(count);
+            assert_eq!(history.len(), count as usize);
+            assert_eq!(history.last().unwrap()["role"], "assistant");
+            assert!(history.windows(2).all(|pair| pair[0]["role"] != pair[1]["role"]));
+        }
+    }
+
+    #[test]
+    fn test_curves() {
+        let cell = |model: &str, messages: u32, latency_ms: u64| ModelSummary {
+            model: model.to_string(),
+            total_tests: 2,
+            success_rate: 1.0,
+            total_prompt_tokens: 2 * (20 + 60 * messages as u64),
+            total_request_ms: 2 * latency_ms,
+            history_messages: Some(messages),
+            ..Default::default()
+        };
+        // Interleaved sweep order, with a cell that failed outright
+        let summaries = vec![
+            cell("a", 20, 1400),
+            cell("b", 0, 500),
+            cell("a", 0, 1000),
+            ModelSummary { success_rate: 0.0, ..cell("b", 20, 0) },
+            cell("a", 10, 1200),
+            ModelSummary { server: Some("gpu2".to_string()), ..cell("a", 0, 900) },
+        ];
+
+        let curves = curves(&summaries);
+        assert_eq!(curves.len(), 3);
+        assert_eq!(curves[2].model, "a @ gpu2");
+        let a = &curves[0];
+        assert_eq!(a.points.iter().map(|p| p.messages).collect::<Vec<_>>(), vec![0, 10, 20]);
+        assert_eq!(a.points[1].avg_prompt_tokens, 620.0);
+        assert_eq!(a.ms_per_message, Some(20.0));
+        assert_eq!((curves[1].points.len(), curves[1].ms_per_message), (1, None));
+    }
+}
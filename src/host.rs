@@ -0,0 +1,115 @@
+use std::collections::HashSet;
+use std::fs;
+
+/// CPU layout of the benchmarking host, for interpreting CPU-only results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostTopology {
+    pub logical_cpus: usize,
+    pub physical_cores: Option<usize>,
+    pub numa_nodes: Option<usize>,
+}
+
+impl HostTopology {
+    pub fn detect() -> Self {
+        Self {
+            logical_cpus: std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            physical_cores: fs::read_to_string("/proc/cpuinfo")
+                .ok()
+                .and_then(|cpuinfo| count_physical_cores(&cpuinfo)),
+            numa_nodes: count_numa_nodes(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("{} logical CPUs", self.logical_cpus)];
+        if let Some(cores) = self.physical_cores {
+            parts.push(format!("{} physical cores", cores));
+        }
+        if let Some(nodes) = self.numa_nodes {
+            parts.push(format!("{} NUMA node{}", nodes, if nodes == 1 { "" } else { "s" }));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Counts unique (physical id, core id) pairs in Linux `/proc/cpuinfo`.
+fn count_physical_cores(cpuinfo: &str) -> Option<usize> {
+    let mut cores = HashSet::new();
+    let mut physical_id = "0";
+
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "physical id" => physical_id = value.trim(),
+            "core id" => {
+                cores.insert((physical_id, value.trim()));
+            }
+            _ => {}
+        }
+    }
+
+    (!cores.is_empty()).then_some(cores.len())
+}
+
+fn count_numa_nodes() -> Option<usize> {
+    let nodes = fs::read_dir("/sys/devices/system/node")
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| name.strip_prefix("node"))
+                .is_some_and(|id| !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()))
+        })
+        .count();
+
+    (nodes > 0).then_some(nodes)
+}
+
+/// Extracts `num_thread` from the `parameters` block of `/api/show`.
+pub fn parse_num_thread(parameters: &str) -> Option<u32> {
+    parse_parameter(parameters, "num_thread")
+}
+
+/// Extracts a single-valued option such as `num_ctx` from the `parameters`
+/// block of `/api/show`.
+pub fn parse_parameter<T: std::str::FromStr>(parameters: &str, name: &str) -> Option<T> {
+    parameters.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) if key == name => value.parse().ok(),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_physical_cores() {
+        let cpuinfo = "processor\t: 0\nphysical id\t: 0\ncore id\t: 0\n\n\
+                       processor\t: 1\nphysical id\t: 0\ncore id\t: 0\n\n\
+                       processor\t: 2\nphysical id\t: 0\ncore id\t: 1\n\n\
+                       processor\t: 3\nphysical id\t: 1\ncore id\t: 0\n";
+        assert_eq!(count_physical_cores(cpuinfo), Some(3));
+        assert_eq!(count_physical_cores(""), None);
+    }
+
+    #[test]
+    fn test_parse_num_thread() {
+        assert_eq!(parse_num_thread("stop \"<|eot|>\"\nnum_thread 8\n"), Some(8));
+        assert_eq!(parse_num_thread("temperature 0.7"), None);
+    }
+
+    #[test]
+    fn test_detect_has_cpus() {
+        assert!(HostTopology::detect().logical_cpus >= 1);
+    }
+}
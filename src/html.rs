@@ -0,0 +1,493 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::benchmark::{calculate_performance_difference, calculate_winner};
+use crate::config::{APP_NAME, APP_VERSION};
+use crate::exclusions::Exclusions;
+use crate::histogram::TokenHistogram;
+use crate::output::{effect_note, effect_sizes};
+use crate::precision::num;
+use crate::types::{ModelSummary, RunTotals, SpeedUnit};
+use crate::warnings::Warnings;
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 960px; color: #222; padding: 0 1rem; }
+h1 { font-size: 1.6rem; }
+table { border-collapse: collapse; width: 100%; margin: 1rem 0; }
+th, td { padding: 0.4rem 0.6rem; border-bottom: 1px solid #ddd; text-align: right; }
+th:first-child, td:first-child, td.reason { text-align: left; }
+th { background: #f4f4f4; }
+.winner { background: #fff8e1; border-left: 4px solid #f9a825; padding: 0.8rem 1rem; margin: 1rem 0; }
+.winner ul { margin: 0.4rem 0 0; }
+.chart { margin: 1rem 0 2rem; }
+.chart text { font-size: 12px; fill: #222; }
+table.heatmap td { text-align: center; }
+table.heatmap td:first-child { text-align: left; }
+table.heatmap td.best { font-weight: bold; }
+table.heatmap td.missing { background: #f4f4f4; color: #999; }
+footer { color: #777; font-size: 0.85rem; margin-top: 2rem; }
+";
+
+/// Chart geometry: label column, bar area and row height, in SVG pixels.
+const LABEL_WIDTH: f64 = 220.0;
+const BAR_WIDTH: f64 = 560.0;
+const ROW_HEIGHT: f64 = 28.0;
+
+/// A self-contained HTML report: summary table, winner callout, heatmap, SVG
+/// charts and any skipped or failed models, with no scripts or external resources.
+pub fn render(
+    summaries: &[ModelSummary],
+    exclusions: Option<&Exclusions>,
+    warnings: Option<&Warnings>,
+    duration: Duration,
+    unit: SpeedUnit,
+) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Ollama Benchmark Results</title>\n");
+    let _ = writeln!(html, "<style>\n{}</style>\n</head>\n<body>", STYLE);
+    html.push_str("<h1>Ollama Benchmark Results</h1>\n");
+
+    if summaries.is_empty() {
+        html.push_str("<p>No results to display</p>\n");
+    } else {
+        write_table(&mut html, summaries, unit);
+        write_winner(&mut html, summaries);
+        if summaries.len() > 1 {
+            write_heatmap(&mut html, summaries, unit);
+        }
+
+        let speeds: Vec<(String, f64, Option<f64>)> = summaries
+            .iter()
+            .map(|s| (s.label(), s.speed_in(s.avg_tokens_per_second, unit), None))
+            .collect();
+        write_bar_chart(&mut html, &format!("Average speed ({})", unit.suffix()), &speeds, unit.suffix(), "#42a5f5");
+
+        let latencies: Vec<(String, f64, Option<f64>)> = summaries
+            .iter()
+            .map(|s| (s.label(), s.avg_ttft_ms, s.ttft_distribution.map(|d| d.p95)))
+            .collect();
+        write_bar_chart(&mut html, "Time to first token (ms; tick = p95)", &latencies, "ms", "#ef6c00");
+
+        if summaries.iter().any(|s| s.completion_histogram.is_some()) {
+            write_completion_histograms(&mut html, summaries);
+        }
+    }
+
+    if summaries.iter().any(|s| s.fastest.is_some()) {
+        write_extremes(&mut html, summaries, unit);
+    }
+
+    if let Some(exclusions) = exclusions {
+        write_exclusions(&mut html, exclusions);
+    }
+
+    if let Some(warnings) = warnings {
+        html.push_str("<h2>Warnings</h2>\n<ul class=\"warnings\">\n");
+        for warning in warnings.iter() {
+            let _ = writeln!(html, "<li>{}</li>", escape(&warning.message));
+        }
+        html.push_str("</ul>\n");
+    }
+
+    let totals = RunTotals::from_summaries(summaries);
+    let _ = writeln!(
+        html,
+        "<footer>{} {} · {} requests, {} tokens generated, {} prompt tokens evaluated · {}s total</footer>",
+        APP_NAME,
+        APP_VERSION,
+        totals.requests,
+        totals.completion_tokens,
+        totals.prompt_tokens,
+        duration.as_secs()
+    );
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_table(html: &mut String, summaries: &[ModelSummary], unit: SpeedUnit) {
+    let suffix = unit.suffix();
+    // Only streamed runs time the first sentence
+    let first_sentence = summaries.iter().any(|s| s.avg_first_sentence_ms.is_some());
+    let _ = writeln!(
+        html,
+        "<table>\n<tr><th>Model</th><th>Success</th><th>Avg {0}</th><th>Min {0}</th><th>Max {0}</th>\
+         <th>Median {0}</th><th>Avg TTFT</th><th>p95 TTFT</th>{1}</tr>",
+        suffix,
+        if first_sentence { "<th>First sentence</th>" } else { "" }
+    );
+    for summary in summaries {
+        let median = summary
+            .speed_distribution
+            .map(|d| num(summary.speed_in(d.median, unit), 1))
+            .unwrap_or_default();
+        let p95_ttft = summary
+            .ttft_distribution
+            .map(|d| format!("{}ms", num(d.p95, 0)))
+            .unwrap_or_default();
+        let sentence = match (first_sentence, summary.avg_first_sentence_ms) {
+            (false, _) => String::new(),
+            (true, Some(ms)) => format!("<td>{}ms</td>", num(ms, 0)),
+            (true, None) => "<td></td>".to_string(),
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}%</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}ms</td><td>{}</td>{}</tr>",
+            escape(&summary.label()),
+            num(summary.success_rate * 100.0, 1),
+            num(summary.speed_in(summary.avg_tokens_per_second, unit), 1),
+            num(summary.speed_in(summary.min_tokens_per_second, unit), 1),
+            num(summary.speed_in(summary.max_tokens_per_second, unit), 1),
+            median,
+            num(summary.avg_ttft_ms, 0),
+            p95_ttft,
+            sentence
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn write_winner(html: &mut String, summaries: &[ModelSummary]) {
+    let Some(winner) = calculate_winner(summaries) else {
+        return;
+    };
+    let _ = writeln!(html, "<div class=\"winner\"><strong>🏆 Winner: {}</strong>", escape(&winner.label()));
+
+    let mut points = Vec::new();
+    for other in summaries {
+        if other.label() == winner.label() || other.success_rate == 0.0 {
+            continue;
+        }
+        let (speed_diff, ttft_diff) = calculate_performance_difference(winner, other);
+        let (speed_d, ttft_d) = effect_sizes(winner, other);
+        if speed_diff > 0.0 {
+            points.push(format!("{}% faster than {}{}", num(speed_diff, 1), other.label(), effect_note(speed_d)));
+        }
+        if ttft_diff > 0.0 {
+            points.push(format!("{}% lower TTFT than {}{}", num(ttft_diff, 0), other.label(), effect_note(ttft_d)));
+        }
+    }
+    if !points.is_empty() {
+        html.push_str("<ul>\n");
+        for point in points {
+            let _ = writeln!(html, "<li>{}</li>", escape(&point));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</div>\n");
+}
+
+fn write_exclusions(html: &mut String, exclusions: &Exclusions) {
+    for (title, models) in [("Skipped", &exclusions.skipped), ("Failed", &exclusions.failed)] {
+        if models.is_empty() {
+            continue;
+        }
+        let _ = writeln!(html, "<h2>{}</h2>\n<table>\n<tr><th>Model</th><th>Reason</th></tr>", title);
+        for model in models {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"reason\">{}</td></tr>",
+                escape(&model.label()),
+                escape(&model.reason)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+/// One heatmap column: a value per summary, colored from worst to best.
+struct Metric {
+    name: String,
+    higher_is_better: bool,
+    precision: usize,
+    values: Vec<Option<f64>>,
+}
+
+impl Metric {
+    fn new(name: impl Into<String>, higher_is_better: bool, precision: usize, values: Vec<Option<f64>>) -> Self {
+        Self { name: name.into(), higher_is_better, precision, values }
+    }
+
+    /// 0.0 for the column's worst value up to 1.0 for its best.
+    fn score(&self, value: f64) -> f64 {
+        let present = self.values.iter().flatten();
+        let min = present.clone().fold(f64::INFINITY, |a, b| a.min(*b));
+        let max = present.fold(f64::NEG_INFINITY, |a, b| a.max(*b));
+        if max - min <= f64::EPSILON {
+            return 1.0;
+        }
+        let position = (value - min) / (max - min);
+        if self.higher_is_better { position } else { 1.0 - position }
+    }
+}
+
+fn heatmap_metrics(summaries: &[ModelSummary], unit: SpeedUnit) -> Vec<Metric> {
+    let measured = |s: &ModelSummary| s.success_rate > 0.0;
+    let column = |f: &dyn Fn(&ModelSummary) -> Option<f64>| -> Vec<Option<f64>> {
+        summaries.iter().map(|s| if measured(s) { f(s) } else { None }).collect()
+    };
+    let suffix = unit.suffix();
+    let metrics = vec![
+        Metric::new(format!("Avg {}", suffix), true, 1, column(&|s| Some(s.speed_in(s.avg_tokens_per_second, unit)))),
+        Metric::new(
+            format!("Median {}", suffix),
+            true,
+            1,
+            column(&|s| s.speed_distribution.map(|d| s.speed_in(d.median, unit))),
+        ),
+        Metric::new("Speed CV", false, 3, column(&|s| s.speed_distribution.and_then(|d| d.cv))),
+        Metric::new("Avg TTFT (ms)", false, 0, column(&|s| Some(s.avg_ttft_ms))),
+        Metric::new("p95 TTFT (ms)", false, 0, column(&|s| s.ttft_distribution.map(|d| d.p95))),
+        Metric::new("Prompt eval tok/s", true, 0, column(&|s| s.prompt_eval_tokens_per_second)),
+        Metric::new("Accuracy %", true, 1, column(&|s| s.eval.as_ref().map(|e| e.accuracy() * 100.0))),
+        Metric::new("Success %", true, 0, summaries.iter().map(|s| Some(s.success_rate * 100.0)).collect()),
+    ];
+    // Columns nobody has a value for only add noise
+    metrics.into_iter().filter(|m| m.values.iter().any(Option::is_some)).collect()
+}
+
+/// Fastest and slowest iteration per model, responses folded away.
+fn write_extremes(html: &mut String, summaries: &[ModelSummary], unit: SpeedUnit) {
+    let _ = writeln!(
+        html,
+        "<h2>Fastest and slowest iterations</h2>\n<table>\n<tr><th>Model</th><th></th><th>{}</th><th>Started</th><th>TTFT</th><th>Tokens (prompt → completion)</th><th>Response</th></tr>",
+        unit.suffix()
+    );
+    for summary in summaries {
+        let (Some(fastest), Some(slowest)) = (&summary.fastest, &summary.slowest) else {
+            continue;
+        };
+        for (which, detail) in [("fastest", fastest), ("slowest", slowest)] {
+            let response = detail
+                .response
+                .as_deref()
+                .map(|text| format!("<details><summary>show</summary><pre>{}</pre></details>", escape(text)))
+                .unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}ms</td><td>{} → {}</td><td>{}</td></tr>",
+                escape(&summary.label()),
+                which,
+                num(summary.speed_in(detail.tokens_per_second, unit), 1),
+                detail.timestamp.format("%Y-%m-%d %H:%M:%S UTC"),
+                detail.time_to_first_token_ms,
+                detail.prompt_tokens,
+                detail.completion_tokens,
+                response
+            );
+        }
+    }
+    html.push_str("</table>\n");
+}
+
+/// One row of columns per model: how its completion token counts spread
+/// from zero to `max_tokens`, each row scaled to its fullest bin.
+fn write_completion_histograms(html: &mut String, summaries: &[ModelSummary]) {
+    let rows: Vec<(String, &TokenHistogram)> = summaries
+        .iter()
+        .filter_map(|s| s.completion_histogram.as_ref().map(|h| (s.label(), h)))
+        .collect();
+    let title = "Completion tokens (0 → max_tokens)";
+    let height = ROW_HEIGHT * rows.len() as f64 + 10.0;
+
+    let _ = writeln!(html, "<h2>{}</h2>", escape(title));
+    let _ = writeln!(
+        html,
+        "<svg class=\"chart\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"{t}\">",
+        w = LABEL_WIDTH + BAR_WIDTH + 90.0,
+        h = height,
+        t = escape(title)
+    );
+    for (i, (label, histogram)) in rows.iter().enumerate() {
+        let y = i as f64 * ROW_HEIGHT + 5.0;
+        let _ = writeln!(html, "<text x=\"{:.0}\" y=\"{:.0}\" text-anchor=\"end\">{}</text>", LABEL_WIDTH - 8.0, y + 17.0, escape(label));
+
+        let fullest = histogram.bins.iter().copied().max().unwrap_or(0).max(1) as f64;
+        let column = BAR_WIDTH / 2.0 / histogram.bins.len() as f64;
+        for (bin, &count) in histogram.bins.iter().enumerate() {
+            let bar = (ROW_HEIGHT - 8.0) * count as f64 / fullest;
+            let _ = writeln!(
+                html,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#7e57c2\"><title>{}</title></rect>",
+                LABEL_WIDTH + bin as f64 * column,
+                y + ROW_HEIGHT - 8.0 - bar,
+                column - 2.0,
+                bar,
+                count
+            );
+        }
+
+        let cap = match histogram.cap {
+            Some(cap) => format!(", {}/{} hit {}", histogram.at_cap, histogram.iterations(), cap),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            html,
+            "<text x=\"{:.1}\" y=\"{:.0}\">{}–{} tokens, median {}{}</text>",
+            LABEL_WIDTH + BAR_WIDTH / 2.0 + 6.0,
+            y + 17.0,
+            histogram.min,
+            histogram.max,
+            histogram.median,
+            cap
+        );
+    }
+    html.push_str("</svg>\n");
+}
+
+/// Models × metrics, each column colored red (worst) to green (best) on
+/// its own scale, with the best value in bold.
+fn write_heatmap(html: &mut String, summaries: &[ModelSummary], unit: SpeedUnit) {
+    let metrics = heatmap_metrics(summaries, unit);
+
+    html.push_str("<h2>Heatmap</h2>\n<table class=\"heatmap\">\n<tr><th>Model</th>");
+    for metric in &metrics {
+        let _ = write!(html, "<th>{}</th>", escape(&metric.name));
+    }
+    html.push_str("</tr>\n");
+
+    for (row, summary) in summaries.iter().enumerate() {
+        let _ = write!(html, "<tr><td>{}</td>", escape(&summary.label()));
+        for metric in &metrics {
+            match metric.values[row] {
+                Some(value) => {
+                    let score = metric.score(value);
+                    let _ = write!(
+                        html,
+                        "<td{} style=\"background: hsl({:.0}, 70%, 80%)\">{}</td>",
+                        if score >= 1.0 { " class=\"best\"" } else { "" },
+                        score * 120.0,
+                        num(value, metric.precision)
+                    );
+                }
+                None => html.push_str("<td class=\"missing\">–</td>"),
+            }
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</table>\n");
+}
+
+/// Horizontal bars scaled to the largest value (or tick), one row per entry.
+fn write_bar_chart(html: &mut String, title: &str, rows: &[(String, f64, Option<f64>)], unit: &str, color: &str) {
+    let max = rows
+        .iter()
+        .map(|(_, value, tick)| value.max(tick.unwrap_or(0.0)))
+        .fold(0.0, f64::max);
+    let scale = if max > 0.0 { BAR_WIDTH / max } else { 0.0 };
+    let height = ROW_HEIGHT * rows.len() as f64 + 10.0;
+
+    let _ = writeln!(html, "<h2>{}</h2>", escape(title));
+    let _ = writeln!(
+        html,
+        "<svg class=\"chart\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" role=\"img\" aria-label=\"{t}\">",
+        w = LABEL_WIDTH + BAR_WIDTH + 90.0,
+        h = height,
+        t = escape(title)
+    );
+    for (i, (label, value, tick)) in rows.iter().enumerate() {
+        let y = i as f64 * ROW_HEIGHT + 5.0;
+        let width = value * scale;
+        let _ = writeln!(
+            html,
+            "<text x=\"{:.0}\" y=\"{:.0}\" text-anchor=\"end\">{}</text>\
+             <rect x=\"{:.0}\" y=\"{:.0}\" width=\"{:.1}\" height=\"{:.0}\" fill=\"{}\"/>\
+             <text x=\"{:.1}\" y=\"{:.0}\">{} {}</text>",
+            LABEL_WIDTH - 8.0,
+            y + 17.0,
+            escape(label),
+            LABEL_WIDTH,
+            y,
+            width,
+            ROW_HEIGHT - 8.0,
+            color,
+            LABEL_WIDTH + width.max(tick.unwrap_or(0.0) * scale) + 6.0,
+            y + 17.0,
+            num(*value, 1),
+            unit
+        );
+        if let Some(tick) = tick {
+            let x = LABEL_WIDTH + tick * scale;
+            let _ = writeln!(
+                html,
+                "<line x1=\"{x:.1}\" y1=\"{:.0}\" x2=\"{x:.1}\" y2=\"{:.0}\" stroke=\"#222\" stroke-width=\"2\"/>",
+                y - 2.0,
+                y + ROW_HEIGHT - 6.0,
+                x = x
+            );
+        }
+    }
+    html.push_str("</svg>\n");
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warnings::WarningKind;
+
+    fn summary(model: &str, speed: f64, ttft: f64) -> ModelSummary {
+        ModelSummary {
+            model: model.to_string(),
+            total_tests: 5,
+            success_rate: 1.0,
+            avg_tokens_per_second: speed,
+            avg_ttft_ms: ttft,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_is_self_contained() {
+        let summaries = vec![summary("llama2:7b", 50.0, 120.0), summary("mistral:7b", 40.0, 150.0)];
+        let html = render(&summaries, None, None, Duration::from_secs(42), SpeedUnit::Tokens);
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("Winner: llama2:7b"));
+        assert!(html.contains("25.0% faster than mistral:7b"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        assert!(!html.contains("<script") && !html.contains("http"), "no scripts or external resources");
+
+        let exclusions = Exclusions::new(Vec::new(), &[ModelSummary { success_rate: 0.0, ..summary("phi:2.7b", 0.0, 0.0) }], &[]);
+        let mut warnings = Warnings::default();
+        warnings.push(WarningKind::Memory, Some("phi:2.7b"), "phi:2.7b (1.6 GB) likely exceeds GPU memory".to_string());
+        let html = render(&summaries, Some(&exclusions), Some(&warnings), Duration::from_secs(42), SpeedUnit::Tokens);
+        assert!(html.contains("<h2>Failed</h2>") && html.contains("every iteration failed"));
+        assert!(html.contains("<li>phi:2.7b (1.6 GB) likely exceeds GPU memory</li>"));
+
+        let histogram = TokenHistogram { min: 12, median: 128, max: 128, cap: Some(128), at_cap: 3, bins: vec![1, 0, 0, 0, 0, 0, 0, 3] };
+        let summaries = vec![ModelSummary { completion_histogram: Some(histogram), ..summary("llama2:7b", 20.0, 200.0) }];
+        let html = render(&summaries, None, None, Duration::from_secs(42), SpeedUnit::Tokens);
+        assert_eq!(html.matches("<svg").count(), 3);
+        assert!(html.contains("12–128 tokens, median 128, 3/4 hit 128"));
+    }
+
+    #[test]
+    fn test_heatmap_scores_each_column_on_its_own_scale() {
+        let summaries = vec![summary("a", 50.0, 300.0), summary("b", 30.0, 100.0), summary("c", 40.0, 200.0)];
+        let metrics = heatmap_metrics(&summaries, SpeedUnit::Tokens);
+        let names: Vec<&str> = metrics.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["Avg tok/s", "Avg TTFT (ms)", "Success %"]);
+
+        let (speed, ttft) = (&metrics[0], &metrics[1]);
+        assert_eq!((speed.score(50.0), speed.score(40.0), speed.score(30.0)), (1.0, 0.5, 0.0));
+        assert_eq!((ttft.score(100.0), ttft.score(300.0)), (1.0, 0.0));
+        assert_eq!(metrics[2].score(100.0), 1.0, "a tie is best for everyone");
+
+        let html = render(&summaries, None, None, Duration::ZERO, SpeedUnit::Tokens);
+        assert_eq!(html.matches("class=\"best\"").count(), 2 + 3);
+    }
+
+    #[test]
+    fn test_labels_are_escaped() {
+        let html = render(&[summary("a<b>&\"c\"", 10.0, 100.0)], None, None, Duration::ZERO, SpeedUnit::Tokens);
+        assert!(html.contains("a&lt;b&gt;&amp;&quot;c&quot;"));
+        assert!(!html.contains("a<b>"));
+    }
+}
@@ -0,0 +1,381 @@
+use std::sync::OnceLock;
+use clap::ValueEnum;
+
+/// Languages for human-readable output. Machine-readable formats (JSON, CSV)
+/// are never translated.
+///
+/// To add a language: add a variant here, an arm in `Lang::text`, and a catalog
+/// function below. Messages missing from a catalog fall back to English.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Lang {
+    #[default]
+    En,
+    Ja,
+    De,
+}
+
+/// Every translatable message. `{}` in a message is replaced by its argument.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Msg {
+    ColumnModel,
+    ColumnAvgSpeed,
+    ColumnTtft,
+    ColumnSuccess,
+    ColumnLatency,
+    ColumnFirstSentence,
+    ColumnEmbeddingSpeed,
+    ColumnDimensions,
+    NoResults,
+    Winner,
+    Faster,
+    LowerTtft,
+    CompletedIn,
+    OllamaNotRunning,
+    HintStartOllama,
+    ModelNotFound,
+    HintPullModel,
+    NetworkTimeout,
+    HintIncreaseTimeout,
+    InvalidModel,
+    HintModelFormat,
+    ConnectionFailed,
+    HintCheckOllama,
+    ParseError,
+    HintVersionMismatch,
+    IoError,
+    HintPermissions,
+    ConfigError,
+    UnknownHost,
+    HintUnknownHost,
+    PortClosed,
+    HintPortClosed,
+    Unresponsive,
+    HintUnresponsive,
+    NotOllama,
+    HintNotOllama,
+    OllamaUnhealthy,
+    HintOllamaUnhealthy,
+    HintInsideContainer,
+    HintOllamaContainer,
+    DoctorFailed,
+    HintDoctorFailed,
+    NoModelSelected,
+    HintNoModelSelected,
+    SpeedRegressed,
+    HintSpeedRegressed,
+    DuelIncomplete,
+    HintDuelIncomplete,
+    TaskFailed,
+    HintTaskFailed,
+    HintContextOverflow,
+    SampleSizeTitle,
+    SampleSizeEnough,
+    SampleSizeNeeded,
+    SampleSizeTooWide,
+    HintRerunIterations,
+    PairedTitle,
+    PairedTooFew,
+    PairedPairs,
+    DuelTitle,
+    DuelTooFew,
+    DuelSpeed,
+    DuelFaster,
+    Significant,
+    WithinNoise,
+    UntilCiTitle,
+    UntilCiAfter,
+    UntilCiNoSpread,
+    UntilCiCapReached,
+}
+
+static CURRENT: OnceLock<Lang> = OnceLock::new();
+
+/// Selects the output language; only the first call has an effect.
+pub fn set_lang(lang: Lang) {
+    CURRENT.get_or_init(|| lang);
+}
+
+/// Translates `msg` into the selected language.
+pub fn t(msg: Msg) -> &'static str {
+    CURRENT.get().copied().unwrap_or_default().text(msg)
+}
+
+/// Translates `msg`, substituting `arg` for its `{}` placeholder.
+pub fn tf(msg: Msg, arg: impl std::fmt::Display) -> String {
+    t(msg).replacen("{}", &arg.to_string(), 1)
+}
+
+/// Translates `msg`, substituting `args` for its `{}` placeholders in order.
+pub fn tfn(msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+    args.iter().fold(t(msg).to_string(), |text, arg| text.replacen("{}", &arg.to_string(), 1))
+}
+
+impl Lang {
+    pub(crate) fn text(self, msg: Msg) -> &'static str {
+        let translated = match self {
+            Lang::En => None,
+            Lang::Ja => ja(msg),
+            Lang::De => de(msg),
+        };
+        translated.unwrap_or_else(|| en(msg))
+    }
+}
+
+fn en(msg: Msg) -> &'static str {
+    match msg {
+        Msg::ColumnModel => "Model",
+        Msg::ColumnAvgSpeed => "Avg Speed",
+        Msg::ColumnTtft => "TTFT",
+        Msg::ColumnSuccess => "Success",
+        Msg::ColumnLatency => "Latency",
+        Msg::ColumnFirstSentence => "1st sentence",
+        Msg::ColumnEmbeddingSpeed => "Emb/s",
+        Msg::ColumnDimensions => "Dims",
+        Msg::NoResults => "No results to display.",
+        Msg::Winner => "Winner",
+        Msg::Faster => "{}% faster",
+        Msg::LowerTtft => "{}% lower TTFT",
+        Msg::CompletedIn => "Completed in",
+        Msg::OllamaNotRunning => "Ollama is not running",
+        Msg::HintStartOllama => "Start with: ollama serve",
+        Msg::ModelNotFound => "Model '{}' not found",
+        Msg::HintPullModel => "Install with: ollama pull {}",
+        Msg::NetworkTimeout => "Network timeout after {}s",
+        Msg::HintIncreaseTimeout => "Try increasing --timeout",
+        Msg::InvalidModel => "Invalid model name: '{}'",
+        Msg::HintModelFormat => "Model names should be in format: model:tag (e.g., llama2:7b)",
+        Msg::ConnectionFailed => "Failed to connect to Ollama at {}",
+        Msg::HintCheckOllama => "Check if Ollama is running and accessible",
+        Msg::ParseError => "Failed to parse response: {}",
+        Msg::HintVersionMismatch => "This might be a compatibility issue with your Ollama version",
+        Msg::IoError => "I/O error: {}",
+        Msg::HintPermissions => "Check file permissions and disk space",
+        Msg::ConfigError => "Configuration error: {}",
+        Msg::UnknownHost => "Cannot resolve host '{}'",
+        Msg::HintUnknownHost => "Check --ollama-url; from inside a container use host.docker.internal or the service name",
+        Msg::PortClosed => "Nothing is listening on {}",
+        Msg::HintPortClosed => "Start with: ollama serve (in Docker, publish the port with -p {}:11434)",
+        Msg::Unresponsive => "{} accepts connections but does not respond",
+        Msg::HintUnresponsive => "The server may be overloaded or behind a proxy; try increasing --timeout",
+        Msg::NotOllama => "Another service is answering at {}, not Ollama",
+        Msg::HintNotOllama => "Check the port mapping or point --ollama-url at Ollama's port",
+        Msg::OllamaUnhealthy => "Ollama is running at {} but its API returned errors",
+        Msg::HintOllamaUnhealthy => "Check the Ollama server logs",
+        Msg::HintInsideContainer => "ollama-bench runs in a container, where localhost is the container itself; try --ollama-url {} or --docker",
+        Msg::HintOllamaContainer => "An Ollama container publishes {}; point --ollama-url at the host port",
+        Msg::DoctorFailed => "{} check(s) failed",
+        Msg::HintDoctorFailed => "Fix the ❌ items above and run ollama-bench doctor again",
+        Msg::NoModelSelected => "No model met the selection criteria",
+        Msg::HintNoModelSelected => "Relax --min-accuracy/--max-ttft or add more candidate models",
+        Msg::SpeedRegressed => "Speed regressed beyond --fail-on-regression: {}",
+        Msg::HintSpeedRegressed => "Re-run to rule out noise, or refresh the baseline if the slowdown is expected",
+        Msg::DuelIncomplete => "Duel {} had no round where both models succeeded",
+        Msg::HintDuelIncomplete => "Check both models answer the prompt (run them with -v), then duel again",
+        Msg::TaskFailed => "A request task stopped unexpectedly: {}",
+        Msg::HintTaskFailed => "This is a bug; please report it with the command line you ran",
+        Msg::HintContextOverflow => "Raise num_ctx (--num-ctx or the Modelfile) or shorten the prompt; --strict-context makes this an error",
+        Msg::SampleSizeTitle => "Iterations for a 95% CI within ±{}% of tok/s:",
+        Msg::SampleSizeEnough => "enough ({} needed, {} ran)",
+        Msg::SampleSizeNeeded => "{} ({} ran)",
+        Msg::SampleSizeTooWide => "more than {}; the spread is too wide to narrow by repetition",
+        Msg::HintRerunIterations => "Rerun with -n {} for a reliable comparison",
+        Msg::PairedTitle => "Paired comparison, {} vs {} (B − A, 95% CI):",
+        Msg::PairedTooFew => "{} usable pairs; need at least 2",
+        Msg::PairedPairs => "{} pairs",
+        Msg::DuelTitle => "Duel, {} vs {} (B − A, 95% CI):",
+        Msg::DuelTooFew => "{} usable rounds; need at least 2",
+        Msg::DuelSpeed => "Speed",
+        Msg::DuelFaster => "Faster: {} in {}/{} rounds ({}%), {} in {}/{} ({}%)",
+        Msg::Significant => "significant",
+        Msg::WithinNoise => "within noise",
+        Msg::UntilCiTitle => "95% CI of tok/s, aiming for ±{}%:",
+        Msg::UntilCiAfter => "{} after {} iterations",
+        Msg::UntilCiNoSpread => "no spread yet",
+        Msg::UntilCiCapReached => "(cap reached)",
+    }
+}
+
+fn ja(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::ColumnModel => "モデル",
+        Msg::ColumnAvgSpeed => "平均速度",
+        Msg::ColumnTtft => "TTFT",
+        Msg::ColumnSuccess => "成功率",
+        Msg::ColumnLatency => "レイテンシ",
+        Msg::ColumnFirstSentence => "最初の文",
+        Msg::ColumnEmbeddingSpeed => "埋め込み/s",
+        Msg::ColumnDimensions => "次元",
+        Msg::NoResults => "表示する結果がありません。",
+        Msg::Winner => "最速",
+        Msg::Faster => "{}% 高速",
+        Msg::LowerTtft => "TTFT {}% 短縮",
+        Msg::CompletedIn => "所要時間",
+        Msg::OllamaNotRunning => "Ollama が起動していません",
+        Msg::HintStartOllama => "起動するには: ollama serve",
+        Msg::ModelNotFound => "モデル '{}' が見つかりません",
+        Msg::HintPullModel => "インストールするには: ollama pull {}",
+        Msg::NetworkTimeout => "{}秒後にネットワークがタイムアウトしました",
+        Msg::HintIncreaseTimeout => "--timeout を増やしてください",
+        Msg::InvalidModel => "無効なモデル名: '{}'",
+        Msg::HintModelFormat => "モデル名は model:tag の形式で指定してください (例: llama2:7b)",
+        Msg::ConnectionFailed => "{} の Ollama に接続できませんでした",
+        Msg::HintCheckOllama => "Ollama が起動していてアクセス可能か確認してください",
+        Msg::ParseError => "レスポンスの解析に失敗しました: {}",
+        Msg::HintVersionMismatch => "Ollama のバージョンとの互換性の問題の可能性があります",
+        Msg::IoError => "I/O エラー: {}",
+        Msg::HintPermissions => "ファイルの権限とディスク容量を確認してください",
+        Msg::ConfigError => "設定エラー: {}",
+        Msg::UnknownHost => "ホスト '{}' を解決できません",
+        Msg::HintUnknownHost => "--ollama-url を確認してください。コンテナ内からは host.docker.internal またはサービス名を使用します",
+        Msg::PortClosed => "{} で待ち受けているサービスがありません",
+        Msg::HintPortClosed => "起動するには: ollama serve (Docker ではポートを -p {}:11434 で公開してください)",
+        Msg::Unresponsive => "{} は接続を受け付けますが応答しません",
+        Msg::HintUnresponsive => "サーバーの過負荷かプロキシの可能性があります。--timeout を増やしてください",
+        Msg::NotOllama => "{} で応答しているのは Ollama ではありません",
+        Msg::HintNotOllama => "ポートの割り当てを確認するか、--ollama-url に Ollama のポートを指定してください",
+        Msg::OllamaUnhealthy => "{} で Ollama は起動していますが API がエラーを返しました",
+        Msg::HintOllamaUnhealthy => "Ollama サーバーのログを確認してください",
+        Msg::HintInsideContainer => "ollama-bench はコンテナ内で動作しており、localhost はコンテナ自身を指します。--ollama-url {} または --docker を試してください",
+        Msg::HintOllamaContainer => "Ollama コンテナは {} を公開しています。--ollama-url にホスト側のポートを指定してください",
+        Msg::DoctorFailed => "{} 件のチェックに失敗しました",
+        Msg::HintDoctorFailed => "上記の ❌ 項目を修正してから ollama-bench doctor を再実行してください",
+        Msg::NoModelSelected => "選定条件を満たすモデルがありません",
+        Msg::HintNoModelSelected => "--min-accuracy/--max-ttft を緩めるか、候補モデルを追加してください",
+        Msg::SpeedRegressed => "速度が --fail-on-regression の閾値を超えて低下しました: {}",
+        Msg::HintSpeedRegressed => "ノイズでないか再実行して確認するか、想定内の低下であればベースラインを更新してください",
+        Msg::DuelIncomplete => "対決 {} で両モデルが成功したラウンドがありません",
+        Msg::HintDuelIncomplete => "両モデルがプロンプトに応答するか (-v で実行して) 確認してから、再度対決してください",
+        Msg::TaskFailed => "リクエストタスクが予期せず停止しました: {}",
+        Msg::HintTaskFailed => "不具合です。実行したコマンドラインを添えて報告してください",
+        Msg::HintContextOverflow => "num_ctx を増やす（--num-ctx または Modelfile）か、プロンプトを短くしてください。--strict-context でエラーにできます",
+        Msg::SampleSizeTitle => "tok/s の 95% 信頼区間を ±{}% 以内にするための反復回数:",
+        Msg::SampleSizeEnough => "十分（必要 {}、実行 {}）",
+        Msg::SampleSizeNeeded => "{}（実行 {}）",
+        Msg::SampleSizeTooWide => "{} 回超。ばらつきが大きく、反復では狭まりません",
+        Msg::HintRerunIterations => "信頼できる比較には -n {} で再実行してください",
+        Msg::PairedTitle => "ペア比較 {} vs {}（B − A、95% 信頼区間）:",
+        Msg::PairedTooFew => "有効なペア {} 組。2 組以上必要です",
+        Msg::PairedPairs => "{} ペア",
+        Msg::DuelTitle => "対決 {} vs {}（B − A、95% 信頼区間）:",
+        Msg::DuelTooFew => "有効なラウンド {} 回。2 回以上必要です",
+        Msg::DuelSpeed => "速度",
+        Msg::DuelFaster => "高速だった回数: {} {}/{} ラウンド（{}%）、{} {}/{}（{}%）",
+        Msg::Significant => "有意",
+        Msg::WithinNoise => "誤差の範囲",
+        Msg::UntilCiTitle => "tok/s の 95% 信頼区間、目標 ±{}%:",
+        Msg::UntilCiAfter => "{}（{} 回の反復後）",
+        Msg::UntilCiNoSpread => "ばらつき未計測",
+        Msg::UntilCiCapReached => "（上限に到達）",
+    })
+}
+
+fn de(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::ColumnModel => "Modell",
+        Msg::ColumnAvgSpeed => "Ø Tempo",
+        Msg::ColumnTtft => "TTFT",
+        Msg::ColumnSuccess => "Erfolg",
+        Msg::ColumnLatency => "Latenz",
+        Msg::ColumnFirstSentence => "Erster Satz",
+        Msg::ColumnEmbeddingSpeed => "Emb./s",
+        Msg::ColumnDimensions => "Dim.",
+        Msg::NoResults => "Keine Ergebnisse vorhanden.",
+        Msg::Winner => "Sieger",
+        Msg::Faster => "{}% schneller",
+        Msg::LowerTtft => "{}% niedrigere TTFT",
+        Msg::CompletedIn => "Abgeschlossen in",
+        Msg::OllamaNotRunning => "Ollama läuft nicht",
+        Msg::HintStartOllama => "Starten mit: ollama serve",
+        Msg::ModelNotFound => "Modell '{}' nicht gefunden",
+        Msg::HintPullModel => "Installieren mit: ollama pull {}",
+        Msg::NetworkTimeout => "Netzwerk-Timeout nach {}s",
+        Msg::HintIncreaseTimeout => "Versuche, --timeout zu erhöhen",
+        Msg::InvalidModel => "Ungültiger Modellname: '{}'",
+        Msg::HintModelFormat => "Modellnamen haben das Format modell:tag (z. B. llama2:7b)",
+        Msg::ConnectionFailed => "Verbindung zu Ollama unter {} fehlgeschlagen",
+        Msg::HintCheckOllama => "Prüfe, ob Ollama läuft und erreichbar ist",
+        Msg::ParseError => "Antwort konnte nicht gelesen werden: {}",
+        Msg::HintVersionMismatch => "Möglicherweise ist deine Ollama-Version nicht kompatibel",
+        Msg::IoError => "E/A-Fehler: {}",
+        Msg::HintPermissions => "Prüfe Dateiberechtigungen und freien Speicherplatz",
+        Msg::ConfigError => "Konfigurationsfehler: {}",
+        Msg::UnknownHost => "Host '{}' kann nicht aufgelöst werden",
+        Msg::HintUnknownHost => "Prüfe --ollama-url; aus einem Container heraus host.docker.internal oder den Dienstnamen verwenden",
+        Msg::PortClosed => "Auf {} lauscht kein Dienst",
+        Msg::HintPortClosed => "Starten mit: ollama serve (in Docker den Port mit -p {}:11434 freigeben)",
+        Msg::Unresponsive => "{} nimmt Verbindungen an, antwortet aber nicht",
+        Msg::HintUnresponsive => "Der Server ist evtl. überlastet oder hinter einem Proxy; versuche, --timeout zu erhöhen",
+        Msg::NotOllama => "Unter {} antwortet ein anderer Dienst, nicht Ollama",
+        Msg::HintNotOllama => "Prüfe die Port-Zuordnung oder setze --ollama-url auf den Ollama-Port",
+        Msg::OllamaUnhealthy => "Ollama läuft unter {}, aber die API meldet Fehler",
+        Msg::HintOllamaUnhealthy => "Prüfe die Logs des Ollama-Servers",
+        Msg::HintInsideContainer => "ollama-bench läuft in einem Container, dort ist localhost der Container selbst; versuche --ollama-url {} oder --docker",
+        Msg::HintOllamaContainer => "Ein Ollama-Container veröffentlicht {}; setze --ollama-url auf den Host-Port",
+        Msg::DoctorFailed => "{} Prüfung(en) fehlgeschlagen",
+        Msg::HintDoctorFailed => "Behebe die ❌-Punkte oben und führe ollama-bench doctor erneut aus",
+        Msg::NoModelSelected => "Kein Modell erfüllt die Auswahlkriterien",
+        Msg::HintNoModelSelected => "Lockere --min-accuracy/--max-ttft oder füge weitere Kandidaten hinzu",
+        Msg::SpeedRegressed => "Geschwindigkeit über --fail-on-regression hinaus gesunken: {}",
+        Msg::HintSpeedRegressed => "Erneut ausführen, um Rauschen auszuschließen, oder die Baseline aktualisieren, wenn der Rückgang erwartet ist",
+        Msg::DuelIncomplete => "Im Duell {} gab es keine Runde, in der beide Modelle erfolgreich waren",
+        Msg::HintDuelIncomplete => "Prüfe, ob beide Modelle auf den Prompt antworten (mit -v ausführen), und starte das Duell erneut",
+        Msg::TaskFailed => "Eine Anfrage-Task wurde unerwartet beendet: {}",
+        Msg::HintTaskFailed => "Das ist ein Fehler; bitte melde ihn mit der verwendeten Befehlszeile",
+        Msg::HintContextOverflow => "Erhöhe num_ctx (--num-ctx oder das Modelfile) oder kürze den Prompt; mit --strict-context wird daraus ein Fehler",
+        Msg::SampleSizeTitle => "Iterationen für ein 95%-KI innerhalb ±{}% der tok/s:",
+        Msg::SampleSizeEnough => "ausreichend ({} nötig, {} gelaufen)",
+        Msg::SampleSizeNeeded => "{} ({} gelaufen)",
+        Msg::SampleSizeTooWide => "mehr als {}; die Streuung ist zu groß, um sie durch Wiederholung zu verringern",
+        Msg::HintRerunIterations => "Für einen verlässlichen Vergleich mit -n {} wiederholen",
+        Msg::PairedTitle => "Paarvergleich, {} gegen {} (B − A, 95%-KI):",
+        Msg::PairedTooFew => "{} verwertbare Paare; mindestens 2 nötig",
+        Msg::PairedPairs => "{} Paare",
+        Msg::DuelTitle => "Duell, {} gegen {} (B − A, 95%-KI):",
+        Msg::DuelTooFew => "{} verwertbare Runden; mindestens 2 nötig",
+        Msg::DuelSpeed => "Tempo",
+        Msg::DuelFaster => "Schneller: {} in {}/{} Runden ({}%), {} in {}/{} ({}%)",
+        Msg::Significant => "signifikant",
+        Msg::WithinNoise => "im Rauschen",
+        Msg::UntilCiTitle => "95%-KI der tok/s, Ziel ±{}%:",
+        Msg::UntilCiAfter => "{} nach {} Iterationen",
+        Msg::UntilCiNoSpread => "noch keine Streuung",
+        Msg::UntilCiCapReached => "(Obergrenze erreicht)",
+    })
+}
+
+/// Terminal columns taken by `text`; CJK and fullwidth characters count double.
+pub fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Left-aligns `text` in `width` terminal columns.
+pub fn pad(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_lookup() {
+        assert_eq!(Lang::En.text(Msg::Winner), "Winner");
+        assert_eq!(Lang::De.text(Msg::Winner), "Sieger");
+        assert_eq!(Lang::Ja.text(Msg::ColumnModel), "モデル");
+        assert_eq!(Lang::Ja.text(Msg::Faster).replacen("{}", "12.5", 1), "12.5% 高速");
+    }
+
+    #[test]
+    fn test_display_width() {
+        assert_eq!(display_width("Model"), 5);
+        assert_eq!(display_width("モデル"), 6);
+        assert_eq!(pad("成功率", 8), "成功率  ");
+        assert_eq!(pad("Ø Tempo", 9), "Ø Tempo  ");
+    }
+}
@@ -0,0 +1,119 @@
+use std::path::Path;
+use std::process::Command;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::{BenchmarkError, Result};
+
+/// Key under which a signed JSON report stores its hash.
+const INTEGRITY_KEY: &str = "integrity";
+
+/// Serializes `value` with object keys sorted at every level, so the same
+/// results always hash the same regardless of field order.
+fn canonicalize(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            let fields: Vec<String> = keys
+                .into_iter()
+                .map(|key| format!("{}:{}", Value::String(key.clone()), canonicalize(&map[key])))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonicalize).collect();
+            format!("[{}]", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+pub fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+/// Adds an `integrity` section holding the SHA-256 of the rest of the report.
+pub fn seal(report: &mut Value) {
+    if let Value::Object(map) = report {
+        map.remove(INTEGRITY_KEY);
+        let hash = sha256_hex(canonicalize(&Value::Object(map.clone())).as_bytes());
+        map.insert(
+            INTEGRITY_KEY.to_string(),
+            serde_json::json!({ "algorithm": "sha256", "hash": hash }),
+        );
+    }
+}
+
+/// Checks a sealed JSON report; `Ok(false)` means it was edited after signing.
+pub fn verify(content: &str) -> Result<bool> {
+    let mut report: Value = serde_json::from_str(content)?;
+    let Some(expected) = report
+        .get(INTEGRITY_KEY)
+        .and_then(|integrity| integrity.get("hash"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+    else {
+        return Err(BenchmarkError::ConfigError(
+            "Report has no integrity hash (was it exported with --sign?)".to_string(),
+        ));
+    };
+
+    seal(&mut report);
+    Ok(report[INTEGRITY_KEY]["hash"] == expected)
+}
+
+/// Writes a `sha256sum`-compatible sidecar next to a non-JSON export,
+/// hashing the file as written (after any compression).
+pub fn write_checksum(path: &str) -> Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    let hash = format!("{:x}", hasher.finalize());
+
+    let file_name = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path);
+    let checksum_path = format!("{}.sha256", path);
+    std::fs::write(&checksum_path, format!("{}  {}\n", hash, file_name))?;
+    Ok(checksum_path)
+}
+
+/// Signs `path` with the `minisign` CLI, producing `<path>.minisig`.
+pub fn minisign(path: &str, secret_key: &str) -> Result<()> {
+    let status = Command::new("minisign")
+        .args(["-S", "-s", secret_key, "-m", path])
+        .status()
+        .map_err(|e| BenchmarkError::ConfigError(format!("Could not run minisign: {}", e)))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(BenchmarkError::ConfigError(format!("minisign failed to sign {}", path)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonicalize_sorts_keys() {
+        let a: Value = serde_json::from_str(r#"{"b": 1, "a": {"d": [1, 2], "c": "x"}}"#).unwrap();
+        let b: Value = serde_json::from_str(r#"{"a": {"c": "x", "d": [1, 2]}, "b": 1}"#).unwrap();
+        assert_eq!(canonicalize(&a), r#"{"a":{"c":"x","d":[1,2]},"b":1}"#);
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+    }
+
+    #[test]
+    fn test_seal_and_verify() {
+        let mut report = serde_json::json!({ "models": [{ "model": "m", "avg_tokens_per_second": 25.5 }] });
+        seal(&mut report);
+        let sealed = serde_json::to_string_pretty(&report).unwrap();
+        assert!(verify(&sealed).unwrap());
+
+        let tampered = sealed.replace("25.5", "35.5");
+        assert!(!verify(&tampered).unwrap());
+        assert!(verify(r#"{"models": []}"#).is_err());
+    }
+}
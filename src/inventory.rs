@@ -0,0 +1,235 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::error::{BenchmarkError, Result};
+use crate::ollama::OllamaClient;
+use crate::types::OllamaModel;
+
+/// Parameter-count buckets for `--size-class`, named after the common
+/// release sizes they gather (e.g. 7b also takes 6.7B, 8B and 9B models).
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum SizeClass {
+    /// 5B to under 10B parameters
+    #[value(name = "7b")]
+    Small,
+    /// 10B to under 20B parameters
+    #[value(name = "13b")]
+    Medium,
+    /// 60B to under 90B parameters
+    #[value(name = "70b")]
+    Large,
+}
+
+impl SizeClass {
+    /// Bounds in billions of parameters, lower inclusive.
+    fn bounds(&self) -> (f64, f64) {
+        match self {
+            SizeClass::Small => (5.0, 10.0),
+            SizeClass::Medium => (10.0, 20.0),
+            SizeClass::Large => (60.0, 90.0),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SizeClass::Small => "7b",
+            SizeClass::Medium => "13b",
+            SizeClass::Large => "70b",
+        }
+    }
+
+    pub fn contains(&self, billions: f64) -> bool {
+        let (low, high) = self.bounds();
+        (low..high).contains(&billions)
+    }
+}
+
+/// One model installed on the server, for `ollama-bench list`.
+#[derive(Debug, Clone, Serialize)]
+pub struct InstalledModel {
+    pub name: String,
+    pub size_bytes: u64,
+    /// As Ollama reports it, e.g. "6.7B"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameter_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+    pub modified_at: String,
+}
+
+/// The installed models, largest first, with the details `/api/show`
+/// adds. A model whose details cannot be read is still listed.
+pub async fn list(client: &OllamaClient) -> Result<Vec<InstalledModel>> {
+    let mut installed = Vec::new();
+    for model in client.list_model_details().await? {
+        let details = client.show_model(&model.name).await.ok().and_then(|show| show.details).unwrap_or_default();
+        installed.push(InstalledModel {
+            parameter_size: details.parameter_size,
+            quantization: details.quantization_level,
+            family: details.family,
+            ..InstalledModel::from(model)
+        });
+    }
+    installed.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then_with(|| a.name.cmp(&b.name)));
+    Ok(installed)
+}
+
+impl InstalledModel {
+    /// Parameter count in billions, from the server's metadata or, failing
+    /// that, from a size in the tag (`llama2:7b`, `mixtral:8x7b-instruct`).
+    pub fn parameter_billions(&self) -> Option<f64> {
+        self.parameter_size.as_deref().and_then(parse_parameter_count).or_else(|| {
+            let (_, tag) = self.name.split_once(':')?;
+            parse_parameter_count(tag.split('-').next()?)
+        })
+    }
+}
+
+/// Names of the installed models in `class`, largest first.
+pub fn in_size_class(models: &[InstalledModel], class: SizeClass) -> Vec<String> {
+    models
+        .iter()
+        .filter(|model| model.parameter_billions().is_some_and(|b| class.contains(b)))
+        .map(|model| model.name.clone())
+        .collect()
+}
+
+/// A MODEL argument to resolve against the installed models.
+pub fn is_pattern(model: &str) -> bool {
+    model.contains('*')
+}
+
+/// Replaces each pattern in `models` with the installed models it matches,
+/// in name order, keeping the first mention of a model that several match.
+pub fn expand_patterns(models: &[String], installed: &[String]) -> Result<Vec<String>> {
+    let mut sorted = installed.to_vec();
+    sorted.sort();
+    let mut expanded: Vec<String> = Vec::new();
+    for model in models {
+        let matched: Vec<&String> = if is_pattern(model) {
+            sorted.iter().filter(|name| matches(model, name)).collect()
+        } else {
+            vec![model]
+        };
+        if matched.is_empty() {
+            return Err(BenchmarkError::ConfigError(format!("{} matches no installed model", model)));
+        }
+        for name in matched {
+            if !expanded.contains(name) {
+                expanded.push(name.clone());
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+/// Whether `name` matches any `--exclude` pattern.
+pub fn excluded(name: &str, patterns: &[String]) -> bool {
+    patterns.iter().any(|pattern| matches(pattern, name))
+}
+
+/// `*` stands for any run of characters, and a pattern without a tag
+/// matches every tag of the model.
+fn matches(pattern: &str, name: &str) -> bool {
+    let base = name.split_once(':').map_or(name, |(base, _)| base);
+    let target = if pattern.contains(':') { name } else { base };
+    glob(pattern.as_bytes(), target.as_bytes())
+}
+
+fn glob(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| glob(rest, &text[skip..])),
+        Some((c, rest)) => text.first() == Some(c) && glob(rest, &text[1..]),
+    }
+}
+
+/// "6.7B", "137M" or "8x7b" as billions of parameters.
+fn parse_parameter_count(text: &str) -> Option<f64> {
+    let text = text.trim().to_lowercase();
+    let (count, scale) = match text.strip_suffix('b') {
+        Some(count) => (count, 1.0),
+        None => (text.strip_suffix('m')?, 1000.0),
+    };
+    let billions = match count.split_once('x') {
+        Some((experts, each)) => experts.parse::<f64>().ok()? * each.parse::<f64>().ok()?,
+        None => count.parse::<f64>().ok()?,
+    };
+    (billions.is_finite() && billions > 0.0).then_some(billions / scale)
+}
+
+impl From<OllamaModel> for InstalledModel {
+    fn from(model: OllamaModel) -> Self {
+        Self {
+            name: model.name,
+            size_bytes: model.size.max(0) as u64,
+            parameter_size: None,
+            quantization: None,
+            family: None,
+            // RFC 3339 with nanoseconds; the date is what matters in a listing
+            modified_at: model.modified_at.get(..10).unwrap_or(&model.modified_at).to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(name: &str, parameter_size: Option<&str>) -> InstalledModel {
+        InstalledModel {
+            name: name.to_string(),
+            size_bytes: 0,
+            parameter_size: parameter_size.map(str::to_string),
+            quantization: None,
+            family: None,
+            modified_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_parameter_billions() {
+        assert_eq!(installed("llama2:latest", Some("6.7B")).parameter_billions(), Some(6.7));
+        assert_eq!(installed("all-minilm:latest", Some("23M")).parameter_billions(), Some(0.023));
+        assert_eq!(installed("mixtral:8x7b-instruct", None).parameter_billions(), Some(56.0));
+        assert_eq!(installed("codellama:13b-code", Some("")).parameter_billions(), Some(13.0));
+        assert_eq!(installed("mistral:latest", None).parameter_billions(), None);
+    }
+
+    #[test]
+    fn test_excluded() {
+        let patterns = ["*:70b".to_string(), "nomic-embed-text".to_string(), "llava*".to_string()];
+        assert!(excluded("llama3:70b", &patterns));
+        assert!(excluded("nomic-embed-text:latest", &patterns));
+        assert!(excluded("llava-phi3:latest", &patterns));
+        assert!(!excluded("llama3:8b", &patterns));
+        assert!(!excluded("nomic-embed-text-v2:latest", &patterns));
+    }
+
+    #[test]
+    fn test_expand_patterns() {
+        let installed: Vec<String> = ["llama3:8b-q8_0", "mistral:7b", "llama3:8b", "llama3.1:8b", "phi3:mini"].map(String::from).to_vec();
+        let models = ["llama3*", "*:7b", "llama3:8b", "phi3:mini"].map(String::from);
+        assert_eq!(
+            expand_patterns(&models, &installed).unwrap(),
+            vec!["llama3.1:8b", "llama3:8b", "llama3:8b-q8_0", "mistral:7b", "phi3:mini"]
+        );
+        assert!(expand_patterns(&["gemma*".to_string()], &installed).is_err());
+    }
+
+    #[test]
+    fn test_in_size_class() {
+        let models = [
+            installed("llama3:70b", Some("70.6B")),
+            installed("qwen2.5:14b", Some("14.8B")),
+            installed("llama3:8b", Some("8.0B")),
+            installed("mistral:latest", Some("7.2B")),
+            installed("phi:2.7b", Some("2.8B")),
+        ];
+        assert_eq!(in_size_class(&models, SizeClass::Small), vec!["llama3:8b", "mistral:latest"]);
+        assert_eq!(in_size_class(&models, SizeClass::Medium), vec!["qwen2.5:14b"]);
+        assert_eq!(in_size_class(&models, SizeClass::Large), vec!["llama3:70b"]);
+    }
+}
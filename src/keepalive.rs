@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+use crate::config::SWAP_LOAD_THRESHOLD_MS;
+use crate::types::BenchmarkResult;
+
+/// One request sent after a model sat idle for `idle_seconds`.
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleSample {
+    pub model: String,
+    pub idle_seconds: u64,
+    pub latency_ms: u64,
+    pub load_ms: u64,
+    /// Ollama had unloaded the model and had to load it again
+    pub reloaded: bool,
+    pub success: bool,
+}
+
+impl IdleSample {
+    pub fn new(idle_seconds: u64, result: &BenchmarkResult) -> Self {
+        Self {
+            model: result.model.clone(),
+            idle_seconds,
+            latency_ms: result.total_duration_ms,
+            load_ms: result.load_duration_ms,
+            reloaded: result.load_duration_ms >= SWAP_LOAD_THRESHOLD_MS,
+            success: result.success,
+        }
+    }
+}
+
+/// Longest idle time that still found `model` loaded, and the shortest that
+/// did not; the keep-alive expiry lies between the two.
+pub fn expiry_bounds(samples: &[IdleSample], model: &str) -> (Option<u64>, Option<u64>) {
+    let probes = samples.iter().filter(|s| s.model == model && s.success);
+    let kept = probes.clone().filter(|s| !s.reloaded).map(|s| s.idle_seconds).max();
+    let expired = probes.filter(|s| s.reloaded).map(|s| s.idle_seconds).min();
+    (kept, expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(idle_seconds: u64, load_ms: u64) -> IdleSample {
+        let result = BenchmarkResult {
+            total_duration_ms: 500 + load_ms,
+            load_duration_ms: load_ms,
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        IdleSample::new(idle_seconds, &result)
+    }
+
+    #[test]
+    fn test_expiry_bounds() {
+        let samples = vec![sample(30, 2), sample(120, 3), sample(360, 2400)];
+        assert!(samples[2].reloaded);
+        assert_eq!(expiry_bounds(&samples, "m"), (Some(120), Some(360)));
+        assert_eq!(expiry_bounds(&samples[..2], "m"), (Some(120), None));
+        assert_eq!(expiry_bounds(&samples, "other"), (None, None));
+    }
+}
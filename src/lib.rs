@@ -0,0 +1,116 @@
+//! Apache Bench-style benchmarking for Ollama models, usable as a library
+//! as well as through the `ollama-bench` CLI.
+//!
+//! ```no_run
+//! use ollama_bench::{BenchmarkConfig, Benchmarker, CancellationToken, OllamaClient, QuietProgress};
+//! use std::time::Duration;
+//!
+//! # async fn run() -> ollama_bench::Result<()> {
+//! let config = BenchmarkConfig { iterations: 3, ..Default::default() };
+//! let client = OllamaClient::new(config.ollama_base_url.clone(), Duration::from_secs(config.timeout_seconds));
+//! let mut benchmarker = Benchmarker::new(client, config, Box::new(QuietProgress));
+//!
+//! let summaries = benchmarker.benchmark_models(vec!["llama2:7b".to_string()], &CancellationToken::new()).await?;
+//! for summary in &summaries {
+//!     println!("{}: {:.1} tok/s", summary.label(), summary.avg_tokens_per_second);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub(crate) mod aggregate;
+pub(crate) mod anomaly;
+pub(crate) mod batch;
+pub(crate) mod benchmark;
+pub(crate) mod cancel;
+pub(crate) mod cli;
+pub(crate) mod clock;
+pub(crate) mod compare;
+pub(crate) mod config;
+pub(crate) mod container;
+pub(crate) mod context;
+pub(crate) mod csvmeta;
+pub(crate) mod doctor;
+pub(crate) mod effect;
+pub(crate) mod endpoints;
+pub(crate) mod error;
+pub(crate) mod eval;
+pub(crate) mod exclusions;
+pub(crate) mod export;
+pub(crate) mod filter;
+pub(crate) mod golden;
+pub(crate) mod gpu;
+pub(crate) mod hedge;
+pub(crate) mod histogram;
+pub(crate) mod history;
+pub(crate) mod html;
+pub(crate) mod host;
+pub(crate) mod i18n;
+pub(crate) mod integrity;
+pub(crate) mod inventory;
+pub(crate) mod keepalive;
+pub(crate) mod load;
+pub(crate) mod net;
+pub(crate) mod ollama;
+pub(crate) mod openmetrics;
+pub(crate) mod outliers;
+pub(crate) mod output;
+pub(crate) mod overhead;
+pub(crate) mod paired;
+pub(crate) mod pattern;
+pub(crate) mod plan;
+pub(crate) mod precision;
+pub(crate) mod prefs;
+pub(crate) mod preset;
+pub(crate) mod progress;
+pub(crate) mod queue;
+pub(crate) mod redact;
+pub(crate) mod reference;
+pub(crate) mod runner;
+pub(crate) mod rundb;
+pub(crate) mod select;
+pub(crate) mod selfcheck;
+pub(crate) mod suite;
+pub(crate) mod telemetry;
+pub(crate) mod template;
+pub(crate) mod timeline;
+pub(crate) mod trend;
+pub(crate) mod types;
+pub(crate) mod vision;
+pub(crate) mod warmpool;
+pub(crate) mod warmup;
+pub(crate) mod warnings;
+
+// Modules stay internal; everything a library user can name is re-exported
+// here, including the types that appear in the public structs' fields and
+// the public methods' signatures (`-W unnameable_types` finds any missed).
+pub use benchmark::{BenchmarkEvent, Benchmarker};
+pub use cli::{Cli, Command, ConfigAction, HistoryAction, JsonSchema, OutputFormat, ProgressMode};
+pub use context::ContextLimit;
+pub use effect::Distribution;
+pub use error::{BenchmarkError, Diagnosis, Result};
+pub use eval::{EvalItem, EvalScore, EvalTask};
+pub use exclusions::ExcludedModel;
+pub use filter::{Filter, SummaryFilter};
+pub use histogram::TokenHistogram;
+pub use i18n::Lang;
+pub use inventory::SizeClass;
+pub use keepalive::IdleSample;
+pub use load::LoadStats;
+pub use ollama::{ClientOptions, ConnectionProfile, OllamaClient};
+pub use outliers::OutlierRule;
+pub use pattern::LoadPattern;
+pub use plan::{OptionSweep, SweepOrder};
+pub use prefs::parse_cli;
+pub use preset::Preset;
+pub use progress::{PlainProgress, ProgressReporter, QuietProgress, TerminalProgress};
+pub use redact::RedactField;
+pub use runner::BenchmarkRunner;
+pub use suite::SuitePrompt;
+pub use telemetry::{GpuSource, TelemetryStats, Usage};
+pub use types::{
+    BatchMode, BenchmarkConfig, BenchmarkResult, EmbeddingStats, Endpoint, IterationDetail, ModelSummary,
+    OllamaModel, OllamaModelDetails, OllamaPullStatus, OllamaRunningModel, OllamaShowResponse, ServerInfo, SpeedUnit,
+};
+pub use vision::ImageInput;
+pub use tokio_util::sync::CancellationToken;
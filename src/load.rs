@@ -0,0 +1,128 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::types::BenchmarkResult;
+
+/// Throughput and latency of one model under concurrent load (`--concurrency`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoadStats {
+    pub concurrency: u32,
+    pub requests: u64,
+    pub failed: u64,
+    /// Wall-clock time from the first request sent to the last one finished
+    pub wall_ms: u64,
+    pub requests_per_second: f64,
+    /// Tokens generated by all requests together per second of wall time
+    pub aggregate_tokens_per_second: f64,
+    pub avg_latency_ms: f64,
+    pub p50_latency_ms: u64,
+    pub p95_latency_ms: u64,
+    pub max_latency_ms: u64,
+    /// Fastest over slowest request, by end-to-end throughput (completion
+    /// tokens over request latency, queueing included)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub throughput_spread: Option<f64>,
+    /// Jain's fairness index of end-to-end throughput: 1.0 when every
+    /// request fares the same, towards 1/n when a few get it all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jain_fairness: Option<f64>,
+}
+
+impl LoadStats {
+    pub fn new(concurrency: u32, results: &[BenchmarkResult], wall: Duration) -> Self {
+        let wall_secs = wall.as_secs_f64().max(f64::EPSILON);
+        let successful: Vec<&BenchmarkResult> = results.iter().filter(|r| r.success).collect();
+        let mut latencies: Vec<u64> = successful.iter().map(|r| r.total_duration_ms).collect();
+        latencies.sort_unstable();
+        let completion_tokens: u64 = successful.iter().map(|r| r.completion_tokens as u64).sum();
+        let throughputs: Vec<f64> = successful
+            .iter()
+            .filter(|r| r.total_duration_ms > 0)
+            .map(|r| r.completion_tokens as f64 * 1000.0 / r.total_duration_ms as f64)
+            .collect();
+
+        Self {
+            concurrency,
+            requests: results.len() as u64,
+            failed: (results.len() - successful.len()) as u64,
+            wall_ms: wall.as_millis() as u64,
+            requests_per_second: results.len() as f64 / wall_secs,
+            aggregate_tokens_per_second: completion_tokens as f64 / wall_secs,
+            avg_latency_ms: if latencies.is_empty() {
+                0.0
+            } else {
+                latencies.iter().sum::<u64>() as f64 / latencies.len() as f64
+            },
+            p50_latency_ms: percentile(&latencies, 50),
+            p95_latency_ms: percentile(&latencies, 95),
+            max_latency_ms: latencies.last().copied().unwrap_or(0),
+            throughput_spread: spread(&throughputs),
+            jain_fairness: jain_index(&throughputs),
+        }
+    }
+}
+
+fn spread(values: &[f64]) -> Option<f64> {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    (values.len() > 1 && min > 0.0).then(|| max / min)
+}
+
+/// `(Σx)² / (n·Σx²)`; needs two values that are not all zero.
+fn jain_index(values: &[f64]) -> Option<f64> {
+    let sum: f64 = values.iter().sum();
+    let sum_of_squares: f64 = values.iter().map(|x| x * x).sum();
+    (values.len() > 1 && sum_of_squares > 0.0).then(|| sum * sum / (values.len() as f64 * sum_of_squares))
+}
+
+/// Nearest-rank percentile of already sorted values.
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len() + 99) / 100;
+    sorted[rank.saturating_sub(1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_percentile() {
+        let values: Vec<u64> = (1..=20).collect();
+        assert_eq!(percentile(&values, 50), 10);
+        assert_eq!(percentile(&values, 95), 19);
+        assert_eq!(percentile(&[7], 95), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_load_stats() {
+        let mut results: Vec<BenchmarkResult> = [1000, 1200, 1400]
+            .iter()
+            .map(|&ms| BenchmarkResult { total_duration_ms: ms, completion_tokens: 100, ..BenchmarkResult::ok("m", 0.0) })
+            .collect();
+        results.push(BenchmarkResult::failed("m", "p", Utc::now(), 50, "refused".to_string()));
+
+        // Four requests, two at a time, done in two seconds
+        let stats = LoadStats::new(2, &results, Duration::from_secs(2));
+        assert_eq!((stats.requests, stats.failed), (4, 1));
+        assert_eq!(stats.requests_per_second, 2.0);
+        assert_eq!(stats.aggregate_tokens_per_second, 150.0);
+        assert_eq!(stats.avg_latency_ms, 1200.0);
+        assert_eq!((stats.p50_latency_ms, stats.max_latency_ms), (1200, 1400));
+        assert!((stats.throughput_spread.unwrap() - 1.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_jain_index() {
+        assert_eq!(jain_index(&[10.0, 10.0, 10.0]), Some(1.0));
+        // One request gets everything: 1/n
+        assert_eq!(jain_index(&[30.0, 0.0, 0.0]), Some(1.0 / 3.0));
+        assert_eq!(jain_index(&[10.0]), None);
+        assert_eq!(spread(&[5.0, 20.0]), Some(4.0));
+        assert_eq!(spread(&[0.0, 20.0]), None);
+    }
+}
@@ -1,24 +1,24 @@
-mod benchmark;
-mod cli;
-mod config;
-mod error;
-mod ollama;
-mod output;
-mod progress;
-mod runner;
-mod types;
-
-use clap::Parser;
 use std::process;
 
-use crate::cli::Cli;
-use crate::runner::BenchmarkRunner;
+use ollama_bench::BenchmarkRunner;
 
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    let cli = match ollama_bench::parse_cli() {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
     
-    let runner = BenchmarkRunner::new(cli);
+    let mut runner = match BenchmarkRunner::new(cli) {
+        Ok(runner) => runner,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
     
     if let Err(e) = runner.run().await {
         eprintln!("{}", e);
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use tokio::net::lookup_host;
+use reqwest::Url;
+
+use crate::ollama::OllamaClient;
+use crate::types::BenchmarkResult;
+
+/// Address family to try first on dual-stack hosts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    fn matches(&self, addr: &SocketAddr) -> bool {
+        match self {
+            IpFamily::V4 => addr.is_ipv4(),
+            IpFamily::V6 => addr.is_ipv6(),
+        }
+    }
+}
+
+/// Host name and every address it resolves to, for URLs whose host is a
+/// name rather than a literal IP.
+pub async fn resolve(base_url: &str) -> Option<(String, Vec<SocketAddr>)> {
+    let url = Url::parse(base_url).ok()?;
+    let host = url.domain()?.to_string();
+    let port = url.port_or_known_default()?;
+    let addrs: Vec<SocketAddr> = lookup_host((host.as_str(), port)).await.ok()?.collect();
+    (!addrs.is_empty()).then_some((host, addrs))
+}
+
+/// Short name for a server in labels, e.g. `gpu-box:11434` for
+/// `http://gpu-box:11434/`.
+pub fn server_name(base_url: &str) -> String {
+    let Ok(url) = Url::parse(base_url) else {
+        return base_url.to_string();
+    };
+    match (url.host_str(), url.port_or_known_default()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        _ => base_url.to_string(),
+    }
+}
+
+/// A server for the post-run probes, named as its summaries' `server`
+/// (`None` on a single-server run).
+pub type ProbeTarget = (OllamaClient, Option<String>);
+
+/// The client whose server produced a summary with this `server`.
+pub fn probe_client<'a>(targets: &'a [ProbeTarget], server: Option<&str>) -> Option<&'a OllamaClient> {
+    targets.iter().find(|(_, name)| name.as_deref() == server).map(|(client, _)| client)
+}
+
+/// Reorders `addrs` so the preferred family comes first; the connector tries
+/// those before falling back to the rest.
+pub fn prefer(addrs: &[SocketAddr], family: IpFamily) -> Vec<SocketAddr> {
+    let (mut preferred, fallback): (Vec<SocketAddr>, Vec<SocketAddr>) =
+        addrs.iter().partition(|addr| family.matches(addr));
+    preferred.extend(fallback);
+    preferred
+}
+
+/// Requests and mean speed/TTFT per remote address.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AddressStats {
+    pub requests: u64,
+    pub avg_tokens_per_second: f64,
+    pub avg_ttft_ms: f64,
+}
+
+/// Per-address breakdown of successful requests; only meaningful when more
+/// than one address served the run.
+pub fn by_address(results: &[BenchmarkResult]) -> BTreeMap<String, AddressStats> {
+    let mut stats: BTreeMap<String, AddressStats> = BTreeMap::new();
+    for result in results.iter().filter(|r| r.success) {
+        let Some(addr) = &result.remote_addr else {
+            continue;
+        };
+        let entry = stats.entry(addr.clone()).or_default();
+        let n = entry.requests as f64;
+        entry.avg_tokens_per_second = (entry.avg_tokens_per_second * n + result.tokens_per_second) / (n + 1.0);
+        entry.avg_ttft_ms = (entry.avg_ttft_ms * n + result.time_to_first_token_ms as f64) / (n + 1.0);
+        entry.requests += 1;
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefer_orders_family_first() {
+        let v4: SocketAddr = "192.0.2.1:11434".parse().unwrap();
+        let v6: SocketAddr = "[2001:db8::1]:11434".parse().unwrap();
+        assert_eq!(prefer(&[v6, v4], IpFamily::V4), vec![v4, v6]);
+        assert_eq!(prefer(&[v4, v6], IpFamily::V6), vec![v6, v4]);
+        assert_eq!(prefer(&[v4], IpFamily::V6), vec![v4]);
+    }
+
+    #[test]
+    fn test_server_name() {
+        assert_eq!(server_name("http://gpu-box:11434/"), "gpu-box:11434");
+        assert_eq!(server_name("https://ollama.example.com"), "ollama.example.com:443");
+        assert_eq!(server_name("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_by_address() {
+        let result = |addr: &str, tps: f64| BenchmarkResult { remote_addr: Some(addr.to_string()), ..BenchmarkResult::ok("m", tps) };
+        let stats = by_address(&[
+            result("[::1]:11434", 40.0),
+            result("127.0.0.1:11434", 50.0),
+            result("127.0.0.1:11434", 60.0),
+        ]);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["127.0.0.1:11434"].requests, 2);
+        assert_eq!(stats["127.0.0.1:11434"].avg_tokens_per_second, 55.0);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_skips_ip_literals() {
+        assert!(resolve("http://127.0.0.1:11434").await.is_none());
+        let (host, addrs) = resolve("http://localhost:11434").await.unwrap();
+        assert_eq!(host, "localhost");
+        assert!(addrs.iter().all(|addr| addr.port() == 11434));
+    }
+}
@@ -1,12 +1,111 @@
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
-use reqwest::Client;
-use serde_json::json;
+use reqwest::{Certificate, Client, Response, StatusCode};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use serde::Deserialize;
+use serde_json::{json, Value};
 use chrono::Utc;
 
 use crate::types::*;
 use crate::error::{BenchmarkError, Result};
-use crate::config::get_user_agent;
+use crate::config::{get_user_agent, MAX_RETRY_BACKOFF_MS, PULL_TIMEOUT_SECONDS};
+use crate::clock::{self, Stamp};
+use crate::context;
+use crate::history;
 
+/// Header carrying `--bench-client`, for spotting benchmark traffic in gateway logs.
+pub const BENCH_CLIENT_HEADER: &str = "X-Bench-Client";
+
+/// How requests identify themselves to Ollama and any gateway in front of
+/// it, and which addresses they connect to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientOptions {
+    pub user_agent: String,
+    pub bench_client: Option<String>,
+    /// Host name and the addresses to use for it, in connection order
+    pub resolved: Option<(String, Vec<SocketAddr>)>,
+    /// Credentials, headers and TLS settings of this host (`--hosts-file`)
+    pub profile: Option<ConnectionProfile>,
+}
+
+impl Default for ClientOptions {
+    fn default() -> Self {
+        Self {
+            user_agent: get_user_agent(),
+            bench_client: None,
+            resolved: None,
+            profile: None,
+        }
+    }
+}
+
+/// One server of a `--hosts-file`, with the settings only that server
+/// needs (gateways in front of different servers rarely share API keys).
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionProfile {
+    pub url: String,
+    /// Sent as `Authorization: Bearer <key>`
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Environment variable holding the API key, so the file itself can be shared
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Extra request headers
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// PEM file of an extra CA to trust, relative to the hosts file
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+    /// Accept any certificate (self-signed test gateways)
+    #[serde(default)]
+    pub insecure: bool,
+    /// Contents of `ca_cert`, read when the file is loaded
+    #[serde(skip)]
+    pub ca_pem: Option<Vec<u8>>,
+}
+
+/// Reads a `--hosts-file`: a JSON array of [`ConnectionProfile`]s. Keys from
+/// `api_key_env` and CA files are resolved up front, so a bad profile fails
+/// before any request is sent.
+pub fn load_profiles(path: &Path) -> Result<Vec<ConnectionProfile>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| BenchmarkError::IoError(format!("{}: {}", path.display(), e)))?;
+    parse_profiles(&content, path.parent().unwrap_or(Path::new(".")))
+        .map_err(|e| BenchmarkError::ConfigError(format!("{}: {}", path.display(), e)))
+}
+
+fn parse_profiles(content: &str, dir: &Path) -> std::result::Result<Vec<ConnectionProfile>, String> {
+    let mut profiles: Vec<ConnectionProfile> = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    if profiles.is_empty() {
+        return Err("no hosts listed".to_string());
+    }
+    for profile in &mut profiles {
+        let url = profile.url.clone();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("{}: URL must start with http:// or https://", url));
+        }
+        if let Some(var) = &profile.api_key_env {
+            let key = std::env::var(var).map_err(|_| format!("{}: environment variable {} is not set", url, var))?;
+            profile.api_key = Some(key);
+        }
+        for (name, value) in &profile.headers {
+            if HeaderName::from_bytes(name.as_bytes()).is_err() || HeaderValue::from_str(value).is_err() {
+                return Err(format!("{}: invalid header {}", url, name));
+            }
+        }
+        if let Some(cert) = &profile.ca_cert {
+            let pem = std::fs::read(dir.join(cert)).map_err(|e| format!("{}: {}: {}", url, cert.display(), e))?;
+            Certificate::from_pem(&pem).map_err(|e| format!("{}: {}: {}", url, cert.display(), e))?;
+            profile.ca_pem = Some(pem);
+        }
+    }
+    Ok(profiles)
+}
+
+#[derive(Clone)]
 pub struct OllamaClient {
     client: Client,
     base_url: String,
@@ -14,15 +113,44 @@ pub struct OllamaClient {
 
 impl OllamaClient {
     pub fn new(base_url: String, timeout: Duration) -> Self {
-        let client = Client::builder()
+        Self::with_options(base_url, timeout, &ClientOptions::default())
+    }
+    
+    pub fn with_options(base_url: String, timeout: Duration, options: &ClientOptions) -> Self {
+        let mut headers = HeaderMap::new();
+        if let Some(value) = options.bench_client.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(BENCH_CLIENT_HEADER, value);
+        }
+        let profile = options.profile.clone().unwrap_or_default();
+        for (name, value) in &profile.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+                headers.insert(name, value);
+            }
+        }
+        if let Some(value) = profile.api_key.and_then(|key| HeaderValue::from_str(&format!("Bearer {}", key)).ok()) {
+            headers.insert(AUTHORIZATION, value);
+        }
+        
+        let mut builder = Client::builder()
             .timeout(timeout)
-            .user_agent(get_user_agent())
-            .build()
-            .unwrap_or_default();
+            .user_agent(options.user_agent.as_str())
+            .default_headers(headers)
+            .danger_accept_invalid_certs(profile.insecure);
+        if let Some(certificate) = profile.ca_pem.and_then(|pem| Certificate::from_pem(&pem).ok()) {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some((host, addrs)) = &options.resolved {
+            builder = builder.resolve_to_addrs(host, addrs);
+        }
+        let client = builder.build().unwrap_or_default();
             
         Self { client, base_url }
     }
     
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+    
     pub async fn health_check(&self) -> Result<bool> {
         let url = format!("{}/api/tags", self.base_url);
         
@@ -38,7 +166,31 @@ impl OllamaClient {
         }
     }
     
+    pub async fn version(&self) -> Result<String> {
+        let url = format!("{}/api/version", self.base_url);
+        
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+            
+        if !response.status().is_success() {
+            return Err(BenchmarkError::ConnectionFailed(
+                format!("HTTP {} from Ollama", response.status())
+            ));
+        }
+        
+        let version: OllamaVersion = response.json().await?;
+        Ok(version.version)
+    }
+    
     pub async fn list_models(&self) -> Result<Vec<String>> {
+        let models = self.list_model_details().await?;
+        Ok(models.into_iter().map(|m| m.name).collect())
+    }
+    
+    /// Installed models with size and digest, from `/api/tags`.
+    pub async fn list_model_details(&self) -> Result<Vec<OllamaModel>> {
         let url = format!("{}/api/tags", self.base_url);
         
         let response = self.client
@@ -53,21 +205,40 @@ impl OllamaClient {
         }
         
         let models_list: OllamaModelsList = response.json().await?;
-        Ok(models_list.models.into_iter().map(|m| m.name).collect())
+        Ok(models_list.models)
     }
     
+    /// Runs one benchmark request through the endpoint selected in `config`.
     pub async fn generate(&self, model: &str, prompt: &str, config: &BenchmarkConfig) -> Result<BenchmarkResult> {
-        let url = format!("{}/api/generate", self.base_url);
-        
-        let request_body = json!({
-            "model": model,
-            "prompt": prompt,
-            "stream": false,
-            "options": {
-                "temperature": config.temperature,
-                "num_predict": config.max_tokens,
+        let mut retries = 0;
+        let (mut result, started) = loop {
+            let started = Stamp::now();
+            let result = match config.endpoint {
+                Endpoint::Embed => self.embed(model, prompt, config).await?,
+                Endpoint::Generate | Endpoint::Chat => self.request(model, prompt, config).await?,
+            };
+            if result.success || !result.transient || retries >= config.retries {
+                break (result, started);
             }
-        });
+            tokio::time::sleep(retry_delay(config.retry_backoff_ms, retries)).await;
+            retries += 1;
+        };
+        result.retries = (retries > 0).then_some(retries);
+        result.mono_start_ms = Some(started.mono_ms());
+        result.clock_jump_ms = clock::jump_ms(&started, &Stamp::now());
+        result.endpoint = config.endpoint;
+        result.raw = config.raw && config.endpoint == Endpoint::Generate;
+        if result.success && config.endpoint != Endpoint::Embed {
+            let num_ctx = context::request_num_ctx(config, &self.base_url, model);
+            result.num_ctx = Some(num_ctx);
+            result.context_limit = context::context_limit(result.prompt_tokens, result.completion_tokens, num_ctx);
+        }
+        Ok(result)
+    }
+    
+    async fn request(&self, model: &str, prompt: &str, config: &BenchmarkConfig) -> Result<BenchmarkResult> {
+        let url = format!("{}{}", self.base_url, config.endpoint.path());
+        let request_body = request_body(model, prompt, config);
         
         let start_time = Instant::now();
         let timestamp = Utc::now();
@@ -80,57 +251,50 @@ impl OllamaClient {
                 Ok(resp) => resp,
                 Err(e) => {
                     return Ok(BenchmarkResult {
-                        model: model.to_string(),
-                        prompt: prompt.to_string(),
-                        timestamp,
-                        success: false,
-                        tokens_per_second: 0.0,
-                        time_to_first_token_ms: 0,
-                        total_duration_ms: start_time.elapsed().as_millis() as u64,
-                        prompt_tokens: 0,
-                        completion_tokens: 0,
-                        error: Some(e.to_string()),
+                        transient: is_transient_send(&e),
+                        ..BenchmarkResult::failed(model, prompt, timestamp, start_time.elapsed().as_millis() as u64, e.to_string())
                     });
                 }
             };
+        let remote_addr = response.remote_addr().map(|addr| addr.to_string());
         
         if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
             
-            // Check if it's a model not found error
-            if status.as_u16() == 404 || error_text.contains("model") {
+            // Check if it's a model not found error; a 5xx mentioning the model is a server fault
+            if status.as_u16() == 404 || (status.is_client_error() && error_text.contains("model")) {
                 return Err(BenchmarkError::ModelNotFound(model.to_string()));
             }
             
             return Ok(BenchmarkResult {
-                model: model.to_string(),
-                prompt: prompt.to_string(),
-                timestamp,
-                success: false,
-                tokens_per_second: 0.0,
-                time_to_first_token_ms: 0,
-                total_duration_ms: start_time.elapsed().as_millis() as u64,
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                error: Some(format!("HTTP {}: {}", status, error_text)),
+                transient: is_transient_status(status),
+                ..BenchmarkResult::failed(
+                    model,
+                    prompt,
+                    timestamp,
+                    start_time.elapsed().as_millis() as u64,
+                    format!("HTTP {}: {}", status, error_text),
+                )
             });
         }
         
-        let ollama_response: OllamaGenerateResponse = match response.json().await {
-            Ok(resp) => resp,
-            Err(e) => {
+        let parsed = if config.stream {
+            read_stream(response, config.endpoint, start_time).await
+        } else {
+            match response.text().await {
+                Ok(body) => parse_chunk(&body, config.endpoint)
+                    .map(|resp| (resp, StreamTimings::default()))
+                    .map_err(|e| (format!("Failed to parse response: {}", e), false)),
+                Err(e) => Err((format!("Failed to read response: {}", e), true)),
+            }
+        };
+        let (ollama_response, timings) = match parsed {
+            Ok(parsed) => parsed,
+            Err((e, transient)) => {
                 return Ok(BenchmarkResult {
-                    model: model.to_string(),
-                    prompt: prompt.to_string(),
-                    timestamp,
-                    success: false,
-                    tokens_per_second: 0.0,
-                    time_to_first_token_ms: 0,
-                    total_duration_ms: start_time.elapsed().as_millis() as u64,
-                    prompt_tokens: 0,
-                    completion_tokens: 0,
-                    error: Some(format!("Failed to parse response: {}", e)),
+                    transient,
+                    ..BenchmarkResult::failed(model, prompt, timestamp, start_time.elapsed().as_millis() as u64, e)
                 });
             }
         };
@@ -139,17 +303,20 @@ impl OllamaClient {
         let total_duration_ms = start_time.elapsed().as_millis() as u64;
         
         // Extract timing information from Ollama response
+        let load_duration_ms = (ollama_response.load_duration.unwrap_or(0) / 1_000_000) as u64;
         let prompt_eval_duration = ollama_response.prompt_eval_duration.unwrap_or(0);
         let eval_duration = ollama_response.eval_duration.unwrap_or(0);
         let prompt_tokens = ollama_response.prompt_eval_count.unwrap_or(0) as u32;
         let completion_tokens = ollama_response.eval_count.unwrap_or(0) as u32;
         
         // Calculate time to first token (approximation)
-        let time_to_first_token_ms = if prompt_eval_duration > 0 {
+        let reported_ttft_ms = if prompt_eval_duration > 0 {
             (prompt_eval_duration / 1_000_000) as u64 // Convert nanoseconds to milliseconds
         } else {
             0
         };
+        // Streaming measures it directly; the approximation is kept for comparison
+        let time_to_first_token_ms = timings.first_token_ms.unwrap_or(reported_ttft_ms);
         
         // Calculate tokens per second
         let tokens_per_second = if eval_duration > 0 && completion_tokens > 0 {
@@ -158,26 +325,500 @@ impl OllamaClient {
             0.0
         };
         
+        // Servers without logprob support simply omit the field
+        let avg_logprob = ollama_response.logprobs
+            .as_ref()
+            .filter(|lp| !lp.is_empty())
+            .map(|lp| lp.iter().map(|t| t.logprob).sum::<f64>() / lp.len() as f64);
+        
         Ok(BenchmarkResult {
             model: model.to_string(),
             prompt: prompt.to_string(),
             timestamp,
+            finished_at: Some(Utc::now()),
             success: true,
             tokens_per_second,
             time_to_first_token_ms,
             total_duration_ms,
+            load_duration_ms,
             prompt_tokens,
             completion_tokens,
             error: None,
+            response: Some(ollama_response.response),
+            avg_logprob,
+            endpoint: Endpoint::default(),
+            raw: false,
+            remote_addr,
+            anomaly: None,
+            warmup: false,
+            outlier: false,
+            embedding_dims: None,
+            reported_ttft_ms: timings.first_token_ms.map(|_| reported_ttft_ms),
+            mono_start_ms: None,
+            clock_jump_ms: None,
+            hedged_copies: None,
+            server: None,
+            variant: None,
+            telemetry: None,
+            batch: None,
+            completions: None,
+            category: None,
+            image: None,
+            num_ctx: None,
+            context_limit: None,
+            first_sentence_ms: timings.first_sentence_ms,
+            last_token_ms: timings.last_token_ms,
+            token_gaps_ms: timings.token_gaps_ms,
+            in_flight: None,
+            retries: None,
+            transient: false,
         })
     }
     
+    /// Streams a generation and abandons it once `tokens` tokens have
+    /// arrived, closing the connection the way an interactive client does
+    /// when the user stops a reply. Returns the tokens received, fewer if
+    /// the generation finished first.
+    pub async fn stream_and_cancel(&self, model: &str, prompt: &str, config: &BenchmarkConfig, tokens: u32) -> Result<u32> {
+        let url = format!("{}{}", self.base_url, config.endpoint.path());
+        let mut request_body = request_body(model, prompt, config);
+        request_body["stream"] = json!(true);
+        
+        let mut response = self.client.post(&url).json(&request_body).send().await?;
+        if !response.status().is_success() {
+            return Err(BenchmarkError::ConnectionFailed(format!("HTTP {} from Ollama", response.status())));
+        }
+        
+        let mut pending = Vec::new();
+        let mut received = 0;
+        while let Some(chunk) = response.chunk().await? {
+            pending.extend_from_slice(&chunk);
+            for line in take_lines(&mut pending) {
+                let part = parse_chunk(&line, config.endpoint)?;
+                if !part.response.is_empty() {
+                    received += 1;
+                }
+                if part.done || received >= tokens {
+                    // Dropping the response closes the connection mid-stream
+                    return Ok(received);
+                }
+            }
+        }
+        Ok(received)
+    }
+    
+    /// Asks the OpenAI-compatible `/v1/chat/completions` for `n` completions
+    /// in one request. The API reports no server timings, so speed is
+    /// completion tokens (over every choice) per second of wall clock.
+    pub async fn chat_completions(&self, model: &str, prompt: &str, config: &BenchmarkConfig, n: u32) -> Result<BenchmarkResult> {
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        
+        let mut messages = Vec::new();
+        if let Some(system) = &config.system {
+            messages.push(json!({ "role": "system", "content": system }));
+        }
+        messages.push(json!({ "role": "user", "content": prompt }));
+        let mut request_body = json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": config.max_tokens,
+            "temperature": config.temperature,
+            "n": n,
+            "stream": false,
+        });
+        if let Some(seed) = config.seed {
+            request_body["seed"] = json!(seed);
+        }
+        // The OpenAI API has no equivalent of top_k or repeat_penalty
+        if let Some(top_p) = config.options.get("top_p") {
+            request_body["top_p"] = top_p.clone();
+        }
+        
+        let start_time = Instant::now();
+        let timestamp = Utc::now();
+        let failed = |error: String| BenchmarkResult::failed(model, prompt, timestamp, start_time.elapsed().as_millis() as u64, error);
+        
+        let response = match self.client.post(&url).json(&request_body).send().await {
+            Ok(resp) => resp,
+            Err(e) => return Ok(BenchmarkResult { transient: is_transient_send(&e), ..failed(e.to_string()) }),
+        };
+        let remote_addr = response.remote_addr().map(|addr| addr.to_string());
+        
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            if status.as_u16() == 404 && error_text.contains("model") {
+                return Err(BenchmarkError::ModelNotFound(model.to_string()));
+            }
+            return Ok(BenchmarkResult { transient: is_transient_status(status), ..failed(format!("HTTP {}: {}", status, error_text)) });
+        }
+        
+        let completion: OpenAiCompletionResponse = match response.json().await {
+            Ok(completion) => completion,
+            Err(e) => return Ok(failed(format!("Failed to parse response: {}", e))),
+        };
+        let total_duration_ms = start_time.elapsed().as_millis() as u64;
+        let usage = completion.usage.unwrap_or_default();
+        
+        Ok(BenchmarkResult {
+            finished_at: Some(Utc::now()),
+            success: true,
+            tokens_per_second: per_second(usage.completion_tokens, total_duration_ms),
+            total_duration_ms,
+            error: None,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            response: completion.choices.first().map(|choice| choice.message.content.clone()),
+            remote_addr,
+            completions: Some(completion.choices.len() as u32),
+            ..failed(String::new())
+        })
+    }
+    
+    /// Embeds `prompt` as a single input; speed is input tokens per second of
+    /// server time, since nothing is generated.
+    async fn embed(&self, model: &str, prompt: &str, config: &BenchmarkConfig) -> Result<BenchmarkResult> {
+        let url = format!("{}{}", self.base_url, Endpoint::Embed.path());
+        
+        let mut request_body = json!({ "model": model, "input": prompt, "options": {} });
+        if let Some(threads) = config.num_thread {
+            request_body["options"]["num_thread"] = json!(threads);
+        }
+        for (name, value) in &config.options {
+            request_body["options"][name] = value.clone();
+        }
+        
+        let start_time = Instant::now();
+        let timestamp = Utc::now();
+        let failed = |error: String| BenchmarkResult::failed(model, prompt, timestamp, start_time.elapsed().as_millis() as u64, error);
+        
+        let response = match self.client.post(&url).json(&request_body).send().await {
+            Ok(resp) => resp,
+            Err(e) => return Ok(BenchmarkResult { transient: is_transient_send(&e), ..failed(e.to_string()) }),
+        };
+        let remote_addr = response.remote_addr().map(|addr| addr.to_string());
+        
+        let status = response.status();
+        if status.as_u16() == 404 {
+            return Err(BenchmarkError::ModelNotFound(model.to_string()));
+        }
+        if !status.is_success() {
+            // e.g. a generation model: "... does not support embeddings"
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Ok(BenchmarkResult { transient: is_transient_status(status), ..failed(format!("HTTP {}: {}", status, error_text)) });
+        }
+        
+        let embed: OllamaEmbedResponse = match response.json().await {
+            Ok(resp) => resp,
+            Err(e) => return Ok(failed(format!("Failed to parse response: {}", e))),
+        };
+        let total_duration_ms = start_time.elapsed().as_millis() as u64;
+        
+        let load_ns = embed.load_duration.unwrap_or(0);
+        let compute_ns = embed.total_duration.unwrap_or(0) - load_ns;
+        let prompt_tokens = embed.prompt_eval_count.unwrap_or(0) as u32;
+        let tokens_per_second = if compute_ns > 0 {
+            prompt_tokens as f64 * 1_000_000_000.0 / compute_ns as f64
+        } else {
+            0.0
+        };
+        
+        Ok(BenchmarkResult {
+            success: true,
+            error: None,
+            tokens_per_second,
+            load_duration_ms: (load_ns / 1_000_000) as u64,
+            prompt_tokens,
+            remote_addr,
+            embedding_dims: embed.embeddings.first().map(|e| e.len() as u32),
+            finished_at: Some(Utc::now()),
+            ..BenchmarkResult::failed(model, prompt, timestamp, total_duration_ms, String::new())
+        })
+    }
+    
+    /// Loads `model` into memory without generating (empty-prompt request).
+    pub async fn load_model(&self, model: &str) -> Result<()> {
+        let url = format!("{}/api/generate", self.base_url);
+        
+        let response = self.client
+            .post(&url)
+            .json(&json!({ "model": model }))
+            .send()
+            .await?;
+            
+        if response.status().as_u16() == 404 {
+            return Err(BenchmarkError::ModelNotFound(model.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(BenchmarkError::ConnectionFailed(
+                format!("HTTP {} from Ollama", response.status())
+            ));
+        }
+        
+        Ok(())
+    }
+    
+    pub async fn show_model(&self, model: &str) -> Result<OllamaShowResponse> {
+        let url = format!("{}/api/show", self.base_url);
+        
+        let response = self.client
+            .post(&url)
+            .json(&json!({ "model": model }))
+            .send()
+            .await?;
+            
+        if response.status().as_u16() == 404 {
+            return Err(BenchmarkError::ModelNotFound(model.to_string()));
+        }
+        if !response.status().is_success() {
+            return Err(BenchmarkError::ConnectionFailed(
+                format!("HTTP {} from Ollama", response.status())
+            ));
+        }
+        
+        Ok(response.json().await?)
+    }
+    
+    pub async fn running_models(&self) -> Result<Vec<OllamaRunningModel>> {
+        let url = format!("{}/api/ps", self.base_url);
+        
+        let response = self.client
+            .get(&url)
+            .send()
+            .await?;
+            
+        if !response.status().is_success() {
+            return Err(BenchmarkError::ConnectionFailed(
+                format!("HTTP {} from Ollama", response.status())
+            ));
+        }
+        
+        let running: OllamaRunningModelsList = response.json().await?;
+        Ok(running.models)
+    }
+    
+    /// Downloads `model` through `/api/pull`, passing each streamed status to
+    /// `on_status` until the server reports success.
+    pub async fn pull_model(&self, model: &str, mut on_status: impl FnMut(&OllamaPullStatus)) -> Result<()> {
+        let url = format!("{}/api/pull", self.base_url);
+        
+        let mut response = self.client
+            .post(&url)
+            .timeout(Duration::from_secs(PULL_TIMEOUT_SECONDS))
+            .json(&json!({ "model": model, "stream": true }))
+            .send()
+            .await?;
+            
+        if !response.status().is_success() {
+            return Err(BenchmarkError::ConnectionFailed(
+                format!("HTTP {} from Ollama", response.status())
+            ));
+        }
+        
+        let mut pending = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            pending.extend_from_slice(&chunk);
+            for line in take_lines(&mut pending) {
+                let status: OllamaPullStatus = serde_json::from_str(&line)?;
+                if let Some(error) = status.error {
+                    return Err(BenchmarkError::ConnectionFailed(error));
+                }
+                on_status(&status);
+                if status.status == "success" {
+                    return Ok(());
+                }
+            }
+        }
+        
+        Err(BenchmarkError::ConnectionFailed("Pull ended before the download finished".to_string()))
+    }
+    
     pub async fn validate_model(&self, model: &str) -> Result<bool> {
         let models = self.list_models().await?;
         Ok(models.iter().any(|m| m == model))
     }
 }
 
+/// Body of a `/api/generate` or `/api/chat` request for `config`.
+fn request_body(model: &str, prompt: &str, config: &BenchmarkConfig) -> Value {
+    let mut request_body = json!({
+        "model": model,
+        "stream": config.stream,
+        "options": {
+            "temperature": config.temperature,
+            "num_predict": config.max_tokens,
+        }
+    });
+    let images: Vec<&str> = config.image.iter().map(|image| image.data.as_str()).collect();
+    match config.endpoint {
+        Endpoint::Generate => {
+            request_body["prompt"] = json!(prompt);
+            if let Some(system) = &config.system {
+                request_body["system"] = json!(system);
+            }
+            if !images.is_empty() {
+                request_body["images"] = json!(images);
+            }
+        }
+        Endpoint::Chat => {
+            let mut messages = Vec::new();
+            if let Some(system) = &config.system {
+                messages.push(json!({ "role": "system", "content": system }));
+            }
+            messages.extend(history::messages(config.history_messages));
+            let mut message = json!({ "role": "user", "content": prompt });
+            if !images.is_empty() {
+                message["images"] = json!(images);
+            }
+            messages.push(message);
+            request_body["messages"] = json!(messages);
+        }
+        Endpoint::Embed => unreachable!("embeddings are requested through OllamaClient::embed"),
+    }
+    if let Some(seed) = config.seed {
+        request_body["options"]["seed"] = json!(seed);
+    }
+    if let Some(threads) = config.num_thread {
+        request_body["options"]["num_thread"] = json!(threads);
+    }
+    for (name, value) in &config.options {
+        request_body["options"][name] = value.clone();
+    }
+    if config.logprobs {
+        request_body["logprobs"] = json!(true);
+    }
+    // Chat requests always go through the template
+    if config.raw && config.endpoint == Endpoint::Generate {
+        request_body["raw"] = json!(true);
+    }
+    request_body
+}
+
+/// Whether a request that could not be sent is worth retrying: it timed
+/// out or the connection failed, rather than the request being malformed.
+fn is_transient_send(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+}
+
+/// Whether an HTTP error is worth retrying: the server failed or asked us
+/// to slow down. Other client errors would fail the same way again.
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Backoff before retry number `retry` (from 0): `backoff_ms`, doubling
+/// each time, up to `MAX_RETRY_BACKOFF_MS`.
+fn retry_delay(backoff_ms: u64, retry: u32) -> Duration {
+    Duration::from_millis(backoff_ms.saturating_mul(1 << retry.min(16)).min(MAX_RETRY_BACKOFF_MS))
+}
+
+/// Throughput over wall-clock time, for APIs without server timings.
+pub fn per_second(tokens: u32, duration_ms: u64) -> f64 {
+    if duration_ms == 0 {
+        return 0.0;
+    }
+    tokens as f64 * 1000.0 / duration_ms as f64
+}
+
+/// Client-side arrival times of a streamed response, in ms since the
+/// request was sent.
+#[derive(Debug, Clone, Default)]
+struct StreamTimings {
+    first_token_ms: Option<u64>,
+    first_sentence_ms: Option<u64>,
+    last_token_ms: Option<u64>,
+    /// Gaps between consecutive chunks that carried text
+    token_gaps_ms: Vec<f64>,
+}
+
+/// Reads a streamed (NDJSON) response into one response with the full text
+/// and the final chunk's timings, plus when the first token, the first
+/// complete sentence and the last token arrived, and the gaps in between.
+/// Errors say whether the connection broke, which is worth retrying.
+async fn read_stream(mut response: Response, endpoint: Endpoint, start_time: Instant) -> std::result::Result<(OllamaGenerateResponse, StreamTimings), (String, bool)> {
+    let mut pending = Vec::new();
+    let mut text = String::new();
+    let mut logprobs = Vec::new();
+    let mut timings = StreamTimings::default();
+    let mut last_token: Option<Duration> = None;
+    
+    loop {
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => return Err(("Stream ended before the final chunk".to_string(), true)),
+            Err(e) => return Err((format!("Stream interrupted: {}", e), true)),
+        };
+        pending.extend_from_slice(&chunk);
+        
+        for line in take_lines(&mut pending) {
+            let mut part = parse_chunk(&line, endpoint).map_err(|e| (format!("Failed to parse response: {}", e), false))?;
+            if !part.response.is_empty() {
+                let now = start_time.elapsed();
+                if let Some(previous) = last_token {
+                    timings.token_gaps_ms.push((now - previous).as_secs_f64() * 1000.0);
+                }
+                last_token = Some(now);
+                timings.first_token_ms.get_or_insert(now.as_millis() as u64);
+                timings.last_token_ms = Some(now.as_millis() as u64);
+            }
+            // Resume the scan at the old last character, which may be a `.`
+            // waiting for the whitespace after it
+            let scan_from = text.char_indices().next_back().map_or(0, |(at, _)| at);
+            text.push_str(&part.response);
+            logprobs.extend(part.logprobs.take().unwrap_or_default());
+            // An answer without a sentence break is one sentence, complete when done
+            if timings.first_sentence_ms.is_none() && (has_complete_sentence(&text, scan_from) || (part.done && !text.trim().is_empty())) {
+                timings.first_sentence_ms = Some(start_time.elapsed().as_millis() as u64);
+            }
+            
+            if part.done {
+                part.response = text;
+                part.logprobs = (!logprobs.is_empty()).then_some(logprobs);
+                return Ok((part, timings));
+            }
+        }
+    }
+}
+
+/// Whether `text`, from byte `from` on, completes a sentence or line: a
+/// newline after some text, `.`, `!` or `?` followed by whitespace (so
+/// `3.14` does not count), or a CJK full stop. Scanning only what a chunk
+/// added keeps a long stream linear.
+fn has_complete_sentence(text: &str, from: usize) -> bool {
+    let leading = text.len() - text.trim_start().len();
+    let mut chars = text[from.max(leading)..].chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' | '。' | '！' | '？' => return true,
+            '.' | '!' | '?' if chars.peek().is_some_and(|next| next.is_whitespace()) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Removes and returns the complete lines buffered so far, leaving any
+/// partial line for the next chunk.
+fn take_lines(pending: &mut Vec<u8>) -> Vec<String> {
+    let Some(end) = pending.iter().rposition(|&b| b == b'\n') else {
+        return Vec::new();
+    };
+    let complete: Vec<u8> = pending.drain(..=end).collect();
+    String::from_utf8_lossy(&complete)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parses a generation response body, or one line of a streamed one.
+fn parse_chunk(line: &str, endpoint: Endpoint) -> serde_json::Result<OllamaGenerateResponse> {
+    match endpoint {
+        Endpoint::Chat => serde_json::from_str::<OllamaChatResponse>(line).map(OllamaGenerateResponse::from),
+        _ => serde_json::from_str(line),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -192,6 +833,70 @@ mod tests {
         assert_eq!(client.base_url, "http://localhost:11434");
     }
     
+    #[test]
+    fn test_parse_profiles() {
+        let content = r#"[
+            {"url": "https://gw-a.example.com", "api_key": "secret-a", "headers": {"X-Tenant": "bench"}},
+            {"url": "http://box:11434"}
+        ]"#;
+        let profiles = parse_profiles(content, Path::new(".")).unwrap();
+        assert_eq!(profiles.len(), 2);
+        assert_eq!(profiles[0].api_key.as_deref(), Some("secret-a"));
+        assert_eq!(profiles[0].headers["X-Tenant"], "bench");
+        assert_eq!(profiles[1], ConnectionProfile { url: "http://box:11434".to_string(), ..Default::default() });
+        
+        assert!(parse_profiles("[]", Path::new(".")).is_err());
+        assert!(parse_profiles(r#"[{"url": "box:11434"}]"#, Path::new(".")).is_err());
+        assert!(parse_profiles(r#"[{"url": "http://box", "token": "x"}]"#, Path::new(".")).is_err());
+        assert!(parse_profiles(r#"[{"url": "http://box", "api_key_env": "OLLAMA_BENCH_TEST_UNSET_KEY"}]"#, Path::new(".")).is_err());
+        assert!(parse_profiles(r#"[{"url": "http://box", "headers": {"Bad Name": "x"}}]"#, Path::new(".")).is_err());
+    }
+    
+    #[test]
+    fn test_take_lines_keeps_partial_line() {
+        let mut pending = b"{\"a\":1}\n\n{\"b\":".to_vec();
+        assert_eq!(take_lines(&mut pending), vec!["{\"a\":1}".to_string()]);
+        assert_eq!(pending, b"{\"b\":");
+        
+        pending.extend_from_slice(b"2}\n");
+        assert_eq!(take_lines(&mut pending), vec!["{\"b\":2}".to_string()]);
+        assert!(pending.is_empty());
+    }
+    
+    #[test]
+    fn test_retry_policy() {
+        assert!(is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_transient_status(StatusCode::BAD_REQUEST));
+        
+        assert_eq!(retry_delay(500, 0), Duration::from_millis(500));
+        assert_eq!(retry_delay(500, 2), Duration::from_millis(2000));
+        assert_eq!(retry_delay(500, 40), Duration::from_millis(MAX_RETRY_BACKOFF_MS));
+    }
+    
+    #[test]
+    fn test_has_complete_sentence() {
+        assert!(!has_complete_sentence("\n\nThe value of pi is 3.14", 0));
+        assert!(has_complete_sentence("The value of pi is 3.14. It", 0));
+        assert!(has_complete_sentence("Silent GPUs hum\n", 0));
+        assert!(has_complete_sentence("こんにちは。", 0));
+        assert!(!has_complete_sentence("Really?", 0));
+        
+        // Resuming at the previous chunk's last character
+        assert!(has_complete_sentence("It is 3. Then", 7));
+        assert!(!has_complete_sentence("\n\nHi", 1));
+        assert!(!has_complete_sentence("Done. Next", 6));
+    }
+    
+    #[test]
+    fn test_parse_chunk_chat() {
+        let line = r#"{"model":"m","created_at":"t","message":{"role":"assistant","content":"Hi"},"done":false}"#;
+        let part = parse_chunk(line, Endpoint::Chat).unwrap();
+        assert_eq!(part.response, "Hi");
+        assert!(!part.done);
+        assert!(parse_chunk(r#"{"error":"model not found"}"#, Endpoint::Generate).is_err());
+    }
+    
     #[tokio::test]
     async fn test_benchmark_result_on_error() {
         let client = OllamaClient::new(
@@ -0,0 +1,160 @@
+use std::fmt::Write;
+
+use crate::config::LATENCY_BUCKETS_SECONDS;
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// OpenMetrics text for `-e metrics.om`: speed and success gauges per
+/// summary, plus TTFT and request latency histograms per model. When the
+/// run went into the history database (`recorded_as` is its id), each
+/// bucket's exemplar names the run and iteration behind its slowest sample,
+/// so a dashboard can go from a spike to the stored record with
+/// `history show RUN_ID` (iteration N is the Nth iteration listed there).
+pub fn render(summaries: &[ModelSummary], results: &[BenchmarkResult], recorded_as: Option<&str>) -> String {
+    let mut text = String::new();
+
+    gauge(&mut text, "ollama_bench_tokens_per_second", "Average generation speed of successful iterations.", summaries, |s| s.avg_tokens_per_second);
+    gauge(&mut text, "ollama_bench_success_ratio", "Share of iterations that succeeded.", summaries, |s| s.success_rate);
+
+    // Numbered over every result, as the history database stores them
+    let measured: Vec<(usize, &BenchmarkResult)> = results
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| r.is_measured())
+        .map(|(index, r)| (index + 1, r))
+        .collect();
+    histogram(&mut text, "ollama_bench_ttft_seconds", "Time to first token.", &measured, recorded_as, |r| r.time_to_first_token_ms);
+    histogram(&mut text, "ollama_bench_request_duration_seconds", "Wall-clock time of a whole request.", &measured, recorded_as, |r| r.total_duration_ms);
+
+    text.push_str("# EOF\n");
+    text
+}
+
+fn gauge(text: &mut String, name: &str, help: &str, summaries: &[ModelSummary], value: impl Fn(&ModelSummary) -> f64) {
+    let _ = writeln!(text, "# TYPE {} gauge\n# HELP {} {}", name, name, help);
+    for summary in summaries {
+        let mut labels = vec![("model", summary.model.as_str())];
+        labels.extend(summary.variant.as_deref().map(|variant| ("variant", variant)));
+        labels.extend(summary.server.as_deref().map(|server| ("server", server)));
+        let _ = writeln!(text, "{}{{{}}} {}", name, label_set(&labels), value(summary));
+    }
+}
+
+/// One histogram per model (and sweep variant and server, where present).
+fn histogram(
+    text: &mut String,
+    name: &str,
+    help: &str,
+    measured: &[(usize, &BenchmarkResult)],
+    recorded_as: Option<&str>,
+    millis: impl Fn(&BenchmarkResult) -> u64,
+) {
+    let _ = writeln!(text, "# TYPE {} histogram\n# UNIT {} seconds\n# HELP {} {}", name, name, name, help);
+
+    let key = |r: &BenchmarkResult| (r.model.clone(), r.variant.clone(), r.server.clone());
+    let mut series = Vec::new();
+    for (_, result) in measured {
+        if !series.contains(&key(result)) {
+            series.push(key(result));
+        }
+    }
+
+    for (model, variant, server) in &series {
+        let samples: Vec<(usize, &BenchmarkResult, f64)> = measured
+            .iter()
+            .filter(|(_, r)| (&r.model, &r.variant, &r.server) == (model, variant, server))
+            .map(|&(iteration, r)| (iteration, r, millis(r) as f64 / 1000.0))
+            .collect();
+        let mut labels = vec![("model", model.as_str())];
+        labels.extend(variant.as_deref().map(|variant| ("variant", variant)));
+        labels.extend(server.as_deref().map(|server| ("server", server)));
+
+        let mut lower = f64::NEG_INFINITY;
+        for upper in LATENCY_BUCKETS_SECONDS.iter().copied().chain([f64::INFINITY]) {
+            let count = samples.iter().filter(|(_, _, seconds)| *seconds <= upper).count();
+            let le = if upper.is_infinite() { "+Inf".to_string() } else { upper.to_string() };
+            let mut bucket_labels = labels.clone();
+            bucket_labels.push(("le", &le));
+            let _ = write!(text, "{}_bucket{{{}}} {}", name, label_set(&bucket_labels), count);
+
+            let slowest = samples
+                .iter()
+                .filter(|(_, _, seconds)| *seconds > lower && *seconds <= upper)
+                .max_by(|a, b| a.2.total_cmp(&b.2));
+            if let (Some((iteration, result, seconds)), Some(run_id)) = (slowest, recorded_as) {
+                let iteration = iteration.to_string();
+                let _ = write!(
+                    text,
+                    " # {{{}}} {} {:.3}",
+                    label_set(&[("run_id", run_id), ("iteration", &iteration)]),
+                    seconds,
+                    result.timestamp.timestamp_millis() as f64 / 1000.0
+                );
+            }
+            text.push('\n');
+            lower = upper;
+        }
+
+        let sum: f64 = samples.iter().map(|(_, _, seconds)| seconds).sum();
+        let _ = writeln!(text, "{}_count{{{}}} {}", name, label_set(&labels), samples.len());
+        let _ = writeln!(text, "{}_sum{{{}}} {}", name, label_set(&labels), sum);
+    }
+}
+
+fn label_set(labels: &[(&str, &str)]) -> String {
+    labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_exemplars_name_the_iteration() {
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let result = |ttft_ms: u64, success: bool| BenchmarkResult {
+            success,
+            timestamp,
+            time_to_first_token_ms: ttft_ms,
+            ..BenchmarkResult::ok("llama2:7b", 0.0)
+        };
+        let results = [result(0, false), result(80, true), result(90, true), result(700, true)];
+        let summaries = [ModelSummary::from_results("llama2:7b".to_string(), &results)];
+
+        let text = render(&summaries, &results, Some("20240101T120000Z-0001"));
+        assert!(text.contains("ollama_bench_success_ratio{model=\"llama2:7b\"} 0.75\n"));
+        // The failed first request still counts towards the numbering
+        assert!(text.contains(
+            "ollama_bench_ttft_seconds_bucket{model=\"llama2:7b\",le=\"0.1\"} 2 # {run_id=\"20240101T120000Z-0001\",iteration=\"3\"} 0.09 1704110400.000\n"
+        ));
+        assert!(text.contains("ollama_bench_ttft_seconds_bucket{model=\"llama2:7b\",le=\"0.25\"} 2\n"));
+        assert!(text.contains("ollama_bench_ttft_seconds_bucket{model=\"llama2:7b\",le=\"1\"} 3 # {run_id=\"20240101T120000Z-0001\",iteration=\"4\"} 0.7"));
+        assert!(text.contains("ollama_bench_ttft_seconds_count{model=\"llama2:7b\"} 3\n"));
+        assert!(text.ends_with("# EOF\n"));
+
+        // Without a stored run there is nothing for an exemplar to point at
+        let unrecorded = render(&summaries, &results, None);
+        assert!(unrecorded.contains("ollama_bench_ttft_seconds_bucket{model=\"llama2:7b\",le=\"0.1\"} 2\n"));
+        assert!(!unrecorded.contains("run_id"));
+    }
+
+    #[test]
+    fn test_variants_get_their_own_series() {
+        let result = |variant: &str, ttft_ms: u64| BenchmarkResult {
+            time_to_first_token_ms: ttft_ms,
+            variant: Some(variant.to_string()),
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        let text = render(&[], &[result("temp=0", 80), result("temp=1", 300)], None);
+        assert!(text.contains("ollama_bench_ttft_seconds_count{model=\"m\",variant=\"temp=0\"} 1\n"));
+        assert!(text.contains("ollama_bench_ttft_seconds_count{model=\"m\",variant=\"temp=1\"} 1\n"));
+    }
+}
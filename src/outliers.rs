@@ -0,0 +1,125 @@
+use clap::ValueEnum;
+
+use crate::effect::{percentile, Distribution};
+use crate::types::BenchmarkResult;
+
+/// Fewer samples than this give quartiles too coarse to judge by.
+const MIN_SAMPLES: usize = 4;
+
+/// Tukey's fences: this many interquartile ranges beyond the quartiles.
+const IQR_FENCE: f64 = 1.5;
+
+/// Modified z-score beyond which a sample is an outlier (Iglewicz and
+/// Hoaglin). Built on the median and MAD, which one stall cannot drag
+/// along the way it drags a mean and standard deviation.
+const Z_THRESHOLD: f64 = 3.5;
+
+/// Scales a median absolute deviation to a standard deviation's size for
+/// normal data.
+const MAD_SCALE: f64 = 0.6745;
+
+/// How `--trim-outliers` decides a sample is an outlier.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutlierRule {
+    /// Beyond 1.5 interquartile ranges outside the quartiles
+    Iqr,
+    /// A modified z-score (from the median and MAD) beyond 3.5
+    Zscore,
+}
+
+impl OutlierRule {
+    /// The (low, high) range of values that are kept; `None` when the
+    /// values have no spread to judge by, so nothing is trimmed.
+    fn bounds(&self, values: &[f64]) -> Option<(f64, f64)> {
+        match self {
+            OutlierRule::Iqr => {
+                let mut sorted = values.to_vec();
+                sorted.sort_by(|a, b| a.total_cmp(b));
+                let (q1, q3) = (percentile(&sorted, 25), percentile(&sorted, 75));
+                let reach = (q3 - q1) * IQR_FENCE;
+                (reach > 0.0).then_some((q1 - reach, q3 + reach))
+            }
+            OutlierRule::Zscore => {
+                let median = Distribution::of(values)?.median;
+                let deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+                let reach = Distribution::of(&deviations)?.median * Z_THRESHOLD / MAD_SCALE;
+                (reach > 0.0).then_some((median - reach, median + reach))
+            }
+        }
+    }
+}
+
+/// Marks measured iterations whose speed or TTFT falls outside `rule`'s
+/// range, such as a request stalled by a GC pause, so they are left out of
+/// the statistics. Returns how many were marked.
+pub fn mark(results: &mut [BenchmarkResult], rule: OutlierRule) -> usize {
+    let measured: Vec<usize> = (0..results.len()).filter(|&i| results[i].is_measured()).collect();
+    if measured.len() < MIN_SAMPLES {
+        return 0;
+    }
+
+    let speeds: Vec<f64> = measured.iter().map(|&i| results[i].tokens_per_second).collect();
+    let ttfts: Vec<f64> = measured.iter().map(|&i| results[i].time_to_first_token_ms as f64).collect();
+    let within = |bounds: Option<(f64, f64)>, value: f64| bounds.map_or(true, |(low, high)| (low..=high).contains(&value));
+    let (speed_bounds, ttft_bounds) = (rule.bounds(&speeds), rule.bounds(&ttfts));
+
+    let mut marked = 0;
+    for (&i, (speed, ttft)) in measured.iter().zip(speeds.into_iter().zip(ttfts)) {
+        if !within(speed_bounds, speed) || !within(ttft_bounds, ttft) {
+            results[i].outlier = true;
+            marked += 1;
+        }
+    }
+    marked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(iterations: &[(f64, u64)]) -> Vec<BenchmarkResult> {
+        iterations
+            .iter()
+            .map(|&(tps, ttft)| BenchmarkResult {
+                time_to_first_token_ms: ttft,
+                ..BenchmarkResult::ok("m", tps)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_iqr_marks_a_stall() {
+        let mut results = run(&[(50.0, 100), (51.0, 105), (49.0, 98), (50.5, 102), (12.0, 100), (50.0, 2400), (49.5, 101)]);
+        assert_eq!(mark(&mut results, OutlierRule::Iqr), 2);
+        assert!(results[4].outlier && results[5].outlier);
+        assert!(!results[0].outlier);
+    }
+
+    #[test]
+    fn test_zscore_marks_a_stall_in_a_short_run() {
+        // The stall cannot inflate the MAD the way it would a standard deviation
+        let mut results = run(&[(50.0, 100), (51.0, 101), (49.0, 99), (50.0, 100), (30.0, 100)]);
+        assert_eq!(mark(&mut results, OutlierRule::Zscore), 1);
+        assert!(results[4].outlier);
+
+        let mut steady = run(&[(50.0, 100), (52.0, 101), (48.0, 99), (51.0, 100), (49.0, 100)]);
+        assert_eq!(mark(&mut steady, OutlierRule::Zscore), 0);
+    }
+
+    #[test]
+    fn test_skips_metrics_without_spread() {
+        // Identical TTFTs leave no IQR; one slow TTFT is not judged by it
+        let mut results = run(&[(49.0, 100), (50.0, 100), (51.0, 100), (49.0, 100), (50.0, 100), (51.0, 900)]);
+        assert_eq!(mark(&mut results, OutlierRule::Iqr), 0);
+    }
+
+    #[test]
+    fn test_leaves_short_runs_and_excluded_iterations_alone() {
+        let mut short = run(&[(50.0, 100), (50.0, 100), (5.0, 100)]);
+        assert_eq!(mark(&mut short, OutlierRule::Iqr), 0);
+
+        let mut results = run(&[(5.0, 100), (50.0, 100), (51.0, 100), (49.0, 100), (50.0, 100)]);
+        results[0].warmup = true;
+        assert_eq!(mark(&mut results, OutlierRule::Iqr), 0);
+    }
+}
@@ -1,40 +1,148 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
 use std::time::Duration;
 use crossterm::{
     execute,
     style::{Color, Print, ResetColor, SetForegroundColor},
 };
 
-use crate::types::ModelSummary;
-use crate::benchmark::{calculate_winner, calculate_performance_difference};
-use crate::config::TABLE_COLUMN_WIDTHS;
+use crate::types::{BenchmarkResult, ModelSummary, RunTotals, ServerInfo, SpeedUnit};
+use crate::cli::JsonSchema;
+use crate::compare::ModelDelta;
+use crate::context::{context_label, ContextCurve};
+use crate::csvmeta;
+use crate::effect::{self, Distribution, Moments, SampleSize};
+use crate::aggregate::ModelAggregate;
+use crate::timeline;
+use crate::endpoints::EndpointComparison;
+use crate::exclusions::Exclusions;
+use crate::hedge::HedgeReport;
+use crate::batch::BatchReport;
+use crate::cancel::CancelStats;
+use crate::history::HistoryCurve;
+use crate::keepalive::{self, IdleSample};
+use crate::net::AddressStats;
+use crate::overhead::OverheadReport;
+use crate::paired::{DuelReport, PairedComparison};
+use crate::pattern::PatternReport;
+use crate::queue::QueueReport;
+use crate::reference::{ReferenceComparison, ReferenceMatch};
+use crate::inventory::InstalledModel;
+use crate::rundb::RunRecord;
+use crate::trend::TrendPoint;
+use crate::select::Selection;
+use crate::suite::CategoryStats;
+use crate::telemetry::{self, TelemetryStats};
+use crate::template::TemplateCost;
+use crate::vision::VisionReport;
+use crate::warmpool::WarmPoolModelReport;
+use crate::warnings::Warnings;
+use crate::benchmark::{calculate_winner, calculate_performance_difference, efficient_frontier, is_tokenizer_artifact};
+use crate::config::{get_user_agent, FAIRNESS_WARN_THRESHOLD, MAX_ITERATIONS, REFERENCE_BELOW_THRESHOLD_PERCENT, SAMPLE_MARGIN_FRACTION, TABLE_COLUMN_WIDTHS, TREND_WINDOW};
+use crate::ollama::BENCH_CLIENT_HEADER;
+use crate::i18n::{pad, t, tf, tfn, Msg};
+use crate::integrity;
+use crate::precision::{num, signed};
 
-pub fn print_results_table(summaries: &[ModelSummary], duration: Duration) {
+pub fn print_results_table(summaries: &[ModelSummary], duration: Duration, unit: SpeedUnit) {
     if summaries.is_empty() {
-        println!("\nNo results to display.");
+        println!("\n{}", t(Msg::NoResults));
         return;
     }
     
-    println!("\n┌─────────────┬─────────────┬─────────────┬──────────────┐");
-    println!("│ Model       │ Avg Speed   │ TTFT        │ Success      │");
-    println!("├─────────────┼─────────────┼─────────────┼──────────────┤");
+    // The model column grows to fit labels so sweep variants are never cut off
+    let model_width = summaries
+        .iter()
+        .map(|s| s.label().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(TABLE_COLUMN_WIDTHS.model - 2);
     
-    for summary in summaries {
-        let model_display = if summary.model.len() > TABLE_COLUMN_WIDTHS.model - 2 {
-            format!("{}…", &summary.model[..TABLE_COLUMN_WIDTHS.model - 3])
-        } else {
-            summary.model.clone()
-        };
-        
-        println!(
-            "│ {:11} │ {:>5.1} tok/s │ {:>9}ms │ {:>11.1}% │",
-            model_display,
-            summary.avg_tokens_per_second,
-            summary.avg_ttft_ms as u64,
-            summary.success_rate * 100.0
-        );
+    if summaries.iter().all(|s| s.embedding.is_some()) {
+        print_embedding_grid(summaries, model_width);
+    } else {
+        print_generation_grid(summaries, model_width, unit);
+        print_distributions(summaries, unit);
+        print_streaming(summaries);
+    }
+    
+    let offloaded: Vec<&ModelSummary> = summaries.iter().filter(|s| s.is_partial_offload()).collect();
+    if !offloaded.is_empty() {
+        println!("\n⚠️  Partial offload (not comparable with fully GPU-resident models):");
+        for summary in offloaded {
+            println!(
+                "   {:11} {}% in VRAM",
+                summary.label(),
+                num(summary.vram_fraction.unwrap_or(0.0) * 100.0, 0)
+            );
+        }
+    }
+    
+    if summaries.iter().any(|s| s.warmup_iterations > 0) {
+        println!("\n🔥 Warm-up iterations excluded (slower than the steady state, or set by --warmup; --keep-warmup keeps them):");
+        for summary in summaries.iter().filter(|s| s.warmup_iterations > 0) {
+            println!("   {:11} first {} of {}", summary.label(), summary.warmup_iterations, summary.total_tests + summary.warmup_iterations);
+        }
+    }
+    
+    if summaries.iter().any(|s| s.outlier_iterations > 0) {
+        println!("\n✂️  Outliers trimmed by --trim-outliers:");
+        for summary in summaries.iter().filter(|s| s.outlier_iterations > 0) {
+            println!("   {:11} {} of {}", summary.label(), summary.outlier_iterations, summary.total_tests + summary.outlier_iterations);
+        }
     }
     
-    println!("└─────────────┴─────────────┴─────────────┴──────────────┘");
+    if summaries.iter().any(|s| s.planned_iterations.is_some()) {
+        println!("\n⏱️  Down-sampled to fit --max-total-time:");
+        for summary in summaries {
+            if let Some(planned) = summary.planned_iterations {
+                println!("   {:11} {}/{} iterations", summary.label(), summary.total_tests, planned);
+            }
+        }
+    }
+    
+    if summaries.iter().any(|s| s.eval.is_some()) {
+        println!("\n🎯 Eval accuracy:");
+        for summary in summaries {
+            if let Some(eval) = &summary.eval {
+                println!(
+                    "   {:11} {:>5}% ({}/{} {})",
+                    summary.label(),
+                    num(eval.accuracy() * 100.0, 1),
+                    eval.correct,
+                    eval.total,
+                    eval.task
+                );
+            }
+        }
+    }
+    
+    if summaries.iter().filter(|s| s.eval.is_some()).count() > 1 {
+        let frontier = efficient_frontier(summaries);
+        println!("\n⚖️  Speed vs accuracy (★ = efficient frontier):");
+        for summary in summaries {
+            if let Some(eval) = &summary.eval {
+                let marker = if frontier.iter().any(|f| std::ptr::eq(*f, summary)) { "★" } else { " " };
+                println!(
+                    "   {} {:11} {:>6} tok/s {:>6}%",
+                    marker,
+                    summary.label(),
+                    num(summary.avg_tokens_per_second, 1),
+                    num(eval.accuracy() * 100.0, 1)
+                );
+            }
+        }
+    }
+    
+    if summaries.iter().any(|s| s.avg_logprob.is_some()) {
+        println!("\n📐 Avg logprob (closer to 0 = more confident):");
+        for summary in summaries {
+            if let Some(logprob) = summary.avg_logprob {
+                println!("   {:11} {:>8}", summary.label(), num(logprob, 3));
+            }
+        }
+    }
     
     // Print winner and comparison
     if summaries.len() > 1 {
@@ -43,21 +151,22 @@ pub fn print_results_table(summaries: &[ModelSummary], duration: Duration) {
                 std::io::stdout(),
                 Print("\n"),
                 SetForegroundColor(Color::Green),
-                Print("🏆 Winner: "),
-                Print(&winner.model),
+                Print(format!("🏆 {}: ", t(Msg::Winner))),
+                Print(winner.label()),
                 ResetColor
             ).ok();
             
             // Calculate and show performance differences
             let mut comparisons = Vec::new();
             for other in summaries {
-                if other.model != winner.model && other.success_rate > 0.0 {
+                if other.label() != winner.label() && other.success_rate > 0.0 {
                     let (speed_diff, ttft_diff) = calculate_performance_difference(winner, other);
+                    let (speed_d, ttft_d) = effect_sizes(winner, other);
                     if speed_diff > 0.0 {
-                        comparisons.push(format!("{:.1}% faster", speed_diff));
+                        comparisons.push(tf(Msg::Faster, num(speed_diff, 1)) + &effect_note(speed_d));
                     }
                     if ttft_diff > 0.0 && comparisons.len() < 2 {
-                        comparisons.push(format!("{:.0}% lower TTFT", ttft_diff));
+                        comparisons.push(tf(Msg::LowerTtft, num(ttft_diff, 0)) + &effect_note(ttft_d));
                     }
                 }
             }
@@ -66,6 +175,10 @@ pub fn print_results_table(summaries: &[ModelSummary], duration: Duration) {
                 print!(" ({})", comparisons.join(", "));
             }
             println!();
+            
+            if unit == SpeedUnit::Tokens {
+                print_tokenizer_warning(winner, summaries);
+            }
         }
     }
     
@@ -76,7 +189,7 @@ pub fn print_results_table(summaries: &[ModelSummary], duration: Duration) {
     execute!(
         std::io::stdout(),
         SetForegroundColor(Color::Cyan),
-        Print("\n📊 Completed in "),
+        Print(format!("\n📊 {} ", t(Msg::CompletedIn))),
         ResetColor
     ).ok();
     
@@ -86,65 +199,540 @@ pub fn print_results_table(summaries: &[ModelSummary], duration: Duration) {
         print!("{}s", duration.as_secs());
     }
     println!();
+    
+    let totals = RunTotals::from_summaries(summaries);
+    println!(
+        "   {} requests, {} tokens generated, {} prompt tokens evaluated",
+        totals.requests, totals.completion_tokens, totals.prompt_tokens
+    );
+}
+
+fn print_generation_grid(summaries: &[ModelSummary], model_width: usize, unit: SpeedUnit) {
+    // The speed column widens for longer unit suffixes such as "words/s"
+    let speed_width = 6 + unit.suffix().len();
+    let speed_border = "─".repeat(speed_width + 2);
+    let model_border = "─".repeat(model_width + 2);
+    // Streaming runs add the time to the first complete sentence
+    let first_sentence = summaries.iter().any(|s| s.avg_first_sentence_ms.is_some());
+    let sentence_border = |joint: &str| if first_sentence { format!("{}──────────────", joint) } else { String::new() };
+    
+    println!("\n┌{}┬{}┬─────────────{}┬──────────────┐", model_border, speed_border, sentence_border("┬"));
+    println!(
+        "│ {} │ {} │ {} │{} {} │",
+        pad(t(Msg::ColumnModel), model_width),
+        pad(t(Msg::ColumnAvgSpeed), speed_width),
+        pad(t(Msg::ColumnTtft), 11),
+        if first_sentence { format!(" {} │", pad(t(Msg::ColumnFirstSentence), 12)) } else { String::new() },
+        pad(t(Msg::ColumnSuccess), 12)
+    );
+    println!("├{}┼{}┼─────────────{}┼──────────────┤", model_border, speed_border, sentence_border("┼"));
+    
+    for summary in summaries {
+        let sentence = match (first_sentence, summary.avg_first_sentence_ms) {
+            (false, _) => String::new(),
+            (true, Some(ms)) => format!(" {:>10}ms │", num(ms.trunc(), 0)),
+            (true, None) => format!(" {:>12} │", "-"),
+        };
+        println!(
+            "│ {:model_width$} │ {:>5} {} │ {:>9}ms │{} {:>11}% │",
+            summary.label(),
+            num(summary.speed_in(summary.avg_tokens_per_second, unit), 1),
+            unit.suffix(),
+            num(summary.avg_ttft_ms.trunc(), 0),
+            sentence,
+            num(summary.success_rate * 100.0, 1)
+        );
+    }
+    
+    println!("└{}┴{}┴─────────────{}┴──────────────┘", model_border, speed_border, sentence_border("┴"));
+}
+
+/// Percentiles under the grid, once any model has a spread to show.
+fn print_distributions(summaries: &[ModelSummary], unit: SpeedUnit) {
+    if !summaries.iter().any(|s| s.speed_distribution.is_some_and(|d| d.cv.is_some())) {
+        return;
+    }
+    println!("\n📊 Distribution (median / p90 / p95 / p99, coefficient of variation):");
+    
+    for summary in summaries {
+        let (Some(speed), Some(ttft)) = (summary.speed_distribution, summary.ttft_distribution) else {
+            continue;
+        };
+        let speed_in = |value| summary.speed_in(value, unit);
+        println!(
+            "   {:11} {} / {} / {} / {} {} (CV {})   TTFT {} / {} / {} / {}ms (CV {})",
+            summary.label(),
+            num(speed_in(speed.median), 1),
+            num(speed_in(speed.p90), 1),
+            num(speed_in(speed.p95), 1),
+            num(speed_in(speed.p99), 1),
+            unit.suffix(),
+            format_cv(speed.cv),
+            num(ttft.median, 0),
+            num(ttft.p90, 0),
+            num(ttft.p95, 0),
+            num(ttft.p99, 0),
+            format_cv(ttft.cv)
+        );
+    }
 }
 
-pub fn print_results_json(summaries: &[ModelSummary]) {
-    match serde_json::to_string_pretty(summaries) {
+/// How evenly streamed tokens arrived; a high p95 shows up as stutter.
+fn print_streaming(summaries: &[ModelSummary]) {
+    if summaries.iter().all(|s| s.avg_inter_token_ms.is_none()) {
+        return;
+    }
+    println!("\n🌀 Streaming (inter-token latency mean / p95, time to last token):");
+    
+    for summary in summaries {
+        let (Some(mean), Some(p95)) = (summary.avg_inter_token_ms, summary.p95_inter_token_ms) else {
+            continue;
+        };
+        let last = summary
+            .avg_last_token_ms
+            .map(|ms| format!(", last token {}ms", num(ms, 0)))
+            .unwrap_or_default();
+        println!("   {:11} {} / {}ms{}", summary.label(), num(mean, 1), num(p95, 1), last);
+    }
+}
+
+fn format_cv(cv: Option<f64>) -> String {
+    cv.map(|cv| format!("{}%", num(cv * 100.0, 1))).unwrap_or_else(|| "-".to_string())
+}
+
+/// Embedding models generate nothing, so they get their own columns.
+fn print_embedding_grid(summaries: &[ModelSummary], model_width: usize) {
+    let model_border = "─".repeat(model_width + 2);
+    
+    println!("\n┌{}┬────────────┬─────────────┬────────┬──────────────┐", model_border);
+    println!(
+        "│ {} │ {} │ {} │ {} │ {} │",
+        pad(t(Msg::ColumnModel), model_width),
+        pad(t(Msg::ColumnEmbeddingSpeed), 10),
+        pad(t(Msg::ColumnLatency), 11),
+        pad(t(Msg::ColumnDimensions), 6),
+        pad(t(Msg::ColumnSuccess), 12)
+    );
+    println!("├{}┼────────────┼─────────────┼────────┼──────────────┤", model_border);
+    
+    for summary in summaries {
+        let embedding = summary.embedding.clone().unwrap_or_default();
+        println!(
+            "│ {:model_width$} │ {:>10} │ {:>9}ms │ {:>6} │ {:>11}% │",
+            summary.label(),
+            num(embedding.embeddings_per_second, 1),
+            num(embedding.avg_latency_ms, 0),
+            embedding.dimensions,
+            num(summary.success_rate * 100.0, 1)
+        );
+    }
+    
+    println!("└{}┴────────────┴─────────────┴────────┴──────────────┘", model_border);
+}
+
+/// Report-level sections that accompany the per-model summaries.
+#[derive(Default)]
+pub struct ReportExtras<'a> {
+    /// Id shared with the run's CSV sidecars
+    pub run_id: Option<&'a str>,
+    pub deltas: Option<&'a [ModelDelta]>,
+    pub server: Option<&'a ServerInfo>,
+    /// Every server of a multi-server run (repeated `--ollama-url`)
+    pub servers: Option<&'a [ServerInfo]>,
+    pub endpoints: Option<&'a [EndpointComparison]>,
+    pub template: Option<&'a [TemplateCost]>,
+    pub hedge: Option<&'a [HedgeReport]>,
+    pub batch: Option<&'a [BatchReport]>,
+    pub reference: Option<&'a [ReferenceComparison]>,
+    pub overhead: Option<&'a OverheadReport>,
+    pub cancel: Option<&'a [CancelStats]>,
+    /// Staggered-start TTFT by queue position (`--queue-depth`)
+    pub queue: Option<&'a [QueueReport]>,
+    pub pattern: Option<&'a [PatternReport]>,
+    /// Paired comparison of the two `--ab-hosts` servers
+    pub paired: Option<&'a [PairedComparison]>,
+    /// Round-by-round comparison of the two `duel` models
+    pub duel: Option<&'a DuelReport>,
+    /// Per-image latency of an `--image-dir` run
+    pub vision: Option<&'a [VisionReport]>,
+    /// Per-category breakdown of a `--prompts-file` suite
+    pub categories: Option<&'a [CategoryStats]>,
+    /// Iterations each model needs for a tight tok/s confidence interval
+    pub sample_size: Option<&'a [SampleSize]>,
+    /// Skipped models and models whose iterations all failed
+    pub exclusions: Option<&'a Exclusions>,
+    /// Non-fatal problems raised during the run
+    pub warnings: Option<&'a Warnings>,
+    pub selection: Option<&'a Selection>,
+    pub keep_alive: Option<&'a [IdleSample]>,
+    /// Per-model prompts (`--model-prompt`)
+    pub prompts: Option<&'a BTreeMap<String, String>>,
+    /// Seal the report with an integrity hash
+    pub signed: bool,
+    pub schema: JsonSchema,
+}
+
+/// JSON document for output and export. Schema 1 is the bare array of
+/// summaries; schema 2 is an object with `schema_version`, `models` and run
+/// `totals` plus any report-level sections (`run_id`, `server`, `servers`, `deltas`, `endpoints`, `template`,
+/// `hedge`, `batch`, `reference`, `overhead`, `cancel`, `queue`, `pattern`, `paired`, `duel`, `vision`, `categories`, `sample_size`, `skipped`, `failed`, `warnings`, `selection`, `keep_alive`, `prompts`, `integrity`).
+pub fn results_json(summaries: &[ModelSummary], extras: &ReportExtras) -> serde_json::Result<String> {
+    if extras.schema == JsonSchema::V1 {
+        return serde_json::to_string_pretty(summaries);
+    }
+    let mut report = serde_json::json!({
+        "schema_version": 2,
+        "models": summaries,
+        "totals": RunTotals::from_summaries(summaries),
+    });
+    if let Some(run_id) = extras.run_id {
+        report["run_id"] = run_id.into();
+    }
+    if let Some(server) = extras.server {
+        report["server"] = serde_json::to_value(server)?;
+    }
+    if let Some(servers) = extras.servers {
+        report["servers"] = serde_json::to_value(servers)?;
+    }
+    if let Some(deltas) = extras.deltas {
+        report["deltas"] = serde_json::to_value(deltas)?;
+    }
+    if let Some(endpoints) = extras.endpoints {
+        report["endpoints"] = serde_json::to_value(endpoints)?;
+    }
+    if let Some(template) = extras.template {
+        report["template"] = serde_json::to_value(template)?;
+    }
+    if let Some(hedge) = extras.hedge {
+        report["hedge"] = serde_json::to_value(hedge)?;
+    }
+    if let Some(batch) = extras.batch {
+        report["batch"] = serde_json::to_value(batch)?;
+    }
+    if let Some(reference) = extras.reference {
+        report["reference"] = serde_json::to_value(reference)?;
+    }
+    if let Some(overhead) = extras.overhead {
+        report["overhead"] = serde_json::to_value(overhead)?;
+    }
+    if let Some(cancel) = extras.cancel {
+        report["cancel"] = serde_json::to_value(cancel)?;
+    }
+    if let Some(queue) = extras.queue {
+        report["queue"] = serde_json::to_value(queue)?;
+    }
+    if let Some(pattern) = extras.pattern {
+        report["pattern"] = serde_json::to_value(pattern)?;
+    }
+    if let Some(paired) = extras.paired {
+        report["paired"] = serde_json::to_value(paired)?;
+    }
+    if let Some(duel) = extras.duel {
+        report["duel"] = serde_json::to_value(duel)?;
+    }
+    if let Some(vision) = extras.vision {
+        report["vision"] = serde_json::to_value(vision)?;
+    }
+    if let Some(categories) = extras.categories {
+        report["categories"] = serde_json::to_value(categories)?;
+    }
+    if let Some(sample_size) = extras.sample_size {
+        report["sample_size"] = serde_json::to_value(sample_size)?;
+    }
+    if let Some(exclusions) = extras.exclusions {
+        if !exclusions.skipped.is_empty() {
+            report["skipped"] = serde_json::to_value(&exclusions.skipped)?;
+        }
+        if !exclusions.failed.is_empty() {
+            report["failed"] = serde_json::to_value(&exclusions.failed)?;
+        }
+    }
+    if let Some(warnings) = extras.warnings {
+        report["warnings"] = serde_json::to_value(warnings)?;
+    }
+    if let Some(selection) = extras.selection {
+        report["selection"] = serde_json::to_value(selection)?;
+    }
+    if let Some(samples) = extras.keep_alive {
+        report["keep_alive"] = serde_json::to_value(samples)?;
+    }
+    if let Some(prompts) = extras.prompts {
+        report["prompts"] = serde_json::to_value(prompts)?;
+    }
+    if extras.signed {
+        integrity::seal(&mut report);
+    }
+    serde_json::to_string_pretty(&report)
+}
+
+pub fn print_server_info(server: &ServerInfo) {
+    let mut details = vec![format!(
+        "Ollama {}",
+        server.version.as_deref().unwrap_or("(unknown version)")
+    )];
+    details.extend(server.env.iter().map(|(k, v)| format!("{}={}", k, v)));
+    println!("🛠️  Server: {}", details.join(", "));
+    
+    // Only worth a line when it differs from the stock identity
+    let custom_agent = server.user_agent.as_deref().filter(|ua| *ua != get_user_agent());
+    if custom_agent.is_some() || server.bench_client.is_some() {
+        let mut client = Vec::new();
+        if let Some(ua) = custom_agent {
+            client.push(format!("User-Agent: {}", ua));
+        }
+        if let Some(id) = &server.bench_client {
+            client.push(format!("{}: {}", BENCH_CLIENT_HEADER, id));
+        }
+        println!("🪪 Client: {}", client.join(", "));
+    }
+    
+    if server.addresses.len() > 1 {
+        println!("🌐 Host resolves to {} addresses: {}", server.addresses.len(), server.addresses.join(", "));
+    }
+}
+
+fn print_tokenizer_warning(winner: &ModelSummary, summaries: &[ModelSummary]) {
+    let skewed: Vec<&ModelSummary> = summaries
+        .iter()
+        .filter(|other| other.label() != winner.label() && is_tokenizer_artifact(winner, other))
+        .collect();
+    if skewed.is_empty() {
+        return;
+    }
+    
+    println!("\n🔤 Token speeds differ mostly because of tokenizers:");
+    for summary in std::iter::once(winner).chain(skewed) {
+        println!(
+            "   {:11} {} chars/token → {} chars/s",
+            summary.label(),
+            num(summary.chars_per_token.unwrap_or(0.0), 2),
+            num(summary.speed_in(summary.avg_tokens_per_second, SpeedUnit::Chars), 1)
+        );
+    }
+    println!("💡 Compare with --speed-unit chars for a tokenizer-neutral ranking");
+}
+
+pub fn print_results_json(summaries: &[ModelSummary], extras: &ReportExtras) {
+    match results_json(summaries, extras) {
         Ok(json) => println!("{}", json),
         Err(e) => eprintln!("Error serializing results: {}", e),
     }
 }
 
-pub fn print_results_csv(summaries: &[ModelSummary]) {
-    println!("Model,Total Tests,Success Rate,Avg Tokens/s,Min Tokens/s,Max Tokens/s,Avg TTFT (ms)");
+/// Cohen's d of the winner's advantage in speed and in TTFT over `other`,
+/// positive when the winner is better.
+pub fn effect_sizes(winner: &ModelSummary, other: &ModelSummary) -> (Option<f64>, Option<f64>) {
+    let speed = Moments::speed(winner).zip(Moments::speed(other)).and_then(|(w, o)| effect::cohens_d(w, o));
+    let ttft = Moments::ttft(other).zip(Moments::ttft(winner)).and_then(|(o, w)| effect::cohens_d(o, w));
+    (speed, ttft)
+}
+
+/// ` (d=0.42, small)`, or nothing without variance data.
+pub fn effect_note(d: Option<f64>) -> String {
+    d.map(|d| format!(" (d={}, {})", num(d, 2), effect::magnitude(d))).unwrap_or_default()
+}
+
+pub fn print_baseline_comparison(deltas: &[ModelDelta]) {
+    println!("\n📏 Compared to baseline:");
+    
+    if deltas.is_empty() {
+        println!("   No models in common with the baseline");
+        return;
+    }
+    
+    for delta in deltas {
+        let describe = |name: &str, unit: &str| -> String {
+            match delta.metric(name) {
+                Some(m) => {
+                    let change = m.percent
+                        .map(|p| format!("{}%", signed(p, 1)))
+                        .unwrap_or_else(|| signed(m.absolute, 1));
+                    let marker = match (m.significant, m.improved) {
+                        (false, _) => "≈",
+                        (true, true) => "⬆",
+                        (true, false) => "⬇",
+                    };
+                    format!("{} {} {}{}", unit, change, marker, effect_note(m.effect_size))
+                }
+                None => String::new(),
+            }
+        };
+        
+        println!(
+            "   {:11} {}, {}",
+            delta.model,
+            describe("avg_tokens_per_second", "speed"),
+            describe("avg_ttft_ms", "TTFT")
+        );
+    }
+}
+
+pub fn print_results_csv(summaries: &[ModelSummary], unit: SpeedUnit) {
+    if !summaries.is_empty() && summaries.iter().all(|s| s.embedding.is_some()) {
+        println!("Model,Total Tests,Success Rate,Embeddings/s,Avg Latency (ms),Input Tokens/s,Dimensions");
+        for summary in summaries {
+            let embedding = summary.embedding.clone().unwrap_or_default();
+            println!(
+                "{},{},{},{},{},{},{}",
+                summary.label(),
+                summary.total_tests,
+                num(summary.success_rate, 2),
+                num(embedding.embeddings_per_second, 2),
+                num(embedding.avg_latency_ms, 0),
+                num(embedding.input_tokens_per_second, 2),
+                embedding.dimensions
+            );
+        }
+        return;
+    }
     
+    let heading = unit.heading();
+    println!(
+        "Model,Total Tests,Success Rate,Avg {0},Min {0},Max {0},Avg TTFT (ms),\
+         Median {0},P90 {0},P95 {0},P99 {0},Stddev {0},CV {0},\
+         Median TTFT (ms),P90 TTFT (ms),P95 TTFT (ms),P99 TTFT (ms),Stddev TTFT (ms),CV TTFT,\
+         Avg First Sentence (ms)",
+        heading
+    );
+    
+    // Blank cells where a model has no successful iterations (or a single one)
+    let cell = |value: Option<f64>, decimals: usize| value.map(|v| num(v, decimals)).unwrap_or_default();
     for summary in summaries {
+        let speed = summary.speed_distribution;
+        let ttft = summary.ttft_distribution;
+        let speed_in = |value: Option<f64>| value.map(|v| summary.speed_in(v, unit));
         println!(
-            "{},{},{:.2},{:.2},{:.2},{:.2},{:.0}",
-            summary.model,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            summary.label(),
             summary.total_tests,
-            summary.success_rate,
-            summary.avg_tokens_per_second,
-            summary.min_tokens_per_second,
-            summary.max_tokens_per_second,
-            summary.avg_ttft_ms
+            num(summary.success_rate, 2),
+            num(summary.speed_in(summary.avg_tokens_per_second, unit), 2),
+            num(summary.speed_in(summary.min_tokens_per_second, unit), 2),
+            num(summary.speed_in(summary.max_tokens_per_second, unit), 2),
+            num(summary.avg_ttft_ms, 0),
+            cell(speed_in(speed.map(|d| d.median)), 2),
+            cell(speed_in(speed.map(|d| d.p90)), 2),
+            cell(speed_in(speed.map(|d| d.p95)), 2),
+            cell(speed_in(speed.map(|d| d.p99)), 2),
+            cell(speed_in(summary.stddev_tokens_per_second), 2),
+            cell(speed.and_then(|d| d.cv), 4),
+            cell(ttft.map(|d| d.median), 0),
+            cell(ttft.map(|d| d.p90), 0),
+            cell(ttft.map(|d| d.p95), 0),
+            cell(ttft.map(|d| d.p99), 0),
+            cell(summary.stddev_ttft_ms, 1),
+            cell(ttft.and_then(|d| d.cv), 4),
+            cell(summary.avg_first_sentence_ms, 0)
         );
     }
 }
 
-pub fn print_results_markdown(summaries: &[ModelSummary], duration: Duration) {
+pub fn print_results_markdown(summaries: &[ModelSummary], duration: Duration, unit: SpeedUnit) {
     println!("# Benchmark Results\n");
     
-    println!("| Model | Success Rate | Avg Speed | Min Speed | Max Speed | Avg TTFT |");
-    println!("|-------|--------------|-----------|-----------|-----------|----------|");
+    let first_sentence = summaries.iter().any(|s| s.avg_first_sentence_ms.is_some());
+    println!(
+        "| Model | Success Rate | Avg Speed | Min Speed | Max Speed | Avg TTFT |{}",
+        if first_sentence { " First Sentence |" } else { "" }
+    );
+    println!(
+        "|-------|--------------|-----------|-----------|-----------|----------|{}",
+        if first_sentence { "----------------|" } else { "" }
+    );
     
     for summary in summaries {
+        let sentence = match (first_sentence, summary.avg_first_sentence_ms) {
+            (false, _) => String::new(),
+            (true, Some(ms)) => format!(" {}ms |", num(ms, 0)),
+            (true, None) => " |".to_string(),
+        };
         println!(
-            "| {} | {:.1}% | {:.1} tok/s | {:.1} tok/s | {:.1} tok/s | {:.0}ms |",
-            summary.model,
-            summary.success_rate * 100.0,
-            summary.avg_tokens_per_second,
-            summary.min_tokens_per_second,
-            summary.max_tokens_per_second,
-            summary.avg_ttft_ms
+            "| {} | {}% | {} {suffix} | {} {suffix} | {} {suffix} | {}ms |{}",
+            summary.label(),
+            num(summary.success_rate * 100.0, 1),
+            num(summary.speed_in(summary.avg_tokens_per_second, unit), 1),
+            num(summary.speed_in(summary.min_tokens_per_second, unit), 1),
+            num(summary.speed_in(summary.max_tokens_per_second, unit), 1),
+            num(summary.avg_ttft_ms, 0),
+            sentence,
+            suffix = unit.suffix()
         );
     }
     
     println!();
     
+    if summaries.iter().any(|s| s.is_partial_offload()) {
+        println!("### Partial Offload\n");
+        for summary in summaries.iter().filter(|s| s.is_partial_offload()) {
+            println!(
+                "- {}: {}% in VRAM",
+                summary.label(),
+                num(summary.vram_fraction.unwrap_or(0.0) * 100.0, 0)
+            );
+        }
+        println!();
+    }
+    
+    if summaries.iter().any(|s| s.eval.is_some()) {
+        println!("### Eval Accuracy\n");
+        for summary in summaries {
+            if let Some(eval) = &summary.eval {
+                println!(
+                    "- {}: {}% ({}/{} {})",
+                    summary.label(),
+                    num(eval.accuracy() * 100.0, 1),
+                    eval.correct,
+                    eval.total,
+                    eval.task
+                );
+            }
+        }
+        println!();
+    }
+    
+    if summaries.iter().filter(|s| s.eval.is_some()).count() > 1 {
+        let frontier = efficient_frontier(summaries);
+        println!("### Speed vs Accuracy\n");
+        println!("| Model | Avg Speed | Accuracy | Efficient Frontier |");
+        println!("|-------|-----------|----------|--------------------|");
+        for summary in summaries {
+            if let Some(eval) = &summary.eval {
+                println!(
+                    "| {} | {} tok/s | {}% | {} |",
+                    summary.label(),
+                    num(summary.avg_tokens_per_second, 1),
+                    num(eval.accuracy() * 100.0, 1),
+                    if frontier.iter().any(|f| std::ptr::eq(*f, summary)) { "★" } else { "" }
+                );
+            }
+        }
+        println!();
+    }
+    
+    if summaries.iter().any(|s| s.avg_logprob.is_some()) {
+        println!("### Avg Logprob\n");
+        for summary in summaries {
+            if let Some(logprob) = summary.avg_logprob {
+                println!("- {}: {}", summary.label(), num(logprob, 3));
+            }
+        }
+        println!();
+    }
+    
     if let Some(winner) = calculate_winner(summaries) {
-        println!("## Winner: {} 🏆", winner.model);
+        println!("## Winner: {} 🏆", winner.label());
         
         if summaries.len() > 1 {
             println!("\n### Performance Comparison:");
             for other in summaries {
-                if other.model != winner.model && other.success_rate > 0.0 {
+                if other.label() != winner.label() && other.success_rate > 0.0 {
                     let (speed_diff, ttft_diff) = calculate_performance_difference(winner, other);
+                    let (speed_d, ttft_d) = effect_sizes(winner, other);
                     if speed_diff > 0.0 {
-                        println!("- {:.1}% faster than {}", speed_diff, other.model);
+                        println!("- {}% faster than {}{}", num(speed_diff, 1), other.label(), effect_note(speed_d));
                     }
                     if ttft_diff > 0.0 {
-                        println!("- {:.0}% lower TTFT than {}", ttft_diff, other.model);
+                        println!("- {}% lower TTFT than {}{}", num(ttft_diff, 0), other.label(), effect_note(ttft_d));
                     }
                 }
             }
@@ -160,6 +748,996 @@ pub fn print_results_markdown(summaries: &[ModelSummary], duration: Duration) {
     } else {
         println!("{}s*", duration.as_secs());
     }
+    
+    let totals = RunTotals::from_summaries(summaries);
+    println!(
+        "*{} requests, {} tokens generated, {} prompt tokens evaluated*",
+        totals.requests, totals.completion_tokens, totals.prompt_tokens
+    );
+}
+
+/// Summary CSV for `--export`: the columns of `csvmeta::summary_columns`.
+pub fn summary_csv(summaries: &[ModelSummary], unit: SpeedUnit) -> String {
+    let mut content = csvmeta::summary_columns(unit).join(",") + "\n";
+    
+    // Blank cells where a model has no successful iterations
+    let cell = |value: Option<f64>, decimals: usize| value.map(|v| num(v, decimals)).unwrap_or_default();
+    for summary in summaries {
+        let speed = |pick: fn(&Distribution) -> f64| summary.speed_distribution.map(|d| summary.speed_in(pick(&d), unit));
+        let ttft = |pick: fn(&Distribution) -> f64| summary.ttft_distribution.map(|d| pick(&d));
+        content.push_str(&format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}\n",
+            summary.label(),
+            num(summary.success_rate * 100.0, 1),
+            num(summary.speed_in(summary.avg_tokens_per_second, unit), 1),
+            num(summary.speed_in(summary.min_tokens_per_second, unit), 1),
+            num(summary.speed_in(summary.max_tokens_per_second, unit), 1),
+            num(summary.avg_ttft_ms, 0),
+            cell(summary.avg_first_sentence_ms, 0),
+            cell(speed(|d| d.median), 1),
+            cell(speed(|d| d.p90), 1),
+            cell(speed(|d| d.p95), 1),
+            cell(speed(|d| d.p99), 1),
+            cell(ttft(|d| d.median), 0),
+            cell(ttft(|d| d.p90), 0),
+            cell(ttft(|d| d.p95), 0),
+            cell(ttft(|d| d.p99), 0)
+        ));
+    }
+    
+    content
+}
+
+/// Summary Markdown for `--export`, shorter than `-o markdown`.
+pub fn summary_markdown(summaries: &[ModelSummary], unit: SpeedUnit) -> String {
+    let mut content = String::from("# Ollama Benchmark Results\n\n");
+    content.push_str(&format!("| Model | Success Rate | Avg {} | TTFT (ms) |\n", unit.heading()));
+    content.push_str("|-------|--------------|--------------|------------|\n");
+    
+    for summary in summaries {
+        content.push_str(&format!(
+            "| {} | {}% | {} | {} |\n",
+            summary.label(),
+            num(summary.success_rate * 100.0, 1),
+            num(summary.speed_in(summary.avg_tokens_per_second, unit), 1),
+            num(summary.avg_ttft_ms, 0)
+        ));
+    }
+    
+    if let Some(winner) = calculate_winner(summaries) {
+        content.push_str(&format!("\n**Winner:** {} 🏆\n", winner.label()));
+        
+        for other in summaries {
+            if other.label() != winner.label() {
+                let (speed_diff, _ttft_diff) = calculate_performance_difference(winner, other);
+                if speed_diff > 0.0 {
+                    content.push_str(&format!(
+                        "- {}% faster than {}{}\n",
+                        num(speed_diff, 1), other.label(), effect_note(effect_sizes(winner, other).0)
+                    ));
+                }
+            }
+        }
+    }
+    
+    content
+}
+
+pub fn print_gpu_placement(summaries: &[ModelSummary], expected: u32) {
+    println!("\n🖥️  GPU placement (expected GPU {}):", expected);
+    
+    for summary in summaries {
+        let Some(devices) = &summary.gpu_devices else {
+            continue;
+        };
+        
+        let placement = if devices.is_empty() {
+            "CPU only ✗".to_string()
+        } else {
+            let list: Vec<String> = devices.iter().map(|d| format!("GPU {}", d)).collect();
+            let verified = devices.as_slice() == [expected];
+            format!("{} {}", list.join(", "), if verified { "✓" } else { "✗" })
+        };
+        
+        println!("   {:11} {}", summary.label(), placement);
+    }
+}
+
+pub fn print_thread_sweep(summaries: &[ModelSummary]) {
+    println!("\n🧵 Thread sweep (best per model):");
+    
+    // Interleaved sweeps list each model's variants apart, so dedup globally
+    let mut models: Vec<&str> = Vec::new();
+    for summary in summaries {
+        if !models.contains(&summary.model.as_str()) {
+            models.push(&summary.model);
+        }
+    }
+    
+    for model in models {
+        let best = summaries
+            .iter()
+            .filter(|s| s.model == model && s.success_rate > 0.0)
+            .max_by(|a, b| {
+                a.avg_tokens_per_second
+                    .partial_cmp(&b.avg_tokens_per_second)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        
+        if let Some(best) = best {
+            println!(
+                "   {:11} {} threads → {} tok/s",
+                model,
+                best.num_thread.map(|n| n.to_string()).unwrap_or_else(|| "?".to_string()),
+                num(best.avg_tokens_per_second, 1)
+            );
+        }
+    }
+}
+
+pub fn print_load_report(summaries: &[ModelSummary]) {
+    let Some(concurrency) = summaries.iter().find_map(|s| s.load.as_ref()).map(|l| l.concurrency) else {
+        return;
+    };
+    println!("\n🚦 Under load ({} concurrent requests):", concurrency);
+    
+    for summary in summaries {
+        let Some(load) = &summary.load else {
+            continue;
+        };
+        println!(
+            "   {:11} {:>6} req/s, {:>7} tok/s aggregate, latency avg {}ms / p50 {}ms / p95 {}ms / max {}ms{}",
+            summary.label(),
+            num(load.requests_per_second, 2),
+            num(load.aggregate_tokens_per_second, 1),
+            num(load.avg_latency_ms, 0),
+            load.p50_latency_ms,
+            load.p95_latency_ms,
+            load.max_latency_ms,
+            if load.failed > 0 { format!(", {} failed", load.failed) } else { String::new() }
+        );
+        if let (Some(jain), Some(spread)) = (load.jain_fairness, load.throughput_spread) {
+            println!(
+                "   {:11} fairness: Jain {}, fastest/slowest request {}x{}",
+                "",
+                num(jain, 3),
+                num(spread, 2),
+                if jain < FAIRNESS_WARN_THRESHOLD { " ⚠️  some requests are starved" } else { "" }
+            );
+        }
+    }
+    println!("💡 Per-request speed in the table above is each request's own generation rate; Ollama serves up to OLLAMA_NUM_PARALLEL at once");
+}
+
+pub fn print_pattern_report(reports: &[PatternReport]) {
+    let Some(pattern) = reports.first().map(|r| &r.pattern) else {
+        return;
+    };
+    println!("\n🌊 Load pattern ({}):", pattern);
+    
+    for report in reports {
+        for phase in &report.phases {
+            println!(
+                "   {:11} {:15} {:>4} requests, latency avg {}ms / p95 {}ms, TTFT {}ms",
+                report.model,
+                phase.phase,
+                phase.requests,
+                num(phase.avg_latency_ms, 0),
+                num(phase.p95_latency_ms, 0),
+                num(phase.avg_ttft_ms, 0)
+            );
+        }
+    }
+}
+
+pub fn print_prefill_report(summaries: &[ModelSummary]) {
+    println!("\n📥 Prompt processing (long input, short output):");
+    
+    for summary in summaries.iter().filter(|s| s.success_rate > 0.0) {
+        let successful = (summary.total_tests as f64 * summary.success_rate).round();
+        let avg_request_ms = summary.total_request_ms as f64 / summary.total_tests as f64;
+        println!(
+            "   {:11} {:>6} prompt tokens, {:>8} tok/s prefill, TTFT {}ms ({}% of request time)",
+            summary.label(),
+            num(summary.total_prompt_tokens as f64 / successful, 0),
+            num(summary.prompt_eval_tokens_per_second.unwrap_or(0.0), 1),
+            num(summary.avg_ttft_ms, 0),
+            num(if avg_request_ms > 0.0 { summary.avg_ttft_ms / avg_request_ms * 100.0 } else { 0.0 }, 0)
+        );
+    }
+    println!("💡 Generation tok/s above covers only the short answer; prefill speed decides this workload");
+}
+
+pub fn print_history_sweep(curves: &[HistoryCurve]) {
+    println!("\n💬 Chat history sweep (latency by messages before the prompt):");
+    
+    for curve in curves {
+        match curve.ms_per_message {
+            Some(slope) => println!("   {:11} {}ms per message", curve.model, signed(slope, 1)),
+            None => println!("   {:11} (one history length, no growth to measure)", curve.model),
+        }
+        for point in &curve.points {
+            println!(
+                "      {:>4} msgs  {:>7} prompt tokens  {:>7}ms latency  {:>6}ms TTFT",
+                point.messages,
+                num(point.avg_prompt_tokens, 0),
+                num(point.avg_latency_ms, 0),
+                num(point.avg_ttft_ms, 0)
+            );
+        }
+    }
+}
+
+pub fn print_context_sweep(curves: &[ContextCurve]) {
+    println!("\n📜 Context sweep (prefill and decode speed by prompt size):");
+    
+    for curve in curves {
+        match (curve.decode_change_percent, curve.points.first(), curve.points.last()) {
+            (Some(change), Some(first), Some(last)) => println!(
+                "   {:11} decode {}% from {} to {} tokens",
+                curve.model,
+                signed(change, 1),
+                context_label(first.tokens),
+                context_label(last.tokens)
+            ),
+            _ => println!("   {:11} (one prompt size, no change to measure)", curve.model),
+        }
+        for point in &curve.points {
+            let prefill = match point.prefill_tokens_per_second {
+                Some(speed) => format!("{:>8}", num(speed, 1)),
+                None => format!("{:>8}", "-"),
+            };
+            println!(
+                "      {:>5}  {:>7} prompt tokens  {} tok/s prefill  {:>6} tok/s decode",
+                context_label(point.tokens),
+                num(point.avg_prompt_tokens, 0),
+                prefill,
+                num(point.decode_tokens_per_second, 1)
+            );
+        }
+    }
+}
+
+pub fn print_warm_pool_report(report: &[WarmPoolModelReport]) {
+    println!("\n🔁 Warm pool (alternating requests):");
+    
+    for entry in report {
+        println!(
+            "   {:11} {} resident, {:>6}ms avg latency, {}/{} swaps{}",
+            entry.model,
+            if entry.resident_after_preload { "✓" } else { "✗" },
+            num(entry.avg_latency_ms, 0),
+            entry.swaps,
+            entry.requests,
+            if entry.swaps > 0 {
+                format!(" ({}ms per swap)", signed(entry.avg_swap_penalty_ms, 0))
+            } else {
+                String::new()
+            }
+        );
+    }
+    
+    if report.iter().any(|e| e.swaps > 0) {
+        println!("💡 Swaps mean the pool does not fit; check VRAM and OLLAMA_MAX_LOADED_MODELS");
+    }
+}
+
+pub fn print_endpoint_report(report: &[EndpointComparison]) {
+    println!("\n🔀 Generate vs chat (overhead outside model compute):");
+    
+    for entry in report {
+        println!(
+            "   {:11} generate {:>5}ms, chat {:>5}ms → chat {}ms per request ({} vs {} prompt tokens)",
+            entry.model,
+            num(entry.generate.avg_overhead_ms, 0),
+            num(entry.chat.avg_overhead_ms, 0),
+            signed(entry.overhead_difference_ms, 0),
+            num(entry.generate.avg_prompt_tokens, 0),
+            num(entry.chat.avg_prompt_tokens, 0),
+        );
+    }
+}
+
+pub fn print_template_report(report: &[TemplateCost]) {
+    println!("\n🧩 Template cost (templated vs raw prompt):");
+    
+    for entry in report {
+        println!(
+            "   {:11} {} prompt tokens, {}ms TTFT, {}ms per request, {} vs {} output tokens",
+            entry.model,
+            signed(entry.extra_prompt_tokens, 0),
+            signed(entry.extra_ttft_ms, 0),
+            signed(entry.extra_latency_ms, 0),
+            num(entry.templated.avg_completion_tokens, 0),
+            num(entry.raw.avg_completion_tokens, 0),
+        );
+    }
+}
+
+pub fn print_hedge_report(report: &[HedgeReport]) {
+    let copies = report.first().map(|entry| entry.copies).unwrap_or(0);
+    println!("\n🏁 Hedged requests ({} copies per iteration, first to finish counts):", copies);
+    
+    for entry in report {
+        println!(
+            "   {:11} p50 {} → {}ms, p95 {} → {}ms ({}%), p99 {} → {}ms",
+            entry.model,
+            num(entry.plain.median, 0),
+            num(entry.hedged.median, 0),
+            num(entry.plain.p95, 0),
+            num(entry.hedged.p95, 0),
+            signed(entry.p95_change_percent, 0),
+            num(entry.plain.p99, 0),
+            num(entry.hedged.p99, 0),
+        );
+    }
+    println!("💡 Hedging costs {}× the requests; copies only run side by side up to OLLAMA_NUM_PARALLEL", copies);
+}
+
+pub fn print_batch_report(report: &[BatchReport]) {
+    let n = report.first().map(|entry| entry.n).unwrap_or(0);
+    println!("\n📦 Batch generation ({} completions per iteration via /v1/chat/completions):", n);
+    
+    for entry in report {
+        if !entry.n_supported {
+            println!(
+                "   {:11} server ignored n ({} completions per request); send separate requests",
+                entry.model, num(entry.combined.avg_completions, 1)
+            );
+            continue;
+        }
+        println!(
+            "   {:11} {} separate: {} tok/s ({}ms), one n={} request: {} tok/s ({}ms) ({}%)",
+            entry.model,
+            n,
+            num(entry.separate.tokens_per_second, 1),
+            num(entry.separate.avg_latency_ms, 0),
+            n,
+            num(entry.combined.tokens_per_second, 1),
+            num(entry.combined.avg_latency_ms, 0),
+            signed(entry.throughput_change_percent, 0),
+        );
+    }
+    println!("💡 Separate requests only run side by side up to OLLAMA_NUM_PARALLEL");
+}
+
+pub fn print_category_report(categories: &[CategoryStats]) {
+    if categories.is_empty() {
+        println!("\nℹ️  No prompt categories in the suite; add \"category\" to the .jsonl lines");
+        return;
+    }
+    println!("\n🗂️  By prompt category:");
+    
+    for entry in categories {
+        println!(
+            "   {:11} {:10} {} tok/s, {}ms TTFT ({} iterations)",
+            entry.model,
+            entry.category,
+            num(entry.avg_tokens_per_second, 1),
+            num(entry.avg_ttft_ms, 0),
+            entry.iterations
+        );
+    }
+}
+
+pub fn print_vision_report(reports: &[VisionReport]) {
+    println!("\n🖼️  Latency by image (smallest first):");
+    
+    for report in reports {
+        let Some(latency) = report.latency_ms else {
+            continue;
+        };
+        println!(
+            "   {:11} median {}ms, p95 {}ms (CV {}) over {} images",
+            report.model,
+            num(latency.median, 0),
+            num(latency.p95, 0),
+            format_cv(latency.cv),
+            report.images.len()
+        );
+        for image in &report.images {
+            let size = match (image.width, image.height) {
+                (Some(width), Some(height)) => format!("{}×{} ({}:1)", width, height, num(width as f64 / height as f64, 2)),
+                _ => "size unknown".to_string(),
+            };
+            println!(
+                "      {:20} {:22} {:>6} KB   TTFT {}ms, latency {}ms",
+                image.image,
+                size,
+                num(image.kilobytes, 0),
+                num(image.avg_ttft_ms, 0),
+                num(image.avg_latency_ms, 0)
+            );
+        }
+    }
+}
+
+pub fn print_overhead_report(report: &OverheadReport) {
+    println!("\n🔌 Request overhead (client, network, HTTP; not the model):");
+    
+    for round_trip in &report.version {
+        let Some(rtt) = &round_trip.rtt_ms else {
+            continue;
+        };
+        let server = round_trip.server.as_ref().map(|server| format!(" to {}", server)).unwrap_or_default();
+        println!("   /api/version round trip{}: p50 {}ms, p95 {}ms", server, num(rtt.median, 1), num(rtt.p95, 1));
+    }
+    for entry in &report.models {
+        let share = entry.ttft_share_percent
+            .map(|share| format!(" ({}% of avg TTFT)", num(share, 0)))
+            .unwrap_or_default();
+        println!(
+            "   {:11} one-token request {}ms = model {}ms + overhead {}ms{}",
+            probe_label(&entry.model, entry.server.as_deref()),
+            num(entry.avg_latency_ms, 1),
+            num(entry.avg_model_ms, 1),
+            num(entry.avg_overhead_ms, 1),
+            share
+        );
+    }
+}
+
+pub fn print_cancel_report(stats: &[CancelStats]) {
+    println!("\n✋ Stream cancellation (first token of the next request):");
+    
+    for entry in stats {
+        let (Some(baseline), Some(after)) = (entry.baseline_ttft_ms, entry.after_cancel_ttft_ms) else {
+            println!(
+                "   {:11} no stream was still generating after {} tokens; try a smaller --cancel-after",
+                probe_label(&entry.model, entry.server.as_deref()), entry.cancel_after_tokens
+            );
+            continue;
+        };
+        println!(
+            "   {:11} idle {}ms, after cancel at {} tokens {}ms ({}ms, p95 {}ms, {} cancelled)",
+            probe_label(&entry.model, entry.server.as_deref()),
+            num(baseline.median, 0),
+            entry.cancel_after_tokens,
+            num(after.median, 0),
+            signed(entry.cost_ms.unwrap_or(0.0), 0),
+            num(after.p95, 0),
+            entry.cancelled
+        );
+    }
+    println!("💡 A large gap means the server keeps generating for a disconnected client before serving the next one");
+}
+
+pub fn print_queue_report(reports: &[QueueReport]) {
+    println!("\n🧍 Queue position (first token of requests arriving while the server is busy):");
+    
+    for report in reports {
+        let slope = report.ms_per_position.map_or("n/a".to_string(), |ms| format!("{}ms", signed(ms, 0)));
+        println!(
+            "   {:11} {} arrivals {}ms apart: {} per position ahead",
+            probe_label(&report.model, report.server.as_deref()), report.depth, report.stagger_ms, slope
+        );
+        if let Some(error) = &report.first_error {
+            println!("      ⚠️  {} request(s) failed, first: {}", report.failed, error);
+        }
+        for stats in &report.positions {
+            let Some(ttft) = stats.ttft_ms else {
+                println!("      #{:<3} no successful request", stats.position);
+                continue;
+            };
+            println!(
+                "      #{:<3} TTFT {}ms (p95 {}ms), queued {}ms",
+                stats.position,
+                num(ttft.median, 0),
+                num(ttft.p95, 0),
+                signed(stats.queued_ms.unwrap_or(0.0), 0)
+            );
+        }
+    }
+    println!("💡 A steep slope means requests are served one at a time; raise OLLAMA_NUM_PARALLEL to overlap them");
+}
+
+/// "model @ server" for a post-run probe of a multi-server run.
+fn probe_label(model: &str, server: Option<&str>) -> String {
+    match server {
+        Some(server) => format!("{} @ {}", model, server),
+        None => model.to_string(),
+    }
+}
+
+pub fn print_paired_report(comparisons: &[PairedComparison]) {
+    let Some(first) = comparisons.first() else {
+        return;
+    };
+    println!("\n🆚 {}", tfn(Msg::PairedTitle, &[&first.host_b, &first.host_a]));
+    
+    for entry in comparisons {
+        let Some(speed) = entry.speed else {
+            println!("   {:11} {}", entry.model, tf(Msg::PairedTooFew, entry.pairs));
+            continue;
+        };
+        let ttft = entry
+            .ttft_ms
+            .map(|ttft| format!(", TTFT {}ms [{}, {}]", signed(ttft.mean, 0), signed(ttft.ci_low, 0), signed(ttft.ci_high, 0)))
+            .unwrap_or_default();
+        println!(
+            "   {:11} {} tok/s [{}, {}] ({}%){}, {}, {}",
+            entry.model,
+            signed(speed.mean, 1),
+            signed(speed.ci_low, 1),
+            signed(speed.ci_high, 1),
+            signed(entry.speed_change_percent.unwrap_or(0.0), 1),
+            ttft,
+            tf(Msg::PairedPairs, entry.pairs),
+            t(if speed.significant() { Msg::Significant } else { Msg::WithinNoise })
+        );
+    }
+}
+
+pub fn print_duel_report(report: &DuelReport) {
+    println!("\n🤺 {}", tfn(Msg::DuelTitle, &[&report.model_b, &report.model_a]));
+    
+    let Some(speed) = report.speed else {
+        println!("   {}", tf(Msg::DuelTooFew, report.pairs));
+        return;
+    };
+    println!(
+        "   {} {} tok/s [{}, {}] ({}%), {}",
+        t(Msg::DuelSpeed),
+        signed(speed.mean, 1),
+        signed(speed.ci_low, 1),
+        signed(speed.ci_high, 1),
+        signed(report.speed_change_percent.unwrap_or(0.0), 1),
+        t(if speed.significant() { Msg::Significant } else { Msg::WithinNoise })
+    );
+    if let Some(ttft) = report.ttft_ms {
+        println!("   TTFT  {}ms [{}, {}]", signed(ttft.mean, 0), signed(ttft.ci_low, 0), signed(ttft.ci_high, 0));
+    }
+    let rate = |wins: u64| wins as f64 / report.pairs as f64 * 100.0;
+    println!(
+        "   {}",
+        tfn(
+            Msg::DuelFaster,
+            &[
+                &report.model_a,
+                &report.a_wins,
+                &report.pairs,
+                &num(rate(report.a_wins), 0),
+                &report.model_b,
+                &report.b_wins,
+                &report.pairs,
+                &num(rate(report.b_wins), 0),
+            ]
+        )
+    );
+}
+
+pub fn print_reference_report(matched: &ReferenceMatch) {
+    let (hardware, comparisons) = (&matched.hardware, &matched.comparisons);
+    if comparisons.is_empty() {
+        println!("\nℹ️  No reference results for these models on {}", hardware);
+        return;
+    }
+    println!("\n📏 Compared with typical {} results (source: {}):", hardware, matched.source);
+    
+    for entry in comparisons {
+        let marker = if entry.difference_percent <= -REFERENCE_BELOW_THRESHOLD_PERCENT { " ⚠️" } else { "" };
+        println!(
+            "   {:11} {} tok/s vs typical {} ({}%){}",
+            entry.model,
+            num(entry.tokens_per_second, 1),
+            num(entry.reference_tokens_per_second, 1),
+            signed(entry.difference_percent, 0),
+            marker
+        );
+    }
+    if comparisons.iter().any(|entry| entry.difference_percent <= -REFERENCE_BELOW_THRESHOLD_PERCENT) {
+        println!("💡 Well below typical: check the model is fully on GPU (ollama ps), the power mode, and background load");
+    }
+}
+
+pub fn print_selection(selection: &Selection) {
+    println!("\n🎯 Model selection:");
+    
+    for candidate in &selection.candidates {
+        let accuracy = candidate.accuracy
+            .map(|a| format!(", {}% accurate", num(a * 100.0, 0)))
+            .unwrap_or_default();
+        let verdict = if candidate.passed {
+            "✓".to_string()
+        } else {
+            format!("✗ {}", candidate.reasons.join(", "))
+        };
+        println!(
+            "   {:11} {:>6} tok/s, {:>5}ms TTFT{}  {}",
+            candidate.model, num(candidate.tokens_per_second, 1), num(candidate.ttft_ms, 0), accuracy, verdict
+        );
+    }
+    
+    match &selection.selected {
+        Some(model) => {
+            println!("\n   Recommended: {}", model);
+            if !selection.runners_up.is_empty() {
+                println!("   Runners-up:  {}", selection.runners_up.join(", "));
+            }
+        }
+        None => println!("\n   No model passed"),
+    }
+}
+
+pub fn print_anomalies(results: &[BenchmarkResult]) {
+    let clock_steps = results.iter().filter(|r| r.clock_jump_ms.is_some() && r.anomaly.is_none()).count();
+    if clock_steps > 0 {
+        println!(
+            "\n🕰️  Wall clock was adjusted during {} iteration(s); durations are monotonic, but their timestamps may be skewed",
+            clock_steps
+        );
+    }
+    
+    let affected: Vec<&BenchmarkResult> = results.iter().filter(|r| r.anomaly.is_some()).collect();
+    if affected.is_empty() {
+        return;
+    }
+    
+    println!("\n⚠️  {} iteration(s) excluded from statistics (system anomalies):", affected.len());
+    for result in affected {
+        println!(
+            "   {:11} {} {}",
+            result.model,
+            result.timestamp.format("%H:%M:%S"),
+            result.anomaly.as_deref().unwrap_or_default()
+        );
+    }
+}
+
+/// Fastest and slowest iteration per model, for `--verbose`.
+pub fn print_extremes(summaries: &[ModelSummary]) {
+    if summaries.iter().all(|s| s.fastest.is_none()) {
+        return;
+    }
+    println!("\n🔬 Fastest and slowest iterations:");
+    for summary in summaries {
+        let (Some(fastest), Some(slowest)) = (&summary.fastest, &summary.slowest) else {
+            continue;
+        };
+        for (index, (which, detail)) in [("fastest", fastest), ("slowest", slowest)].into_iter().enumerate() {
+            let response = detail
+                .response
+                .as_deref()
+                .map(|text| format!("  \"{}\"", preview(text, 60)))
+                .unwrap_or_default();
+            println!(
+                "   {:11} {} {} tok/s at {}, TTFT {}ms, {} → {} tokens{}",
+                if index == 0 { summary.label() } else { String::new() },
+                which,
+                num(detail.tokens_per_second, 1),
+                detail.timestamp.format("%H:%M:%S"),
+                detail.time_to_first_token_ms,
+                detail.prompt_tokens,
+                detail.completion_tokens,
+                response
+            );
+        }
+    }
+}
+
+/// Completion token spread per model, for `--verbose`: a model that always
+/// hits `max_tokens` is measured on cut-off answers.
+pub fn print_completion_histograms(summaries: &[ModelSummary]) {
+    if summaries.iter().all(|s| s.completion_histogram.is_none()) {
+        return;
+    }
+    println!("\n📶 Completion tokens (0 → max_tokens):");
+    for summary in summaries {
+        let Some(histogram) = &summary.completion_histogram else {
+            continue;
+        };
+        let cap = match histogram.cap {
+            Some(cap) => format!(", {}/{} hit max_tokens {}", histogram.at_cap, histogram.iterations(), cap),
+            None => String::new(),
+        };
+        println!(
+            "   {:11} [{}] {}–{} tokens, median {}{}",
+            summary.label(),
+            histogram.sparkline(),
+            histogram.min,
+            histogram.max,
+            histogram.median,
+            cap
+        );
+    }
+}
+
+/// Recommends a rerun when some model's tok/s spread is too wide for a
+/// 95% confidence interval within ±`SAMPLE_MARGIN_FRACTION` of its mean.
+/// Prints nothing when every model ran enough iterations.
+pub fn print_sample_size(sizes: &[SampleSize]) {
+    if sizes.iter().all(SampleSize::is_enough) {
+        return;
+    }
+    
+    println!("\n🔢 {}", tf(Msg::SampleSizeTitle, num(SAMPLE_MARGIN_FRACTION * 100.0, 0)));
+    for size in sizes {
+        let text = match size.needed {
+            Some(n) if size.is_enough() => tfn(Msg::SampleSizeEnough, &[&n, &size.ran]),
+            Some(n) => tfn(Msg::SampleSizeNeeded, &[&n, &size.ran]),
+            None => tf(Msg::SampleSizeTooWide, MAX_ITERATIONS),
+        };
+        println!("   {:11} {}", size.model, text);
+    }
+    if let Some(n) = sizes.iter().filter_map(|size| size.recommended_iterations).max() {
+        println!("💡 {}", tf(Msg::HintRerunIterations, n));
+    }
+}
+
+/// `--until-ci`: the confidence interval each model reached, and whether
+/// it stopped there or at the iteration cap.
+pub fn print_until_ci(summaries: &[ModelSummary], margin: f64, cap: u32) {
+    println!("\n🎯 {}", tf(Msg::UntilCiTitle, num(margin * 100.0, 1)));
+    for summary in summaries {
+        let reached = Moments::speed(summary).and_then(effect::relative_margin);
+        let capped = if summary.total_tests >= cap as u64 && reached.map_or(true, |m| m > margin) {
+            format!(" {}", t(Msg::UntilCiCapReached))
+        } else {
+            String::new()
+        };
+        let spread = reached.map_or(t(Msg::UntilCiNoSpread).to_string(), |m| format!("±{}%", num(m * 100.0, 1)));
+        println!(
+            "   {:11} {}{}",
+            summary.label(),
+            tfn(Msg::UntilCiAfter, &[&spread, &summary.total_tests]),
+            capped
+        );
+    }
+}
+
+/// First `chars` characters of `text` on one line, with an ellipsis if cut.
+pub(crate) fn preview(text: &str, chars: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    match line.char_indices().nth(chars) {
+        Some((cut, _)) => format!("{}…", &line[..cut]),
+        None => line,
+    }
+}
+
+/// Per-model resource usage from `--telemetry`, across measured iterations.
+pub fn print_telemetry(summaries: &[ModelSummary], results: &[BenchmarkResult]) {
+    println!("\n📡 Resource usage during requests (average / peak):");
+    for summary in summaries {
+        let stats: Vec<TelemetryStats> = results
+            .iter()
+            .filter(|r| summary.owns(r) && r.is_measured())
+            .filter_map(|r| r.telemetry)
+            .collect();
+        let Some(usage) = telemetry::combine(&stats) else {
+            continue;
+        };
+        
+        let mut parts = Vec::new();
+        if let Some(gpu) = usage.gpu_util_percent {
+            parts.push(format!("GPU {}% / {}%", num(gpu.avg, 0), num(gpu.peak, 0)));
+        }
+        if let Some(vram) = usage.vram_used_mb {
+            parts.push(format!("VRAM {} / {} GB", num(vram.avg / 1024.0, 1), num(vram.peak / 1024.0, 1)));
+        }
+        if let Some(cpu) = usage.cpu_percent {
+            parts.push(format!("CPU {}% / {}%", num(cpu.avg, 0), num(cpu.peak, 0)));
+        }
+        if let Some(ram) = usage.ram_used_mb {
+            parts.push(format!("RAM {} / {} GB", num(ram.avg / 1024.0, 1), num(ram.peak / 1024.0, 1)));
+        }
+        if parts.is_empty() {
+            parts.push("no metrics available on this host".to_string());
+        }
+        println!("   {:11} {}", summary.label(), parts.join("   "));
+    }
+}
+
+pub fn print_keep_alive_report(models: &[String], samples: &[IdleSample]) {
+    println!("\n💤 Latency after idle time:");
+    
+    for model in models {
+        for sample in samples.iter().filter(|s| &s.model == model) {
+            let state = match (sample.success, sample.reloaded) {
+                (false, _) => "failed".to_string(),
+                (true, true) => format!("reloaded (+{}ms load)", sample.load_ms),
+                (true, false) => "still loaded".to_string(),
+            };
+            println!(
+                "   {:11} idle {:>5}s → {:>6}ms, {}",
+                model, sample.idle_seconds, sample.latency_ms, state
+            );
+        }
+        
+        match keepalive::expiry_bounds(samples, model) {
+            (Some(kept), Some(expired)) if kept < expired => {
+                println!("   {:11} keep-alive expires between {}s and {}s idle", model, kept, expired)
+            }
+            (_, Some(expired)) => println!("   {:11} unloaded after {}s idle or less", model, expired),
+            (Some(kept), None) => println!("   {:11} stayed loaded for at least {}s idle", model, kept),
+            (None, None) => {}
+        }
+    }
+    
+    println!("💡 Ollama unloads idle models after keep_alive (default 5m; OLLAMA_KEEP_ALIVE server-wide)");
+}
+
+pub fn print_address_breakdown(by_address: &BTreeMap<String, AddressStats>) {
+    println!("\n🌐 Requests by server address:");
+    
+    let width = by_address.keys().map(|addr| addr.len()).max().unwrap_or(0);
+    for (addr, stats) in by_address {
+        println!(
+            "   {:width$} {:>4} requests, {:>6} tok/s, {:>5}ms TTFT",
+            addr, stats.requests, num(stats.avg_tokens_per_second, 1), num(stats.avg_ttft_ms, 0),
+            width = width
+        );
+    }
+    println!("💡 Use --prefer-ipv4 or --prefer-ipv6 to pin the address family");
+}
+
+pub fn print_run_list(runs: &[RunRecord], path: &Path) {
+    if runs.is_empty() {
+        println!("📚 No recorded runs in {}", path.display());
+        return;
+    }
+    println!("📚 {} run(s) from {}:", runs.len(), path.display());
+    
+    for run in runs {
+        let models: Vec<String> = run.models
+            .iter()
+            .map(|summary| format!("{} {} tok/s", summary.label(), num(summary.avg_tokens_per_second, 1)))
+            .collect();
+        let experiment = run.experiment.as_ref().map(|name| format!("[{}]  ", name)).unwrap_or_default();
+        println!(
+            "   {}  {}  {}{}",
+            run.run_id,
+            run.recorded_at.format("%Y-%m-%d %H:%M"),
+            experiment,
+            models.join(", ")
+        );
+    }
+    println!("💡 ollama-bench history show <RUN_ID> for the full results");
+}
+
+pub fn print_trend(model: &str, points: &[TrendPoint]) {
+    if points.is_empty() {
+        return;
+    }
+    println!("\n📈 {} tok/s by run, against the median and p10–p90 of up to {} runs before it:", model, TREND_WINDOW);
+    
+    for point in points {
+        let band = match point.band {
+            Some(band) => format!(
+                "median {} [{}, {}]{}",
+                num(band.median, 1),
+                num(band.p10, 1),
+                num(band.p90, 1),
+                if point.is_outside() { "  ⚠️  outside" } else { "" }
+            ),
+            None => "too few earlier runs for a band".to_string(),
+        };
+        println!("   {}  {:>7} tok/s  {}", point.run_id, num(point.tokens_per_second, 1), band);
+    }
+    
+    let latest = points.last().filter(|point| point.is_outside());
+    if let Some((point, band)) = latest.and_then(|point| Some((point, point.band?))) {
+        let direction = if point.tokens_per_second < band.p10 { "slower" } else { "faster" };
+        println!(
+            "⚠️  The latest run ({}) is {} than the p10–p90 band of the {} runs before it",
+            point.run_id, direction, band.runs
+        );
+    }
+}
+
+pub fn print_installed_models(models: &[InstalledModel], url: &str) {
+    if models.is_empty() {
+        println!("📦 No models installed on {}", url);
+        return;
+    }
+    println!("📦 {} model(s) on {}:", models.len(), url);
+    
+    let width = models.iter().map(|m| m.name.len()).max().unwrap_or(0);
+    for model in models {
+        println!(
+            "   {:width$}  {:>6} GB  {:>7}  {:8}  {}",
+            model.name,
+            num(model.size_bytes as f64 / 1e9, 1),
+            model.parameter_size.as_deref().unwrap_or("-"),
+            model.quantization.as_deref().unwrap_or("-"),
+            model.modified_at,
+            width = width
+        );
+    }
+}
+
+pub fn print_aggregate_report(run_count: usize, report: &[ModelAggregate]) {
+    println!("📚 Pooled results from {} runs:", run_count);
+    
+    for model in report {
+        let means: Vec<String> = model.run_means.iter().map(|&m| num(m, 1)).collect();
+        println!(
+            "   {:11} {:>6} ± {} tok/s, {:>5}ms TTFT over {} iterations in {} runs",
+            model.model, num(model.avg_tokens_per_second, 1), num(model.stddev_tokens_per_second, 1),
+            num(model.avg_ttft_ms, 0), model.iterations, model.runs
+        );
+        println!(
+            "   {:11} run means {} (between-run σ {}, within-run σ {})",
+            "", means.join(" / "), num(model.between_run_stddev, 2), num(model.within_run_stddev, 2)
+        );
+    }
+    
+    let inconsistent: Vec<&str> = report.iter().filter(|m| !m.consistent).map(|m| m.model.as_str()).collect();
+    if inconsistent.is_empty() {
+        println!("✅ Runs are consistent: differences between them are within iteration noise");
+    } else {
+        println!(
+            "⚠️  Runs disagree for {}: run means differ more than iteration noise explains",
+            inconsistent.join(", ")
+        );
+        println!("💡 Check for changed server settings, thermal throttling or background load between runs");
+    }
+}
+
+/// Width of the timeline bars, in terminal columns.
+const TIMELINE_WIDTH: usize = 60;
+
+pub fn print_timeline(results: &[BenchmarkResult]) {
+    let rows = timeline::render(results, TIMELINE_WIDTH);
+    if rows.is_empty() {
+        return;
+    }
+    
+    println!("\n🕒 Timeline (█ request, ✗ failed):");
+    let label_width = rows.iter().map(|(model, _)| model.chars().count()).max().unwrap_or(0);
+    for (model, bar) in &rows {
+        println!("   {:width$} │{}│", model, bar, width = label_width);
+    }
+    
+    if let Some((gap_ms, from)) = timeline::longest_gap(results) {
+        println!("   Longest idle gap: {}ms at {}", gap_ms, from.format("%H:%M:%S%.3f"));
+    }
+}
+
+/// Streams one row per iteration, for `--export-raw` to CSV.
+pub fn write_raw_csv(writer: &mut impl Write, results: &[BenchmarkResult]) -> std::io::Result<()> {
+    writeln!(writer, "{}", csvmeta::RAW_COLUMNS.join(","))?;
+    
+    for result in results {
+        let telemetry = result.telemetry;
+        let usage = |metric: Option<f64>| metric.map(|v| format!("{:.1}", v)).unwrap_or_default();
+        writeln!(
+            writer,
+            "{},{},{},{},{:.2},{},{},{},{},{},\"{}\",{},\"{}\",{},{},{},{},{},{},{},{}",
+            result.model,
+            result.timestamp.to_rfc3339(),
+            result.finished_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            result.success,
+            result.tokens_per_second,
+            result.time_to_first_token_ms,
+            result.total_duration_ms,
+            result.load_duration_ms,
+            result.prompt_tokens,
+            result.completion_tokens,
+            result.error.as_deref().unwrap_or("").replace('"', "\"\""),
+            result.remote_addr.as_deref().unwrap_or_default(),
+            result.anomaly.as_deref().unwrap_or_default(),
+            result.clock_jump_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+            result.reported_ttft_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+            usage(telemetry.and_then(|t| t.gpu_util_percent).map(|u| u.avg)),
+            usage(telemetry.and_then(|t| t.gpu_util_percent).map(|u| u.peak)),
+            usage(telemetry.and_then(|t| t.vram_used_mb).map(|u| u.peak)),
+            usage(telemetry.and_then(|t| t.cpu_percent).map(|u| u.avg)),
+            usage(telemetry.and_then(|t| t.ram_used_mb).map(|u| u.peak)),
+            result.first_sentence_ms.map(|ms| ms.to_string()).unwrap_or_default()
+        )?;
+    }
+    
+    Ok(())
+}
+
+/// Streams iterations as a JSON array, one object per line.
+pub fn write_raw_json(writer: &mut impl Write, results: &[BenchmarkResult]) -> std::io::Result<()> {
+    writer.write_all(b"[")?;
+    for (i, result) in results.iter().enumerate() {
+        writer.write_all(if i == 0 { b"\n" } else { b",\n" })?;
+        serde_json::to_writer(&mut *writer, result)?;
+    }
+    writer.write_all(b"\n]\n")
 }
 
 #[cfg(test)]
@@ -177,10 +1755,37 @@ mod tests {
                 min_tokens_per_second: 20.0,
                 max_tokens_per_second: 30.0,
                 avg_ttft_ms: 200.0,
+                ..Default::default()
             }
         ];
         
         // This test just ensures the function doesn't panic
-        print_results_csv(&summaries);
+        print_results_csv(&summaries, SpeedUnit::Tokens);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_preview() {
+        assert_eq!(preview("Silent GPUs\nhum along", 60), "Silent GPUs hum along");
+        assert_eq!(preview("量子コンピュータ", 2), "量子…");
+    }
+    
+    #[test]
+    fn test_write_raw_results() {
+        let timestamp = chrono::Utc::now();
+        let results = vec![BenchmarkResult::failed("m", "p", timestamp, 12, "said \"no\"".to_string())];
+        
+        let mut csv = Vec::new();
+        write_raw_csv(&mut csv, &results).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with(&format!("m,{},", timestamp.to_rfc3339())));
+        // Later columns follow the error, empty for a failed request
+        assert!(row.ends_with(",\"said \"\"no\"\"\",,\"\",,,,,,,,"));
+        assert_eq!(row.matches(',').count() + 1, csvmeta::RAW_COLUMNS.len());
+        
+        let mut json = Vec::new();
+        write_raw_json(&mut json, &results).unwrap();
+        let parsed: Vec<BenchmarkResult> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed.len(), 1);
+    }
+}
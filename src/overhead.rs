@@ -0,0 +1,150 @@
+use std::time::Instant;
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::OVERHEAD_SAMPLES;
+use crate::effect::Distribution;
+use crate::endpoints::overhead_ms;
+use crate::net::{probe_client, ProbeTarget};
+use crate::types::{BenchmarkConfig, BenchmarkResult, Endpoint, ModelSummary};
+
+/// Prompt of the minimal request: one token in, one token out.
+const MINIMAL_PROMPT: &str = "Hi";
+
+/// Time spent outside the model, per model, for one minimal request.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelOverhead {
+    pub model: String,
+    /// Server of a multi-server run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    pub requests: u64,
+    pub avg_latency_ms: f64,
+    /// Load, prompt evaluation and generation as reported by the server
+    pub avg_model_ms: f64,
+    /// The rest: client, network, HTTP and server scheduling
+    pub avg_overhead_ms: f64,
+    /// Overhead as a share of the benchmark's average TTFT
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttft_share_percent: Option<f64>,
+}
+
+/// Round trip of `/api/version` to one server; it never touches a model.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionRoundTrip {
+    /// Server of a multi-server run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rtt_ms: Option<Distribution>,
+}
+
+/// `--overhead-check`: what a request costs before the model is involved.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverheadReport {
+    pub version: Vec<VersionRoundTrip>,
+    pub models: Vec<ModelOverhead>,
+}
+
+/// Times `/api/version` per server and a one-token generation per model
+/// and server. Runs after the benchmark, so models are loaded and
+/// `config`'s options (e.g. `num_ctx`) match and do not force a reload.
+/// Stops at the first sample after `cancel` with what it has.
+pub async fn measure(
+    targets: &[ProbeTarget],
+    config: &BenchmarkConfig,
+    summaries: &[ModelSummary],
+    cancel: &CancellationToken,
+) -> OverheadReport {
+    let mut report = OverheadReport { version: Vec::new(), models: Vec::new() };
+    for (client, server) in targets {
+        let mut round_trips = Vec::new();
+        for _ in 0..OVERHEAD_SAMPLES {
+            let start = Instant::now();
+            let response = tokio::select! {
+                response = client.version() => response,
+                _ = cancel.cancelled() => return report,
+            };
+            if response.is_ok() {
+                round_trips.push(start.elapsed().as_secs_f64() * 1000.0);
+            }
+        }
+        report.version.push(VersionRoundTrip { server: server.clone(), rtt_ms: Distribution::of(&round_trips) });
+    }
+
+    let minimal = BenchmarkConfig {
+        max_tokens: 1,
+        stream: false,
+        endpoint: Endpoint::Generate,
+        history_messages: 0,
+        ..config.clone()
+    };
+    for summary in summaries.iter().filter(|s| s.variant.is_none() && s.success_rate > 0.0) {
+        let Some(client) = probe_client(targets, summary.server.as_deref()) else {
+            continue;
+        };
+        let mut results = Vec::new();
+        for _ in 0..OVERHEAD_SAMPLES {
+            let response = tokio::select! {
+                response = client.generate(&summary.model, MINIMAL_PROMPT, &minimal) => response,
+                _ = cancel.cancelled() => return report,
+            };
+            if let Ok(result) = response {
+                results.push(result);
+            }
+        }
+        if let Some(mut overhead) = model_overhead(&summary.model, &results, summary.avg_ttft_ms) {
+            overhead.server = summary.server.clone();
+            report.models.push(overhead);
+        }
+    }
+    report
+}
+
+fn model_overhead(model: &str, results: &[BenchmarkResult], avg_ttft_ms: f64) -> Option<ModelOverhead> {
+    let successful: Vec<&BenchmarkResult> = results.iter().filter(|r| r.success).collect();
+    if successful.is_empty() {
+        return None;
+    }
+    let mean = |value: &dyn Fn(&BenchmarkResult) -> f64| successful.iter().map(|r| value(r)).sum::<f64>() / successful.len() as f64;
+    let avg_latency_ms = mean(&|r| r.total_duration_ms as f64);
+    let avg_overhead_ms = mean(&overhead_ms);
+
+    Some(ModelOverhead {
+        model: model.to_string(),
+        server: None,
+        requests: successful.len() as u64,
+        avg_latency_ms,
+        avg_model_ms: avg_latency_ms - avg_overhead_ms,
+        avg_overhead_ms,
+        ttft_share_percent: (avg_ttft_ms > 0.0).then(|| avg_overhead_ms / avg_ttft_ms * 100.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_model_overhead() {
+        let result = |success: bool, total_ms: u64| BenchmarkResult {
+            success,
+            load_duration_ms: 0,
+            time_to_first_token_ms: 20,
+            completion_tokens: 1,
+            // One token at 100 tok/s: 10ms of generation
+            tokens_per_second: 100.0,
+            ..BenchmarkResult::failed("m", MINIMAL_PROMPT, Utc::now(), total_ms, String::new())
+        };
+
+        let overhead = model_overhead("m", &[result(true, 40), result(true, 50), result(false, 900)], 200.0).unwrap();
+        assert_eq!(overhead.requests, 2);
+        assert_eq!(overhead.avg_latency_ms, 45.0);
+        assert_eq!(overhead.avg_model_ms, 30.0);
+        assert_eq!(overhead.avg_overhead_ms, 15.0);
+        assert_eq!(overhead.ttft_share_percent, Some(7.5));
+        assert!(model_overhead("m", &[result(false, 900)], 200.0).is_none());
+    }
+}
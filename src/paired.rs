@@ -0,0 +1,194 @@
+use serde::Serialize;
+
+use crate::effect::{mean, stddev, t_critical_95};
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// Mean of per-pair differences (B − A) with its 95% confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PairedDiff {
+    pub mean: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+}
+
+impl PairedDiff {
+    /// Needs at least two pairs.
+    pub fn of(differences: &[f64]) -> Option<Self> {
+        let n = differences.len();
+        let sd = stddev(differences)?;
+        let mean = mean(differences.iter().copied());
+        let margin = t_critical_95(n - 1) * sd / (n as f64).sqrt();
+        Some(Self { mean, ci_low: mean - margin, ci_high: mean + margin })
+    }
+
+    /// The interval excludes zero: the hosts differ beyond pair-to-pair noise.
+    pub fn significant(&self) -> bool {
+        self.ci_low > 0.0 || self.ci_high < 0.0
+    }
+}
+
+/// `--ab-hosts`: one model's identical requests on host A and host B,
+/// compared pair by pair so drift over the run cancels out.
+#[derive(Debug, Clone, Serialize)]
+pub struct PairedComparison {
+    pub model: String,
+    pub host_a: String,
+    pub host_b: String,
+    /// Iterations where both requests succeeded outside warm-up
+    pub pairs: u64,
+    /// Tokens/s of B minus A
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<PairedDiff>,
+    /// Mean speed difference relative to A's mean speed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_change_percent: Option<f64>,
+    /// TTFT of B minus A, in ms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttft_ms: Option<PairedDiff>,
+}
+
+/// For each model summarized on `host_a`, zips its iterations there with
+/// those on `host_b` in the order they ran.
+pub fn compare(summaries: &[ModelSummary], host_a: &str, host_b: &str, results: &[BenchmarkResult]) -> Vec<PairedComparison> {
+    summaries
+        .iter()
+        .filter(|summary| summary.server.as_deref() == Some(host_a))
+        .map(|summary| {
+            let on = |host: &str| -> Vec<&BenchmarkResult> {
+                results
+                    .iter()
+                    .filter(|r| r.model == summary.model && r.variant == summary.variant && r.server.as_deref() == Some(host))
+                    .collect()
+            };
+            let pairs = pair(on(host_a), on(host_b));
+            let (speed, speed_change_percent, ttft_ms) = differences(&pairs);
+            PairedComparison {
+                model: summary.model.clone(),
+                host_a: host_a.to_string(),
+                host_b: host_b.to_string(),
+                pairs: pairs.len() as u64,
+                speed,
+                speed_change_percent,
+                ttft_ms,
+            }
+        })
+        .collect()
+}
+
+/// `duel`: two models given identical prompts and seeds, alternating
+/// request by request, compared pair by pair.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuelReport {
+    pub model_a: String,
+    pub model_b: String,
+    /// Rounds where both requests succeeded outside warm-up
+    pub pairs: u64,
+    /// Tokens/s of B minus A
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<PairedDiff>,
+    /// Mean speed difference relative to A's mean speed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed_change_percent: Option<f64>,
+    /// TTFT of B minus A, in ms
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttft_ms: Option<PairedDiff>,
+    /// Rounds where A generated faster than B
+    pub a_wins: u64,
+    /// Rounds where B generated faster than A; ties count for neither
+    pub b_wins: u64,
+}
+
+/// Pairs round N of `model_a` with round N of `model_b`.
+pub fn duel(model_a: &str, model_b: &str, results: &[BenchmarkResult]) -> DuelReport {
+    let of = |model: &str| -> Vec<&BenchmarkResult> { results.iter().filter(|r| r.model == model).collect() };
+    let pairs = pair(of(model_a), of(model_b));
+    let (speed, speed_change_percent, ttft_ms) = differences(&pairs);
+    DuelReport {
+        model_a: model_a.to_string(),
+        model_b: model_b.to_string(),
+        pairs: pairs.len() as u64,
+        speed,
+        speed_change_percent,
+        ttft_ms,
+        a_wins: pairs.iter().filter(|(a, b)| a.tokens_per_second > b.tokens_per_second).count() as u64,
+        b_wins: pairs.iter().filter(|(a, b)| b.tokens_per_second > a.tokens_per_second).count() as u64,
+    }
+}
+
+/// Zips two runs in order, keeping pairs where both sides are usable.
+fn pair<'a>(a: Vec<&'a BenchmarkResult>, b: Vec<&'a BenchmarkResult>) -> Vec<(&'a BenchmarkResult, &'a BenchmarkResult)> {
+    a.into_iter()
+        .zip(b).filter(|(a, b)| a.is_measured() && b.is_measured()).collect()
+}
+
+/// Speed difference, its share of A's mean speed, and TTFT difference (B − A).
+fn differences(pairs: &[(&BenchmarkResult, &BenchmarkResult)]) -> (Option<PairedDiff>, Option<f64>, Option<PairedDiff>) {
+    let speed = PairedDiff::of(&pairs.iter().map(|(a, b)| b.tokens_per_second - a.tokens_per_second).collect::<Vec<_>>());
+    let mean_a = mean(pairs.iter().map(|(a, _)| a.tokens_per_second));
+    let ttft = PairedDiff::of(
+        &pairs
+            .iter()
+            .map(|(a, b)| b.time_to_first_token_ms as f64 - a.time_to_first_token_ms as f64)
+            .collect::<Vec<_>>(),
+    );
+    (speed, speed.filter(|_| mean_a > 0.0).map(|s| s.mean / mean_a * 100.0), ttft)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paired_diff() {
+        let diff = PairedDiff::of(&[2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(diff.mean, 3.0);
+        // sd 1, n 3, t(2) 4.303
+        assert!((diff.ci_high - diff.mean - 4.303 / 3f64.sqrt()).abs() < 1e-9);
+        assert!(diff.significant());
+        assert!(!PairedDiff::of(&[1.0, 2.0, 3.0]).unwrap().significant());
+        assert!(PairedDiff::of(&[1.0]).is_none());
+    }
+
+    #[test]
+    fn test_compare_pairs_in_order() {
+        let result = |server: &str, speed: f64, success: bool| BenchmarkResult {
+            success,
+            server: Some(server.to_string()),
+            ..BenchmarkResult::ok("m", speed)
+        };
+        let results = vec![
+            result("a", 20.0, true),
+            result("b", 22.0, true),
+            result("b", 22.0, true),
+            result("a", 20.0, true),
+            result("a", 21.0, true),
+            result("b", 0.0, false),
+            result("a", 20.0, true),
+            result("b", 22.0, true),
+        ];
+
+        let mut summary = ModelSummary::from_results("m".to_string(), &[]);
+        summary.server = Some("a".to_string());
+        let comparison = &compare(&[summary], "a", "b", &results)[0];
+        assert_eq!(comparison.pairs, 3);
+        assert_eq!(comparison.speed.unwrap().mean, 2.0);
+        assert_eq!(comparison.speed_change_percent, Some(10.0));
+    }
+
+    #[test]
+    fn test_duel_counts_wins() {
+        let result = BenchmarkResult::ok;
+        let results = vec![
+            result("a", 20.0),
+            result("b", 22.0),
+            result("a", 21.0),
+            result("b", 20.0),
+            result("a", 20.0),
+            result("b", 24.0),
+        ];
+
+        let report = duel("a", "b", &results);
+        assert_eq!((report.pairs, report.a_wins, report.b_wins), (3, 1, 2));
+        assert_eq!(report.speed.unwrap().mean, 5.0 / 3.0);
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::effect::{mean, Distribution};
+use crate::plan::parse_duration;
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// Time-varying load for `--pattern`, on top of the steady `--concurrency`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadPattern {
+    /// `size` extra requests at once every `every`, starting after one period
+    Burst { size: u32, every: Duration },
+    /// Requests in flight grow linearly from `from` to `to` over `over`, then hold
+    Ramp { from: u32, to: u32, over: Duration },
+    /// Requests in flight swing between `low` and `high` once per `period`
+    Sine { low: u32, high: u32, period: Duration },
+}
+
+impl LoadPattern {
+    /// Requests to keep in flight `elapsed` into the run; bursts come on
+    /// top of the steady `concurrency`.
+    pub fn target(&self, concurrency: u32, elapsed: Duration) -> u32 {
+        match *self {
+            LoadPattern::Burst { .. } => concurrency,
+            LoadPattern::Ramp { from, to, over } => {
+                let progress = (elapsed.as_secs_f64() / over.as_secs_f64()).min(1.0);
+                (from as f64 + (to as f64 - from as f64) * progress).round() as u32
+            }
+            LoadPattern::Sine { low, high, period } => {
+                let phase = 2.0 * PI * elapsed.as_secs_f64() / period.as_secs_f64();
+                (low as f64 + (high - low) as f64 * (1.0 - phase.cos()) / 2.0).round() as u32
+            }
+        }
+    }
+}
+
+impl fmt::Display for LoadPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadPattern::Burst { size, every } => write!(f, "bursts of {} every {}s", size, every.as_secs_f64()),
+            LoadPattern::Ramp { from, to, over } => write!(f, "ramp from {} to {} in flight over {}s", from, to, over.as_secs_f64()),
+            LoadPattern::Sine { low, high, period } => write!(f, "sine between {} and {} in flight every {}s", low, high, period.as_secs_f64()),
+        }
+    }
+}
+
+/// Parses `burst:10x5s`, `ramp:1-8/30s` or `sine:1-8/60s`.
+pub fn parse_pattern(text: &str) -> Result<LoadPattern, String> {
+    let invalid = || format!("invalid load pattern '{}' (use e.g. burst:10x5s, ramp:1-8/30s, sine:1-8/60s)", text);
+    let (kind, spec) = text.trim().split_once(':').ok_or_else(invalid)?;
+    let count = |n: &str| n.trim().parse::<u32>().ok().filter(|&n| n > 0).ok_or_else(invalid);
+    // A zero period would divide by zero in `target` or fire every tick
+    let period = |d: &str| Some(parse_duration(d)?).filter(|d| !d.is_zero()).ok_or_else(invalid);
+    let range = |spec: &str| -> Result<(u32, u32, Duration), String> {
+        let (range, duration) = spec.split_once('/').ok_or_else(invalid)?;
+        let (low, high) = range.split_once('-').ok_or_else(invalid)?;
+        Ok((count(low)?, count(high)?, period(duration)?))
+    };
+
+    match kind.trim() {
+        "burst" => {
+            let (size, every) = spec.split_once('x').ok_or_else(invalid)?;
+            Ok(LoadPattern::Burst { size: count(size)?, every: period(every)? })
+        }
+        "ramp" => {
+            let (from, to, over) = range(spec)?;
+            Ok(LoadPattern::Ramp { from, to, over })
+        }
+        "sine" => {
+            let (low, high, period) = range(spec)?;
+            if low > high {
+                return Err(invalid());
+            }
+            Ok(LoadPattern::Sine { low, high, period })
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Latency of the requests sent under one level of load.
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseStats {
+    /// e.g. `during bursts` or `4 in flight`
+    pub phase: String,
+    pub requests: u64,
+    pub avg_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub avg_ttft_ms: f64,
+}
+
+/// How one model's latency follows the `--pattern` load.
+#[derive(Debug, Clone, Serialize)]
+pub struct PatternReport {
+    pub model: String,
+    pub pattern: String,
+    pub phases: Vec<PhaseStats>,
+}
+
+/// Groups successful requests by the load they were sent under: for
+/// bursts, while burst requests were still in flight versus after they
+/// drained; otherwise by requests in flight.
+pub fn build_report(summaries: &[ModelSummary], pattern: LoadPattern, concurrency: u32, results: &[BenchmarkResult]) -> Vec<PatternReport> {
+    summaries
+        .iter()
+        .map(|summary| {
+            let mut groups: BTreeMap<u32, Vec<&BenchmarkResult>> = BTreeMap::new();
+            for result in results.iter().filter(|r| summary.owns(r) && r.is_measured()) {
+                let Some(in_flight) = result.in_flight else {
+                    continue;
+                };
+                let key = match pattern {
+                    LoadPattern::Burst { .. } => u32::from(in_flight > concurrency),
+                    _ => in_flight,
+                };
+                groups.entry(key).or_default().push(result);
+            }
+
+            let phases = groups
+                .into_iter()
+                .map(|(key, results)| {
+                    let phase = match pattern {
+                        LoadPattern::Burst { .. } if key == 1 => "during bursts".to_string(),
+                        LoadPattern::Burst { .. } => "between bursts".to_string(),
+                        _ => format!("{} in flight", key),
+                    };
+                    phase_stats(phase, &results)
+                })
+                .collect();
+            PatternReport { model: summary.label(), pattern: pattern.to_string(), phases }
+        })
+        .collect()
+}
+
+fn phase_stats(phase: String, results: &[&BenchmarkResult]) -> PhaseStats {
+    let latencies: Vec<f64> = results.iter().map(|r| r.total_duration_ms as f64).collect();
+    PhaseStats {
+        phase,
+        requests: results.len() as u64,
+        avg_latency_ms: mean(latencies.iter().copied()),
+        p95_latency_ms: Distribution::of(&latencies).map_or(0.0, |d| d.p95),
+        avg_ttft_ms: mean(results.iter().map(|r| r.time_to_first_token_ms as f64)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(parse_pattern("burst:10x5s"), Ok(LoadPattern::Burst { size: 10, every: Duration::from_secs(5) }));
+        assert_eq!(
+            parse_pattern("ramp:1-8/30s"),
+            Ok(LoadPattern::Ramp { from: 1, to: 8, over: Duration::from_secs(30) })
+        );
+        assert!(parse_pattern("sine:8-1/60s").is_err());
+        assert!(parse_pattern("burst:0x5s").is_err());
+        assert!(parse_pattern("burst:10x0s").is_err());
+        assert!(parse_pattern("sine:1-8/0ms").is_err());
+        assert!(parse_pattern("spike:10").is_err());
+    }
+
+    #[test]
+    fn test_target() {
+        let ramp = LoadPattern::Ramp { from: 1, to: 9, over: Duration::from_secs(40) };
+        assert_eq!(ramp.target(1, Duration::ZERO), 1);
+        assert_eq!(ramp.target(1, Duration::from_secs(20)), 5);
+        assert_eq!(ramp.target(1, Duration::from_secs(90)), 9);
+
+        let sine = LoadPattern::Sine { low: 2, high: 8, period: Duration::from_secs(60) };
+        assert_eq!(sine.target(1, Duration::ZERO), 2);
+        assert_eq!(sine.target(1, Duration::from_secs(30)), 8);
+        assert_eq!(LoadPattern::Burst { size: 10, every: Duration::from_secs(5) }.target(3, Duration::ZERO), 3);
+    }
+
+    #[test]
+    fn test_build_report() {
+        let result = |in_flight: u32, latency_ms: u64| BenchmarkResult {
+            total_duration_ms: latency_ms,
+            in_flight: Some(in_flight),
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        let results = vec![result(1, 100), result(11, 900), result(11, 1100), result(2, 300)];
+        let pattern = LoadPattern::Burst { size: 10, every: Duration::from_secs(5) };
+
+        let report = build_report(&[ModelSummary::from_results("m".to_string(), &[])], pattern, 2, &results);
+        let phases: Vec<(&str, u64, f64)> = report[0]
+            .phases
+            .iter()
+            .map(|p| (p.phase.as_str(), p.requests, p.avg_latency_ms))
+            .collect();
+        assert_eq!(phases, vec![("between bursts", 2, 200.0), ("during bursts", 2, 1000.0)]);
+    }
+}
@@ -0,0 +1,261 @@
+use std::time::Duration;
+use clap::ValueEnum;
+use serde_json::Value;
+
+use crate::types::BenchmarkConfig;
+
+/// How sweep cells are ordered across models.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum SweepOrder {
+    /// All cells for a model run back to back, minimizing model loads (default)
+    #[default]
+    Grouped,
+    /// Alternate models between cells, spreading slow drift (thermals,
+    /// background load) evenly at the cost of a reload per cell
+    Interleaved,
+}
+
+/// One model under one configuration variant.
+#[derive(Debug, Clone)]
+pub struct Cell {
+    pub model: String,
+    pub variant: Option<String>,
+    pub config: BenchmarkConfig,
+}
+
+impl Cell {
+    pub fn label(&self) -> String {
+        match &self.variant {
+            Some(variant) => format!("{} [{}]", self.model, variant),
+            None => self.model.clone(),
+        }
+    }
+
+    /// Whether moving from `previous` to this cell makes Ollama load the
+    /// model again. Changing `num_thread` restarts the runner.
+    fn needs_load_after(&self, previous: Option<&Cell>) -> bool {
+        previous.map_or(true, |prev| {
+            prev.model != self.model || prev.config.num_thread != self.config.num_thread
+        })
+    }
+}
+
+/// Orders every model × variant cell of a run.
+pub fn plan(models: &[String], config: &BenchmarkConfig, order: SweepOrder) -> Vec<Cell> {
+    let variants = config.variants();
+    let cell = |model: &String, (variant, config): &(Option<String>, BenchmarkConfig)| Cell {
+        model: model.clone(),
+        variant: variant.clone(),
+        config: config.clone(),
+    };
+
+    match order {
+        SweepOrder::Grouped => models
+            .iter()
+            .flat_map(|model| variants.iter().map(move |variant| cell(model, variant)))
+            .collect(),
+        SweepOrder::Interleaved => variants
+            .iter()
+            .flat_map(|variant| models.iter().map(move |model| cell(model, variant)))
+            .collect(),
+    }
+}
+
+/// Expected number of model loads when running `cells` in order.
+pub fn load_count(cells: &[Cell]) -> usize {
+    cells
+        .iter()
+        .enumerate()
+        .filter(|(i, cell)| cell.needs_load_after(i.checked_sub(1).map(|p| &cells[p])))
+        .count()
+}
+
+/// Iterations to run in the next cell so the remaining cells fit in
+/// `remaining` time. Sampling strategy: the leftover budget is split evenly
+/// across all remaining cells, and every cell keeps at least one iteration
+/// so nothing goes unmeasured (a very tight budget can therefore overrun).
+/// Before any timing is observed the full iteration count is used.
+pub fn budget_iterations(
+    planned: u32,
+    remaining_cells: usize,
+    remaining: Duration,
+    per_iteration: Option<Duration>,
+) -> u32 {
+    let Some(per_iteration) = per_iteration.filter(|d| !d.is_zero()) else {
+        return planned;
+    };
+    let per_cell = remaining.as_secs_f64() / remaining_cells.max(1) as f64;
+    let affordable = (per_cell / per_iteration.as_secs_f64()).floor() as u32;
+    affordable.clamp(1, planned.max(1))
+}
+
+/// One Ollama option swept over several values, e.g. `temperature=0,0.7,1.2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionSweep {
+    pub name: String,
+    pub values: Vec<Value>,
+}
+
+impl OptionSweep {
+    /// Variant label for one value, e.g. `temperature=0.7` or `mirostat=2`.
+    pub fn label(&self, value: &Value) -> String {
+        match value {
+            Value::String(text) => format!("{}={}", self.name, text),
+            other => format!("{}={}", self.name, other),
+        }
+    }
+}
+
+/// Parses `NAME=V1,V2,...`. Values that read as JSON (numbers, booleans)
+/// are sent as such; anything else is sent as a string.
+pub fn parse_option_sweep(text: &str) -> Result<OptionSweep, String> {
+    let (name, values) = text
+        .split_once('=')
+        .ok_or_else(|| format!("invalid option sweep '{}' (use e.g. temperature=0,0.7,1.2)", text))?;
+    let name = name.trim();
+    let values: Vec<Value> = values
+        .split(',')
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string())))
+        .collect();
+
+    if name.is_empty() || values.is_empty() {
+        return Err(format!("invalid option sweep '{}' (use e.g. temperature=0,0.7,1.2)", text));
+    }
+    Ok(OptionSweep { name: name.to_string(), values })
+}
+
+/// Parses durations like `400ms`, `90s`, `45m`, `2h` or `1h30m`; bare numbers
+/// are seconds. Zero and durations too long to count in milliseconds are
+/// rejected.
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let invalid = || format!("invalid duration '{}' (use e.g. 400ms, 90s, 45m, 2h)", text);
+    if let Ok(seconds) = text.parse::<u64>() {
+        return match seconds {
+            0 => Err(invalid()),
+            _ => Ok(Duration::from_secs(seconds)),
+        };
+    }
+
+    let mut total_ms = 0u64;
+    let mut number = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit_ms = match c {
+            'h' => 3_600_000,
+            'm' if chars.next_if_eq(&'s').is_some() => 1,
+            'm' => 60_000,
+            's' => 1_000,
+            _ => return Err(invalid()),
+        };
+        let value: u64 = number.parse().map_err(|_| invalid())?;
+        total_ms = value
+            .checked_mul(unit_ms)
+            .and_then(|ms| total_ms.checked_add(ms))
+            .ok_or_else(invalid)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || total_ms == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_millis(total_ms))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep() -> (Vec<String>, BenchmarkConfig) {
+        let models = vec!["a".to_string(), "b".to_string()];
+        let config = BenchmarkConfig {
+            thread_sweep: vec![4, 8],
+            ..Default::default()
+        };
+        (models, config)
+    }
+
+    #[test]
+    fn test_grouped_plan_keeps_models_contiguous() {
+        let (models, config) = sweep();
+        let cells = plan(&models, &config, SweepOrder::Grouped);
+
+        let labels: Vec<String> = cells.iter().map(Cell::label).collect();
+        assert_eq!(labels, vec![
+            "a [num_thread=4]", "a [num_thread=8]",
+            "b [num_thread=4]", "b [num_thread=8]",
+        ]);
+        assert_eq!(load_count(&cells), 4);
+    }
+
+    #[test]
+    fn test_interleaved_plan_alternates_models() {
+        let (models, config) = sweep();
+        let cells = plan(&models, &config, SweepOrder::Interleaved);
+
+        let models: Vec<&str> = cells.iter().map(|c| c.model.as_str()).collect();
+        assert_eq!(models, vec!["a", "b", "a", "b"]);
+        assert_eq!(load_count(&cells), 4);
+    }
+
+    #[test]
+    fn test_load_count_skips_unchanged_cells() {
+        let cell = |model: &str, threads: Option<u32>| Cell {
+            model: model.to_string(),
+            variant: None,
+            config: BenchmarkConfig { num_thread: threads, ..Default::default() },
+        };
+
+        assert_eq!(load_count(&[cell("a", None), cell("a", None), cell("b", None)]), 2);
+        assert_eq!(load_count(&[cell("a", None), cell("b", None), cell("a", None)]), 3);
+        assert_eq!(load_count(&[cell("a", Some(4)), cell("a", Some(8))]), 2);
+        assert_eq!(load_count(&[]), 0);
+    }
+
+    #[test]
+    fn test_budget_iterations() {
+        let secs = Duration::from_secs;
+        // 4 cells left, 100s left, 5s per iteration → 5 iterations per cell
+        assert_eq!(budget_iterations(10, 4, secs(100), Some(secs(5))), 5);
+        assert_eq!(budget_iterations(3, 4, secs(100), Some(secs(5))), 3);
+        assert_eq!(budget_iterations(10, 4, secs(1), Some(secs(5))), 1);
+        assert_eq!(budget_iterations(10, 4, secs(1), None), 10);
+    }
+
+    #[test]
+    fn test_parse_option_sweep() {
+        let sweep = parse_option_sweep("temperature=0, 0.7,1.2").unwrap();
+        assert_eq!(sweep.name, "temperature");
+        assert_eq!(sweep.values, vec![serde_json::json!(0), serde_json::json!(0.7), serde_json::json!(1.2)]);
+        assert_eq!(sweep.label(&sweep.values[1]), "temperature=0.7");
+
+        let sweep = parse_option_sweep("penalize_newline=true,false").unwrap();
+        assert_eq!(sweep.values[0], Value::Bool(true));
+        assert_eq!(parse_option_sweep("stop=END").unwrap().label(&Value::String("END".into())), "stop=END");
+
+        assert!(parse_option_sweep("temperature").is_err());
+        assert!(parse_option_sweep("temperature=").is_err());
+        assert!(parse_option_sweep("=1,2").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("2h"), Ok(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("400ms"), Ok(Duration::from_millis(400)));
+        assert_eq!(parse_duration("1m30s"), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("2x").is_err());
+        assert!(parse_duration("h").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("99999999999999999h").is_err());
+        assert!(parse_duration("18446744073709551615ms1ms").is_err());
+    }
+}
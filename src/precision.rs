@@ -0,0 +1,66 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// `--precision` of the current run; zero for none.
+static DIGITS: AtomicU32 = AtomicU32::new(0);
+
+/// Selects `--precision` for every report printed from here on; `None`
+/// restores each format's own decimals.
+pub fn set_precision(digits: Option<u32>) {
+    DIGITS.store(digits.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Formats a number for every human-readable report, terminal or file:
+/// `--precision` significant digits when set, otherwise the format's own
+/// `decimals`. JSON, OpenMetrics and the raw exports never go through
+/// here and keep full precision.
+pub fn num(value: f64, decimals: usize) -> String {
+    match DIGITS.load(Ordering::Relaxed) {
+        0 => format!("{:.*}", decimals, value),
+        digits => significant(value, digits),
+    }
+}
+
+/// `num` with an explicit sign, for differences: `+2.5`, `-0.3`.
+pub fn signed(value: f64, decimals: usize) -> String {
+    let text = num(value, decimals);
+    if text.starts_with('-') { text } else { format!("+{}", text) }
+}
+
+/// Rounds to `digits` significant digits, but never drops integer digits:
+/// 3 digits give 45.7, 0.0123 and 1235.
+fn significant(value: f64, digits: u32) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format!("{:.*}", digits.saturating_sub(1) as usize, value);
+    }
+    let decimals = |magnitude: i32| (digits as i32 - 1 - magnitude).max(0) as usize;
+    let magnitude = value.abs().log10().floor() as i32;
+    let text = format!("{:.*}", decimals(magnitude), value);
+    // Rounding up can carry into a new digit: 99.96 → 100.0 → 100
+    match text.parse::<f64>() {
+        Ok(rounded) if rounded.abs() >= 10f64.powi(magnitude + 1) => format!("{:.*}", decimals(magnitude + 1), value),
+        _ => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_significant() {
+        assert_eq!(significant(45.678, 3), "45.7");
+        assert_eq!(significant(4.5678, 3), "4.57");
+        assert_eq!(significant(0.012345, 3), "0.0123");
+        assert_eq!(significant(1234.6, 3), "1235");
+        assert_eq!(significant(-2.5, 2), "-2.5");
+        assert_eq!(significant(0.0, 3), "0.00");
+        assert_eq!(significant(99.96, 3), "100");
+    }
+
+    #[test]
+    fn test_signed() {
+        assert_eq!(signed(2.54, 1), "+2.5");
+        assert_eq!(signed(-0.3, 1), "-0.3");
+        assert_eq!(signed(0.0, 0), "+0");
+    }
+}
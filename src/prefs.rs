@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, FromArgMatches, ValueEnum};
+
+use crate::cli::{Cli, OutputFormat};
+use crate::config::APP_NAME;
+use crate::error::{BenchmarkError, Result};
+
+/// Keys `ollama-bench config set` accepts, and what each one defaults.
+pub const KEYS: &[(&str, &str)] = &[
+    ("default.url", "--ollama-url"),
+    ("default.output", "--output"),
+    ("default.iterations", "--iterations"),
+    ("default.export_dir", "directory for relative --export/--export-raw paths"),
+];
+
+/// Personal defaults from the config file, applied wherever the command
+/// line leaves a flag unset.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Preferences {
+    values: BTreeMap<String, String>,
+}
+
+/// `$OLLAMA_BENCH_CONFIG`, else `config.json` in the platform config
+/// directory (`$XDG_CONFIG_HOME`, `%APPDATA%` or `~/.config`).
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("OLLAMA_BENCH_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .or_else(|| std::env::var_os("APPDATA"))
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join(APP_NAME).join("config.json"))
+}
+
+impl Preferences {
+    /// Reads the config file; a missing file means no preferences.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(BenchmarkError::IoError(format!("{}: {}", path.display(), e))),
+        };
+        let values = serde_json::from_str(&content)
+            .map_err(|e| BenchmarkError::ConfigError(format!("{}: {}", path.display(), e)))?;
+        Ok(Self { values })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(&self.values)? + "\n")?;
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    /// Stores `value` after checking it is one the flag would accept.
+    pub fn set(&mut self, key: &str, value: &str) -> std::result::Result<(), String> {
+        validate(key, value)?;
+        self.values.insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    pub fn unset(&mut self, key: &str) -> bool {
+        self.values.remove(key).is_some()
+    }
+
+    /// Fills in flags neither the command line nor the environment set.
+    pub fn apply(&self, cli: &mut Cli, matches: &ArgMatches) -> std::result::Result<(), String> {
+        let defaulted = |id: &str| !matches!(matches.value_source(id), Some(ValueSource::CommandLine | ValueSource::EnvVariable));
+        for (key, value) in self.iter() {
+            validate(key, value)?;
+            match key {
+                "default.url" if defaulted("ollama_url") => {
+                    cli.ollama_url = value.split(',').map(|url| url.trim().to_string()).collect();
+                }
+                "default.output" if defaulted("output") => cli.output = OutputFormat::from_str(value, true)?,
+                "default.iterations" if defaulted("iterations") => cli.iterations = value.parse().map_err(|_| value.to_string())?,
+                "default.export_dir" => {
+                    for path in [&mut cli.export, &mut cli.export_raw].into_iter().flatten() {
+                        if Path::new(path.as_str()).is_relative() {
+                            *path = Path::new(value).join(&*path).to_string_lossy().into_owned();
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate(key: &str, value: &str) -> std::result::Result<(), String> {
+    let valid = match key {
+        "default.url" | "default.export_dir" => !value.trim().is_empty(),
+        "default.output" => OutputFormat::from_str(value, true).is_ok(),
+        "default.iterations" => value.parse::<u32>().is_ok_and(|n| n > 0),
+        _ => {
+            let keys: Vec<&str> = KEYS.iter().map(|(key, _)| *key).collect();
+            return Err(format!("unknown preference '{}' (use one of {})", key, keys.join(", ")));
+        }
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid value '{}' for {}", value, key))
+    }
+}
+
+/// Parses the command line and `OLLAMA_BENCH_*` variables, then applies preferences from the config file.
+/// `config` subcommands manage the file rather than use it.
+pub fn parse_cli() -> Result<Cli> {
+    let matches = Cli::command_with_env().get_matches_from(Cli::default_command(std::env::args_os()));
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    cli.check_sources(&matches).map_err(BenchmarkError::ConfigError)?;
+    if cli.is_config_command() {
+        return Ok(cli);
+    }
+    if let Some(path) = config_path() {
+        Preferences::load(&path)?
+            .apply(&mut cli, &matches)
+            .map_err(|e| BenchmarkError::ConfigError(format!("{}: {}", path.display(), e)))?;
+    }
+    Ok(cli)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn parse(args: &[&str]) -> (Cli, ArgMatches) {
+        let matches = Cli::command().get_matches_from(std::iter::once(APP_NAME).chain(args.iter().copied()));
+        (Cli::from_arg_matches(&matches).unwrap(), matches)
+    }
+
+    #[test]
+    fn test_set_validates() {
+        let mut prefs = Preferences::default();
+        assert!(prefs.set("default.output", "json").is_ok());
+        assert!(prefs.set("default.output", "yaml").is_err());
+        assert!(prefs.set("default.iterations", "0").is_err());
+        assert!(prefs.set("default.colour", "on").is_err());
+        assert_eq!(prefs.get("default.output"), Some("json"));
+        assert!(prefs.unset("default.output"));
+        assert!(!prefs.unset("default.output"));
+    }
+
+    #[test]
+    fn test_apply_keeps_command_line() {
+        let mut prefs = Preferences::default();
+        prefs.set("default.output", "json").unwrap();
+        prefs.set("default.iterations", "10").unwrap();
+        prefs.set("default.export_dir", "results").unwrap();
+
+        let (mut cli, matches) = parse(&["-n", "3", "-e", "run.csv", "--export-raw", "/tmp/raw.json", "llama2:7b"]);
+        prefs.apply(&mut cli, &matches).unwrap();
+        assert_eq!(cli.output, OutputFormat::Json);
+        assert_eq!(cli.iterations, 3);
+        assert_eq!(cli.export.as_deref(), Some(Path::new("results").join("run.csv").to_str().unwrap()));
+        assert_eq!(cli.export_raw.as_deref(), Some("/tmp/raw.json"));
+    }
+}
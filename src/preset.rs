@@ -0,0 +1,99 @@
+use clap::ValueEnum;
+
+use crate::types::BenchmarkConfig;
+
+/// Sections in the summarize document; about 8k tokens with common tokenizers.
+const SUMMARIZE_SECTIONS: u32 = 70;
+
+const SUMMARIZE_MAX_TOKENS: i32 = 128;
+
+/// Context window for the summarize preset: the document plus the summary,
+/// with headroom for tokenizers that split the text finer.
+const SUMMARIZE_NUM_CTX: u32 = 12288;
+
+const TOPICS: [&str; 5] = ["storage", "networking", "scheduling", "billing", "monitoring"];
+const OUTCOMES: [&str; 4] = [
+    "stayed within its budget",
+    "overran its budget by a small margin",
+    "was delayed by a dependency on another team",
+    "finished early and freed capacity for the next quarter",
+];
+
+/// Workload shapes that replace the default short-prompt, short-answer run.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Preset {
+    /// Long document in (~8k tokens), short summary out (128 tokens): prompt evaluation dominates
+    Summarize,
+}
+
+impl Preset {
+    /// Sets the prompt, output length and context window of the preset.
+    pub fn apply(&self, config: &mut BenchmarkConfig) {
+        match self {
+            Preset::Summarize => {
+                config.prompt = summarize_prompt();
+                config.max_tokens = SUMMARIZE_MAX_TOKENS;
+                config.options.entry("num_ctx".to_string()).or_insert(SUMMARIZE_NUM_CTX.into());
+                // A repeated document would be served from the prompt cache
+                config.unique_prompts = true;
+            }
+        }
+    }
+}
+
+fn summarize_prompt() -> String {
+    let mut document = String::from(REPORT_TITLE);
+    for section in 1..=SUMMARIZE_SECTIONS {
+        document.push_str(&report_section(section));
+    }
+    document.push_str("Summarize the report above in three sentences.");
+    document
+}
+
+pub const REPORT_TITLE: &str = "Quarterly engineering report\n\n";
+
+/// One section of a synthetic engineering report, about 120 tokens. The
+/// numbers vary with `section`, so no two sections are identical.
+pub fn report_section(section: u32) -> String {
+    let topic = TOPICS[section as usize % TOPICS.len()];
+    let outcome = OUTCOMES[section as usize % OUTCOMES.len()];
+    format!(
+        "Section {section}. The {topic} workstream {outcome}. Over the quarter the team shipped \
+         {changes} changes, closed {tickets} support tickets and spent roughly {hours} engineer hours \
+         on maintenance. The main risk carried into next quarter is that the {topic} platform still \
+         depends on configuration that is edited by hand, which caused {incidents} incidents this \
+         quarter. The proposed follow-up is to automate those edits and to review the on-call rotation \
+         before the next release.\n\n",
+        changes = 10 + section * 7 % 40,
+        tickets = 3 + section * 11 % 25,
+        hours = 40 + section * 13 % 120,
+        incidents = section % 4,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize_preset() {
+        let mut config = BenchmarkConfig::default();
+        Preset::Summarize.apply(&mut config);
+        assert_eq!(config.max_tokens, 128);
+        assert!(config.unique_prompts);
+        assert_eq!(config.options["num_ctx"], 12288);
+
+        // Roughly 8k tokens at ~4 characters per token, ending with the task
+        let chars = config.prompt.len();
+        assert!((28_000..40_000).contains(&chars), "{} chars", chars);
+        assert!(config.prompt.ends_with("three sentences."));
+    }
+
+    #[test]
+    fn test_summarize_keeps_explicit_num_ctx() {
+        let mut config = BenchmarkConfig::default();
+        config.options.insert("num_ctx".to_string(), 32768.into());
+        Preset::Summarize.apply(&mut config);
+        assert_eq!(config.options["num_ctx"], 32768);
+    }
+}
@@ -13,6 +13,8 @@ pub trait ProgressReporter: Send {
     fn update_progress(&mut self, model: &str, current: u32, total: u32);
     fn complete_model(&mut self, model: &str);
     fn print_info(&mut self, message: &str);
+    /// A `--pull-missing` download status; `total` is 0 for steps without a size.
+    fn pull_progress(&mut self, model: &str, status: &str, completed: u64, total: u64);
     #[allow(dead_code)]
     fn print_error(&mut self, message: &str);
 }
@@ -33,11 +35,7 @@ impl TerminalProgress {
             return;
         }
         
-        let percentage = if total > 0 {
-            (current * 100) / total
-        } else {
-            0
-        };
+        let percentage = (current * 100).checked_div(total).unwrap_or(0);
         
         let filled = if total > 0 {
             (PROGRESS_BAR_WIDTH * current as usize) / total as usize
@@ -105,6 +103,56 @@ impl ProgressReporter for TerminalProgress {
         }
     }
     
+    fn pull_progress(&mut self, model: &str, status: &str, completed: u64, total: u64) {
+        if self.quiet {
+            return;
+        }
+        
+        execute!(
+            io::stdout(),
+            cursor::MoveToColumn(0),
+            Clear(ClearType::CurrentLine),
+            Print(format!("Pulling {}: {}{}", model, status, pull_detail(completed, total)))
+        ).ok();
+        if status == "success" {
+            println!();
+        }
+        io::stdout().flush().ok();
+    }
+    
+    fn print_error(&mut self, message: &str) {
+        eprintln!("{}", message);
+    }
+}
+
+/// Line-oriented progress for CI logs and other non-TTY outputs: one line
+/// per event, no cursor movement or colors.
+pub struct PlainProgress;
+
+impl ProgressReporter for PlainProgress {
+    fn start_model(&mut self, model: &str, current: u32, total: u32) {
+        println!("[{}/{}] Testing {}", current, total, model);
+    }
+    
+    fn update_progress(&mut self, model: &str, current: u32, total: u32) {
+        println!("  {} iteration {}/{}", model, current, total);
+    }
+    
+    fn complete_model(&mut self, model: &str) {
+        println!("  {} complete", model);
+    }
+    
+    fn print_info(&mut self, message: &str) {
+        println!("{}", message);
+    }
+    
+    fn pull_progress(&mut self, model: &str, status: &str, completed: u64, total: u64) {
+        // Every tenth percent is plenty for a log
+        if total == 0 || (completed * 100 / total) % 10 == 0 {
+            println!("  pulling {}: {}{}", model, status, pull_detail(completed, total));
+        }
+    }
+    
     fn print_error(&mut self, message: &str) {
         eprintln!("{}", message);
     }
@@ -117,11 +165,25 @@ impl ProgressReporter for QuietProgress {
     fn update_progress(&mut self, _model: &str, _current: u32, _total: u32) {}
     fn complete_model(&mut self, _model: &str) {}
     fn print_info(&mut self, _message: &str) {}
+    fn pull_progress(&mut self, _model: &str, _status: &str, _completed: u64, _total: u64) {}
     fn print_error(&mut self, message: &str) {
         eprintln!("{}", message);
     }
 }
 
+/// ` 45% (1.2/2.7 GB)` for steps with a size, nothing otherwise.
+fn pull_detail(completed: u64, total: u64) -> String {
+    if total == 0 {
+        return String::new();
+    }
+    format!(
+        " {}% ({:.1}/{:.1} GB)",
+        completed * 100 / total,
+        completed as f64 / 1e9,
+        total as f64 / 1e9
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,6 +198,16 @@ mod tests {
         assert!(quiet_progress.quiet);
     }
     
+    #[test]
+    fn test_plain_progress() {
+        let mut progress = PlainProgress;
+        // These should not panic
+        progress.start_model("test", 1, 1);
+        progress.update_progress("test", 1, 1);
+        progress.complete_model("test");
+        progress.print_info("info");
+    }
+    
     #[test]
     fn test_quiet_progress() {
         let mut progress = QuietProgress;
@@ -146,4 +218,10 @@ mod tests {
         progress.print_info("info");
         progress.print_error("error");
     }
+    
+    #[test]
+    fn test_pull_detail() {
+        assert_eq!(pull_detail(0, 0), "");
+        assert_eq!(pull_detail(1_200_000_000, 2_700_000_000), " 44% (1.2/2.7 GB)");
+    }
 }
\ No newline at end of file
@@ -0,0 +1,166 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::QUEUE_ROUNDS;
+use crate::effect::Distribution;
+use crate::net::{probe_client, ProbeTarget};
+use crate::types::{BenchmarkConfig, ModelSummary};
+
+/// TTFT of the requests that arrived at one position of a staggered start.
+#[derive(Debug, Clone, Serialize)]
+pub struct PositionStats {
+    /// 1 for the request that found the server idle
+    pub position: u32,
+    pub requests: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttft_ms: Option<Distribution>,
+    /// Median TTFT beyond the first position's: time spent waiting behind
+    /// earlier arrivals
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub queued_ms: Option<f64>,
+}
+
+/// `--queue-depth`: how one model's TTFT grows with a request's place in
+/// a queue of arrivals `stagger_ms` apart.
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueReport {
+    pub model: String,
+    /// Server of a multi-server run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    pub depth: u32,
+    pub stagger_ms: u64,
+    pub positions: Vec<PositionStats>,
+    /// Least-squares slope of TTFT over position: what each request ahead
+    /// in the queue adds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ms_per_position: Option<f64>,
+    /// Requests that failed or whose task died, left out of the positions
+    pub failed: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_error: Option<String>,
+}
+
+/// Per model and server, starts `depth` requests `stagger_ms` apart,
+/// `QUEUE_ROUNDS` times, and times each one's first token. Runs after the
+/// benchmark, so models are loaded and the first arrival finds the server
+/// idle. Streams, so TTFT includes the wait in the server's queue.
+pub async fn measure(
+    targets: &[ProbeTarget],
+    config: &BenchmarkConfig,
+    summaries: &[ModelSummary],
+    depth: u32,
+    stagger_ms: u64,
+    cancel: &CancellationToken,
+) -> Vec<QueueReport> {
+    let queued = BenchmarkConfig {
+        stream: true,
+        history_messages: 0,
+        ..config.clone()
+    };
+
+    let mut reports = Vec::new();
+    for summary in summaries.iter().filter(|s| s.variant.is_none() && s.success_rate > 0.0) {
+        let Some(client) = probe_client(targets, summary.server.as_deref()) else {
+            continue;
+        };
+        let model = &summary.model;
+        let (mut samples, mut errors) = (Vec::new(), Vec::new());
+        for round in 0..QUEUE_ROUNDS {
+            if cancel.is_cancelled() {
+                return reports;
+            }
+            let mut in_flight = JoinSet::new();
+            for position in 1..=depth {
+                let (client, config, model) = (client.clone(), queued.clone(), model.clone());
+                // Numbered prompts keep the prompt cache from favouring later arrivals
+                let prompt = format!("[q{}-{}] {}", round, position, config.iteration_prompt(&model, 0));
+                in_flight.spawn(async move {
+                    tokio::time::sleep(Duration::from_millis(stagger_ms * (position - 1) as u64)).await;
+                    (position, client.generate(&model, &prompt, &config).await)
+                });
+            }
+            loop {
+                // Dropping the set aborts the requests still in flight
+                let joined = tokio::select! {
+                    joined = in_flight.join_next() => joined,
+                    _ = cancel.cancelled() => return reports,
+                };
+                match joined {
+                    None => break,
+                    Some(Ok((position, Ok(result)))) if result.success => samples.push((position, result.time_to_first_token_ms as f64)),
+                    Some(Ok((_, Ok(result)))) => errors.push(result.error.unwrap_or_else(|| "request failed".to_string())),
+                    Some(Ok((_, Err(e)))) => errors.push(e.to_string()),
+                    Some(Err(e)) => errors.push(format!("probe task failed: {}", e)),
+                }
+            }
+        }
+        let mut report = build_report(model, depth, stagger_ms, &samples);
+        report.server = summary.server.clone();
+        report.failed = errors.len() as u64;
+        report.first_error = errors.into_iter().next();
+        reports.push(report);
+    }
+    reports
+}
+
+/// `samples` are (position, TTFT in ms) of the successful requests.
+fn build_report(model: &str, depth: u32, stagger_ms: u64, samples: &[(u32, f64)]) -> QueueReport {
+    let mut positions: Vec<PositionStats> = (1..=depth)
+        .map(|position| {
+            let ttfts: Vec<f64> = samples.iter().filter(|(p, _)| *p == position).map(|(_, ttft)| *ttft).collect();
+            PositionStats { position, requests: ttfts.len() as u64, ttft_ms: Distribution::of(&ttfts), queued_ms: None }
+        })
+        .collect();
+    if let Some(first) = positions[0].ttft_ms.map(|d| d.median) {
+        for stats in &mut positions {
+            stats.queued_ms = stats.ttft_ms.map(|d| d.median - first);
+        }
+    }
+
+    QueueReport {
+        model: model.to_string(),
+        server: None,
+        depth,
+        stagger_ms,
+        positions,
+        ms_per_position: slope(samples),
+        failed: 0,
+        first_error: None,
+    }
+}
+
+fn slope(samples: &[(u32, f64)]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let n = samples.len() as f64;
+    let mean_x = samples.iter().map(|(p, _)| *p as f64).sum::<f64>() / n;
+    let mean_y = samples.iter().map(|(_, ttft)| ttft).sum::<f64>() / n;
+    let spread: f64 = samples.iter().map(|(p, _)| (*p as f64 - mean_x).powi(2)).sum();
+    if spread == 0.0 {
+        return None;
+    }
+    Some(samples.iter().map(|(p, ttft)| (*p as f64 - mean_x) * (ttft - mean_y)).sum::<f64>() / spread)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_report() {
+        let samples = [(1, 100.0), (2, 300.0), (3, 500.0), (1, 120.0), (2, 280.0), (3, 520.0)];
+        let report = build_report("m", 4, 100, &samples);
+
+        let queued: Vec<Option<f64>> = report.positions.iter().map(|p| p.queued_ms).collect();
+        assert_eq!(queued, vec![Some(0.0), Some(180.0), Some(400.0), None]);
+        assert_eq!(report.positions[3].requests, 0);
+        assert!((report.ms_per_position.unwrap() - 200.0).abs() < 1e-9);
+
+        assert_eq!(build_report("m", 2, 100, &[(1, 100.0)]).ms_per_position, None);
+    }
+}
@@ -0,0 +1,214 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use clap::ValueEnum;
+use reqwest::Url;
+
+use crate::exclusions::{ExcludedModel, Exclusions};
+use crate::types::{BenchmarkResult, ModelSummary, ServerInfo};
+
+const REDACTED: &str = "[redacted]";
+
+/// Categories of potentially sensitive data that can be stripped from reports.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum RedactField {
+    /// Prompt text and model responses
+    Prompts,
+    /// The Ollama server's host name or address
+    Hostname,
+    /// Filesystem paths in error messages and server settings
+    Paths,
+}
+
+/// Applies the selected redactions to everything that ends up in a report,
+/// so every output format sees the same scrubbed data.
+pub struct Redactor {
+    fields: Vec<RedactField>,
+    hosts: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(fields: &[RedactField], server_urls: &[String]) -> Self {
+        Self {
+            fields: fields.to_vec(),
+            hosts: server_urls
+                .iter()
+                .filter_map(|url| Url::parse(url).ok()?.host_str().map(str::to_string))
+                .collect(),
+        }
+    }
+
+    fn enabled(&self, field: RedactField) -> bool {
+        self.fields.contains(&field)
+    }
+
+    /// Iterations scrubbed for reports and exports; without `--redact` they
+    /// are borrowed as they are, so a long run is not copied.
+    pub fn results<'a>(&self, results: &'a [BenchmarkResult]) -> Cow<'a, [BenchmarkResult]> {
+        if self.fields.is_empty() {
+            return Cow::Borrowed(results);
+        }
+        results
+            .iter()
+            .cloned()
+            .map(|mut result| {
+                if self.enabled(RedactField::Prompts) {
+                    result.prompt = REDACTED.to_string();
+                    result.response = None;
+                }
+                result.error = result.error.map(|error| self.text(&error));
+                result.server = result.server.map(|server| self.text(&server));
+                if self.enabled(RedactField::Hostname) {
+                    result.remote_addr = None;
+                }
+                result
+            })
+            .collect()
+    }
+
+    pub fn server(&self, server: &ServerInfo) -> ServerInfo {
+        let mut server = server.clone();
+        server.url = self.text(&server.url);
+        if self.enabled(RedactField::Hostname) {
+            server.addresses.clear();
+        }
+        for value in server.env.values_mut() {
+            *value = self.text(value);
+        }
+        server
+    }
+
+    /// Summaries with their server names, and with `Prompts` the responses
+    /// of their fastest and slowest iterations, scrubbed.
+    pub fn summaries(&self, summaries: &[ModelSummary]) -> Vec<ModelSummary> {
+        summaries
+            .iter()
+            .cloned()
+            .map(|mut summary| {
+                summary.server = summary.server.map(|server| self.text(&server));
+                if self.enabled(RedactField::Prompts) {
+                    for detail in [&mut summary.fastest, &mut summary.slowest].into_iter().flatten() {
+                        detail.response = None;
+                    }
+                }
+                summary
+            })
+            .collect()
+    }
+
+    /// Skipped and failed models, with server names and reasons scrubbed.
+    pub fn exclusions(&self, exclusions: &Exclusions) -> Exclusions {
+        let scrub = |models: &[ExcludedModel]| -> Vec<ExcludedModel> {
+            models
+                .iter()
+                .map(|model| ExcludedModel {
+                    model: model.model.clone(),
+                    server: model.server.as_ref().map(|server| self.text(server)),
+                    variant: model.variant.clone(),
+                    reason: self.text(&model.reason),
+                })
+                .collect()
+        };
+        Exclusions { skipped: scrub(&exclusions.skipped), failed: scrub(&exclusions.failed) }
+    }
+
+    /// A prompt, hidden when prompts are redacted.
+    pub fn prompt(&self, prompt: &str) -> String {
+        if self.enabled(RedactField::Prompts) { REDACTED.to_string() } else { prompt.to_string() }
+    }
+
+    /// Per-model prompt mapping, with the text hidden when prompts are redacted.
+    pub fn prompts(&self, prompts: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+        prompts
+            .iter()
+            .map(|(model, prompt)| {
+                (model.clone(), self.prompt(prompt))
+            })
+            .collect()
+    }
+
+    /// Scrubs free-form text such as error messages.
+    pub fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        if self.enabled(RedactField::Hostname) {
+            // Several servers stay distinguishable, just not identifiable
+            for (i, host) in self.hosts.iter().enumerate() {
+                let replacement = if self.hosts.len() > 1 { format!("[redacted-{}]", i + 1) } else { REDACTED.to_string() };
+                text = text.replace(host.as_str(), &replacement);
+            }
+        }
+        if self.enabled(RedactField::Paths) {
+            text = text
+                .split(' ')
+                .map(|word| if looks_like_path(word) { REDACTED } else { word })
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+        text
+    }
+}
+
+fn looks_like_path(word: &str) -> bool {
+    let word = word.trim_matches(|c: char| matches!(c, '\'' | '"' | '(' | ')' | ',' | ':'));
+    let windows_drive = word.len() > 2
+        && word.as_bytes()[0].is_ascii_alphabetic()
+        && word[1..].starts_with(":\\");
+    (word.starts_with('/') && word.len() > 1) || word.starts_with("~/") || windows_drive
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_redact_results() {
+        let redactor = Redactor::new(&[RedactField::Prompts, RedactField::Hostname], &["http://gpu-box-7.corp:11434".to_string()]);
+        let mut result = BenchmarkResult::failed(
+            "m",
+            "secret prompt",
+            Utc::now(),
+            0,
+            "error sending request for url (http://gpu-box-7.corp:11434/api/generate)".to_string(),
+        );
+        result.response = Some("secret answer".to_string());
+
+        let results = [result];
+        let redacted = &redactor.results(&results)[0];
+        assert_eq!(redacted.prompt, REDACTED);
+        assert!(redacted.response.is_none());
+        assert!(!redacted.error.as_ref().unwrap().contains("gpu-box-7"));
+
+        let prompts = BTreeMap::from([("codellama".to_string(), "internal code".to_string())]);
+        assert_eq!(redactor.prompts(&prompts)["codellama"], REDACTED);
+
+        assert!(matches!(Redactor::new(&[], &[]).results(&results), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_redact_several_servers() {
+        let urls = ["http://desktop.lan:11434".to_string(), "http://mac-mini.lan:11434".to_string()];
+        let redactor = Redactor::new(&[RedactField::Hostname], &urls);
+        let summaries = [
+            ModelSummary { server: Some("desktop.lan:11434".to_string()), ..Default::default() },
+            ModelSummary { server: Some("mac-mini.lan:11434".to_string()), ..Default::default() },
+        ];
+
+        let servers: Vec<Option<String>> = redactor.summaries(&summaries).into_iter().map(|s| s.server).collect();
+        assert_eq!(servers, vec![Some("[redacted-1]:11434".to_string()), Some("[redacted-2]:11434".to_string())]);
+    }
+
+    #[test]
+    fn test_redact_server_paths() {
+        let redactor = Redactor::new(&[RedactField::Paths], &["http://localhost:11434".to_string()]);
+        let mut server = ServerInfo { url: "http://localhost:11434".to_string(), ..Default::default() };
+        server.env.insert("OLLAMA_MODELS".to_string(), "/srv/acme/models".to_string());
+        server.env.insert("OLLAMA_NUM_PARALLEL".to_string(), "4".to_string());
+
+        let redacted = redactor.server(&server);
+        assert_eq!(redacted.url, "http://localhost:11434");
+        assert_eq!(redacted.env["OLLAMA_MODELS"], REDACTED);
+        assert_eq!(redacted.env["OLLAMA_NUM_PARALLEL"], "4");
+        assert!(looks_like_path("C:\\Users\\me"));
+        assert!(!looks_like_path("/"));
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::BTreeSet;
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{BenchmarkError, Result};
+use crate::gpu;
+use crate::types::ModelSummary;
+
+/// Typical speed of one model on one kind of hardware.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReferenceResult {
+    pub model: String,
+    /// e.g. `RTX 4090` or `Apple M2 Pro`
+    pub hardware: String,
+    pub tokens_per_second: f64,
+    #[serde(default)]
+    pub ttft_ms: Option<f64>,
+}
+
+/// Results read from `--reference-file`. None are bundled: a number with
+/// no known origin is no yardstick, so each file names its own source.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReferenceDb {
+    /// Where the numbers come from, e.g. a URL or "our CI fleet, 2026-09"
+    pub source: String,
+    #[serde(default)]
+    pub updated: Option<String>,
+    pub results: Vec<ReferenceResult>,
+}
+
+/// A benchmarked model next to its reference on the matched hardware.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReferenceComparison {
+    pub model: String,
+    pub hardware: String,
+    pub tokens_per_second: f64,
+    pub reference_tokens_per_second: f64,
+    /// Negative means slower than typical
+    pub difference_percent: f64,
+}
+
+/// `--compare-reference`: the hardware matched and how each model compares.
+#[derive(Debug, Clone)]
+pub struct ReferenceMatch {
+    pub hardware: String,
+    /// The file's source, with its update date when it has one
+    pub source: String,
+    pub comparisons: Vec<ReferenceComparison>,
+}
+
+impl ReferenceDb {
+    pub fn load(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BenchmarkError::IoError(format!("{}: {}", path, e)))?;
+        serde_json::from_str(&content).map_err(|e| BenchmarkError::ParseError(format!("{}: {}", path, e)))
+    }
+
+    pub fn hardware(&self) -> BTreeSet<&str> {
+        self.results.iter().map(|r| r.hardware.as_str()).collect()
+    }
+
+    /// The listed hardware `name` refers to, matching whole words. A
+    /// detected `NVIDIA GeForce RTX 4090` finds `RTX 4090`, the most
+    /// specific listing whose words it contains, so `Apple M2 Pro` is not
+    /// mistaken for `Apple M2`. A shorthand such as `M2 Pro` must pick out
+    /// a single listing: `Apple` alone matches none.
+    pub fn find_hardware(&self, name: &str) -> Option<&str> {
+        let query = words(name);
+        let hardware = self.hardware();
+
+        let contained: Vec<&str> = hardware.iter().copied().filter(|h| words(h).is_subset(&query)).collect();
+        if let Some(most) = contained.iter().map(|h| words(h).len()).max() {
+            let best: Vec<&str> = contained.into_iter().filter(|h| words(h).len() == most).collect();
+            return unique(best);
+        }
+        unique(hardware.into_iter().filter(|h| query.is_subset(&words(h))).collect())
+    }
+
+    /// Reference results for `hardware` matching the benchmarked models;
+    /// `llama3` and `llama3:latest` are treated as the same model.
+    pub fn compare(&self, hardware: &str, summaries: &[ModelSummary]) -> ReferenceMatch {
+        let comparisons: Vec<ReferenceComparison> = summaries
+            .iter()
+            .filter(|s| s.success_rate > 0.0 && s.variant.is_none())
+            .filter_map(|summary| {
+                let reference = self
+                    .results
+                    .iter()
+                    .find(|r| r.hardware == hardware && same_model(&r.model, &summary.model))?;
+                Some(ReferenceComparison {
+                    model: summary.model.clone(),
+                    hardware: hardware.to_string(),
+                    tokens_per_second: summary.avg_tokens_per_second,
+                    reference_tokens_per_second: reference.tokens_per_second,
+                    difference_percent: (summary.avg_tokens_per_second - reference.tokens_per_second)
+                        / reference.tokens_per_second
+                        * 100.0,
+                })
+            })
+            .collect();
+        let source = match &self.updated {
+            Some(updated) => format!("{}, updated {}", self.source, updated),
+            None => self.source.clone(),
+        };
+        ReferenceMatch { hardware: hardware.to_string(), source, comparisons }
+    }
+}
+
+fn unique(matches: Vec<&str>) -> Option<&str> {
+    match matches[..] {
+        [only] => Some(only),
+        _ => None,
+    }
+}
+
+fn words(name: &str) -> BTreeSet<String> {
+    name.split_whitespace().map(str::to_lowercase).collect()
+}
+
+fn same_model(a: &str, b: &str) -> bool {
+    let normalize = |name: &str| name.strip_suffix(":latest").unwrap_or(name).to_lowercase();
+    normalize(a) == normalize(b)
+}
+
+/// The first NVIDIA GPU, else the CPU brand on macOS (`Apple M2 Pro`),
+/// where the GPU is part of the chip. Describes this machine, so it only
+/// stands for a server on loopback.
+pub fn detect_hardware() -> Option<String> {
+    if let Some(gpu) = gpu::list_gpus().into_iter().next() {
+        return Some(gpu.name);
+    }
+    let output = Command::new("sysctl").args(["-n", "machdep.cpu.brand_string"]).output().ok()?;
+    let brand = String::from_utf8(output.stdout).ok()?;
+    let brand = brand.trim();
+    (output.status.success() && !brand.is_empty()).then(|| brand.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db(hardware: &[&str]) -> ReferenceDb {
+        let results = hardware
+            .iter()
+            .map(|hardware| ReferenceResult {
+                model: "llama3:8b".to_string(),
+                hardware: hardware.to_string(),
+                tokens_per_second: 40.0,
+                ttft_ms: None,
+            })
+            .collect();
+        ReferenceDb { source: "test".to_string(), updated: None, results }
+    }
+
+    #[test]
+    fn test_find_hardware() {
+        let db = db(&["RTX 4090", "Apple M1", "Apple M2", "Apple M2 Pro", "Apple M3 Max"]);
+        assert_eq!(db.find_hardware("NVIDIA GeForce RTX 4090"), Some("RTX 4090"));
+        assert_eq!(db.find_hardware("Apple M2 Pro"), Some("Apple M2 Pro"));
+        assert_eq!(db.find_hardware("m2 pro"), Some("Apple M2 Pro"));
+        assert_eq!(db.find_hardware("Apple M1"), Some("Apple M1"));
+        assert_eq!(db.find_hardware("Intel(R) Core(TM) i7"), None);
+        // Several listings share the word, and none is named in full
+        assert_eq!(db.find_hardware("Apple"), None);
+        assert_eq!(db.find_hardware("M2"), None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let db = db(&["Apple M2 Pro"]);
+        let summary = |model: &str, speed: f64| ModelSummary {
+            model: model.to_string(),
+            success_rate: 1.0,
+            avg_tokens_per_second: speed,
+            ..Default::default()
+        };
+
+        let matched = db.compare("Apple M2 Pro", &[summary("llama3:8B", 34.0), summary("mistral:7b", 50.0)]);
+        assert_eq!(matched.comparisons.len(), 1);
+        assert_eq!(matched.comparisons[0].difference_percent, -15.0);
+        assert!(db.compare("RTX 4090", &[summary("llama3:8b", 34.0)]).comparisons.is_empty());
+    }
+}
@@ -0,0 +1,457 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::HISTORY_BUSY_TIMEOUT_MS;
+use crate::csvmeta::RunMeta;
+use crate::error::{BenchmarkError, Result};
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// Layout of the tables below, kept in SQLite's `user_version`. Bump it
+/// with a migration in `RunDb::init` when a table changes.
+const SCHEMA_VERSION: i64 = 2;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    run_id TEXT NOT NULL UNIQUE,
+    recorded_at TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    server TEXT,
+    config TEXT NOT NULL,
+    experiment TEXT
+);
+CREATE TABLE IF NOT EXISTS summaries (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    model TEXT NOT NULL,
+    tokens_per_second REAL NOT NULL,
+    ttft_ms REAL NOT NULL,
+    success_rate REAL NOT NULL,
+    summary TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS iterations (
+    run INTEGER NOT NULL REFERENCES runs(id),
+    model TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    success INTEGER NOT NULL,
+    tokens_per_second REAL NOT NULL,
+    ttft_ms INTEGER NOT NULL,
+    result TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS summaries_model ON summaries(model);
+CREATE INDEX IF NOT EXISTS iterations_run ON iterations(run);
+CREATE INDEX IF NOT EXISTS runs_experiment ON runs(experiment);
+";
+
+/// `~/.ollama-bench/history.db`.
+pub fn default_path() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".ollama-bench").join("history.db"))
+}
+
+/// `--since` as a date (`2026-10-01`, midnight UTC) or an RFC 3339 time.
+pub fn parse_since(text: &str) -> std::result::Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc());
+    }
+    DateTime::parse_from_rfc3339(text)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| format!("invalid date '{}' (use e.g. 2026-10-01)", text))
+}
+
+/// Fixed-width UTC timestamps, so text order is time order in SQL.
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// One recorded run.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunRecord {
+    pub run_id: String,
+    pub recorded_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// `--experiment` group the run was recorded under
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub experiment: Option<String>,
+    pub config: Value,
+    pub models: Vec<ModelSummary>,
+}
+
+/// Which runs `history list` shows.
+#[derive(Debug, Clone, Default)]
+pub struct RunFilter {
+    /// Runs that benchmarked this model
+    pub model: Option<String>,
+    /// Runs recorded under this `--experiment`
+    pub experiment: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+/// Which runs `history vacuum` deletes; with neither limit set, none.
+#[derive(Debug, Clone, Default)]
+pub struct Retention {
+    /// Delete runs recorded before this time
+    pub before: Option<DateTime<Utc>>,
+    /// Delete all but the newest this many runs
+    pub keep_runs: Option<usize>,
+}
+
+/// Every run appended to a local SQLite file, so results outlive the
+/// terminal. Full summaries and iterations are kept as JSON; the columns
+/// beside them are for filtering and ad-hoc SQL.
+///
+/// Several processes may have the file open at once (scheduled runs,
+/// manual runs, `history` readers): the database is in WAL mode, so
+/// readers never block the writer, and each run is written in one
+/// transaction, so readers see either all of it or none.
+pub struct RunDb {
+    conn: Connection,
+}
+
+impl RunDb {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)?;
+        // Wait for a concurrent writer instead of failing with SQLITE_BUSY
+        conn.busy_timeout(Duration::from_millis(HISTORY_BUSY_TIMEOUT_MS))?;
+        conn.query_row("PRAGMA journal_mode = WAL", [], |row| row.get::<_, String>(0))?;
+        conn.execute_batch("PRAGMA synchronous = NORMAL;")?;
+        Self::init(conn)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version > SCHEMA_VERSION {
+            return Err(BenchmarkError::ConfigError(format!(
+                "the history database has schema {}, newer than this ollama-bench understands ({}); upgrade ollama-bench",
+                version, SCHEMA_VERSION
+            )));
+        }
+        // Files from before versioning have the version 1 layout
+        let has_runs = conn.query_row("SELECT count(*) FROM sqlite_master WHERE type = 'table' AND name = 'runs'", [], |row| {
+            row.get::<_, i64>(0)
+        })? > 0;
+        let migration = if has_runs && version < 2 { "ALTER TABLE runs ADD COLUMN experiment TEXT;" } else { "" };
+        conn.execute_batch(&format!("BEGIN; {} {} PRAGMA user_version = {}; COMMIT;", migration, SCHEMA, SCHEMA_VERSION))?;
+        Ok(Self { conn })
+    }
+
+    /// Appends a finished (or cancelled) run.
+    pub fn record(
+        &mut self,
+        meta: &RunMeta,
+        server: Option<&str>,
+        experiment: Option<&str>,
+        duration: Duration,
+        summaries: &[ModelSummary],
+        results: &[BenchmarkResult],
+    ) -> Result<()> {
+        // Take the write lock up front; upgrading a read transaction can fail
+        // with SQLITE_BUSY regardless of the busy timeout
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        tx.execute(
+            "INSERT INTO runs (run_id, recorded_at, duration_ms, server, config, experiment) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![meta.run_id, timestamp(Utc::now()), duration.as_millis() as i64, server, meta.config.to_string(), experiment],
+        )?;
+        let run = tx.last_insert_rowid();
+
+        for summary in summaries {
+            tx.execute(
+                "INSERT INTO summaries (run, model, tokens_per_second, ttft_ms, success_rate, summary) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    run,
+                    summary.model,
+                    summary.avg_tokens_per_second,
+                    summary.avg_ttft_ms,
+                    summary.success_rate,
+                    serde_json::to_string(summary)?
+                ],
+            )?;
+        }
+        for result in results {
+            tx.execute(
+                "INSERT INTO iterations (run, model, started_at, success, tokens_per_second, ttft_ms, result) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    run,
+                    result.model,
+                    timestamp(result.timestamp),
+                    result.success,
+                    result.tokens_per_second,
+                    result.time_to_first_token_ms as i64,
+                    serde_json::to_string(result)?
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Matching runs, newest first.
+    pub fn list(&self, filter: &RunFilter) -> Result<Vec<RunRecord>> {
+        let limit = filter.limit.map_or(-1, |limit| limit as i64);
+        let runs = self.query_runs(
+            "WHERE (?1 IS NULL OR recorded_at >= ?1)
+               AND (?2 IS NULL OR EXISTS (SELECT 1 FROM summaries WHERE run = runs.id AND model = ?2))
+               AND (?3 IS NULL OR experiment = ?3)
+             ORDER BY recorded_at DESC, id DESC
+             LIMIT ?4",
+            params![filter.since.map(timestamp), filter.model, filter.experiment, limit],
+        )?;
+        Ok(runs.into_iter().map(|(_, run)| run).collect())
+    }
+
+    /// The run whose id is or starts with `run_id`, with its iterations.
+    pub fn show(&self, run_id: &str) -> Result<(RunRecord, Vec<BenchmarkResult>)> {
+        let mut matching = self.query_runs("WHERE substr(run_id, 1, length(?1)) = ?1", params![run_id])?;
+        let (id, run) = match matching.len() {
+            1 => matching.remove(0),
+            0 => return Err(BenchmarkError::ConfigError(format!("no recorded run '{}'", run_id))),
+            n => return Err(BenchmarkError::ConfigError(format!("'{}' matches {} runs; give more of the id", run_id, n))),
+        };
+        let iterations = self.json_rows("SELECT result FROM iterations WHERE run = ?1 ORDER BY rowid", id)?;
+        Ok((run, iterations))
+    }
+
+    /// The runs recorded under `experiment`, oldest first, with their
+    /// iterations.
+    pub fn experiment(&self, experiment: &str) -> Result<Vec<(RunRecord, Vec<BenchmarkResult>)>> {
+        let runs = self.query_runs("WHERE experiment = ?1 ORDER BY recorded_at, id", params![experiment])?;
+        if runs.is_empty() {
+            return Err(BenchmarkError::ConfigError(format!("no runs recorded under experiment '{}'", experiment)));
+        }
+        runs.into_iter()
+            .map(|(id, run)| Ok((run, self.json_rows("SELECT result FROM iterations WHERE run = ?1 ORDER BY rowid", id)?)))
+            .collect()
+    }
+
+    /// Runs selected by `clause` (the SQL after `FROM runs`), with their
+    /// summaries, keyed by row id.
+    fn query_runs(&self, clause: &str, params: impl rusqlite::Params) -> Result<Vec<(i64, RunRecord)>> {
+        let mut statement = self
+            .conn
+            .prepare(&format!("SELECT id, run_id, recorded_at, duration_ms, server, config, experiment FROM runs {}", clause))?;
+        let rows = statement.query_map(params, |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get(4)?,
+                row.get::<_, String>(5)?,
+                row.get(6)?,
+            ))
+        })?;
+
+        let mut runs = Vec::new();
+        for row in rows {
+            let (id, run_id, recorded_at, duration_ms, server, config, experiment) = row?;
+            let run = RunRecord {
+                run_id,
+                recorded_at: DateTime::parse_from_rfc3339(&recorded_at)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|e| BenchmarkError::ParseError(e.to_string()))?,
+                duration_ms: duration_ms as u64,
+                server,
+                experiment,
+                config: serde_json::from_str(&config)?,
+                models: self.json_rows("SELECT summary FROM summaries WHERE run = ?1 ORDER BY rowid", id)?,
+            };
+            runs.push((id, run));
+        }
+        Ok(runs)
+    }
+
+    /// Deletes the runs `retention` selects, with their summaries and
+    /// iterations, in one transaction. Returns how many runs were deleted.
+    pub fn prune(&mut self, retention: &Retention) -> Result<usize> {
+        let tx = self.conn.transaction_with_behavior(TransactionBehavior::Immediate)?;
+        let keep = retention.keep_runs.map_or(-1, |n| n as i64);
+        tx.execute_batch("CREATE TEMP TABLE IF NOT EXISTS doomed (id INTEGER PRIMARY KEY); DELETE FROM doomed;")?;
+        tx.execute(
+            "INSERT INTO doomed SELECT id FROM runs
+             WHERE (?1 IS NOT NULL AND recorded_at < ?1)
+                OR (?2 >= 0 AND id NOT IN (SELECT id FROM runs ORDER BY recorded_at DESC, id DESC LIMIT ?2))",
+            params![retention.before.map(timestamp), keep],
+        )?;
+        tx.execute("DELETE FROM iterations WHERE run IN (SELECT id FROM doomed)", [])?;
+        tx.execute("DELETE FROM summaries WHERE run IN (SELECT id FROM doomed)", [])?;
+        let deleted = tx.execute("DELETE FROM runs WHERE id IN (SELECT id FROM doomed)", [])?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Rebuilds the file without the space of deleted rows and folds the
+    /// write-ahead log back into it.
+    pub fn vacuum(&self) -> Result<()> {
+        self.conn.execute_batch("VACUUM;")?;
+        self.conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
+    fn json_rows<T: serde::de::DeserializeOwned>(&self, sql: &str, run: i64) -> Result<Vec<T>> {
+        let mut statement = self.conn.prepare(sql)?;
+        let rows = statement.query_map(params![run], |row| row.get::<_, String>(0))?;
+        let mut values = Vec::new();
+        for row in rows {
+            values.push(serde_json::from_str(&row?)?);
+        }
+        Ok(values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(db: &mut RunDb, run_id: &str, model: &str) {
+        let results = vec![BenchmarkResult::failed(model, "p", Utc::now(), 10, "timeout".to_string())];
+        record_results(db, run_id, None, &results);
+    }
+
+    fn record_results(db: &mut RunDb, run_id: &str, experiment: Option<&str>, results: &[BenchmarkResult]) {
+        let meta = RunMeta { run_id: run_id.to_string(), config: json!({ "iterations": 1 }), recorded: false };
+        let summaries = vec![ModelSummary::from_results(results[0].model.clone(), results)];
+        db.record(&meta, Some("localhost:11434"), experiment, Duration::from_secs(3), &summaries, results).unwrap();
+    }
+
+    #[test]
+    fn test_record_and_list() {
+        let mut db = RunDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        record(&mut db, "20261001T090000Z-0001", "llama2:7b");
+        record(&mut db, "20261002T090000Z-0002", "mistral:7b");
+
+        let all = db.list(&RunFilter::default()).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0].run_id, "20261002T090000Z-0002");
+        assert_eq!(all[0].models[0].model, "mistral:7b");
+
+        let filter = RunFilter { model: Some("llama2:7b".to_string()), ..Default::default() };
+        assert_eq!(db.list(&filter).unwrap().len(), 1);
+        let filter = RunFilter { since: Some(Utc::now() + chrono::Duration::hours(1)), ..Default::default() };
+        assert!(db.list(&filter).unwrap().is_empty());
+        let filter = RunFilter { limit: Some(1), ..Default::default() };
+        assert_eq!(db.list(&filter).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_show_by_prefix() {
+        let mut db = RunDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        record(&mut db, "20261001T090000Z-0001", "llama2:7b");
+        record(&mut db, "20261001T100000Z-0002", "llama2:7b");
+
+        let (run, iterations) = db.show("20261001T09").unwrap();
+        assert_eq!(run.run_id, "20261001T090000Z-0001");
+        assert_eq!(iterations.len(), 1);
+        assert_eq!(iterations[0].error.as_deref(), Some("timeout"));
+        assert!(db.show("20261001").is_err());
+        assert!(db.show("2025").is_err());
+    }
+
+    #[test]
+    fn test_schema_version() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA user_version = 99;").unwrap();
+        assert!(RunDb::init(conn).is_err());
+
+        let db = RunDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        let version: i64 = db.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_version_1() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE runs (id INTEGER PRIMARY KEY, run_id TEXT NOT NULL UNIQUE, recorded_at TEXT NOT NULL,
+                duration_ms INTEGER NOT NULL, server TEXT, config TEXT NOT NULL);
+             INSERT INTO runs (run_id, recorded_at, duration_ms, config) VALUES ('20261001T090000Z-0001', '2026-10-01T09:00:00.000Z', 10, '{}');
+             PRAGMA user_version = 1;",
+        )
+        .unwrap();
+
+        let db = RunDb::init(conn).unwrap();
+        let runs = db.list(&RunFilter::default()).unwrap();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].experiment, None);
+        let version: i64 = db.conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_experiment() {
+        let mut db = RunDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        let run = |speed: f64| vec![BenchmarkResult::ok("llama2:7b", speed), BenchmarkResult::ok("llama2:7b", speed + 1.0)];
+        record_results(&mut db, "20261001T090000Z-0001", Some("undervolt"), &run(20.0));
+        record_results(&mut db, "20261001T100000Z-0002", None, &run(30.0));
+        record_results(&mut db, "20261001T110000Z-0003", Some("undervolt"), &run(22.0));
+
+        let group = db.experiment("undervolt").unwrap();
+        let ids: Vec<&str> = group.iter().map(|(run, _)| run.run_id.as_str()).collect();
+        assert_eq!(ids, ["20261001T090000Z-0001", "20261001T110000Z-0003"]);
+        assert_eq!(group[1].1.len(), 2);
+        assert!(db.experiment("baseline").is_err());
+
+        let filter = RunFilter { experiment: Some("undervolt".to_string()), ..Default::default() };
+        assert_eq!(db.list(&filter).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut db = RunDb::init(Connection::open_in_memory().unwrap()).unwrap();
+        for run_id in ["20261001T090000Z-0001", "20261002T090000Z-0002", "20261003T090000Z-0003"] {
+            record(&mut db, run_id, "llama2:7b");
+        }
+
+        assert_eq!(db.prune(&Retention::default()).unwrap(), 0);
+        assert_eq!(db.prune(&Retention { keep_runs: Some(2), ..Default::default() }).unwrap(), 1);
+        let left: Vec<String> = db.list(&RunFilter::default()).unwrap().into_iter().map(|run| run.run_id).collect();
+        assert_eq!(left, ["20261003T090000Z-0003", "20261002T090000Z-0002"]);
+        let orphans: i64 = db.conn.query_row("SELECT count(*) FROM iterations", [], |row| row.get(0)).unwrap();
+        assert_eq!(orphans, 2);
+
+        let retention = Retention { before: Some(Utc::now() + chrono::Duration::hours(1)), keep_runs: None };
+        assert_eq!(db.prune(&retention).unwrap(), 2);
+        db.vacuum().unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let path = std::env::temp_dir().join(format!("ollama-bench-history-{}.db", std::process::id()));
+        let mut first = RunDb::open(&path).unwrap();
+        let mut second = RunDb::open(&path).unwrap();
+        let mode: String = first.conn.query_row("PRAGMA journal_mode", [], |row| row.get(0)).unwrap();
+        assert_eq!(mode, "wal");
+
+        // A reader mid-transaction does not block the other connection's write
+        let reader = first.conn.transaction().unwrap();
+        let before: i64 = reader.query_row("SELECT count(*) FROM runs", [], |row| row.get(0)).unwrap();
+        record(&mut second, "20261001T090000Z-0001", "llama2:7b");
+        let during: i64 = reader.query_row("SELECT count(*) FROM runs", [], |row| row.get(0)).unwrap();
+        assert_eq!(before, during);
+        reader.commit().unwrap();
+        record(&mut first, "20261001T100000Z-0002", "llama2:7b");
+        assert_eq!(second.list(&RunFilter::default()).unwrap().len(), 2);
+
+        drop((first, second));
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    #[test]
+    fn test_parse_since() {
+        assert_eq!(timestamp(parse_since("2026-10-01").unwrap()), "2026-10-01T00:00:00.000Z");
+        assert!(parse_since("2026-10-01T12:00:00+02:00").is_ok());
+        assert!(parse_since("last week").is_err());
+    }
+}
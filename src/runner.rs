@@ -1,25 +1,114 @@
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
-use std::fs::File;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use tokio_util::sync::CancellationToken;
 
-use crate::cli::{Cli, OutputFormat};
-use crate::types::{BenchmarkConfig, ModelSummary};
+use clap::ValueEnum;
+use crate::cli::{Cli, Command, ConfigAction, HistoryAction, OutputFormat, ProgressMode};
+use crate::types::{BenchmarkConfig, BenchmarkResult, ModelSummary, ServerInfo};
 use crate::error::{Result, BenchmarkError};
-use crate::ollama::OllamaClient;
-use crate::benchmark::{Benchmarker, calculate_winner, calculate_performance_difference};
-use crate::progress::{ProgressReporter, TerminalProgress, QuietProgress};
-use crate::output::{print_results_table, print_results_json, print_results_csv, print_results_markdown};
+use crate::ollama::{self, ClientOptions, ConnectionProfile, OllamaClient};
+use crate::net::{self, IpFamily};
+use crate::config::{get_user_agent, SAMPLE_MARGIN_FRACTION};
+use crate::effect;
+use crate::benchmark::Benchmarker;
+use crate::progress::{ProgressReporter, TerminalProgress, PlainProgress, QuietProgress};
+use crate::golden::{self, GoldenOutcome};
+use crate::gpu;
+use crate::precision::num;
+use crate::host::{self, HostTopology};
+use crate::warmpool;
+use crate::warnings::{WarningKind, Warnings};
+use crate::aggregate;
+use crate::endpoints;
+use crate::exclusions::Exclusions;
+use crate::hedge;
+use crate::batch;
+use crate::history;
+use crate::html;
+use crate::telemetry::GpuSource;
+use crate::template;
+use crate::trend;
+use crate::select;
+use crate::selfcheck;
+use crate::suite;
+use crate::vision;
+use crate::compare;
+use crate::container;
+use crate::context::{self, Budget};
+use crate::csvmeta::{self, RunMeta};
+use crate::doctor;
+use crate::i18n::{t, Msg};
+use crate::plan;
+use crate::prefs::{self, Preferences};
+use crate::integrity;
+use crate::inventory;
+use crate::openmetrics;
+use crate::overhead;
+use crate::paired;
+use crate::pattern;
+use crate::cancel;
+use crate::queue;
+use crate::export::{format_extension, ExportWriter};
+use crate::redact::{RedactField, Redactor};
+use crate::reference::{self, ReferenceDb, ReferenceMatch};
+use crate::rundb::{Retention, RunDb, RunFilter, RunRecord};
+use crate::output::{preview, ReportExtras, print_server_info, print_results_table, print_results_json, results_json, print_baseline_comparison, print_results_csv, print_results_markdown, summary_csv, summary_markdown, print_gpu_placement, print_thread_sweep, print_load_report, print_pattern_report, print_history_sweep, print_context_sweep, print_prefill_report, print_warm_pool_report, print_endpoint_report, print_template_report, print_hedge_report, print_batch_report, print_selection, print_address_breakdown, print_keep_alive_report, print_anomalies, print_telemetry, print_extremes, print_sample_size, print_until_ci, print_completion_histograms, print_aggregate_report, print_installed_models, print_timeline, print_run_list, print_trend, print_reference_report, print_overhead_report, print_cancel_report, print_queue_report, print_paired_report, print_duel_report, print_category_report, print_vision_report, write_raw_csv, write_raw_json};
 
 pub struct BenchmarkRunner {
     cli: Cli,
+    /// Per-host settings from `--hosts-file`
+    profiles: Vec<ConnectionProfile>,
 }
 
 impl BenchmarkRunner {
-    pub fn new(cli: Cli) -> Self {
-        Self { cli }
+    /// Fails when the `--hosts-file` cannot be read or has a bad profile.
+    pub fn new(cli: Cli) -> Result<Self> {
+        let profiles = match &cli.hosts_file {
+            Some(path) => ollama::load_profiles(std::path::Path::new(path))?,
+            None => Vec::new(),
+        };
+        if profiles.len() > 1 && (cli.warm_pool || !cli.idle_probe.is_empty()) {
+            return Err(BenchmarkError::ConfigError("--warm-pool and --idle-probe measure one server; list a single host in --hosts-file".to_string()));
+        }
+        Ok(Self { cli, profiles })
     }
     
-    pub async fn run(&self) -> Result<()> {
+    pub async fn run(&mut self) -> Result<()> {
+        crate::i18n::set_lang(self.cli.lang);
+        crate::precision::set_precision(self.cli.precision);
+        
+        if let Some(path) = &self.cli.verify {
+            return self.verify_report(path);
+        }
+        
+        if self.cli.self_check_format {
+            return self.self_check_format();
+        }
+        
+        match &self.cli.command {
+            Some(Command::List { json }) => return self.run_list(*json).await,
+            Some(Command::Compare { baseline, current, fail_on_regression, json }) => {
+                return self.run_compare(baseline, current, *fail_on_regression, *json);
+            }
+            Some(Command::Doctor { json }) => return self.run_doctor(*json).await,
+            Some(Command::Aggregate { files, json }) => return self.run_aggregate(files, *json),
+            Some(Command::History { action }) => return self.run_history(action),
+            Some(Command::Config { action }) => return self.run_config(action),
+            Some(Command::Duel { model_a, model_b }) => {
+                let (model_a, model_b) = (model_a.clone(), model_b.clone());
+                self.cli.start_duel(&model_a, &model_b).map_err(BenchmarkError::ConfigError)?;
+            }
+            Some(Command::Run) | None => {}
+        }
+        
+        if self.cli.all || self.cli.size_class.is_some() {
+            self.cli.models = self.installed_selection().await?;
+        } else if self.cli.models.iter().any(|model| inventory::is_pattern(model)) {
+            let models = self.expand_model_patterns().await?;
+            self.cli.model_patterns = std::mem::replace(&mut self.cli.models, models);
+        }
+        
         // Validate CLI arguments
         self.cli.validate()
             .map_err(BenchmarkError::ConfigError)?;
@@ -30,171 +119,1347 @@ impl BenchmarkRunner {
         }
         
         // Create configuration
-        let config = BenchmarkConfig {
-            iterations: self.cli.iterations,
+        let mut config = BenchmarkConfig {
+            iterations: self.cli.iteration_cap(),
             prompt: self.cli.get_prompt(),
             temperature: self.cli.temperature,
             max_tokens: self.cli.max_tokens,
             timeout_seconds: self.cli.timeout,
-            ollama_base_url: self.cli.ollama_url.clone(),
+            ollama_base_url: self.server_urls()[0].clone(),
+            seed: self.cli.seed,
+            logprobs: self.cli.logprobs,
+            concurrency: self.cli.concurrency,
+            system: self.cli.system.clone(),
+            model_prompts: self.cli.model_prompt_map(),
+            model_num_ctx: BTreeMap::new(),
+            prompt_suite: match &self.cli.prompts_file {
+                Some(path) => suite::load(path)?,
+                None => Vec::new(),
+            },
+            images: match &self.cli.image_dir {
+                Some(dir) => vision::load_dir(dir, self.cli.samples)?,
+                None => Vec::new(),
+            },
+            image: None,
+            unique_prompts: false,
+            keep_warmup: self.cli.keep_warmup,
+            warmup: self.cli.warmup,
+            trim_outliers: self.cli.trim_outliers,
+            stream: self.cli.stream,
+            eval: self.cli.eval,
+            gpu_filter: self.cli.gpu_filter,
+            num_thread: self.cli.num_thread,
+            thread_sweep: self.cli.sweep_threads.clone(),
+            sweep_order: self.cli.sweep_order,
+            interleave: self.cli.interleave,
+            load_pattern: self.cli.pattern,
+            time_budget: self.cli.max_total_time,
+            until_ci: self.cli.until_ci.map(|pct| pct / 100.0),
+            endpoint: self.cli.endpoint,
+            compare_endpoints: self.cli.compare_endpoints,
+            raw: self.cli.raw,
+            compare_template: self.cli.compare_template,
+            options: self.cli.sampling_options(),
+            option_sweep: self.cli.sweep_option.clone(),
+            history_messages: 0,
+            history_sweep: self.cli.sweep_history.clone(),
+            context_sweep: self.cli.context_sweep.clone(),
+            context_tokens: None,
+            hedge_copies: 1,
+            hedged: self.cli.hedged,
+            pull_missing: self.cli.pull_missing,
+            keep_going: self.cli.keep_going,
+            retries: self.cli.retries,
+            retry_backoff_ms: self.cli.retry_backoff_ms,
+            telemetry: self.cli.telemetry,
+            batch_n: self.cli.batch_n,
+            batch_mode: None,
         };
+        if let Some(num_ctx) = self.cli.num_ctx {
+            config.options.insert("num_ctx".to_string(), num_ctx.into());
+        }
+        if let Some(preset) = self.cli.preset {
+            preset.apply(&mut config);
+        }
+        if !config.images.is_empty() {
+            // The first request also loads the vision projector; without a
+            // known warm-up, detection could drop an image's only request
+            if config.warmup.is_none() && !config.keep_warmup {
+                config.warmup = Some(1);
+            }
+            if self.cli.samples.is_some() {
+                let sampled = config.images.len() as u32 + config.warmup.unwrap_or(0);
+                config.iterations = config.iterations.max(sampled);
+            }
+        }
+        if !config.context_sweep.is_empty() {
+            let num_ctx = context::sweep_num_ctx(&config.context_sweep, config.max_tokens);
+            config.options.entry("num_ctx".to_string()).or_insert(num_ctx.into());
+        }
         
-        // Create Ollama client
-        let client = OllamaClient::new(
-            config.ollama_base_url.clone(),
-            Duration::from_secs(config.timeout_seconds),
-        );
+        // Load the baseline up front so a bad path fails before benchmarking
+        let baseline = match &self.cli.baseline {
+            Some(path) => Some(compare::load_run(path)?),
+            None => None,
+        };
         
         // Check Ollama connectivity
         if !self.cli.quiet {
             println!("🔍 Checking Ollama connection...");
         }
         
-        client.health_check().await?;
+        let mut clients = Vec::new();
+        for url in &self.server_urls() {
+            clients.push(self.connect(url, Duration::from_secs(config.timeout_seconds)).await?);
+        }
+        config.ollama_base_url = clients[0].base_url().to_string();
+        let base_urls: Vec<String> = clients.iter().map(|client| client.base_url().to_string()).collect();
+        
+        let redactor = Redactor::new(&self.cli.redact, &base_urls);
+        let mut servers = Vec::new();
+        for client in &clients {
+            let server = redactor.server(&self.collect_server_info(client).await);
+            if !self.cli.quiet {
+                print_server_info(&server);
+            }
+            servers.push(server);
+        }
+        
+        let mut warnings = Warnings::default();
+        if let Some(index) = self.cli.gpu_filter {
+            self.print_gpu_guidance(index, &mut warnings);
+        }
+        
+        for client in &clients {
+            self.precheck_vram(client, &mut warnings).await;
+            let server = (clients.len() > 1).then(|| redactor.text(&net::server_name(client.base_url())));
+            self.precheck_context(client, server.as_deref(), &mut config, &mut warnings).await?;
+        }
+        
+        let thread_aware = self.cli.num_thread.is_some() || !self.cli.sweep_threads.is_empty();
+        if (thread_aware || self.cli.verbose) && !self.cli.quiet {
+            println!("🧮 Host: {}", HostTopology::detect().describe());
+        }
+        
+        if self.cli.raw && !self.cli.quiet {
+            println!("📝 Raw mode: prompts are sent verbatim, without the model's template");
+        }
+        
+        let duel = matches!(self.cli.command, Some(Command::Duel { .. }));
+        if duel && plan::plan(&self.cli.models, &config, config.sweep_order).len() != 2 {
+            return Err(BenchmarkError::ConfigError("duel compares two models as they are; drop the sweep flags".to_string()));
+        }
+        if duel && base_urls.len() > 1 {
+            return Err(BenchmarkError::ConfigError("duel pairs rounds on one server; pass a single --ollama-url".to_string()));
+        }
+        
+        if !self.cli.quiet && !self.cli.warm_pool && self.cli.idle_probe.is_empty() {
+            let cells = plan::plan(&self.cli.models, &config, config.sweep_order);
+            if cells.len() > self.cli.models.len() {
+                println!(
+                    "🗺️  Plan: {} cells, ~{} model loads ({} order)",
+                    cells.len(),
+                    plan::load_count(&cells),
+                    format!("{:?}", config.sweep_order).to_lowercase()
+                );
+            }
+        }
         
         // Create progress reporter
         let progress: Box<dyn ProgressReporter> = if self.cli.quiet {
             Box::new(QuietProgress)
+        } else if self.use_plain_progress() {
+            Box::new(PlainProgress)
         } else {
             Box::new(TerminalProgress::new(self.cli.quiet, self.cli.verbose))
         };
         
         // Create benchmarker
-        let mut benchmarker = Benchmarker::new(client, config, progress);
+        let mut benchmarker = Benchmarker::new(clients[0].clone(), config.clone(), progress);
+        // The sampler reads this machine, which says nothing about a remote server
+        let remote = base_urls.iter().find(|url| !container::is_loopback(url));
+        if let (true, Some(url)) = (config.telemetry, remote) {
+            let message = format!("Telemetry skipped: {} is not on this machine", redactor.text(url));
+            let warning = warnings.push(WarningKind::Server, None, message);
+            if !self.cli.quiet {
+                println!("📡 {}", warning);
+            }
+        } else if config.telemetry {
+            let source = GpuSource::detect();
+            if !self.cli.quiet {
+                println!("📡 Telemetry: GPU via {}, CPU/RAM via /proc", source.describe());
+            }
+            benchmarker.enable_telemetry(source);
+        }
+        
+        // Ctrl-C stops the run early but still reports completed iterations
+        let cancel = CancellationToken::new();
+        let ctrl_c_cancel = cancel.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                ctrl_c_cancel.cancel();
+            }
+        });
         
         // Run benchmarks
         let start_time = Instant::now();
-        let summaries = benchmarker.benchmark_models(self.cli.models.clone()).await?;
+        let mut resident = None;
+        let mut idle_samples = None;
+        let summaries = if self.cli.warm_pool {
+            let (summaries, models) = benchmarker.benchmark_warm_pool(self.cli.models.clone(), &cancel).await?;
+            resident = Some(models);
+            summaries
+        } else if !self.cli.idle_probe.is_empty() {
+            let (summaries, samples) = benchmarker
+                .benchmark_idle_probe(self.cli.models.clone(), &self.cli.idle_probe, &cancel)
+                .await?;
+            idle_samples = Some(samples);
+            summaries
+        } else if !self.cli.ab_hosts.is_empty() {
+            let hosts: Vec<(OllamaClient, String)> = clients
+                .iter()
+                .map(|client| (client.clone(), net::server_name(client.base_url())))
+                .collect();
+            benchmarker.benchmark_ab(self.cli.models.clone(), &hosts, &cancel).await?
+        } else if base_urls.len() > 1 {
+            // Same models on every server, one after the other, so hosts
+            // never compete for the network or the benchmark's own CPU
+            let mut summaries = Vec::new();
+            for client in &clients {
+                let name = net::server_name(client.base_url());
+                if !self.cli.quiet {
+                    println!("\n🖥️  Server: {}", redactor.text(&name));
+                }
+                benchmarker.use_server(client.clone(), name);
+                summaries.extend(benchmarker.benchmark_models(self.cli.models.clone(), &cancel).await?);
+                if cancel.is_cancelled() {
+                    break;
+                }
+            }
+            summaries
+        } else {
+            benchmarker.benchmark_models(self.cli.models.clone(), &cancel).await?
+        };
+        let exclusions = redactor.exclusions(&Exclusions::new(benchmarker.skipped().to_vec(), &summaries, benchmarker.raw_results()));
+        let mut summaries = redactor.summaries(&summaries);
+        // Redacted like the summaries, so reports can match them up
+        let raw_results = redactor.results(benchmarker.raw_results());
+        if cancel.is_cancelled() {
+            let warning = warnings.push(WarningKind::Cancelled, None, "Benchmark cancelled, showing partial results".to_string());
+            if !self.cli.quiet {
+                println!("\n⚠️  {}", warning);
+            }
+        }
         let total_duration = start_time.elapsed();
         
+        // The regression gate sees every model; --filter only trims what is shown
+        let all_deltas = baseline
+            .as_ref()
+            .map(|baseline| compare::compute_deltas(&baseline.models, &summaries));
+        let regressions = match (&baseline, self.cli.fail_on_regression) {
+            (Some(baseline), Some(threshold)) => compare::regressions(&baseline.models, &summaries, threshold),
+            _ => Vec::new(),
+        };
+        
+        let filter = self.cli.summary_filter();
+        if filter.is_active() {
+            let before = summaries.len();
+            summaries.retain(|summary| filter.keeps(summary));
+            if summaries.len() < before && !self.cli.quiet {
+                println!("\n🔎 Filtered out {} of {} results", before - summaries.len(), before);
+            }
+        }
+        let deltas = all_deltas.as_ref().map(|deltas| {
+            deltas
+                .iter()
+                .filter(|delta| summaries.iter().any(|s| s.label() == delta.model))
+                .cloned()
+                .collect::<Vec<_>>()
+        });
+        let endpoints = self.cli.compare_endpoints
+            .then(|| endpoints::build_report(&summaries, &raw_results));
+        let template_costs = self.cli.compare_template
+            .then(|| template::build_report(&summaries, &raw_results));
+        let hedge_report = self.cli.hedged
+            .map(|_| hedge::build_report(&summaries, &raw_results));
+        let batch_report = self.cli.batch_n
+            .map(|n| batch::build_report(&summaries, n, &raw_results));
+        // Post-run probes go to the server each summary came from
+        let probe_targets: Vec<net::ProbeTarget> = clients
+            .iter()
+            .map(|client| (client.clone(), (clients.len() > 1).then(|| redactor.text(&net::server_name(client.base_url())))))
+            .collect();
+        let overhead = if self.cli.overhead_check && !cancel.is_cancelled() {
+            Some(overhead::measure(&probe_targets, &config, &summaries, &cancel).await)
+        } else {
+            None
+        };
+        let pattern_report = self.cli.pattern
+            .map(|p| pattern::build_report(&summaries, p, self.cli.concurrency, &raw_results));
+        let cancel_stats = match self.cli.cancel_after {
+            Some(tokens) if !cancel.is_cancelled() => Some(cancel::measure(&probe_targets, &config, &summaries, tokens, &cancel).await),
+            _ => None,
+        };
+        let queue_report = match self.cli.queue_depth {
+            Some(depth) if !cancel.is_cancelled() => {
+                Some(queue::measure(&probe_targets, &config, &summaries, depth, self.cli.stagger_ms, &cancel).await)
+            }
+            _ => None,
+        };
+        let paired_report = (!self.cli.ab_hosts.is_empty()).then(|| {
+            let [host_a, host_b] = [&clients[0], &clients[1]].map(|client| redactor.text(&net::server_name(client.base_url())));
+            paired::compare(&summaries, &host_a, &host_b, &raw_results)
+        });
+        let duel_report = duel.then(|| paired::duel(&self.cli.models[0], &self.cli.models[1], benchmarker.raw_results()));
+        // A duel with one side missing has nothing to compare, whatever --keep-going says
+        if let Some(report) = duel_report.as_ref().filter(|_| !cancel.is_cancelled()) {
+            if let Some(skipped) = exclusions.skipped.first() {
+                return Err(BenchmarkError::ModelNotFound(skipped.model.clone()));
+            }
+            if report.pairs == 0 {
+                return Err(BenchmarkError::DuelIncomplete(format!("{} vs {}", report.model_a, report.model_b)));
+            }
+        }
+        let vision_report = (!config.images.is_empty())
+            .then(|| vision::build_report(&summaries, &config.images, &raw_results));
+        let categories = self.cli.by_category
+            .then(|| suite::category_breakdown(&summaries, &raw_results));
+        let reference = match &self.cli.compare_reference {
+            Some(hardware) => self.compare_reference(hardware, &base_urls, &summaries)?,
+            None => None,
+        };
+        let selection = self.cli.select.then(|| {
+            let criteria = select::Criteria {
+                min_accuracy: self.cli.min_accuracy,
+                max_ttft_ms: self.cli.max_ttft.map(|d| d.as_secs_f64() * 1000.0),
+            };
+            select::select(&summaries, &criteria)
+        });
+        let prompts = (!config.model_prompts.is_empty()).then(|| redactor.prompts(&config.model_prompts));
+        
+        // Anomalies, clock steps and the summaries' caveats have their own
+        // listings; the rest are printed after the results
+        warnings.add_anomalies(benchmarker.raw_results());
+        warnings.add_clock_steps(benchmarker.raw_results());
+        for summary in &summaries {
+            warnings.add_summary_caveats(summary);
+        }
+        let reported = warnings.len();
+        if let (Some(baseline_server), [server]) = (baseline.as_ref().and_then(|b| b.server.as_ref()), servers.as_slice()) {
+            for difference in compare::server_differences(baseline_server, server) {
+                warnings.push(WarningKind::Server, None, format!("Server config differs from baseline: {}", difference));
+            }
+        }
+        for (server, base_url) in servers.iter().zip(&base_urls) {
+            self.verify_server_env(server, base_url, &mut warnings).await;
+        }
+        for summary in &summaries {
+            warnings.add_context_limits(summary);
+            warnings.add_throttling(summary);
+        }
+        let mut run_meta = RunMeta::new(&config, &self.cli.models, redactor.prompt(&config.prompt));
+        if !self.cli.no_history {
+            let server = (servers.len() == 1).then(|| servers[0].url.as_str());
+            run_meta.recorded = self.record_history(&run_meta, server, total_duration, &summaries, &raw_results);
+        }
+        let sample_sizes = effect::sample_sizes(&summaries, SAMPLE_MARGIN_FRACTION);
+        let extras = ReportExtras {
+            run_id: Some(&run_meta.run_id),
+            deltas: deltas.as_deref(),
+            server: (servers.len() == 1).then(|| &servers[0]),
+            servers: (servers.len() > 1).then_some(servers.as_slice()),
+            endpoints: endpoints.as_deref(),
+            template: template_costs.as_deref(),
+            hedge: hedge_report.as_deref(),
+            batch: batch_report.as_deref(),
+            reference: reference.as_ref().map(|matched| matched.comparisons.as_slice()),
+            overhead: overhead.as_ref(),
+            cancel: cancel_stats.as_deref(),
+            queue: queue_report.as_deref(),
+            pattern: pattern_report.as_deref(),
+            paired: paired_report.as_deref(),
+            duel: duel_report.as_ref(),
+            vision: vision_report.as_deref(),
+            categories: categories.as_deref(),
+            sample_size: (config.until_ci.is_none() && !sample_sizes.is_empty()).then_some(sample_sizes.as_slice()),
+            exclusions: (!exclusions.is_empty()).then_some(&exclusions),
+            warnings: (!warnings.is_empty()).then_some(&warnings),
+            selection: selection.as_ref(),
+            keep_alive: idle_samples.as_deref(),
+            prompts: prompts.as_ref(),
+            signed: self.cli.sign,
+            schema: self.cli.json_schema,
+        };
+        
         // Output results
-        self.output_results(&summaries, total_duration)?;
+        self.output_results(&self.cli.output, &summaries, &extras, total_duration)?;
+        
+        // CSV output lists every warning on stderr instead
+        if !self.cli.quiet && self.cli.output != OutputFormat::Csv {
+            for warning in warnings.iter().skip(reported) {
+                println!("⚠️  {}", warning);
+            }
+        }
+        
+        if let Some(index) = self.cli.gpu_filter {
+            print_gpu_placement(&summaries, index);
+        }
+        
+        if !self.cli.sweep_threads.is_empty() && !self.cli.quiet {
+            print_thread_sweep(&summaries);
+        }
+        
+        if (self.cli.concurrency > 1 || self.cli.pattern.is_some()) && !self.cli.quiet {
+            print_load_report(&summaries);
+        }
+        
+        if let Some(pattern_report) = &pattern_report {
+            if !self.cli.quiet {
+                print_pattern_report(pattern_report);
+            }
+        }
+        
+        if self.cli.preset.is_some() && !self.cli.quiet {
+            print_prefill_report(&summaries);
+        }
+        
+        if !self.cli.sweep_history.is_empty() && !self.cli.quiet {
+            print_history_sweep(&history::curves(&summaries));
+        }
+        
+        if !self.cli.context_sweep.is_empty() && !self.cli.quiet {
+            print_context_sweep(&context::curves(&summaries));
+        }
+        
+        if let Some(resident) = resident {
+            if !self.cli.quiet {
+                print_warm_pool_report(&warmpool::build_report(&self.cli.models, &resident, benchmarker.raw_results()));
+            }
+        }
+        
+        if let Some(endpoints) = &endpoints {
+            if !self.cli.quiet {
+                print_endpoint_report(endpoints);
+            }
+        }
+        
+        if let Some(template_costs) = &template_costs {
+            if !self.cli.quiet {
+                print_template_report(template_costs);
+            }
+        }
+        
+        if let Some(hedge_report) = &hedge_report {
+            if !self.cli.quiet {
+                print_hedge_report(hedge_report);
+            }
+        }
+        
+        if let Some(batch_report) = &batch_report {
+            if !self.cli.quiet {
+                print_batch_report(batch_report);
+            }
+        }
+        
+        if let Some(categories) = &categories {
+            if !self.cli.quiet {
+                print_category_report(categories);
+            }
+        }
+        
+        if let Some(vision_report) = &vision_report {
+            if !self.cli.quiet {
+                print_vision_report(vision_report);
+            }
+        }
+        
+        if let Some(overhead) = &overhead {
+            if !self.cli.quiet {
+                print_overhead_report(overhead);
+            }
+        }
+        
+        if let Some(cancel_stats) = &cancel_stats {
+            if !self.cli.quiet {
+                print_cancel_report(cancel_stats);
+            }
+        }
+        
+        if let Some(queue_report) = &queue_report {
+            if !self.cli.quiet {
+                print_queue_report(queue_report);
+            }
+        }
+        
+        if let Some(paired_report) = &paired_report {
+            if !self.cli.quiet {
+                print_paired_report(paired_report);
+            }
+        }
+        
+        if let Some(duel_report) = &duel_report {
+            if !self.cli.quiet {
+                print_duel_report(duel_report);
+            }
+        }
+        
+        if let Some(matched) = &reference {
+            if !self.cli.quiet {
+                print_reference_report(matched);
+            }
+        }
+        
+        if let Some(selection) = &selection {
+            if !self.cli.quiet {
+                print_selection(selection);
+            }
+        }
+        
+        if let Some(samples) = &idle_samples {
+            if !self.cli.quiet {
+                print_keep_alive_report(&self.cli.models, samples);
+            }
+        }
+        
+        if !self.cli.quiet {
+            print_anomalies(benchmarker.raw_results());
+        }
+        
+        if self.cli.telemetry && !self.cli.quiet {
+            print_telemetry(&summaries, &raw_results);
+        }
+        
+        if self.cli.verbose && !self.cli.quiet {
+            print_extremes(&summaries);
+            print_completion_histograms(&summaries);
+        }
+        
+        // Dual-stack or multi-A hosts can spread requests over addresses
+        let by_address = net::by_address(benchmarker.raw_results());
+        if by_address.len() > 1 && !self.cli.quiet && !self.cli.redact.contains(&RedactField::Hostname) {
+            print_address_breakdown(&by_address);
+        }
+        
+        if self.cli.timeline && !self.cli.quiet {
+            print_timeline(&raw_results);
+        }
+        
+        if let (Some(margin), false) = (config.until_ci, self.cli.quiet) {
+            print_until_ci(&summaries, margin, config.iterations);
+        } else if !self.cli.quiet {
+            print_sample_size(&sample_sizes);
+        }
+        
+        // Compare against golden outputs if requested
+        if let Some(golden_dir) = &self.cli.golden {
+            self.check_golden(benchmarker.raw_results(), &config, golden_dir)?;
+        }
         
         // Export if requested
         if let Some(export_path) = &self.cli.export {
-            self.export_results(&summaries, export_path)?;
+            self.export_results(&summaries, &raw_results, &extras, &run_meta, total_duration, export_path)?;
         }
         
+        if let Some(raw_path) = &self.cli.export_raw {
+            self.export_raw_results(&raw_results, &run_meta, raw_path)?;
+        }
+        
+        if let Some(selection) = &selection {
+            if let Some(path) = &self.cli.select_output {
+                self.write_selection(selection, path)?;
+            }
+            if selection.selected.is_none() {
+                return Err(BenchmarkError::NoModelSelected);
+            }
+        }
+        
+        // Missing models fail the run; --keep-going accepts the partial results
+        if !self.cli.keep_going && !cancel.is_cancelled() {
+            if let Some(skipped) = exclusions.skipped.first() {
+                return Err(BenchmarkError::ModelNotFound(skipped.model.clone()));
+            }
+        }
+        
+        if !regressions.is_empty() {
+            return Err(BenchmarkError::SpeedRegressed(regressions));
+        }
+        
+        Ok(())
+    }
+    
+    /// Writes the `--select` result as JSON or, for any other extension, as
+    /// `KEY=value` lines a deployment script can source.
+    fn write_selection(&self, selection: &select::Selection, path: &str) -> Result<()> {
+        let content = if path.ends_with(".json") {
+            serde_json::to_string_pretty(selection)?
+        } else {
+            select::to_env(selection)
+        };
+        std::fs::write(path, content)?;
+        
+        if !self.cli.quiet {
+            println!("\n🎯 Selection written to: {}", path);
+        }
         Ok(())
     }
     
-    fn output_results(&self, summaries: &[ModelSummary], duration: Duration) -> Result<()> {
-        match self.cli.output {
+    /// Returns a client for a healthy Ollama at `base_url`. With `--docker`,
+    /// common container-to-host URLs are tried before giving up; failures are
+    /// probed so the error explains what is actually wrong.
+    async fn connect(&self, base_url: &str, timeout: Duration) -> Result<OllamaClient> {
+        let client = OllamaClient::with_options(base_url.to_string(), timeout, &self.client_options(base_url).await);
+        
+        match client.health_check().await {
+            Ok(true) => return Ok(client),
+            Ok(false) | Err(BenchmarkError::OllamaNotRunning) | Err(BenchmarkError::ConnectionFailed(_)) => {}
+            Err(e) => return Err(e),
+        }
+        
+        if self.cli.docker {
+            for candidate in container::alternative_urls(base_url) {
+                let client = OllamaClient::with_options(candidate.clone(), timeout, &self.client_options(&candidate).await);
+                if matches!(client.health_check().await, Ok(true)) {
+                    if !self.cli.quiet {
+                        println!("🐳 Ollama not reachable at {}, using {}", base_url, candidate);
+                    }
+                    return Ok(client);
+                }
+            }
+        }
+        
+        Err(BenchmarkError::Unreachable {
+            url: base_url.to_string(),
+            diagnosis: crate::error::diagnose(base_url).await,
+            container_hint: container::hint(base_url, self.cli.docker),
+        })
+    }
+    
+    fn ip_preference(&self) -> Option<IpFamily> {
+        if self.cli.prefer_ipv4 {
+            Some(IpFamily::V4)
+        } else if self.cli.prefer_ipv6 {
+            Some(IpFamily::V6)
+        } else {
+            None
+        }
+    }
+    
+    /// The `--hosts-file` servers, else the ones given on the command line.
+    fn server_urls(&self) -> Vec<String> {
+        if self.profiles.is_empty() {
+            self.cli.server_urls().to_vec()
+        } else {
+            self.profiles.iter().map(|profile| profile.url.clone()).collect()
+        }
+    }
+    
+    /// Client identity, plus the host's addresses in preferred order when
+    /// `--prefer-ipv4/--prefer-ipv6` is set.
+    async fn client_options(&self, base_url: &str) -> ClientOptions {
+        let resolved = match self.ip_preference() {
+            Some(family) => net::resolve(base_url).await.map(|(host, addrs)| (host, net::prefer(&addrs, family))),
+            None => None,
+        };
+        ClientOptions {
+            user_agent: self.cli.user_agent.clone().unwrap_or_else(get_user_agent),
+            bench_client: self.cli.bench_client.clone(),
+            resolved,
+            profile: self.profiles.iter().find(|profile| profile.url == base_url).cloned(),
+        }
+    }
+    
+    /// Version and loaded models come from the API; environment settings
+    /// are invisible to clients, so they are taken from `--server-env`.
+    async fn collect_server_info(&self, client: &OllamaClient) -> ServerInfo {
+        let options = self.client_options(client.base_url()).await;
+        let addresses = match &options.resolved {
+            Some((_, addrs)) => addrs.clone(),
+            None => net::resolve(client.base_url()).await.map(|(_, addrs)| addrs).unwrap_or_default(),
+        };
+        ServerInfo {
+            url: client.base_url().to_string(),
+            version: client.version().await.ok(),
+            loaded_models: client
+                .running_models()
+                .await
+                .map(|models| models.into_iter().map(|m| m.name).collect())
+                .unwrap_or_default(),
+            env: self.cli.server_env_map(),
+            user_agent: Some(options.user_agent),
+            bench_client: options.bench_client,
+            addresses: addresses.iter().map(|addr| addr.to_string()).collect(),
+        }
+    }
+    
+    /// Checks declared settings that are observable after the run.
+    async fn verify_server_env(&self, server: &ServerInfo, base_url: &str, warnings: &mut Warnings) {
+        let Some(max_loaded) = server.env.get("OLLAMA_MAX_LOADED_MODELS").and_then(|v| v.parse::<usize>().ok()) else {
+            return;
+        };
+        
+        let client = OllamaClient::with_options(
+            base_url.to_string(),
+            Duration::from_secs(self.cli.timeout),
+            &self.client_options(base_url).await,
+        );
+        if let Ok(running) = client.running_models().await {
+            if running.len() > max_loaded {
+                let message = format!(
+                    "OLLAMA_MAX_LOADED_MODELS={} was declared but {} models are loaded",
+                    max_loaded,
+                    running.len()
+                );
+                warnings.push(WarningKind::Server, None, message);
+            }
+        }
+    }
+    
+    /// Warns about models whose weights alone exceed available GPU memory.
+    /// Skipped silently when no NVIDIA GPU is visible (e.g. Apple Silicon).
+    async fn precheck_vram(&self, client: &OllamaClient, warnings: &mut Warnings) {
+        let gpus = gpu::list_gpus();
+        if gpus.is_empty() {
+            return;
+        }
+        
+        let Ok(installed) = client.list_model_details().await else {
+            return;
+        };
+        
+        for model in installed.iter().filter(|m| self.cli.models.contains(&m.name)) {
+            if !gpu::fits_in_vram(model.size.max(0) as u64, &gpus) {
+                let message = format!(
+                    "{} ({} GB) likely exceeds GPU memory and will partially run on CPU",
+                    model.name,
+                    num(model.size as f64 / 1e9, 1)
+                );
+                let warning = warnings.push(WarningKind::Memory, Some(&model.name), message);
+                if !self.cli.quiet {
+                    println!("⚠️  {}", warning);
+                }
+            }
+        }
+    }
+    
+    /// Compares the estimated request size of every model and sweep variant
+    /// with its `num_ctx`, since Ollama truncates an overlong prompt without
+    /// reporting it. Records each server's Modelfile `num_ctx` so finished
+    /// requests can be checked against it; `server` names the server in the
+    /// warnings of a multi-server run.
+    async fn precheck_context(&self, client: &OllamaClient, server: Option<&str>, config: &mut BenchmarkConfig, warnings: &mut Warnings) -> Result<()> {
+        let mut shown = Vec::new();
+        for model in &self.cli.models {
+            // Missing models are reported (or pulled) later
+            let Ok(show) = client.show_model(model).await else {
+                continue;
+            };
+            if let Some(num_ctx) = show.parameters.as_deref().and_then(|p| host::parse_parameter(p, "num_ctx")) {
+                config.model_num_ctx.insert((client.base_url().to_string(), model.clone()), num_ctx);
+            }
+            shown.push(model.clone());
+        }
+        
+        let mut warned = false;
+        for cell in plan::plan(&shown, config, config.sweep_order) {
+            let budget = Budget::new(&cell.config, client.base_url(), &cell.model);
+            if !budget.overflows() {
+                continue;
+            }
+            
+            let label = match server {
+                Some(server) => format!("{} @ {}", cell.model, server),
+                None => cell.model.clone(),
+            };
+            let label = match &cell.variant {
+                Some(variant) => format!("{} [{}]", label, variant),
+                None => label,
+            };
+            let message = budget.describe(&label);
+            if self.cli.strict_context {
+                return Err(BenchmarkError::ConfigError(message));
+            }
+            let warning = warnings.push(WarningKind::Context, Some(&cell.model), message);
+            if !self.cli.quiet {
+                println!("⚠️  {}", warning);
+                warned = true;
+            }
+        }
+        
+        if warned {
+            println!("💡 {}", t(Msg::HintContextOverflow));
+        }
+        Ok(())
+    }
+    
+    fn print_gpu_guidance(&self, index: u32, warnings: &mut Warnings) {
+        let gpus = gpu::list_gpus();
+        let message = match gpus.iter().find(|g| g.index == index) {
+            Some(device) => {
+                if !self.cli.quiet {
+                    println!("🖥️  Expecting GPU {} ({}, {} MB)", device.index, device.name, device.memory_total_mb);
+                }
+                None
+            }
+            None if gpus.is_empty() => Some("nvidia-smi not available; GPU placement cannot be verified".to_string()),
+            None => Some(format!("GPU {} not found ({} GPU(s) detected)", index, gpus.len())),
+        };
+        if let Some(message) = message {
+            let warning = warnings.push(WarningKind::Gpu, None, message);
+            if !self.cli.quiet {
+                println!("⚠️  {}", warning);
+            }
+        }
+        if self.cli.quiet {
+            return;
+        }
+        println!(
+            "💡 Ollama picks devices at startup; pin it with: CUDA_VISIBLE_DEVICES={} ollama serve (ROCm: HIP_VISIBLE_DEVICES)",
+            index
+        );
+    }
+    
+    fn use_plain_progress(&self) -> bool {
+        match self.cli.progress {
+            ProgressMode::Plain => true,
+            ProgressMode::Bar => false,
+            ProgressMode::Auto => !std::io::stdout().is_terminal(),
+        }
+    }
+    
+    fn output_results(&self, format: &OutputFormat, summaries: &[ModelSummary], extras: &ReportExtras, duration: Duration) -> Result<()> {
+        match format {
             OutputFormat::Table => {
-                print_results_table(summaries, duration);
+                print_results_table(summaries, duration, self.cli.speed_unit);
+                if let Some(exclusions) = extras.exclusions {
+                    print!("{}", exclusions.listing());
+                }
+                if let Some(deltas) = extras.deltas {
+                    print_baseline_comparison(deltas);
+                }
             }
             OutputFormat::Json => {
-                print_results_json(summaries);
+                print_results_json(summaries, extras);
             }
             OutputFormat::Csv => {
-                print_results_csv(summaries);
+                print_results_csv(summaries, self.cli.speed_unit);
+                // Standard output stays plain CSV
+                if let Some(exclusions) = extras.exclusions {
+                    eprint!("{}", exclusions.listing());
+                }
+                if let Some(warnings) = extras.warnings {
+                    eprint!("{}", warnings.listing());
+                }
             }
             OutputFormat::Markdown => {
-                print_results_markdown(summaries, duration);
+                print_results_markdown(summaries, duration, self.cli.speed_unit);
+                if let Some(exclusions) = extras.exclusions {
+                    print!("\n{}", exclusions.markdown());
+                }
+                if let Some(warnings) = extras.warnings {
+                    print!("\n{}", warnings.markdown());
+                }
+            }
+            OutputFormat::Html => {
+                print!("{}", html::render(summaries, extras.exclusions, extras.warnings, duration, self.cli.speed_unit));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// `--self-check-format`: prints the fixture summaries in every output
+    /// format, then checks that the rendered reports agree on every value.
+    fn self_check_format(&self) -> Result<()> {
+        let summaries = selfcheck::fixture_summaries();
+        for format in OutputFormat::value_variants() {
+            println!("\n===== {:?} =====", format);
+            self.output_results(format, &summaries, &ReportExtras::default(), selfcheck::FIXTURE_DURATION)?;
+        }
+        
+        let renders = selfcheck::render(&summaries, self.cli.speed_unit);
+        let problems = selfcheck::check(&summaries, &renders, self.cli.speed_unit);
+        if problems.is_empty() {
+            println!("\n✅ {} formats agree on the fixture results", renders.len());
+            Ok(())
+        } else {
+            Err(BenchmarkError::ConfigError(format!("output formats disagree: {}", problems.join("; "))))
+        }
+    }
+    
+    fn check_golden(&self, results: &[BenchmarkResult], config: &BenchmarkConfig, dir: &str) -> Result<()> {
+        if !self.cli.quiet {
+            println!("\n🔎 Golden output check ({})", dir);
+        }
+        
+        let checks = golden::check_results(std::path::Path::new(dir), results, config.seed)?;
+        if self.cli.quiet {
+            return Ok(());
+        }
+        
+        let many_prompts = checks.iter().any(|check| check.prompt != checks[0].prompt);
+        for check in &checks {
+            let model = if many_prompts {
+                format!("{} \"{}\"", check.model, preview(&check.prompt, 40))
+            } else {
+                check.model.clone()
+            };
+            match check.outcome {
+                GoldenOutcome::Recorded => println!("  📝 {}: recorded new golden output", model),
+                GoldenOutcome::Matched => println!("  ✅ {}: output unchanged", model),
+                GoldenOutcome::Drifted { similarity } => println!(
+                    "  ⚠️  {}: output drifted ({}% word similarity)",
+                    model,
+                    num(similarity * 100.0, 0)
+                ),
             }
         }
         
+        if config.seed.is_none() {
+            println!("  💡 Pass --seed for reproducible outputs; unseeded runs drift naturally");
+        }
+        
         Ok(())
     }
     
-    fn export_results(&self, summaries: &[ModelSummary], path: &str) -> Result<()> {
-        let content = match path.rsplit('.').next() {
-            Some("json") => serde_json::to_string_pretty(summaries)?,
-            Some("csv") => self.generate_csv_content(summaries),
-            Some("md") => self.generate_markdown_content(summaries),
+    fn export_results(
+        &self,
+        summaries: &[ModelSummary],
+        results: &[BenchmarkResult],
+        extras: &ReportExtras,
+        meta: &RunMeta,
+        duration: Duration,
+        path: &str,
+    ) -> Result<()> {
+        let format = format_extension(path);
+        let content = match format {
+            Some("json") => results_json(summaries, extras)?,
+            Some("csv") => summary_csv(summaries, self.cli.speed_unit),
+            Some("md") => {
+                summary_markdown(summaries, self.cli.speed_unit)
+                    + &extras.exclusions.map(|e| format!("\n{}", e.markdown())).unwrap_or_default()
+                    + &extras.warnings.map(|w| format!("\n{}", w.markdown())).unwrap_or_default()
+            }
+            Some("html") => html::render(summaries, extras.exclusions, extras.warnings, duration, self.cli.speed_unit),
+            Some("om") | Some("prom") => openmetrics::render(summaries, results, meta.recorded.then_some(meta.run_id.as_str())),
             _ => {
                 return Err(BenchmarkError::ConfigError(
-                    "Export file must have .json, .csv, or .md extension".to_string()
+                    "Export file must have .json, .csv, .md, .html, .om or .prom extension (optionally .gz or .zst)".to_string()
                 ));
             }
         };
         
-        let mut file = File::create(path)?;
-        file.write_all(content.as_bytes())?;
+        let mut writer = ExportWriter::create(path)?;
+        writer.write_all(content.as_bytes())?;
+        writer.finish()?;
         
         if !self.cli.quiet {
             println!("📊 Results exported to: {}", path);
         }
         
+        let columns = if format == Some("csv") { csvmeta::summary_columns(self.cli.speed_unit) } else { Vec::new() };
+        self.write_sidecar(path, "summary", &columns, meta, extras.exclusions, extras.warnings)?;
+        
+        // JSON reports carry their hash inline
+        self.sign_export(path, format == Some("json"))
+    }
+    
+    /// Streams iterations straight to disk; raw exports can run to millions of rows.
+    fn export_raw_results(&self, results: &[BenchmarkResult], meta: &RunMeta, path: &str) -> Result<()> {
+        let format = format_extension(path);
+        if !matches!(format, Some("json") | Some("csv")) {
+            return Err(BenchmarkError::ConfigError(
+                "Raw export file must have .json or .csv extension (optionally .gz or .zst)".to_string()
+            ));
+        }
+        
+        let mut writer = ExportWriter::create(path)?;
+        if format == Some("json") {
+            write_raw_json(&mut writer, results)?;
+        } else {
+            write_raw_csv(&mut writer, results)?;
+        }
+        writer.finish()?;
+        
+        if !self.cli.quiet {
+            println!("📊 Raw results exported to: {}", path);
+        }
+        
+        let columns: Vec<String> = match format {
+            Some("csv") => csvmeta::RAW_COLUMNS.iter().map(|c| c.to_string()).collect(),
+            _ => Vec::new(),
+        };
+        self.write_sidecar(path, "raw", &columns, meta, None, None)?;
+        
+        self.sign_export(path, false)
+    }
+    
+    /// Run id and settings for an export (plus the column schema of a CSV),
+    /// the models it has no entry for and the run's warnings, next to it as
+    /// `.meta.json`.
+    fn write_sidecar(
+        &self,
+        path: &str,
+        kind: &str,
+        columns: &[String],
+        meta: &RunMeta,
+        exclusions: Option<&Exclusions>,
+        warnings: Option<&Warnings>,
+    ) -> Result<()> {
+        let sidecar = csvmeta::write_sidecar(path, kind, columns, meta, exclusions, warnings)?;
+        if !self.cli.quiet {
+            println!("🗂️  Run metadata written to: {}", sidecar);
+        }
         Ok(())
     }
     
-    fn generate_csv_content(&self, summaries: &[ModelSummary]) -> String {
-        let mut content = String::from("Model,Success Rate,Avg Tokens/s,Min Tokens/s,Max Tokens/s,Avg TTFT (ms)\n");
+    fn sign_export(&self, path: &str, hash_embedded: bool) -> Result<()> {
+        if !self.cli.sign {
+            return Ok(());
+        }
         
-        for summary in summaries {
-            content.push_str(&format!(
-                "{},{:.1},{:.1},{:.1},{:.1},{:.0}\n",
-                summary.model,
-                summary.success_rate * 100.0,
-                summary.avg_tokens_per_second,
-                summary.min_tokens_per_second,
-                summary.max_tokens_per_second,
-                summary.avg_ttft_ms
-            ));
+        if !hash_embedded {
+            let checksum_path = integrity::write_checksum(path)?;
+            if !self.cli.quiet {
+                println!("🔏 Checksum written to: {}", checksum_path);
+            }
+        }
+        
+        if let Some(key) = &self.cli.sign_key {
+            integrity::minisign(path, key)?;
+            if !self.cli.quiet {
+                println!("🔏 Signature written to: {}.minisig", path);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    async fn run_doctor(&self, json: bool) -> Result<()> {
+        let checks = doctor::run(&self.server_urls()[0], Duration::from_secs(self.cli.timeout)).await;
+        
+        if json {
+            println!("{}", serde_json::to_string_pretty(&checks)?);
+        } else {
+            doctor::print_report(&checks);
+        }
+        
+        match doctor::failures(&checks) {
+            0 => Ok(()),
+            failed => Err(BenchmarkError::DoctorFailed(failed)),
+        }
+    }
+    
+    async fn run_list(&self, json: bool) -> Result<()> {
+        let url = &self.server_urls()[0];
+        let client = self.connect(url, Duration::from_secs(self.cli.timeout)).await?;
+        let models = inventory::list(&client).await?;
+        
+        if json {
+            println!("{}", serde_json::to_string_pretty(&models)?);
+        } else {
+            print_installed_models(&models, url);
         }
+        Ok(())
+    }
+    
+    /// `--all` or `--size-class`: the installed models to benchmark, largest
+    /// first, minus those matching an `--exclude` pattern.
+    async fn installed_selection(&self) -> Result<Vec<String>> {
+        let client = self.connect(&self.server_urls()[0], Duration::from_secs(self.cli.timeout)).await?;
+        let installed = inventory::list(&client).await?;
+        let (models, selection) = match self.cli.size_class {
+            Some(class) => (inventory::in_size_class(&installed, class), format!("{} class", class.name())),
+            None => (installed.into_iter().map(|model| model.name).collect(), "All models".to_string()),
+        };
+        let models: Vec<String> = models
+            .into_iter()
+            .filter(|model| !inventory::excluded(model, &self.cli.exclude))
+            .collect();
         
-        content
+        if models.is_empty() {
+            let excluded = if self.cli.exclude.is_empty() { "" } else { " outside --exclude" };
+            return Err(BenchmarkError::ConfigError(format!("{}: no installed models{}", selection, excluded)));
+        }
+        if !self.cli.quiet {
+            println!("📦 {}: {}", selection, models.join(", "));
+        }
+        Ok(models)
     }
     
-    fn generate_markdown_content(&self, summaries: &[ModelSummary]) -> String {
-        let mut content = String::from("# Ollama Benchmark Results\n\n");
-        content.push_str("| Model | Success Rate | Avg Tokens/s | TTFT (ms) |\n");
-        content.push_str("|-------|--------------|--------------|------------|\n");
+    /// MODEL arguments like `llama3*` or `*:7b`, resolved against the
+    /// models installed on any of the servers and then narrowed by `--exclude`.
+    async fn expand_model_patterns(&self) -> Result<Vec<String>> {
+        let mut installed = Vec::new();
+        for url in &self.server_urls() {
+            let client = self.connect(url, Duration::from_secs(self.cli.timeout)).await?;
+            installed.extend(client.list_models().await?);
+        }
+        let models: Vec<String> = inventory::expand_patterns(&self.cli.models, &installed)?
+            .into_iter()
+            .filter(|model| !inventory::excluded(model, &self.cli.exclude))
+            .collect();
         
-        for summary in summaries {
-            content.push_str(&format!(
-                "| {} | {:.1}% | {:.1} | {:.0} |\n",
-                summary.model,
-                summary.success_rate * 100.0,
-                summary.avg_tokens_per_second,
-                summary.avg_ttft_ms
+        if models.is_empty() {
+            return Err(BenchmarkError::ConfigError("every model matched was left out by --exclude".to_string()));
+        }
+        if !self.cli.quiet {
+            println!("📦 Models: {}", models.join(", "));
+        }
+        Ok(models)
+    }
+    
+    /// Diffs two saved runs the way `--baseline` diffs a run with its
+    /// baseline, without benchmarking anything.
+    fn run_compare(&self, baseline: &str, current: &str, fail_on_regression: Option<f64>, json: bool) -> Result<()> {
+        let before = compare::load_run(baseline)?;
+        let after = compare::load_run(current)?;
+        let deltas = compare::compute_deltas(&before.models, &after.models);
+        let differences = match (&before.server, &after.server) {
+            (Some(before), Some(after)) => compare::server_differences(before, after),
+            _ => Vec::new(),
+        };
+        
+        if json {
+            let output = serde_json::json!({
+                "baseline": baseline,
+                "current": current,
+                "server_differences": differences,
+                "models": deltas,
+            });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            println!("🆚 {} → {}", baseline, current);
+            for difference in &differences {
+                println!("⚠️  Server config differs: {}", difference);
+            }
+            print_baseline_comparison(&deltas);
+        }
+        
+        if let Some(threshold) = fail_on_regression {
+            let regressions = compare::regressions(&before.models, &after.models, threshold);
+            if !regressions.is_empty() {
+                return Err(BenchmarkError::SpeedRegressed(regressions));
+            }
+        }
+        Ok(())
+    }
+    
+    fn run_aggregate(&self, files: &[String], json: bool) -> Result<()> {
+        let runs = files
+            .iter()
+            .map(|path| aggregate::load_iterations(path))
+            .collect::<Result<Vec<_>>>()?;
+        let report = aggregate::aggregate(&runs);
+        if report.is_empty() {
+            return Err(BenchmarkError::ConfigError(
+                "no model has successful iterations in at least two of the runs".to_string()
             ));
         }
         
-        if let Some(winner) = calculate_winner(summaries) {
-            content.push_str(&format!("\n**Winner:** {} 🏆\n", winner.model));
-            
-            for other in summaries {
-                if other.model != winner.model {
-                    let (speed_diff, _ttft_diff) = calculate_performance_difference(winner, other);
-                    if speed_diff > 0.0 {
-                        content.push_str(&format!(
-                            "- {:.1}% faster than {}\n",
-                            speed_diff, other.model
-                        ));
+        if json {
+            let output = serde_json::json!({ "runs": files, "models": report });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        } else {
+            print_aggregate_report(files.len(), &report);
+        }
+        Ok(())
+    }
+    
+    /// Matches `hardware` ("auto" to detect it) in the reference results and
+    /// compares the summaries with it. Unknown or ambiguous hardware is
+    /// reported, not fatal; so is "auto" against a server on another machine.
+    fn compare_reference(&self, hardware: &str, base_urls: &[String], summaries: &[ModelSummary]) -> Result<Option<ReferenceMatch>> {
+        let path = self.cli.reference_file.as_deref()
+            .ok_or_else(|| BenchmarkError::ConfigError("--compare-reference needs --reference-file".to_string()))?;
+        let db = ReferenceDb::load(path)?;
+        // Detection reads this machine, which says nothing about a remote server
+        let remote = base_urls.iter().any(|url| !container::is_loopback(url));
+        let name = match hardware {
+            "auto" if remote => None,
+            "auto" => reference::detect_hardware(),
+            named => Some(named.to_string()),
+        };
+        
+        let Some(matched) = name.as_deref().and_then(|name| db.find_hardware(name)) else {
+            if !self.cli.quiet {
+                let listed: Vec<&str> = db.hardware().into_iter().collect();
+                let described = match &name {
+                    Some(name) => format!("No single reference match for {}", name),
+                    None if remote => "The server's hardware can't be detected from here".to_string(),
+                    None => "No reference results for undetected hardware".to_string(),
+                };
+                println!("⚠️  {}; pick one with --compare-reference=NAME: {}", described, listed.join(", "));
+            }
+            return Ok(None);
+        };
+        Ok(Some(db.compare(matched, summaries)))
+    }
+    
+    /// Appends the run to the history database and reports whether it got
+    /// there. Failing to record never fails the run itself.
+    fn record_history(&self, meta: &RunMeta, server: Option<&str>, duration: Duration, summaries: &[ModelSummary], results: &[BenchmarkResult]) -> bool {
+        let Some(path) = self.cli.history_db_path() else {
+            return false;
+        };
+        let recorded = RunDb::open(&path).and_then(|mut db| db.record(meta, server, self.cli.experiment.as_deref(), duration, summaries, results));
+        match recorded {
+            Ok(()) if self.cli.verbose && !self.cli.quiet => println!("📚 Run {} recorded in {}", meta.run_id, path.display()),
+            Ok(()) => {}
+            Err(e) => {
+                eprintln!("⚠️  Could not record the run in {}: {}", path.display(), e);
+                return false;
+            }
+        }
+        true
+    }
+    
+    fn run_history(&self, action: &HistoryAction) -> Result<()> {
+        let path = self.cli.history_db_path()
+            .ok_or_else(|| BenchmarkError::ConfigError("no home directory; pass --history-db".to_string()))?;
+        let mut db = RunDb::open(&path)?;
+        
+        match action {
+            HistoryAction::List { model, experiment, since, limit, json } => {
+                let filter = RunFilter { model: model.clone(), experiment: experiment.clone(), since: *since, limit: Some(*limit) };
+                let runs = db.list(&filter)?;
+                if *json {
+                    println!("{}", serde_json::to_string_pretty(&runs)?);
+                } else {
+                    print_run_list(&runs, &path);
+                    // The band of the oldest listed runs needs the runs before them
+                    if let Some(model) = model {
+                        let history = db.list(&RunFilter { limit: None, ..filter })?;
+                        let points = trend::trend(&history, model);
+                        print_trend(model, &points[points.len().saturating_sub(*limit)..]);
+                    }
+                }
+            }
+            HistoryAction::Show { run_id: None, experiment: Some(experiment), json } => {
+                let (runs, iterations): (Vec<RunRecord>, Vec<Vec<BenchmarkResult>>) = db.experiment(experiment)?.into_iter().unzip();
+                let run_ids: Vec<&str> = runs.iter().map(|run| run.run_id.as_str()).collect();
+                let report = aggregate::aggregate(&iterations);
+                if report.is_empty() {
+                    return Err(BenchmarkError::ConfigError(format!(
+                        "no model has successful iterations in at least two runs of experiment '{}'",
+                        experiment
+                    )));
+                }
+                
+                if *json {
+                    let output = serde_json::json!({ "experiment": experiment, "runs": run_ids, "models": report });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                } else {
+                    println!("🧪 Experiment {}: {}", experiment, run_ids.join(", "));
+                    print_aggregate_report(runs.len(), &report);
+                }
+            }
+            HistoryAction::Show { run_id, json, .. } => {
+                let Some(run_id) = run_id else {
+                    return Err(BenchmarkError::ConfigError("give a RUN_ID or --experiment NAME".to_string()));
+                };
+                let (run, iterations) = db.show(run_id)?;
+                if *json {
+                    let output = serde_json::json!({ "run": run, "iterations": iterations });
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                } else {
+                    println!("📚 Run {} recorded {}", run.run_id, run.recorded_at.format("%Y-%m-%d %H:%M UTC"));
+                    if let Some(experiment) = &run.experiment {
+                        println!("🧪 Experiment: {}", experiment);
                     }
+                    if let Some(server) = &run.server {
+                        println!("🖥️  Server: {}", server);
+                    }
+                    println!("⚙️  Config: {}", run.config);
+                    print_results_table(&run.models, Duration::from_millis(run.duration_ms), self.cli.speed_unit);
+                    println!("\n🔁 {} iteration(s) recorded", iterations.len());
                 }
             }
+            HistoryAction::Vacuum { keep_days, keep_runs } => {
+                let retention = Retention {
+                    before: keep_days.map(|days| chrono::Utc::now() - chrono::Duration::days(days as i64)),
+                    keep_runs: *keep_runs,
+                };
+                let size = || std::fs::metadata(&path).map_or(0, |m| m.len());
+                let before = size();
+                let deleted = db.prune(&retention)?;
+                db.vacuum()?;
+                println!(
+                    "🧹 Deleted {} run(s); {} compacted from {} MB to {} MB",
+                    deleted,
+                    path.display(),
+                    num(before as f64 / 1e6, 1),
+                    num(size() as f64 / 1e6, 1)
+                );
+            }
+        }
+        Ok(())
+    }
+    
+    fn run_config(&self, action: &ConfigAction) -> Result<()> {
+        let path = prefs::config_path()
+            .ok_or_else(|| BenchmarkError::ConfigError("no config directory; set OLLAMA_BENCH_CONFIG".to_string()))?;
+        if *action == ConfigAction::Path {
+            println!("{}", path.display());
+            return Ok(());
         }
+        let mut preferences = Preferences::load(&path)?;
         
-        content
+        match action {
+            ConfigAction::Set { key, value } => {
+                preferences.set(key, value).map_err(BenchmarkError::ConfigError)?;
+                preferences.save(&path)?;
+                println!("✅ {} = {} ({})", key, value, path.display());
+            }
+            ConfigAction::Get { key } => match preferences.get(key) {
+                Some(value) => println!("{}", value),
+                None => return Err(BenchmarkError::ConfigError(format!("{} is not set", key))),
+            },
+            ConfigAction::Unset { key } => {
+                if preferences.unset(key) {
+                    preferences.save(&path)?;
+                    println!("🗑️  {} removed ({})", key, path.display());
+                } else {
+                    println!("ℹ️  {} was not set", key);
+                }
+            }
+            ConfigAction::List => {
+                let mut empty = true;
+                for (key, value) in preferences.iter() {
+                    println!("{} = {}", key, value);
+                    empty = false;
+                }
+                if empty {
+                    println!("ℹ️  No defaults set ({})", path.display());
+                }
+            }
+            ConfigAction::Path => unreachable!("handled before loading"),
+        }
+        Ok(())
+    }
+    
+    fn verify_report(&self, path: &str) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| BenchmarkError::IoError(format!("{}: {}", path, e)))?;
+        
+        if integrity::verify(&content)? {
+            println!("✅ {} matches its integrity hash", path);
+            Ok(())
+        } else {
+            Err(BenchmarkError::ConfigError(format!(
+                "{} was modified after it was signed",
+                path
+            )))
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cli::OutputFormat;
+    use crate::types::SpeedUnit;
 
     #[test]
     fn test_generate_csv_content() {
-        let cli = Cli {
-            models: vec!["test".to_string()],
-            iterations: 5,
-            output: OutputFormat::Csv,
-            prompt: None,
-            max_tokens: 100,
-            temperature: 0.7,
-            timeout: 120,
-            ollama_url: "http://localhost:11434".to_string(),
-            quiet: false,
-            verbose: false,
-            export: None,
-        };
-        
-        let runner = BenchmarkRunner::new(cli);
-        
         let summaries = vec![
             ModelSummary {
                 model: "test-model".to_string(),
@@ -204,10 +1469,11 @@ mod tests {
                 min_tokens_per_second: 20.0,
                 max_tokens_per_second: 30.0,
                 avg_ttft_ms: 200.0,
+                ..Default::default()
             }
         ];
         
-        let csv = runner.generate_csv_content(&summaries);
+        let csv = summary_csv(&summaries, SpeedUnit::Tokens);
         assert!(csv.contains("Model,Success Rate"));
         assert!(csv.contains("test-model,100.0,25.5"));
     }
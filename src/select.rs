@@ -0,0 +1,172 @@
+use serde::Serialize;
+
+use crate::precision::num;
+use crate::types::ModelSummary;
+
+/// How many passing models besides the pick are reported as runners-up.
+const MAX_RUNNERS_UP: usize = 3;
+
+/// Thresholds a model must meet to be selected.
+#[derive(Debug, Clone, Default)]
+pub struct Criteria {
+    pub min_accuracy: Option<f64>,
+    pub max_ttft_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Candidate {
+    /// Model with its sweep variant and server, as in the results table
+    pub model: String,
+    /// Model name alone, as Ollama knows it
+    #[serde(skip)]
+    pub name: String,
+    pub tokens_per_second: f64,
+    pub ttft_ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accuracy: Option<f64>,
+    pub passed: bool,
+    /// Why the model was rejected; empty when it passed
+    pub reasons: Vec<String>,
+}
+
+/// The fastest model that meets every criterion, plus the rest of the field.
+#[derive(Debug, Clone, Serialize)]
+pub struct Selection {
+    pub selected: Option<String>,
+    pub runners_up: Vec<String>,
+    pub candidates: Vec<Candidate>,
+}
+
+fn judge(summary: &ModelSummary, criteria: &Criteria) -> Candidate {
+    let accuracy = summary.eval.as_ref().map(|score| score.accuracy());
+    let mut reasons = Vec::new();
+
+    if summary.success_rate == 0.0 {
+        reasons.push("no successful requests".to_string());
+    }
+    if let Some(min) = criteria.min_accuracy {
+        match accuracy {
+            Some(accuracy) if accuracy < min => {
+                reasons.push(format!("accuracy {}% < {}%", num(accuracy * 100.0, 0), num(min * 100.0, 0)))
+            }
+            Some(_) => {}
+            None => reasons.push("no eval score".to_string()),
+        }
+    }
+    if let Some(max) = criteria.max_ttft_ms {
+        if summary.avg_ttft_ms > max {
+            reasons.push(format!("TTFT {}ms > {}ms", num(summary.avg_ttft_ms, 0), num(max, 0)));
+        }
+    }
+
+    Candidate {
+        model: summary.label(),
+        name: summary.model.clone(),
+        tokens_per_second: summary.avg_tokens_per_second,
+        ttft_ms: summary.avg_ttft_ms,
+        accuracy,
+        passed: reasons.is_empty(),
+        reasons,
+    }
+}
+
+/// Picks the fastest summary that passes `criteria`; slower passing models
+/// follow as runners-up.
+pub fn select(summaries: &[ModelSummary], criteria: &Criteria) -> Selection {
+    let candidates: Vec<Candidate> = summaries.iter().map(|s| judge(s, criteria)).collect();
+
+    let mut passing: Vec<&Candidate> = candidates.iter().filter(|c| c.passed).collect();
+    passing.sort_by(|a, b| {
+        b.tokens_per_second
+            .partial_cmp(&a.tokens_per_second)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut ranked = passing.into_iter().map(|c| c.model.clone());
+    Selection {
+        selected: ranked.next(),
+        runners_up: ranked.take(MAX_RUNNERS_UP).collect(),
+        candidates,
+    }
+}
+
+/// Shell-style `KEY='value'` lines for deployment scripts, naming models
+/// as Ollama does: a sweep variant or server picked is left out, and a
+/// runner-up that is only another variant of the pick is not repeated.
+pub fn to_env(selection: &Selection) -> String {
+    let name_of = |label: &String| selection.candidates.iter().find(|c| &c.model == label).map(|c| c.name.as_str());
+    let selected = selection.selected.as_ref().and_then(name_of);
+    let mut runners_up: Vec<&str> = Vec::new();
+    for name in selection.runners_up.iter().filter_map(name_of) {
+        if Some(name) != selected && !runners_up.contains(&name) {
+            runners_up.push(name);
+        }
+    }
+    format!(
+        "SELECTED_MODEL={}\nRUNNERS_UP={}\n",
+        shell_quote(selected.unwrap_or_default()),
+        shell_quote(&runners_up.join(","))
+    )
+}
+
+/// Single-quotes `value` for POSIX shells, so `source`-ing the output
+/// cannot run anything.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::EvalScore;
+
+    fn summary(model: &str, tps: f64, ttft_ms: f64, correct: u32) -> ModelSummary {
+        ModelSummary {
+            model: model.to_string(),
+            total_tests: 5,
+            success_rate: 1.0,
+            avg_tokens_per_second: tps,
+            avg_ttft_ms: ttft_ms,
+            eval: Some(EvalScore { task: "arithmetic".to_string(), correct, total: 10 }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_select_fastest_passing() {
+        let summaries = vec![
+            summary("fast-but-wrong", 90.0, 100.0, 5),
+            summary("slow-start", 80.0, 900.0, 9),
+            summary("good", 60.0, 200.0, 9),
+            summary("also-good", 40.0, 300.0, 8),
+        ];
+        let criteria = Criteria { min_accuracy: Some(0.8), max_ttft_ms: Some(400.0) };
+
+        let selection = select(&summaries, &criteria);
+        assert_eq!(selection.selected.as_deref(), Some("good"));
+        assert_eq!(selection.runners_up, vec!["also-good"]);
+        assert_eq!(selection.candidates[0].reasons, vec!["accuracy 50% < 80%"]);
+        assert_eq!(selection.candidates[1].reasons, vec!["TTFT 900ms > 400ms"]);
+    }
+
+    #[test]
+    fn test_select_none_passing() {
+        let mut unscored = summary("m", 50.0, 100.0, 0);
+        unscored.eval = None;
+        let selection = select(&[unscored], &Criteria { min_accuracy: Some(0.5), max_ttft_ms: None });
+        assert_eq!(selection.selected, None);
+        assert_eq!(selection.candidates[0].reasons, vec!["no eval score"]);
+        assert_eq!(to_env(&selection), "SELECTED_MODEL=''\nRUNNERS_UP=''\n");
+    }
+
+    #[test]
+    fn test_to_env_names_models() {
+        let variant = |model: &str, tps: f64, variant: &str| ModelSummary {
+            variant: Some(variant.to_string()),
+            ..summary(model, tps, 100.0, 9)
+        };
+        let summaries = [variant("good", 60.0, "num_ctx=2048"), variant("good", 50.0, "num_ctx=8192"), variant("it's", 40.0, "num_ctx=2048")];
+        let selection = select(&summaries, &Criteria::default());
+        assert_eq!(selection.selected.as_deref(), Some("good [num_ctx=2048]"));
+        assert_eq!(to_env(&selection), "SELECTED_MODEL='good'\nRUNNERS_UP='it'\\''s'\n");
+    }
+}
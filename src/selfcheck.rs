@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+
+use crate::html;
+use crate::openmetrics;
+use crate::cli::JsonSchema;
+use crate::output::{results_json, summary_csv, summary_markdown, ReportExtras};
+use crate::precision::num;
+use crate::types::{BenchmarkResult, ModelSummary, SpeedUnit};
+
+/// Wall clock of the fixture run.
+pub const FIXTURE_DURATION: Duration = Duration::from_secs(75);
+
+/// Two models whose averages land on rounding boundaries (e.g. 25.45
+/// tok/s), plus a failed iteration and a sweep variant, so every format
+/// has to agree on how halves and labels are printed.
+pub fn fixture_summaries() -> Vec<ModelSummary> {
+    let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+    let result = |model: &str, speed: f64, ttft_ms: u64, success: bool| BenchmarkResult {
+        success,
+        tokens_per_second: speed,
+        time_to_first_token_ms: ttft_ms,
+        prompt_tokens: 26,
+        completion_tokens: if success { 128 } else { 0 },
+        finished_at: Some(timestamp),
+        error: (!success).then(|| "request timed out".to_string()),
+        response: success.then(|| "Quantum computers use qubits, which can be 0 and 1 at once.".to_string()),
+        ..BenchmarkResult::failed(model, "Explain quantum computing", timestamp, 5_000, String::new())
+    };
+
+    let fast = [
+        result("llama2:7b", 24.35, 180, true),
+        result("llama2:7b", 26.55, 221, true),
+        result("llama2:7b", 25.45, 200, true),
+    ];
+    let slow = [
+        result("mistral:7b", 19.95, 251, true),
+        result("mistral:7b", 20.05, 249, true),
+        result("mistral:7b", 0.0, 0, false),
+    ];
+
+    let mut variant = ModelSummary::from_results("mistral:7b".to_string(), &slow);
+    variant.variant = Some("temp=0.7".to_string());
+    vec![ModelSummary::from_results("llama2:7b".to_string(), &fast), variant]
+}
+
+/// Every string-rendered report format, by name.
+pub fn render(summaries: &[ModelSummary], unit: SpeedUnit) -> Vec<(&'static str, String)> {
+    vec![
+        ("json", results_json(summaries, &ReportExtras::default()).expect("summaries serialize")),
+        ("json-v2", results_json(summaries, &ReportExtras { schema: JsonSchema::V2, ..Default::default() }).expect("summaries serialize")),
+        ("csv", summary_csv(summaries, unit)),
+        ("markdown", summary_markdown(summaries, unit)),
+        ("html", html::render(summaries, None, None, FIXTURE_DURATION, unit)),
+        ("openmetrics", openmetrics::render(summaries, &[], None)),
+    ]
+}
+
+/// Problems with `renders`: a rounded average that some format prints
+/// differently, or JSON or OpenMetrics that does not carry the exact value.
+pub fn check(summaries: &[ModelSummary], renders: &[(&str, String)], unit: SpeedUnit) -> Vec<String> {
+    let mut problems = Vec::new();
+    for (format, content) in renders {
+        if format.starts_with("json") {
+            let parsed: serde_json::Value = match serde_json::from_str(content) {
+                Ok(parsed) => parsed,
+                Err(e) => {
+                    problems.push(format!("{}: does not parse: {}", format, e));
+                    continue;
+                }
+            };
+            for (index, summary) in summaries.iter().enumerate() {
+                // Schema 1 is the array itself
+                let models = parsed.get("models").unwrap_or(&parsed);
+                let speed = models[index]["avg_tokens_per_second"].as_f64();
+                if speed != Some(summary.avg_tokens_per_second) {
+                    problems.push(format!("{}: {} speed {:?}, expected {}", format, summary.label(), speed, summary.avg_tokens_per_second));
+                }
+            }
+            continue;
+        }
+        if *format == "openmetrics" {
+            // Gauges carry full precision, one line per summary in order
+            let speeds: Vec<Option<f64>> = content
+                .lines()
+                .filter(|line| line.starts_with("ollama_bench_tokens_per_second{"))
+                .map(|line| line.rsplit(' ').next().and_then(|value| value.parse().ok()))
+                .collect();
+            for (index, summary) in summaries.iter().enumerate() {
+                let speed = speeds.get(index).copied().flatten();
+                if speed != Some(summary.avg_tokens_per_second) {
+                    problems.push(format!("{}: {} speed {:?}, expected {}", format, summary.label(), speed, summary.avg_tokens_per_second));
+                }
+            }
+            continue;
+        }
+
+        for summary in summaries {
+            let speed = num(summary.speed_in(summary.avg_tokens_per_second, unit), 1);
+            if !content.contains(&summary.label()) || !content.contains(&speed) {
+                problems.push(format!("{}: {} missing or not shown as {}", format, summary.label(), speed));
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::APP_VERSION;
+
+    #[test]
+    fn test_snapshots() {
+        let summaries = fixture_summaries();
+        for (format, content) in render(&summaries, SpeedUnit::Tokens) {
+            // Releases should not churn the snapshots
+            insta::assert_snapshot!(format, content.replace(APP_VERSION, "[version]"));
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let summaries = fixture_summaries();
+        let mut renders = render(&summaries, SpeedUnit::Tokens);
+        assert_eq!(check(&summaries, &renders, SpeedUnit::Tokens), Vec::<String>::new());
+
+        let csv = renders.iter_mut().find(|(format, _)| *format == "csv").unwrap();
+        csv.1 = csv.1.replace("25.5", "25.4");
+        assert_eq!(check(&summaries, &renders, SpeedUnit::Tokens).len(), 1);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{BenchmarkError, Result};
+use crate::effect::mean;
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// One prompt of a `--prompts-file` suite.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuitePrompt {
+    #[serde(alias = "text")]
+    pub prompt: String,
+    /// Group for the per-category breakdown, e.g. `code` or `chat`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// Reads a prompt suite: JSON Lines (`.jsonl`) with `prompt` and optional
+/// `category` per line, or plain text with one prompt per line. Blank
+/// lines and comments are skipped: in plain text a comment is `#` alone or
+/// followed by a space, so prompts like `#1 tip for...` are kept.
+pub fn load(path: &str) -> Result<Vec<SuitePrompt>> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| BenchmarkError::IoError(format!("{}: {}", path, e)))?;
+    let prompts = parse(&content, path.ends_with(".jsonl"))
+        .map_err(|e| BenchmarkError::ParseError(format!("{}: {}", path, e)))?;
+    if prompts.is_empty() {
+        return Err(BenchmarkError::ConfigError(format!("{} contains no prompts", path)));
+    }
+    Ok(prompts)
+}
+
+fn parse(content: &str, jsonl: bool) -> std::result::Result<Vec<SuitePrompt>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !is_comment(line.trim(), jsonl))
+        .map(|(index, line)| {
+            if jsonl {
+                serde_json::from_str(line).map_err(|e| format!("line {}: {}", index + 1, e))
+            } else {
+                Ok(SuitePrompt { prompt: line.trim().to_string(), category: None })
+            }
+        })
+        .collect()
+}
+
+fn is_comment(line: &str, jsonl: bool) -> bool {
+    if jsonl {
+        line.starts_with('#')
+    } else {
+        line == "#" || line.starts_with("# ")
+    }
+}
+
+/// One model's iterations for one prompt category.
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryStats {
+    pub model: String,
+    pub category: String,
+    pub iterations: u64,
+    pub avg_tokens_per_second: f64,
+    pub avg_ttft_ms: f64,
+}
+
+/// Measured iterations grouped by summary and prompt category; iterations
+/// without a category are left out.
+pub fn category_breakdown(summaries: &[ModelSummary], results: &[BenchmarkResult]) -> Vec<CategoryStats> {
+    let mut breakdown = Vec::new();
+    for summary in summaries {
+        let mut groups: BTreeMap<&str, Vec<&BenchmarkResult>> = BTreeMap::new();
+        for result in results.iter().filter(|r| summary.owns(r) && r.is_measured()) {
+            if let Some(category) = result.category.as_deref() {
+                groups.entry(category).or_default().push(result);
+            }
+        }
+        breakdown.extend(groups.into_iter().map(|(category, results)| CategoryStats {
+            model: summary.label(),
+            category: category.to_string(),
+            iterations: results.len() as u64,
+            avg_tokens_per_second: mean(results.iter().map(|r| r.tokens_per_second)),
+            avg_ttft_ms: mean(results.iter().map(|r| r.time_to_first_token_ms as f64)),
+        }));
+    }
+    breakdown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = parse("# suite\n#\nWrite a haiku\n\n  Summarize this  \n#rustlang in one line\n", false).unwrap();
+        assert_eq!(text.len(), 3);
+        assert_eq!(text[1].prompt, "Summarize this");
+        assert_eq!(text[2].prompt, "#rustlang in one line");
+
+        let jsonl = parse("{\"prompt\": \"Fix this bug\", \"category\": \"code\"}\n{\"text\": \"Hello\"}\n", true).unwrap();
+        assert_eq!(jsonl[0].category.as_deref(), Some("code"));
+        assert_eq!(jsonl[1], SuitePrompt { prompt: "Hello".to_string(), category: None });
+        assert!(parse("not json", true).unwrap_err().starts_with("line 1"));
+    }
+
+    #[test]
+    fn test_category_breakdown() {
+        let result = |model: &str, category: Option<&str>, speed: f64| BenchmarkResult {
+            category: category.map(str::to_string),
+            ..BenchmarkResult::ok(model, speed)
+        };
+        let summaries = ["b", "a"].map(|model| ModelSummary::from_results(model.to_string(), &[]));
+        let results = vec![
+            result("a", Some("code"), 10.0),
+            result("a", Some("code"), 20.0),
+            result("a", Some("chat"), 30.0),
+            result("b", Some("chat"), 40.0),
+            result("b", None, 50.0),
+        ];
+
+        let breakdown = category_breakdown(&summaries, &results);
+        let rows: Vec<(&str, &str, f64)> = breakdown
+            .iter()
+            .map(|s| (s.model.as_str(), s.category.as_str(), s.avg_tokens_per_second))
+            .collect();
+        assert_eq!(rows, vec![("b", "chat", 40.0), ("a", "chat", 30.0), ("a", "code", 15.0)]);
+    }
+}
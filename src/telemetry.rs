@@ -0,0 +1,335 @@
+use std::process::Command;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::gpu;
+
+/// Time between samples; short iterations still get the one taken at start.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Average and peak of one resource over an iteration.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    pub avg: f64,
+    pub peak: f64,
+}
+
+impl Usage {
+    fn of(values: impl Iterator<Item = f64>) -> Option<Self> {
+        let values: Vec<f64> = values.collect();
+        if values.is_empty() {
+            return None;
+        }
+        Some(Self {
+            avg: values.iter().sum::<f64>() / values.len() as f64,
+            peak: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// Resource usage sampled while one iteration ran (`--telemetry`). Metrics
+/// the host cannot report are left out.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryStats {
+    pub samples: u32,
+    /// Busiest GPU's utilization, percent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_util_percent: Option<Usage>,
+    /// VRAM in use across all GPUs, MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vram_used_mb: Option<Usage>,
+    /// Host CPU busy time, percent of all cores
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<Usage>,
+    /// Host memory in use, MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ram_used_mb: Option<Usage>,
+}
+
+impl TelemetryStats {
+    fn from_samples(samples: &[Sample]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        Some(Self {
+            samples: samples.len() as u32,
+            gpu_util_percent: Usage::of(samples.iter().filter_map(|s| s.gpu_util_percent)),
+            vram_used_mb: Usage::of(samples.iter().filter_map(|s| s.vram_used_mb)),
+            cpu_percent: Usage::of(samples.iter().filter_map(|s| s.cpu_percent)),
+            ram_used_mb: Usage::of(samples.iter().filter_map(|s| s.ram_used_mb)),
+        })
+    }
+}
+
+/// Several iterations' usage as one: sample-weighted averages, overall peaks.
+pub fn combine(stats: &[TelemetryStats]) -> Option<TelemetryStats> {
+    let samples: u32 = stats.iter().map(|s| s.samples).sum();
+    if samples == 0 {
+        return None;
+    }
+    let merge = |usage: fn(&TelemetryStats) -> Option<Usage>| -> Option<Usage> {
+        let parts: Vec<(Usage, u32)> = stats.iter().filter_map(|s| Some((usage(s)?, s.samples))).collect();
+        let weight: u32 = parts.iter().map(|(_, n)| n).sum();
+        (weight > 0).then(|| Usage {
+            avg: parts.iter().map(|(u, n)| u.avg * *n as f64).sum::<f64>() / weight as f64,
+            peak: parts.iter().map(|(u, _)| u.peak).fold(f64::NEG_INFINITY, f64::max),
+        })
+    };
+    Some(TelemetryStats {
+        samples,
+        gpu_util_percent: merge(|s| s.gpu_util_percent),
+        vram_used_mb: merge(|s| s.vram_used_mb),
+        cpu_percent: merge(|s| s.cpu_percent),
+        ram_used_mb: merge(|s| s.ram_used_mb),
+    })
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct Sample {
+    gpu_util_percent: Option<f64>,
+    vram_used_mb: Option<f64>,
+    cpu_percent: Option<f64>,
+    ram_used_mb: Option<f64>,
+}
+
+/// Where GPU figures come from on this host.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GpuSource {
+    Nvidia,
+    Rocm,
+    /// macOS; needs root, and reports no VRAM on unified memory
+    PowerMetrics,
+    None,
+}
+
+impl GpuSource {
+    /// Probes the vendor tools in turn; each probe is one sample.
+    pub fn detect() -> Self {
+        if nvidia_sample().is_some() {
+            GpuSource::Nvidia
+        } else if rocm_sample().is_some() {
+            GpuSource::Rocm
+        } else if cfg!(target_os = "macos") && powermetrics_sample().is_some() {
+            GpuSource::PowerMetrics
+        } else {
+            GpuSource::None
+        }
+    }
+
+    pub fn describe(&self) -> &'static str {
+        match self {
+            GpuSource::Nvidia => "nvidia-smi",
+            GpuSource::Rocm => "rocm-smi",
+            GpuSource::PowerMetrics => "powermetrics",
+            GpuSource::None => "no GPU tool found",
+        }
+    }
+
+    /// GPU utilization percent and VRAM used (MB).
+    fn sample(&self) -> (Option<f64>, Option<f64>) {
+        match self {
+            GpuSource::Nvidia => nvidia_sample().unwrap_or_default(),
+            GpuSource::Rocm => rocm_sample().unwrap_or_default(),
+            GpuSource::PowerMetrics => (powermetrics_sample(), None),
+            GpuSource::None => (None, None),
+        }
+    }
+}
+
+/// Samples in a background thread from `start` until `finish`.
+pub struct Sampler {
+    stop: mpsc::Sender<()>,
+    handle: thread::JoinHandle<Vec<Sample>>,
+}
+
+impl Sampler {
+    pub fn start(source: GpuSource) -> Self {
+        let (stop, stopped) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let mut samples = Vec::new();
+            let mut cpu = CpuCounter::default();
+            loop {
+                let (gpu_util_percent, vram_used_mb) = source.sample();
+                samples.push(Sample {
+                    gpu_util_percent,
+                    vram_used_mb,
+                    cpu_percent: cpu.sample(),
+                    ram_used_mb: read("/proc/meminfo").and_then(|text| parse_meminfo_used_mb(&text)),
+                });
+                // A stop message or a dropped sampler both end the loop
+                if stopped.recv_timeout(SAMPLE_INTERVAL) != Err(RecvTimeoutError::Timeout) {
+                    break;
+                }
+            }
+            samples
+        });
+        Self { stop, handle }
+    }
+
+    pub fn finish(self) -> Option<TelemetryStats> {
+        self.stop.send(()).ok();
+        let samples = self.handle.join().unwrap_or_default();
+        TelemetryStats::from_samples(&samples)
+    }
+}
+
+/// CPU busy share between consecutive `/proc/stat` reads (Linux only).
+#[derive(Default)]
+struct CpuCounter {
+    last: Option<(u64, u64)>,
+}
+
+impl CpuCounter {
+    fn sample(&mut self) -> Option<f64> {
+        let (busy, total) = read("/proc/stat").and_then(|text| parse_proc_stat(&text))?;
+        let previous = self.last.replace((busy, total));
+        let (last_busy, last_total) = previous?;
+        let elapsed = total.checked_sub(last_total).filter(|t| *t > 0)?;
+        Some(busy.saturating_sub(last_busy) as f64 / elapsed as f64 * 100.0)
+    }
+}
+
+fn read(path: &str) -> Option<String> {
+    std::fs::read_to_string(path).ok()
+}
+
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout).ok()
+}
+
+fn nvidia_sample() -> Option<(Option<f64>, Option<f64>)> {
+    gpu::run_nvidia_smi(&["--query-gpu=utilization.gpu,memory.used", "--format=csv,noheader,nounits"])
+        .and_then(|out| parse_nvidia(&out))
+}
+
+fn rocm_sample() -> Option<(Option<f64>, Option<f64>)> {
+    run("rocm-smi", &["--showuse", "--showmeminfo", "vram", "--csv"]).and_then(|out| parse_rocm(&out))
+}
+
+fn powermetrics_sample() -> Option<f64> {
+    run("powermetrics", &["--samplers", "gpu_power", "-i", "100", "-n", "1"]).and_then(|out| parse_powermetrics(&out))
+}
+
+/// `45, 8123` per GPU → busiest utilization, total memory used.
+fn parse_nvidia(output: &str) -> Option<(Option<f64>, Option<f64>)> {
+    let rows: Vec<(f64, f64)> = output
+        .lines()
+        .filter_map(|line| {
+            let (util, used) = line.split_once(',')?;
+            Some((util.trim().parse().ok()?, used.trim().parse().ok()?))
+        })
+        .collect();
+    if rows.is_empty() {
+        return None;
+    }
+    let util = rows.iter().map(|(util, _)| *util).fold(0.0, f64::max);
+    Some((Some(util), Some(rows.iter().map(|(_, used)| used).sum())))
+}
+
+/// rocm-smi CSV, with columns found by header name since their order
+/// varies between versions.
+fn parse_rocm(output: &str) -> Option<(Option<f64>, Option<f64>)> {
+    let mut lines = output.lines().filter(|line| line.contains(','));
+    let header: Vec<&str> = lines.next()?.split(',').collect();
+    let util_column = header.iter().position(|h| h.contains("GPU use"));
+    let used_column = header.iter().position(|h| h.contains("VRAM Total Used Memory"));
+    if util_column.is_none() && used_column.is_none() {
+        return None;
+    }
+
+    let (mut util, mut used) = (None::<f64>, None::<f64>);
+    for line in lines {
+        let fields: Vec<&str> = line.split(',').collect();
+        let field = |column: Option<usize>| column.and_then(|c| fields.get(c)?.trim().parse::<f64>().ok());
+        if let Some(value) = field(util_column) {
+            util = Some(util.unwrap_or(0.0).max(value));
+        }
+        if let Some(bytes) = field(used_column) {
+            used = Some(used.unwrap_or(0.0) + bytes / 1024.0 / 1024.0);
+        }
+    }
+    Some((util, used))
+}
+
+/// `GPU HW active residency:  45.12% (...)`; older macOS omits `HW`.
+fn parse_powermetrics(output: &str) -> Option<f64> {
+    output.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("GPU")?;
+        let (label, value) = rest.split_once(':')?;
+        if !label.contains("active residency") {
+            return None;
+        }
+        value.trim().split('%').next()?.trim().parse().ok()
+    })
+}
+
+/// Busy and total jiffies from the aggregate `cpu` line; iowait counts as idle.
+fn parse_proc_stat(text: &str) -> Option<(u64, u64)> {
+    let line = text.lines().find(|line| line.starts_with("cpu "))?;
+    let fields: Vec<u64> = line.split_whitespace().skip(1).take(8).filter_map(|f| f.parse().ok()).collect();
+    if fields.len() < 5 {
+        return None;
+    }
+    let total: u64 = fields.iter().sum();
+    let idle = fields[3] + fields[4];
+    Some((total - idle, total))
+}
+
+fn parse_meminfo_used_mb(text: &str) -> Option<f64> {
+    let field = |name: &str| -> Option<f64> {
+        let line = text.lines().find(|line| line.starts_with(name))?;
+        line.split_whitespace().nth(1)?.parse().ok()
+    };
+    Some((field("MemTotal:")? - field("MemAvailable:")?) / 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_gpu_tools() {
+        assert_eq!(parse_nvidia("45, 8000\n90, 2000\n"), Some((Some(90.0), Some(10000.0))));
+        assert_eq!(parse_nvidia("No devices were found\n"), None);
+
+        let rocm = "device,GPU use (%),VRAM Total Memory (B),VRAM Total Used Memory (B)\n\
+                    card0,12,17179869184,1073741824\ncard1,30,17179869184,2147483648\n";
+        assert_eq!(parse_rocm(rocm), Some((Some(30.0), Some(3072.0))));
+
+        let powermetrics = "**** GPU usage ****\nGPU HW active frequency: 1398 MHz\nGPU HW active residency:  45.12% (389 MHz: 0%)\n";
+        assert_eq!(parse_powermetrics(powermetrics), Some(45.12));
+    }
+
+    #[test]
+    fn test_parse_host_counters() {
+        let stat = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_proc_stat(stat), Some((150, 1000)));
+
+        let meminfo = "MemTotal:       16384000 kB\nMemFree:         1000000 kB\nMemAvailable:    8192000 kB\n";
+        assert_eq!(parse_meminfo_used_mb(meminfo), Some(8000.0));
+    }
+
+    #[test]
+    fn test_stats_from_samples() {
+        let sample = |gpu: f64, cpu: Option<f64>| Sample { gpu_util_percent: Some(gpu), cpu_percent: cpu, ..Default::default() };
+        let stats = TelemetryStats::from_samples(&[sample(20.0, None), sample(80.0, Some(40.0))]).unwrap();
+        assert_eq!(stats.samples, 2);
+        assert_eq!(stats.gpu_util_percent, Some(Usage { avg: 50.0, peak: 80.0 }));
+        assert_eq!(stats.cpu_percent, Some(Usage { avg: 40.0, peak: 40.0 }));
+        assert_eq!(stats.vram_used_mb, None);
+        assert!(TelemetryStats::from_samples(&[]).is_none());
+
+        let more = TelemetryStats::from_samples(&[sample(100.0, None)]).unwrap();
+        let combined = combine(&[stats, more]).unwrap();
+        assert_eq!(combined.samples, 3);
+        assert_eq!(combined.gpu_util_percent, Some(Usage { avg: 200.0 / 3.0, peak: 100.0 }));
+        assert_eq!(combined.cpu_percent, Some(Usage { avg: 40.0, peak: 40.0 }));
+    }
+}
@@ -0,0 +1,105 @@
+use serde::Serialize;
+
+use crate::effect::mean;
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// Averages for one model's requests with or without its prompt template.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TemplateStats {
+    pub requests: u64,
+    pub avg_prompt_tokens: f64,
+    /// Mostly prompt evaluation, so it grows with the template's tokens
+    pub avg_ttft_ms: f64,
+    pub avg_completion_tokens: f64,
+    pub avg_latency_ms: f64,
+}
+
+/// What a model's chat template costs compared to the same prompt sent raw.
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateCost {
+    pub model: String,
+    pub templated: TemplateStats,
+    pub raw: TemplateStats,
+    pub extra_prompt_tokens: f64,
+    pub extra_ttft_ms: f64,
+    pub extra_latency_ms: f64,
+}
+
+fn stats(results: &[&BenchmarkResult]) -> TemplateStats {
+    if results.is_empty() {
+        return TemplateStats::default();
+    }
+    let avg = |value: fn(&BenchmarkResult) -> f64| mean(results.iter().map(|r| value(r)));
+
+    TemplateStats {
+        requests: results.len() as u64,
+        avg_prompt_tokens: avg(|r| r.prompt_tokens as f64),
+        avg_ttft_ms: avg(|r| r.time_to_first_token_ms as f64),
+        avg_completion_tokens: avg(|r| r.completion_tokens as f64),
+        avg_latency_ms: avg(|r| r.total_duration_ms as f64),
+    }
+}
+
+/// Templated against raw requests for each summary that has successful
+/// ones of both kinds.
+pub fn build_report(summaries: &[ModelSummary], results: &[BenchmarkResult]) -> Vec<TemplateCost> {
+    summaries
+        .iter()
+        .filter_map(|summary| {
+            let with_raw = |raw: bool| -> Vec<&BenchmarkResult> {
+                results
+                    .iter()
+                    .filter(|r| summary.owns(r) && r.success && r.raw == raw)
+                    .collect()
+            };
+            let templated = with_raw(false);
+            let raw = with_raw(true);
+            if templated.is_empty() || raw.is_empty() {
+                return None;
+            }
+
+            let (templated, raw) = (stats(&templated), stats(&raw));
+            Some(TemplateCost {
+                model: summary.label(),
+                extra_prompt_tokens: templated.avg_prompt_tokens - raw.avg_prompt_tokens,
+                extra_ttft_ms: templated.avg_ttft_ms - raw.avg_ttft_ms,
+                extra_latency_ms: templated.avg_latency_ms - raw.avg_latency_ms,
+                templated,
+                raw,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, raw: bool, prompt_tokens: u32, ttft_ms: u64, total_ms: u64) -> BenchmarkResult {
+        BenchmarkResult {
+            raw,
+            prompt_tokens,
+            time_to_first_token_ms: ttft_ms,
+            total_duration_ms: total_ms,
+            ..BenchmarkResult::ok(model, 0.0)
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let summaries = ["a", "b"].map(|model| ModelSummary::from_results(model.to_string(), &[]));
+        let results = vec![
+            result("a", false, 30, 120, 1100),
+            result("a", false, 30, 140, 1300),
+            result("a", true, 10, 60, 1000),
+            result("b", true, 10, 60, 1000),
+        ];
+
+        let report = build_report(&summaries, &results);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].templated.requests, 2);
+        assert_eq!(report[0].extra_prompt_tokens, 20.0);
+        assert_eq!(report[0].extra_ttft_ms, 70.0);
+        assert_eq!(report[0].extra_latency_ms, 200.0);
+    }
+}
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+
+use crate::types::BenchmarkResult;
+
+/// Span of one request in milliseconds. Uses the monotonic run clock so a
+/// wall-clock adjustment mid-run cannot reorder or stretch requests; results
+/// recorded before it existed fall back to their wall-clock timestamps.
+fn span(result: &BenchmarkResult) -> (i64, i64) {
+    let duration_ms = result.total_duration_ms as i64;
+    if let Some(start) = result.mono_start_ms {
+        return (start as i64, start as i64 + duration_ms);
+    }
+    let start = result.timestamp.timestamp_millis();
+    let end = result.finished_at.map_or(start + duration_ms, |t| t.timestamp_millis());
+    (start, end.max(start))
+}
+
+/// Wall-clock time a request finished, for display.
+fn finished_at(result: &BenchmarkResult) -> DateTime<Utc> {
+    result.finished_at.unwrap_or_else(|| {
+        result.timestamp + chrono::Duration::milliseconds(result.total_duration_ms as i64)
+    })
+}
+
+/// One row per model, `width` columns covering the whole run: `█` while a
+/// request was in flight, `✗` for failed requests, blank while idle.
+pub fn render(results: &[BenchmarkResult], width: usize) -> Vec<(String, String)> {
+    let Some(start) = results.iter().map(|r| span(r).0).min() else {
+        return Vec::new();
+    };
+    let end = results.iter().map(|r| span(r).1).max().unwrap_or(start);
+    let total_ms = (end - start).max(1) as f64;
+    let column = |t: i64| {
+        let offset = (t - start) as f64 / total_ms;
+        ((offset * width as f64) as usize).min(width.saturating_sub(1))
+    };
+
+    let mut models: Vec<&str> = Vec::new();
+    for result in results {
+        if !models.contains(&result.model.as_str()) {
+            models.push(&result.model);
+        }
+    }
+
+    models
+        .into_iter()
+        .map(|model| {
+            let mut row = vec![' '; width];
+            for result in results.iter().filter(|r| r.model == model) {
+                let (from, to) = span(result);
+                let mark = if result.success { '█' } else { '✗' };
+                for cell in &mut row[column(from)..=column(to)] {
+                    // A failure anywhere in the cell stays visible
+                    if *cell != '✗' {
+                        *cell = mark;
+                    }
+                }
+            }
+            (model.to_string(), row.into_iter().collect())
+        })
+        .collect()
+}
+
+/// Longest idle period between consecutive requests, with the time it began.
+pub fn longest_gap(results: &[BenchmarkResult]) -> Option<(i64, DateTime<Utc>)> {
+    let mut spans: Vec<_> = results.iter().map(|r| (span(r), r)).collect();
+    spans.sort_by_key(|((from, _), _)| *from);
+
+    spans
+        .windows(2)
+        .map(|pair| ((pair[1].0 .0 - pair[0].0 .1), finished_at(pair[0].1)))
+        .filter(|(gap, _)| *gap > 0)
+        .max_by_key(|(gap, _)| *gap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, start_ms: i64, duration_ms: i64, success: bool) -> BenchmarkResult {
+        let epoch = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let timestamp = epoch + chrono::Duration::milliseconds(start_ms);
+        let mut result = BenchmarkResult::failed(model, "p", timestamp, duration_ms as u64, String::new());
+        result.finished_at = Some(timestamp + chrono::Duration::milliseconds(duration_ms));
+        result.success = success;
+        result
+    }
+
+    #[test]
+    fn test_render() {
+        let results = vec![
+            result("a", 0, 250, true),
+            result("b", 500, 250, false),
+            result("a", 750, 250, true),
+        ];
+
+        let rows = render(&results, 8);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], ("a".to_string(), "███   ██".to_string()));
+        assert_eq!(rows[1], ("b".to_string(), "    ✗✗✗ ".to_string()));
+        assert!(render(&[], 8).is_empty());
+    }
+
+    #[test]
+    fn test_longest_gap() {
+        let results = vec![
+            result("a", 0, 100, true),
+            result("a", 200, 100, true),
+            result("a", 2300, 100, true),
+        ];
+
+        let (gap, from) = longest_gap(&results).unwrap();
+        assert_eq!(gap, 2000);
+        assert_eq!(from, results[1].finished_at.unwrap());
+        assert!(longest_gap(&results[..1]).is_none());
+    }
+
+    #[test]
+    fn test_spans_ignore_wall_clock_steps() {
+        // The wall clock was stepped back an hour before the second request
+        let mut results = vec![result("a", 0, 100, true), result("a", -3_600_000, 100, true)];
+        results[0].mono_start_ms = Some(0);
+        results[1].mono_start_ms = Some(300);
+
+        let (gap, from) = longest_gap(&results).unwrap();
+        assert_eq!(gap, 200);
+        assert_eq!(from, results[0].finished_at.unwrap());
+        assert_eq!(render(&results, 4)[0].1, "██ █");
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::config::{TREND_MIN_RUNS, TREND_WINDOW};
+use crate::effect::{percentile, Distribution};
+use crate::rundb::RunRecord;
+
+/// Rolling median and p10–p90 band of a model's tok/s over earlier runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Band {
+    pub runs: usize,
+    pub median: f64,
+    pub p10: f64,
+    pub p90: f64,
+}
+
+impl Band {
+    /// `None` for fewer than `TREND_MIN_RUNS` values: too few for a band.
+    fn of(values: &[f64]) -> Option<Self> {
+        if values.len() < TREND_MIN_RUNS {
+            return None;
+        }
+        let distribution = Distribution::of(values)?;
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        Some(Self { runs: values.len(), median: distribution.median, p10: percentile(&sorted, 10), p90: distribution.p90 })
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        (self.p10..=self.p90).contains(&value)
+    }
+}
+
+/// One run's tok/s for a model, against the runs recorded before it.
+#[derive(Debug, Clone, Serialize)]
+pub struct TrendPoint {
+    pub run_id: String,
+    pub recorded_at: DateTime<Utc>,
+    pub tokens_per_second: f64,
+    /// Band of up to `TREND_WINDOW` previous runs; `None` until there are
+    /// `TREND_MIN_RUNS` of them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub band: Option<Band>,
+}
+
+impl TrendPoint {
+    /// Slower or faster than the band of the runs before it.
+    pub fn is_outside(&self) -> bool {
+        self.band.is_some_and(|band| !band.contains(self.tokens_per_second))
+    }
+}
+
+/// `model`'s tok/s in each of `runs` (newest first, as `RunDb::list`
+/// returns them), oldest first. Runs without a successful, unswept summary
+/// of the model are left out.
+pub fn trend(runs: &[RunRecord], model: &str) -> Vec<TrendPoint> {
+    let speeds: Vec<(&RunRecord, f64)> = runs
+        .iter()
+        .rev()
+        .filter_map(|run| {
+            let summary = run.models.iter().find(|s| s.model == model && s.variant.is_none() && s.success_rate > 0.0)?;
+            Some((run, summary.avg_tokens_per_second))
+        })
+        .collect();
+
+    speeds
+        .iter()
+        .enumerate()
+        .map(|(index, &(run, tokens_per_second))| {
+            let previous: Vec<f64> = speeds[index.saturating_sub(TREND_WINDOW)..index].iter().map(|&(_, tps)| tps).collect();
+            TrendPoint {
+                run_id: run.run_id.clone(),
+                recorded_at: run.recorded_at,
+                tokens_per_second,
+                band: Band::of(&previous),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ModelSummary;
+    use serde_json::Value;
+
+    fn run(run_id: &str, speed: Option<f64>) -> RunRecord {
+        let models = speed
+            .map(|speed| ModelSummary { model: "m".to_string(), success_rate: 1.0, avg_tokens_per_second: speed, ..Default::default() })
+            .into_iter()
+            .collect();
+        RunRecord {
+            run_id: run_id.to_string(),
+            recorded_at: Utc::now(),
+            duration_ms: 0,
+            server: None,
+            experiment: None,
+            config: Value::Null,
+            models,
+        }
+    }
+
+    #[test]
+    fn test_trend() {
+        // Newest first, as listed
+        let runs = [run("6", Some(12.0)), run("5", Some(20.5)), run("4", None), run("3", Some(21.0)), run("2", Some(19.0)), run("1", Some(20.0))];
+        let points = trend(&runs, "m");
+
+        let ids: Vec<&str> = points.iter().map(|p| p.run_id.as_str()).collect();
+        assert_eq!(ids, ["1", "2", "3", "5", "6"]);
+        assert!(points[2].band.is_none());
+        let band = points[3].band.unwrap();
+        assert_eq!((band.runs, band.median, band.p10, band.p90), (3, 20.0, 19.0, 21.0));
+        assert!(!points[3].is_outside());
+        assert!(points[4].is_outside());
+    }
+}
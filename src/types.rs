@@ -1,32 +1,467 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
 use chrono::{DateTime, Utc};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 
+use crate::config::DEFAULT_RETRY_BACKOFF_MS;
+use crate::context::{self, ContextLimit};
+use crate::effect::{self, Distribution};
+use crate::histogram::TokenHistogram;
+use crate::eval::{EvalScore, EvalTask};
+use crate::load::LoadStats;
+use crate::outliers::OutlierRule;
+use crate::pattern::LoadPattern;
+use crate::plan::{OptionSweep, SweepOrder};
+use crate::suite::SuitePrompt;
+use crate::telemetry::TelemetryStats;
+use crate::vision::ImageInput;
+use crate::warmup;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BenchmarkResult {
     pub model: String,
     pub prompt: String,
+    /// When the request was sent
     pub timestamp: DateTime<Utc>,
+    /// When the response (or error) was received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
     pub success: bool,
     pub tokens_per_second: f64,
     pub time_to_first_token_ms: u64,
     pub total_duration_ms: u64,
+    /// Time Ollama spent loading the model for this request (high = reload)
+    #[serde(default)]
+    pub load_duration_ms: u64,
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    /// Mean log-probability of the generated tokens, when the server reports logprobs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f64>,
+    /// API the request went through; omitted for the default `/api/generate`
+    #[serde(default, skip_serializing_if = "Endpoint::is_generate")]
+    pub endpoint: Endpoint,
+    /// Sent with `raw: true`, so the model's prompt template was not applied
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw: bool,
+    /// Server address the request connected to, e.g. `[::1]:11434`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_addr: Option<String>,
+    /// System event (suspend, OOM kill) that invalidates this iteration's timing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub anomaly: Option<String>,
+    /// Early iteration still warming up (caches, clocks), left out of statistics
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub warmup: bool,
+    /// Trimmed by `--trim-outliers`, left out of statistics
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub outlier: bool,
+    /// Vector length returned by `/api/embed`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_dims: Option<u32>,
+    /// Server-side TTFT approximation (prompt eval time), kept alongside the
+    /// measured `time_to_first_token_ms` when streaming
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reported_ttft_ms: Option<u64>,
+    /// Time until the first complete sentence or line arrived, when
+    /// streaming; closer to perceived responsiveness in a chat UI than TTFT
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_sentence_ms: Option<u64>,
+    /// Time until the last token arrived, when streaming
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_token_ms: Option<u64>,
+    /// Gaps between streamed tokens; left out of exports, summaries pool them
+    #[serde(skip)]
+    pub token_gaps_ms: Vec<f64>,
+    /// Requests in flight right after this one was sent, in `--pattern` runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_flight: Option<u32>,
+    /// Attempts that failed transiently before this one, with `--retries`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Failed in a way worth retrying: the connection broke or timed out,
+    /// or the server answered 5xx or 429
+    #[serde(skip)]
+    pub transient: bool,
+    /// Monotonic milliseconds since the run started timing; orders and spaces
+    /// iterations even if the wall clock is adjusted mid-run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mono_start_ms: Option<u64>,
+    /// Wall-clock step during this request (NTP, suspend), in ms; durations
+    /// are monotonic and unaffected, but `timestamp`/`finished_at` are skewed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clock_jump_ms: Option<i64>,
+    /// Identical requests raced for this iteration (`--hedged`); the result
+    /// is the first to finish
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hedged_copies: Option<u32>,
+    /// Server that ran this iteration, when several were benchmarked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Sweep variant this iteration ran as, e.g. `temp=0.7`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// GPU and host resource usage while the request ran (`--telemetry`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub telemetry: Option<TelemetryStats>,
+    /// How this `--batch-n` iteration requested its completions, through
+    /// the OpenAI-compatible `/v1/chat/completions`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub batch: Option<BatchMode>,
+    /// Completions the iteration produced; a server ignoring `n` returns one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<u32>,
+    /// Category of the `--prompts-file` prompt this iteration sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// `--image-dir` image attached to this iteration's prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// Context window the request ran with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    /// Set when prompt and output used most or all of `num_ctx`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_limit: Option<ContextLimit>,
+}
+
+impl BenchmarkResult {
+    /// A successful iteration that counts toward the statistics: not
+    /// warm-up, an outlier, or hit by a system anomaly.
+    pub fn is_measured(&self) -> bool {
+        self.success && !self.warmup && !self.outlier && self.anomaly.is_none()
+    }
+    
+    pub fn failed(model: &str, prompt: &str, timestamp: DateTime<Utc>, total_duration_ms: u64, error: String) -> Self {
+        Self {
+            model: model.to_string(),
+            prompt: prompt.to_string(),
+            timestamp,
+            finished_at: Some(Utc::now()),
+            success: false,
+            tokens_per_second: 0.0,
+            time_to_first_token_ms: 0,
+            total_duration_ms,
+            load_duration_ms: 0,
+            prompt_tokens: 0,
+            completion_tokens: 0,
+            error: Some(error),
+            response: None,
+            avg_logprob: None,
+            endpoint: Endpoint::default(),
+            raw: false,
+            remote_addr: None,
+            anomaly: None,
+            warmup: false,
+            outlier: false,
+            embedding_dims: None,
+            reported_ttft_ms: None,
+            mono_start_ms: None,
+            clock_jump_ms: None,
+            hedged_copies: None,
+            server: None,
+            variant: None,
+            telemetry: None,
+            batch: None,
+            completions: None,
+            category: None,
+            image: None,
+            num_ctx: None,
+            context_limit: None,
+            first_sentence_ms: None,
+            last_token_ms: None,
+            token_gaps_ms: Vec::new(),
+            in_flight: None,
+            retries: None,
+            transient: false,
+        }
+    }
+    
+    /// A successful 100ms iteration of `model` at `tokens_per_second`; tests
+    /// override the fields they care about.
+    #[cfg(test)]
+    pub fn ok(model: &str, tokens_per_second: f64) -> Self {
+        Self {
+            success: true,
+            error: None,
+            tokens_per_second,
+            ..Self::failed(model, "p", Utc::now(), 100, String::new())
+        }
+    }
+}
+
+/// Ollama API used for benchmark requests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Endpoint {
+    /// `/api/generate` with a prompt (default)
+    #[default]
+    Generate,
+    /// `/api/chat` with the prompt as a user message, after any system message
+    Chat,
+    /// `/api/embed` with the prompt as the input (embedding models)
+    Embed,
+}
+
+impl Endpoint {
+    pub fn path(&self) -> &'static str {
+        match self {
+            Endpoint::Generate => "/api/generate",
+            Endpoint::Chat => "/api/chat",
+            Endpoint::Embed => "/api/embed",
+        }
+    }
+    
+    fn is_generate(&self) -> bool {
+        *self == Endpoint::Generate
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// How a `--batch-n` iteration asks for its completions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchMode {
+    /// N concurrent requests with one completion each
+    Separate,
+    /// One request with `n` set to N
+    Combined,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModelSummary {
     pub model: String,
-    pub total_tests: u32,
+    pub total_tests: u64,
     pub success_rate: f64,
     pub avg_tokens_per_second: f64,
     pub min_tokens_per_second: f64,
     pub max_tokens_per_second: f64,
     pub avg_ttft_ms: f64,
+    /// Spread of successful iterations' speeds; needs at least two
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stddev_tokens_per_second: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stddev_ttft_ms: Option<f64>,
+    /// Median, p90/p95/p99 and coefficient of variation of tokens/s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_distribution: Option<Distribution>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttft_distribution: Option<Distribution>,
+    /// Prompt tokens evaluated per second of time to first token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_eval_tokens_per_second: Option<f64>,
+    /// Mean time to the first complete sentence or line, in streaming runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_first_sentence_ms: Option<f64>,
+    /// Mean gap between streamed tokens, over every iteration's tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_inter_token_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p95_inter_token_ms: Option<f64>,
+    /// Mean time to the last streamed token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_last_token_ms: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eval: Option<EvalScore>,
+    /// GPU indices hosting the model after the run (empty = CPU only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gpu_devices: Option<Vec<u32>>,
+    /// Fraction of the loaded model resident in VRAM after the run (1.0 = fully on GPU)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vram_fraction: Option<f64>,
+    /// Sweep setting this summary was measured under, e.g. `num_thread=8`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    /// Server this summary was measured on, when several were benchmarked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// Effective CPU thread count (configured, or from the model's parameters)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_thread: Option<u32>,
+    /// Synthetic chat messages sent ahead of the prompt, in `--sweep-history` runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_messages: Option<u32>,
+    /// Requested prompt size in tokens, in `--context-sweep` runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_tokens: Option<u32>,
+    /// Response characters per generated token, across successful iterations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chars_per_token: Option<f64>,
+    /// Response words per generated token, across successful iterations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub words_per_token: Option<f64>,
+    /// Iterations originally planned, when fewer ran to fit the time budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planned_iterations: Option<u32>,
+    /// Fraction of speed lost between the first and last third of the
+    /// run, when large enough to suggest throttling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_decline: Option<f64>,
+    /// Prompts bypassed the model template (`--raw`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw: bool,
+    /// Prompt tokens evaluated across successful iterations
+    #[serde(default)]
+    pub total_prompt_tokens: u64,
+    /// Tokens generated across successful iterations
+    #[serde(default)]
+    pub total_completion_tokens: u64,
+    /// Wall-clock time spent in requests, failed ones included
+    #[serde(default)]
+    pub total_request_ms: u64,
+    /// Iterations left out of every statistic because of a system anomaly
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub excluded_iterations: u64,
+    /// Leading iterations treated as warm-up and left out of every statistic
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub warmup_iterations: u64,
+    /// Iterations trimmed as outliers by `--trim-outliers`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub outlier_iterations: u64,
+    /// Context window of the successful iterations (the largest, if it varied)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_ctx: Option<u32>,
+    /// Successful iterations that used most of `num_ctx`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub near_context_limit: u64,
+    /// Successful iterations that filled `num_ctx`, so the prompt was likely truncated
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub context_truncated: u64,
+    /// Spread of the completion token counts, against `max_tokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completion_histogram: Option<TokenHistogram>,
+    /// Throughput and latency under `--concurrency`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<LoadStats>,
+    /// Summary columns of `--endpoint embed` runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<EmbeddingStats>,
+    /// Successful iteration with the highest tokens/s, when there were several
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fastest: Option<IterationDetail>,
+    /// Successful iteration with the lowest tokens/s, when there were several
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slowest: Option<IterationDetail>,
+}
+
+/// One iteration singled out in a summary, for inspecting outliers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IterationDetail {
+    pub timestamp: DateTime<Utc>,
+    pub tokens_per_second: f64,
+    pub time_to_first_token_ms: u64,
+    pub total_duration_ms: u64,
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+}
+
+impl IterationDetail {
+    fn of(result: &BenchmarkResult) -> Self {
+        Self {
+            timestamp: result.timestamp,
+            tokens_per_second: result.tokens_per_second,
+            time_to_first_token_ms: result.time_to_first_token_ms,
+            total_duration_ms: result.total_duration_ms,
+            prompt_tokens: result.prompt_tokens,
+            completion_tokens: result.completion_tokens,
+            response: result.response.clone(),
+        }
+    }
+}
+
+/// Embedding-model summary: one input per request.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingStats {
+    /// Embeddings per second of request time
+    pub embeddings_per_second: f64,
+    pub avg_latency_ms: f64,
+    /// Input tokens embedded per second of request time
+    pub input_tokens_per_second: f64,
+    pub dimensions: u32,
+}
+
+impl EmbeddingStats {
+    fn from_results(successful: &[&BenchmarkResult]) -> Option<Self> {
+        let dimensions = successful.iter().find_map(|r| r.embedding_dims)?;
+        let total_secs = successful.iter().map(|r| r.total_duration_ms).sum::<u64>() as f64 / 1000.0;
+        if total_secs <= 0.0 {
+            return None;
+        }
+        let input_tokens: u64 = successful.iter().map(|r| r.prompt_tokens as u64).sum();
+        
+        Some(Self {
+            embeddings_per_second: successful.len() as f64 / total_secs,
+            avg_latency_ms: total_secs * 1000.0 / successful.len() as f64,
+            input_tokens_per_second: input_tokens as f64 / total_secs,
+            dimensions,
+        })
+    }
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+/// Aggregate counts for a whole run, sized for multi-hour load tests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunTotals {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub request_ms: u64,
+}
+
+impl RunTotals {
+    pub fn from_summaries(summaries: &[ModelSummary]) -> Self {
+        summaries.iter().fold(Self::default(), |totals, s| Self {
+            requests: totals.requests + s.total_tests,
+            prompt_tokens: totals.prompt_tokens + s.total_prompt_tokens,
+            completion_tokens: totals.completion_tokens + s.total_completion_tokens,
+            request_ms: totals.request_ms + s.total_request_ms,
+        })
+    }
+}
+
+/// Unit for reporting generation speed. Token counts depend on each model's
+/// tokenizer, so characters or words compare better across model families.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum SpeedUnit {
+    #[default]
+    Tokens,
+    Chars,
+    Words,
+}
+
+impl SpeedUnit {
+    /// Short suffix for values, e.g. `tok/s`.
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            SpeedUnit::Tokens => "tok/s",
+            SpeedUnit::Chars => "chars/s",
+            SpeedUnit::Words => "words/s",
+        }
+    }
+    
+    /// Column heading for CSV and markdown, e.g. `Tokens/s`.
+    pub fn heading(&self) -> &'static str {
+        match self {
+            SpeedUnit::Tokens => "Tokens/s",
+            SpeedUnit::Chars => "Chars/s",
+            SpeedUnit::Words => "Words/s",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct OllamaGenerateRequest {
     pub model: String,
     pub prompt: String,
@@ -35,9 +470,11 @@ pub struct OllamaGenerateRequest {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(dead_code)]
 pub struct OllamaOptions {
     pub temperature: Option<f32>,
     pub num_predict: Option<i32>,
+    pub seed: Option<i64>,
     pub stop: Option<Vec<String>>,
 }
 
@@ -54,6 +491,66 @@ pub struct OllamaGenerateResponse {
     pub prompt_eval_duration: Option<i64>,
     pub eval_count: Option<i32>,
     pub eval_duration: Option<i64>,
+    #[serde(default)]
+    pub logprobs: Option<Vec<OllamaTokenLogprob>>,
+}
+
+/// `/api/embed` response; one vector per input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaEmbedResponse {
+    pub model: String,
+    pub embeddings: Vec<Vec<f32>>,
+    pub total_duration: Option<i64>,
+    pub load_duration: Option<i64>,
+    pub prompt_eval_count: Option<i32>,
+}
+
+/// Non-streaming `/api/chat` response; timings match `/api/generate`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaChatResponse {
+    pub model: String,
+    pub created_at: String,
+    pub message: OllamaChatMessage,
+    pub done: bool,
+    pub total_duration: Option<i64>,
+    pub load_duration: Option<i64>,
+    pub prompt_eval_count: Option<i32>,
+    pub prompt_eval_duration: Option<i64>,
+    pub eval_count: Option<i32>,
+    pub eval_duration: Option<i64>,
+    #[serde(default)]
+    pub logprobs: Option<Vec<OllamaTokenLogprob>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl From<OllamaChatResponse> for OllamaGenerateResponse {
+    fn from(chat: OllamaChatResponse) -> Self {
+        Self {
+            model: chat.model,
+            created_at: chat.created_at,
+            response: chat.message.content,
+            done: chat.done,
+            context: None,
+            total_duration: chat.total_duration,
+            load_duration: chat.load_duration,
+            prompt_eval_count: chat.prompt_eval_count,
+            prompt_eval_duration: chat.prompt_eval_duration,
+            eval_count: chat.eval_count,
+            eval_duration: chat.eval_duration,
+            logprobs: chat.logprobs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaTokenLogprob {
+    pub token: String,
+    pub logprob: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,11 +561,112 @@ pub struct OllamaModel {
     pub digest: String,
 }
 
+/// Server configuration recorded alongside results, so runs made under
+/// different Ollama settings can be told apart.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Models loaded when the run started, from `/api/ps`
+    #[serde(default)]
+    pub loaded_models: Vec<String>,
+    /// Environment declared via `--server-env` (not observable over the API)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// User-Agent the benchmark requests were sent with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// `X-Bench-Client` header value, if one was sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bench_client: Option<String>,
+    /// Every address the server's host name resolved to, in connection order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaVersion {
+    pub version: String,
+}
+
+/// Subset of the `/api/show` response used for model metadata.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaShowResponse {
+    #[serde(default)]
+    pub parameters: Option<String>,
+    #[serde(default)]
+    pub details: Option<OllamaModelDetails>,
+    #[serde(default)]
+    pub model_info: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaModelDetails {
+    #[serde(default)]
+    pub family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OllamaModelsList {
     pub models: Vec<OllamaModel>,
 }
 
+/// A model currently loaded in memory, from `/api/ps`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaRunningModel {
+    pub name: String,
+    pub size: i64,
+    #[serde(default)]
+    pub size_vram: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaRunningModelsList {
+    pub models: Vec<OllamaRunningModel>,
+}
+
+/// Non-streamed `/v1/chat/completions` response.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenAiCompletionResponse {
+    #[serde(default)]
+    pub choices: Vec<OpenAiChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAiUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiChoice {
+    pub message: OllamaChatMessage,
+}
+
+/// Token counts summed over every choice.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenAiUsage {
+    #[serde(default)]
+    pub prompt_tokens: u32,
+    #[serde(default)]
+    pub completion_tokens: u32,
+}
+
+/// One line of a streamed `/api/pull` response.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OllamaPullStatus {
+    #[serde(default)]
+    pub status: String,
+    /// Bytes of the layer being downloaded
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct BenchmarkConfig {
     pub iterations: u32,
@@ -77,6 +675,85 @@ pub struct BenchmarkConfig {
     pub max_tokens: i32,
     pub timeout_seconds: u64,
     pub ollama_base_url: String,
+    pub seed: Option<i64>,
+    pub logprobs: bool,
+    /// Requests kept in flight at once per model
+    pub concurrency: u32,
+    /// System prompt sent with every request
+    pub system: Option<String>,
+    /// Prompts replacing `prompt` for specific models
+    pub model_prompts: BTreeMap<String, String>,
+    /// `num_ctx` from each model's Modelfile on each server (keyed by base
+    /// URL and model), for requests that do not set it
+    pub model_num_ctx: BTreeMap<(String, String), u32>,
+    /// Prompts cycled through iteration by iteration, replacing `prompt`
+    pub prompt_suite: Vec<SuitePrompt>,
+    /// Images cycled through iteration by iteration (`--image-dir`)
+    pub images: Vec<Arc<ImageInput>>,
+    /// Image attached to requests made with this config
+    pub image: Option<Arc<ImageInput>>,
+    /// Number each iteration's prompt so the server cannot reuse a cached prefix
+    pub unique_prompts: bool,
+    /// Keep slow leading iterations in the statistics instead of dropping them as warm-up
+    pub keep_warmup: bool,
+    /// Leading iterations treated as warm-up, overriding detection
+    pub warmup: Option<u32>,
+    /// Leave out iterations this rule calls outliers
+    pub trim_outliers: Option<OutlierRule>,
+    /// Stream the response so TTFT is measured at the first token's arrival
+    pub stream: bool,
+    pub eval: Option<EvalTask>,
+    pub gpu_filter: Option<u32>,
+    pub num_thread: Option<u32>,
+    pub thread_sweep: Vec<u32>,
+    pub sweep_order: SweepOrder,
+    /// Alternate models (and sweep cells) every iteration instead of
+    /// running each one's iterations back to back
+    pub interleave: bool,
+    /// Time-varying load on top of `concurrency`
+    pub load_pattern: Option<LoadPattern>,
+    /// Wall-clock budget for the whole run; iterations are reduced to fit
+    pub time_budget: Option<Duration>,
+    /// Stop a model's iterations once the 95% confidence interval of tok/s
+    /// is within this fraction of the mean; `iterations` is then the cap
+    pub until_ci: Option<f64>,
+    pub endpoint: Endpoint,
+    /// Run every variant through both `/api/generate` and `/api/chat`
+    pub compare_endpoints: bool,
+    /// Send prompts verbatim, bypassing the model's template
+    pub raw: bool,
+    /// Run every variant both with and without the model's template
+    pub compare_template: bool,
+    /// Extra Ollama options sent with every request, overriding the ones above
+    pub options: BTreeMap<String, serde_json::Value>,
+    /// Run every variant once per value of this option
+    pub option_sweep: Option<OptionSweep>,
+    /// Synthetic chat messages sent ahead of the prompt
+    pub history_messages: u32,
+    /// Run every variant once per chat history length
+    pub history_sweep: Vec<u32>,
+    /// Run every variant once per padded prompt size, in tokens
+    pub context_sweep: Vec<u32>,
+    /// Prompt size this variant was padded to
+    pub context_tokens: Option<u32>,
+    /// Identical requests raced per iteration, the first to finish counting
+    pub hedge_copies: u32,
+    /// Run every variant both plain and hedged with this many copies
+    pub hedged: Option<u32>,
+    /// Pull models missing on the server instead of skipping them
+    pub pull_missing: bool,
+    /// Record request errors as failed iterations instead of ending the run
+    pub keep_going: bool,
+    /// Extra attempts for a request that fails transiently
+    pub retries: u32,
+    /// Delay before the first retry, doubling for each one after
+    pub retry_backoff_ms: u64,
+    /// Sample GPU and host resource usage during each iteration
+    pub telemetry: bool,
+    /// Completions per iteration through `/v1/chat/completions`
+    pub batch_n: Option<u32>,
+    /// How this variant requests its `batch_n` completions
+    pub batch_mode: Option<BatchMode>,
 }
 
 impl Default for BenchmarkConfig {
@@ -88,10 +765,222 @@ impl Default for BenchmarkConfig {
             max_tokens: 100,
             timeout_seconds: 120,
             ollama_base_url: "http://localhost:11434".to_string(),
+            seed: None,
+            logprobs: false,
+            concurrency: 1,
+            system: None,
+            model_prompts: BTreeMap::new(),
+            model_num_ctx: BTreeMap::new(),
+            prompt_suite: Vec::new(),
+            images: Vec::new(),
+            image: None,
+            unique_prompts: false,
+            keep_warmup: false,
+            warmup: None,
+            trim_outliers: None,
+            stream: false,
+            eval: None,
+            gpu_filter: None,
+            num_thread: None,
+            thread_sweep: Vec::new(),
+            sweep_order: SweepOrder::default(),
+            interleave: false,
+            load_pattern: None,
+            time_budget: None,
+            until_ci: None,
+            endpoint: Endpoint::default(),
+            compare_endpoints: false,
+            raw: false,
+            compare_template: false,
+            options: BTreeMap::new(),
+            option_sweep: None,
+            history_messages: 0,
+            history_sweep: Vec::new(),
+            context_sweep: Vec::new(),
+            context_tokens: None,
+            hedge_copies: 1,
+            hedged: None,
+            pull_missing: false,
+            keep_going: false,
+            retries: 0,
+            retry_backoff_ms: DEFAULT_RETRY_BACKOFF_MS,
+            telemetry: false,
+            batch_n: None,
+            batch_mode: None,
+        }
+    }
+}
+
+impl BenchmarkConfig {
+    /// The prompt to send to `model`: its own, if one was assigned.
+    pub fn prompt_for(&self, model: &str) -> &str {
+        self.model_prompts.get(model).unwrap_or(&self.prompt)
+    }
+    
+    /// The prompt for one iteration of `model`: the next suite prompt if
+    /// there is a suite, numbered with `unique_prompts`.
+    pub fn iteration_prompt(&self, model: &str, iteration: u32) -> String {
+        let prompt = match self.suite_prompt(iteration) {
+            Some(entry) => &entry.prompt,
+            None => self.prompt_for(model),
+        };
+        if self.unique_prompts {
+            format!("[{}] {}", iteration + 1, prompt)
+        } else {
+            prompt.to_string()
+        }
+    }
+    
+    /// Category of the suite prompt `iteration_prompt` picks.
+    pub fn iteration_category(&self, iteration: u32) -> Option<String> {
+        self.suite_prompt(iteration)?.category.clone()
+    }
+    
+    /// The config for one iteration: the next `--image-dir` image attached,
+    /// or unchanged without images. Warm-up iterations all send the first
+    /// image, so every image still gets measured requests.
+    pub fn for_iteration(&self, iteration: u32) -> BenchmarkConfig {
+        let image = if self.images.is_empty() {
+            self.image.clone()
+        } else {
+            let measured = iteration.saturating_sub(self.warmup.unwrap_or(0));
+            Some(self.images[measured as usize % self.images.len()].clone())
+        };
+        BenchmarkConfig { image, ..self.clone() }
+    }
+    
+    fn suite_prompt(&self, iteration: u32) -> Option<&SuitePrompt> {
+        if self.prompt_suite.is_empty() {
+            return None;
+        }
+        self.prompt_suite.get(iteration as usize % self.prompt_suite.len())
+    }
+    
+    /// Configurations to run for each model: the base config, multiplied out
+    /// by every sweep axis in use (thread counts, option values, history
+    /// lengths, prompt sizes, hedging, endpoints, template on/off).
+    pub fn variants(&self) -> Vec<(Option<String>, BenchmarkConfig)> {
+        let mut variants = vec![(None, self.clone())];
+        
+        if !self.thread_sweep.is_empty() {
+            variants = expand(variants, |config| {
+                self.thread_sweep
+                    .iter()
+                    .map(|&threads| {
+                        let config = BenchmarkConfig { num_thread: Some(threads), ..config.clone() };
+                        (format!("num_thread={}", threads), config)
+                    })
+                    .collect()
+            });
         }
+        
+        if let Some(sweep) = &self.option_sweep {
+            variants = expand(variants, |config| {
+                sweep
+                    .values
+                    .iter()
+                    .map(|value| {
+                        let mut config = config.clone();
+                        config.options.insert(sweep.name.clone(), value.clone());
+                        (sweep.label(value), config)
+                    })
+                    .collect()
+            });
+        }
+        
+        if !self.history_sweep.is_empty() {
+            variants = expand(variants, |config| {
+                self.history_sweep
+                    .iter()
+                    .map(|&messages| {
+                        let config = BenchmarkConfig { history_messages: messages, ..config.clone() };
+                        (format!("history={}", messages), config)
+                    })
+                    .collect()
+            });
+        }
+        
+        if !self.context_sweep.is_empty() {
+            variants = expand(variants, |config| {
+                self.context_sweep
+                    .iter()
+                    .map(|&tokens| {
+                        let config = BenchmarkConfig {
+                            prompt: context::padded_prompt(tokens),
+                            context_tokens: Some(tokens),
+                            // A repeated long prompt would be served from the prompt cache
+                            unique_prompts: true,
+                            ..config.clone()
+                        };
+                        (format!("context={}", context::context_label(tokens)), config)
+                    })
+                    .collect()
+            });
+        }
+        
+        if let Some(copies) = self.hedged {
+            variants = expand(variants, |config| {
+                vec![
+                    ("hedge=off".to_string(), BenchmarkConfig { hedge_copies: 1, ..config.clone() }),
+                    (format!("hedge={}", copies), BenchmarkConfig { hedge_copies: copies, ..config.clone() }),
+                ]
+            });
+        }
+        
+        if let Some(n) = self.batch_n {
+            variants = expand(variants, |config| {
+                [(BatchMode::Separate, "batch=separate".to_string()), (BatchMode::Combined, format!("batch=n{}", n))]
+                    .into_iter()
+                    .map(|(mode, label)| (label, BenchmarkConfig { batch_mode: Some(mode), ..config.clone() }))
+                    .collect()
+            });
+        }
+        
+        if self.compare_endpoints {
+            variants = expand(variants, |config| {
+                [Endpoint::Generate, Endpoint::Chat]
+                    .into_iter()
+                    .map(|endpoint| {
+                        let label = format!("endpoint={}", format!("{:?}", endpoint).to_lowercase());
+                        (label, BenchmarkConfig { endpoint, ..config.clone() })
+                    })
+                    .collect()
+            });
+        }
+        
+        if self.compare_template {
+            variants = expand(variants, |config| {
+                vec![
+                    ("template=applied".to_string(), BenchmarkConfig { raw: false, ..config.clone() }),
+                    ("template=raw".to_string(), BenchmarkConfig { raw: true, ..config.clone() }),
+                ]
+            });
+        }
+        
+        variants
     }
 }
 
+/// Splits every variant into the configurations `axis` derives from it,
+/// joining labels like `num_thread=4, endpoint=chat`.
+fn expand(
+    variants: Vec<(Option<String>, BenchmarkConfig)>,
+    axis: impl Fn(&BenchmarkConfig) -> Vec<(String, BenchmarkConfig)>,
+) -> Vec<(Option<String>, BenchmarkConfig)> {
+    variants
+        .into_iter()
+        .flat_map(|(variant, config)| {
+            axis(&config).into_iter().map(move |(label, config)| {
+                let label = match &variant {
+                    Some(variant) => format!("{}, {}", variant, label),
+                    None => label,
+                };
+                (Some(label), config)
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct BenchmarkProgress {
@@ -104,12 +993,19 @@ pub struct BenchmarkProgress {
 
 impl ModelSummary {
     pub fn from_results(model: String, results: &[BenchmarkResult]) -> Self {
+        // Iterations hit by a suspend or OOM kill would skew every statistic
+        let excluded_iterations = results.iter().filter(|r| r.anomaly.is_some()).count() as u64;
+        let warmup_iterations = results.iter().filter(|r| r.warmup).count() as u64;
+        let outlier_iterations = results.iter().filter(|r| r.outlier).count() as u64;
+        let results: Vec<&BenchmarkResult> = results.iter().filter(|r| r.anomaly.is_none() && !r.warmup && !r.outlier).collect();
+        
         let successful_results: Vec<&BenchmarkResult> = results
             .iter()
+            .copied()
             .filter(|r| r.success)
             .collect();
         
-        let total_tests = results.len() as u32;
+        let total_tests = results.len() as u64;
         let success_rate = if total_tests > 0 {
             successful_results.len() as f64 / total_tests as f64
         } else {
@@ -141,6 +1037,58 @@ impl ModelSummary {
             0.0
         };
         
+        // Embeddings report no TTFT, so prompt evaluation has no time of its own
+        let prefilled: Vec<&&BenchmarkResult> = successful_results
+            .iter()
+            .filter(|r| r.time_to_first_token_ms > 0)
+            .collect();
+        let prefill_ms: u64 = prefilled.iter().map(|r| r.time_to_first_token_ms).sum();
+        let prompt_eval_tokens_per_second = (prefill_ms > 0).then(|| {
+            prefilled.iter().map(|r| r.prompt_tokens as f64).sum::<f64>() * 1000.0 / prefill_ms as f64
+        });
+        
+        let first_sentences: Vec<f64> = successful_results
+            .iter()
+            .filter_map(|r| r.first_sentence_ms)
+            .map(|ms| ms as f64)
+            .collect();
+        let avg_first_sentence_ms = (!first_sentences.is_empty())
+            .then(|| first_sentences.iter().sum::<f64>() / first_sentences.len() as f64);
+        
+        let gaps: Vec<f64> = successful_results.iter().flat_map(|r| r.token_gaps_ms.iter().copied()).collect();
+        let last_tokens: Vec<f64> = successful_results
+            .iter()
+            .filter_map(|r| r.last_token_ms)
+            .map(|ms| ms as f64)
+            .collect();
+        let avg_last_token_ms = (!last_tokens.is_empty())
+            .then(|| last_tokens.iter().sum::<f64>() / last_tokens.len() as f64);
+        
+        let logprobs: Vec<f64> = successful_results
+            .iter()
+            .filter_map(|r| r.avg_logprob)
+            .collect();
+        let avg_logprob = if !logprobs.is_empty() {
+            Some(logprobs.iter().sum::<f64>() / logprobs.len() as f64)
+        } else {
+            None
+        };
+        
+        // Ratios over all responses, so long answers weigh in proportionally
+        let with_text: Vec<(&str, u32)> = successful_results
+            .iter()
+            .filter_map(|r| Some((r.response.as_deref()?, r.completion_tokens)))
+            .filter(|(_, tokens)| *tokens > 0)
+            .collect();
+        let total_tokens: u64 = with_text.iter().map(|(_, tokens)| *tokens as u64).sum();
+        let per_token = |count: usize| (total_tokens > 0).then(|| count as f64 / total_tokens as f64);
+        let chars_per_token = per_token(with_text.iter().map(|(text, _)| text.chars().count()).sum());
+        let words_per_token = per_token(with_text.iter().map(|(text, _)| text.split_whitespace().count()).sum());
+        
+        // With a single iteration there is no outlier to point at
+        let by_speed = |a: &&&BenchmarkResult, b: &&&BenchmarkResult| a.tokens_per_second.total_cmp(&b.tokens_per_second);
+        let extreme = |pick: Option<&&BenchmarkResult>| pick.filter(|_| successful_results.len() > 1).map(|r| IterationDetail::of(r));
+        
         Self {
             model,
             total_tests,
@@ -149,6 +1097,91 @@ impl ModelSummary {
             min_tokens_per_second: if min_tokens_per_second.is_infinite() { 0.0 } else { min_tokens_per_second },
             max_tokens_per_second: if max_tokens_per_second.is_infinite() { 0.0 } else { max_tokens_per_second },
             avg_ttft_ms,
+            stddev_tokens_per_second: effect::stddev(&speeds),
+            stddev_ttft_ms: effect::stddev(&ttfts),
+            speed_distribution: Distribution::of(&speeds),
+            ttft_distribution: Distribution::of(&ttfts),
+            prompt_eval_tokens_per_second,
+            avg_first_sentence_ms,
+            avg_inter_token_ms: (!gaps.is_empty()).then(|| gaps.iter().sum::<f64>() / gaps.len() as f64),
+            p95_inter_token_ms: Distribution::of(&gaps).map(|d| d.p95),
+            avg_last_token_ms,
+            avg_logprob,
+            eval: None,
+            gpu_devices: None,
+            vram_fraction: None,
+            variant: None,
+            server: None,
+            num_thread: None,
+            history_messages: None,
+            context_tokens: None,
+            chars_per_token,
+            words_per_token,
+            planned_iterations: None,
+            speed_decline: warmup::decline(&successful_results),
+            raw: !results.is_empty() && results.iter().all(|r| r.raw),
+            total_prompt_tokens: successful_results.iter().map(|r| r.prompt_tokens as u64).sum(),
+            total_completion_tokens: successful_results.iter().map(|r| r.completion_tokens as u64).sum(),
+            total_request_ms: results.iter().map(|r| r.total_duration_ms).sum(),
+            excluded_iterations,
+            warmup_iterations,
+            outlier_iterations,
+            num_ctx: successful_results.iter().filter_map(|r| r.num_ctx).max(),
+            near_context_limit: successful_results.iter().filter(|r| r.context_limit == Some(ContextLimit::Near)).count() as u64,
+            context_truncated: successful_results.iter().filter(|r| r.context_limit == Some(ContextLimit::Reached)).count() as u64,
+            completion_histogram: None,
+            load: None,
+            embedding: EmbeddingStats::from_results(&successful_results),
+            fastest: extreme(successful_results.iter().max_by(by_speed)),
+            slowest: extreme(successful_results.iter().min_by(by_speed)),
+        }
+    }
+    
+    /// Converts a tokens/s figure from this summary into `unit`.
+    pub fn speed_in(&self, tokens_per_second: f64, unit: SpeedUnit) -> f64 {
+        let factor = match unit {
+            SpeedUnit::Tokens => Some(1.0),
+            SpeedUnit::Chars => self.chars_per_token,
+            SpeedUnit::Words => self.words_per_token,
+        };
+        tokens_per_second * factor.unwrap_or(0.0)
+    }
+    
+    /// Loaded partly in VRAM and partly in system RAM, which makes speeds
+    /// incomparable with fully GPU-resident models.
+    pub fn is_partial_offload(&self) -> bool {
+        self.vram_fraction.is_some_and(|f| f > 0.0 && f < 1.0)
+    }
+    
+    /// The iteration was run for this summary: same model, sweep variant
+    /// and server.
+    pub fn owns(&self, result: &BenchmarkResult) -> bool {
+        result.model == self.model && result.variant == self.variant && result.server == self.server
+    }
+    
+    /// Display name: the model plus its sweep variant, if any.
+    pub fn label(&self) -> String {
+        self.label_with(self.variant.as_deref())
+    }
+    
+    /// Display name without one sweep axis, e.g. `history`: what every
+    /// cell along that axis has in common.
+    pub fn label_without(&self, axis: &str) -> String {
+        let prefix = format!("{}=", axis);
+        let variant = self.variant.as_deref().map(|variant| {
+            variant.split(", ").filter(|part| !part.starts_with(&prefix)).collect::<Vec<_>>().join(", ")
+        });
+        self.label_with(variant.as_deref().filter(|variant| !variant.is_empty()))
+    }
+    
+    fn label_with(&self, variant: Option<&str>) -> String {
+        let model = match &self.server {
+            Some(server) => format!("{} @ {}", self.model, server),
+            None => self.model.clone(),
+        };
+        match variant {
+            Some(variant) => format!("{} [{}]", model, variant),
+            None => model,
         }
     }
 }
@@ -160,43 +1193,17 @@ mod tests {
 
     #[test]
     fn test_model_summary_from_results() {
+        let result = |tps: f64, ttft_ms: u64, total_ms: u64, completion_tokens: u32| BenchmarkResult {
+            time_to_first_token_ms: ttft_ms,
+            total_duration_ms: total_ms,
+            prompt_tokens: 10,
+            completion_tokens,
+            ..BenchmarkResult::ok("test-model", tps)
+        };
         let results = vec![
-            BenchmarkResult {
-                model: "test-model".to_string(),
-                prompt: "test".to_string(),
-                timestamp: Utc::now(),
-                success: true,
-                tokens_per_second: 25.0,
-                time_to_first_token_ms: 200,
-                total_duration_ms: 1000,
-                prompt_tokens: 10,
-                completion_tokens: 25,
-                error: None,
-            },
-            BenchmarkResult {
-                model: "test-model".to_string(),
-                prompt: "test".to_string(),
-                timestamp: Utc::now(),
-                success: true,
-                tokens_per_second: 30.0,
-                time_to_first_token_ms: 150,
-                total_duration_ms: 900,
-                prompt_tokens: 10,
-                completion_tokens: 27,
-                error: None,
-            },
-            BenchmarkResult {
-                model: "test-model".to_string(),
-                prompt: "test".to_string(),
-                timestamp: Utc::now(),
-                success: false,
-                tokens_per_second: 0.0,
-                time_to_first_token_ms: 0,
-                total_duration_ms: 0,
-                prompt_tokens: 0,
-                completion_tokens: 0,
-                error: Some("Failed".to_string()),
-            },
+            result(25.0, 200, 1000, 25),
+            result(30.0, 150, 900, 27),
+            BenchmarkResult::failed("test-model", "test", Utc::now(), 0, "Failed".to_string()),
         ];
         
         let summary = ModelSummary::from_results("test-model".to_string(), &results);
@@ -207,6 +1214,185 @@ mod tests {
         assert_eq!(summary.min_tokens_per_second, 25.0);
         assert_eq!(summary.max_tokens_per_second, 30.0);
         assert_eq!(summary.avg_ttft_ms, 175.0);
+        assert_eq!(summary.avg_logprob, None);
+        assert_eq!(summary.fastest.map(|f| f.tokens_per_second), Some(30.0));
+        assert_eq!(summary.slowest.map(|s| s.tokens_per_second), Some(25.0));
+    }
+    
+    #[test]
+    fn test_model_summary_excludes_anomalies() {
+        let ok = BenchmarkResult::ok("m", 30.0);
+        let mut slept = ok.clone();
+        slept.tokens_per_second = 1.0;
+        slept.anomaly = Some("suspend/resume".to_string());
+        let mut cold = ok.clone();
+        cold.tokens_per_second = 12.0;
+        cold.warmup = true;
+        let mut stalled = ok.clone();
+        stalled.tokens_per_second = 3.0;
+        stalled.outlier = true;
+        
+        let summary = ModelSummary::from_results("m".to_string(), &[cold, ok, slept, stalled]);
+        assert_eq!(summary.total_tests, 1);
+        assert_eq!(summary.excluded_iterations, 1);
+        assert_eq!(summary.warmup_iterations, 1);
+        assert_eq!(summary.outlier_iterations, 1);
+        assert_eq!(summary.avg_tokens_per_second, 30.0);
+    }
+    
+    #[test]
+    fn test_run_totals() {
+        let result = BenchmarkResult {
+            total_duration_ms: 3_000_000_000,
+            prompt_tokens: u32::MAX,
+            completion_tokens: u32::MAX,
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        
+        // Two u32::MAX counts would overflow a u32 total
+        let summary = ModelSummary::from_results("m".to_string(), &[result.clone(), result]);
+        assert_eq!(summary.total_completion_tokens, 2 * u32::MAX as u64);
+        
+        let totals = RunTotals::from_summaries(&[summary.clone(), summary]);
+        assert_eq!(totals.requests, 4);
+        assert_eq!(totals.prompt_tokens, 4 * u32::MAX as u64);
+        assert_eq!(totals.request_ms, 12_000_000_000);
+    }
+    
+    #[test]
+    fn test_model_summary_avg_logprob() {
+        let first = BenchmarkResult { avg_logprob: Some(-0.5), ..BenchmarkResult::ok("m", 0.0) };
+        let mut second = first.clone();
+        second.avg_logprob = Some(-1.5);
+        
+        let summary = ModelSummary::from_results("m".to_string(), &[first, second]);
+        assert_eq!(summary.avg_logprob, Some(-1.0));
+    }
+    
+    #[test]
+    fn test_model_summary_inter_token_latency() {
+        let result = |gaps: &[f64], last_ms: u64| BenchmarkResult {
+            token_gaps_ms: gaps.to_vec(),
+            last_token_ms: Some(last_ms),
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        
+        // Gaps are pooled, so the longer stream weighs more
+        let summary = ModelSummary::from_results("m".to_string(), &[result(&[10.0, 20.0, 30.0], 900), result(&[40.0], 1100)]);
+        assert_eq!(summary.avg_inter_token_ms, Some(25.0));
+        assert_eq!(summary.avg_last_token_ms, Some(1000.0));
+        assert!(summary.p95_inter_token_ms.is_some_and(|p95| p95 > 30.0));
+        assert!(ModelSummary::from_results("m".to_string(), &[result(&[], 0)]).avg_inter_token_ms.is_none());
+    }
+    
+    #[test]
+    fn test_model_summary_speed_units() {
+        let result = BenchmarkResult {
+            completion_tokens: 4,
+            response: Some("日本語の文章です".to_string()),
+            ..BenchmarkResult::ok("m", 10.0)
+        };
+        
+        let summary = ModelSummary::from_results("m".to_string(), &[result]);
+        assert_eq!(summary.chars_per_token, Some(2.0));
+        assert_eq!(summary.speed_in(10.0, SpeedUnit::Chars), 20.0);
+        assert_eq!(summary.speed_in(10.0, SpeedUnit::Words), 2.5);
+        assert_eq!(summary.speed_in(10.0, SpeedUnit::Tokens), 10.0);
+    }
+    
+    #[test]
+    fn test_config_variants() {
+        let mut config = BenchmarkConfig::default();
+        assert_eq!(config.variants().len(), 1);
+        
+        config.thread_sweep = vec![4, 8];
+        let variants = config.variants();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[1].0.as_deref(), Some("num_thread=8"));
+        assert_eq!(variants[1].1.num_thread, Some(8));
+    }
+    
+    #[test]
+    fn test_config_variants_compare_endpoints() {
+        let mut config = BenchmarkConfig { compare_endpoints: true, ..Default::default() };
+        let labels: Vec<Option<String>> = config.variants().into_iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, vec![Some("endpoint=generate".to_string()), Some("endpoint=chat".to_string())]);
+        
+        config.thread_sweep = vec![4, 8];
+        let variants = config.variants();
+        assert_eq!(variants.len(), 4);
+        assert_eq!(variants[3].0.as_deref(), Some("num_thread=8, endpoint=chat"));
+        assert_eq!(variants[3].1.endpoint, Endpoint::Chat);
+        assert_eq!(variants[3].1.num_thread, Some(8));
+    }
+    
+    #[test]
+    fn test_config_variants_option_sweep() {
+        let config = BenchmarkConfig {
+            option_sweep: Some(crate::plan::parse_option_sweep("temperature=0,1.2").unwrap()),
+            ..Default::default()
+        };
+        let variants = config.variants();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[1].0.as_deref(), Some("temperature=1.2"));
+        assert_eq!(variants[1].1.options["temperature"], serde_json::json!(1.2));
+    }
+    
+    #[test]
+    fn test_for_iteration_skips_warmup() {
+        let image = |name: &str| Arc::new(ImageInput { name: name.to_string(), bytes: 0, dimensions: None, data: String::new() });
+        let config = BenchmarkConfig { images: vec![image("a"), image("b")], warmup: Some(1), ..Default::default() };
+        let sent: Vec<String> = (0..4).map(|i| config.for_iteration(i).image.unwrap().name.clone()).collect();
+        assert_eq!(sent, ["a", "a", "b", "a"]);
+    }
+    
+    #[test]
+    fn test_summary_embedding_stats() {
+        let results: Vec<BenchmarkResult> = [200, 300]
+            .iter()
+            .map(|&ms| BenchmarkResult {
+                total_duration_ms: ms,
+                prompt_tokens: 10,
+                embedding_dims: Some(768),
+                ..BenchmarkResult::ok("nomic-embed-text", 0.0)
+            })
+            .collect();
+        
+        let embedding = ModelSummary::from_results("nomic-embed-text".to_string(), &results).embedding.unwrap();
+        assert_eq!(embedding.embeddings_per_second, 4.0);
+        assert_eq!(embedding.avg_latency_ms, 250.0);
+        assert_eq!(embedding.input_tokens_per_second, 40.0);
+        assert_eq!(embedding.dimensions, 768);
+        
+        // Generation results have no embedding columns
+        assert!(ModelSummary::from_results("m".to_string(), &results[..0]).embedding.is_none());
+    }
+    
+    #[test]
+    fn test_config_variants_compare_template() {
+        let config = BenchmarkConfig { compare_template: true, thread_sweep: vec![4], ..Default::default() };
+        let variants = config.variants();
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[1].0.as_deref(), Some("num_thread=4, template=raw"));
+        assert!(variants[1].1.raw);
+        assert!(!variants[0].1.raw);
+    }
+    
+    #[test]
+    fn test_iteration_prompt_cycles_suite() {
+        let entry = |prompt: &str, category: Option<&str>| SuitePrompt {
+            prompt: prompt.to_string(),
+            category: category.map(str::to_string),
+        };
+        let config = BenchmarkConfig {
+            prompt_suite: vec![entry("a", Some("code")), entry("b", None)],
+            unique_prompts: true,
+            ..Default::default()
+        };
+        assert_eq!(config.iteration_prompt("m", 0), "[1] a");
+        assert_eq!(config.iteration_prompt("m", 3), "[4] b");
+        assert_eq!(config.iteration_category(2).as_deref(), Some("code"));
+        assert_eq!(BenchmarkConfig::default().iteration_category(0), None);
     }
     
     #[test]
@@ -0,0 +1,213 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use base64::Engine;
+use serde::Serialize;
+
+use crate::effect::{mean, Distribution};
+use crate::error::{BenchmarkError, Result};
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// File extensions `--image-dir` picks up.
+const IMAGE_EXTENSIONS: [&str; 4] = ["png", "jpg", "jpeg", "webp"];
+
+/// One image of a `--image-dir` set, ready to attach to requests.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageInput {
+    /// File name, for the report
+    pub name: String,
+    pub bytes: u64,
+    /// Pixel size from the file header; `None` when the header is unreadable
+    pub dimensions: Option<(u32, u32)>,
+    /// Base64 of the file, as Ollama's `images` field expects
+    pub data: String,
+}
+
+/// Reads the images in `dir` in name order; with `samples`, that many
+/// spread evenly over the set, so a large folder still covers its range.
+pub fn load_dir(dir: &str, samples: Option<u32>) -> Result<Vec<Arc<ImageInput>>> {
+    let io_error = |e: std::io::Error| BenchmarkError::IoError(format!("{}: {}", dir, e));
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .map_err(io_error)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_image(path))
+        .collect();
+    paths.sort();
+    if paths.is_empty() {
+        return Err(BenchmarkError::ConfigError(format!("{} contains no PNG, JPEG or WebP images", dir)));
+    }
+
+    let picked: Vec<_> = match samples {
+        Some(n) if (n as usize) < paths.len() => (0..n as usize).map(|i| &paths[i * paths.len() / n as usize]).collect(),
+        _ => paths.iter().collect(),
+    };
+    picked
+        .into_iter()
+        .map(|path| {
+            let content = std::fs::read(path).map_err(io_error)?;
+            Ok(Arc::new(ImageInput {
+                name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                bytes: content.len() as u64,
+                dimensions: dimensions(&content),
+                data: base64::engine::general_purpose::STANDARD.encode(&content),
+            }))
+        })
+        .collect()
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Width and height from a PNG, JPEG or WebP header.
+fn dimensions(content: &[u8]) -> Option<(u32, u32)> {
+    let be16 = |at: usize| Some(u16::from_be_bytes([*content.get(at)?, *content.get(at + 1)?]) as u32);
+    let be32 = |at: usize| Some(u32::from_be_bytes(content.get(at..at + 4)?.try_into().ok()?));
+
+    if content.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some((be32(16)?, be32(20)?));
+    }
+    if content.starts_with(b"RIFF") && content.get(8..12) == Some(b"WEBP") {
+        return webp_dimensions(content);
+    }
+    if !content.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+    // Walk the JPEG segments to the frame header (SOF0-SOF15, minus DHT,
+    // JPG and DAC, which share the range)
+    let mut at = 2;
+    while *content.get(at)? == 0xFF {
+        let marker = *content.get(at + 1)?;
+        if (0xC0..=0xCF).contains(&marker) && ![0xC4, 0xC8, 0xCC].contains(&marker) {
+            return Some((be16(at + 7)?, be16(at + 5)?));
+        }
+        at += 2 + be16(at + 2)? as usize;
+    }
+    None
+}
+
+/// Width and height from the first chunk of a WebP file: the canvas of an
+/// extended (VP8X) file, or the frame of a simple lossy or lossless one.
+fn webp_dimensions(content: &[u8]) -> Option<(u32, u32)> {
+    let le = |at: usize, len: usize| {
+        let bytes = content.get(at..at + len)?;
+        Some(bytes.iter().rev().fold(0u32, |value, &byte| value << 8 | byte as u32))
+    };
+    match content.get(12..16)? {
+        b"VP8X" => Some((le(24, 3)? + 1, le(27, 3)? + 1)),
+        // Lossy: a 3-byte frame tag, the 9D 01 2A start code, then 14-bit sizes
+        b"VP8 " if content.get(23..26) == Some(&[0x9D, 0x01, 0x2A]) => Some((le(26, 2)? & 0x3FFF, le(28, 2)? & 0x3FFF)),
+        // Lossless: the 0x2F signature, then two 14-bit sizes minus one
+        b"VP8L" if content.get(20) == Some(&0x2F) => {
+            let bits = le(21, 4)?;
+            Some(((bits & 0x3FFF) + 1, (bits >> 14 & 0x3FFF) + 1))
+        }
+        _ => None,
+    }
+}
+
+/// One image's requests for one model.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImageStats {
+    pub image: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    pub kilobytes: f64,
+    pub requests: u64,
+    /// Includes encoding the image, which grows with its pixel count
+    pub avg_ttft_ms: f64,
+    pub avg_latency_ms: f64,
+}
+
+/// How one model's latency varies over the `--image-dir` set.
+#[derive(Debug, Clone, Serialize)]
+pub struct VisionReport {
+    pub model: String,
+    /// Over every successful request, whatever its image
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<Distribution>,
+    /// Smallest image first; images of unknown size last
+    pub images: Vec<ImageStats>,
+}
+
+pub fn build_report(summaries: &[ModelSummary], images: &[Arc<ImageInput>], results: &[BenchmarkResult]) -> Vec<VisionReport> {
+    summaries
+        .iter()
+        .map(|summary| {
+            let successful: Vec<&BenchmarkResult> = results
+                .iter()
+                .filter(|r| summary.owns(r) && r.is_measured())
+                .collect();
+            let latencies: Vec<f64> = successful.iter().map(|r| r.total_duration_ms as f64).collect();
+
+            let mut stats: Vec<ImageStats> = images
+                .iter()
+                .filter_map(|image| {
+                    let sent: Vec<&&BenchmarkResult> = successful.iter().filter(|r| r.image.as_deref() == Some(image.name.as_str())).collect();
+                    if sent.is_empty() {
+                        return None;
+                    }
+                    Some(ImageStats {
+                        image: image.name.clone(),
+                        width: image.dimensions.map(|(w, _)| w),
+                        height: image.dimensions.map(|(_, h)| h),
+                        kilobytes: image.bytes as f64 / 1024.0,
+                        requests: sent.len() as u64,
+                        avg_ttft_ms: mean(sent.iter().map(|r| r.time_to_first_token_ms as f64)),
+                        avg_latency_ms: mean(sent.iter().map(|r| r.total_duration_ms as f64)),
+                    })
+                })
+                .collect();
+            stats.sort_by_key(|s| s.width.zip(s.height).map_or(u64::MAX, |(w, h)| w as u64 * h as u64));
+
+            VisionReport { model: summary.label(), latency_ms: Distribution::of(&latencies), images: stats }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimensions() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&640u32.to_be_bytes());
+        png.extend_from_slice(&480u32.to_be_bytes());
+        assert_eq!(dimensions(&png), Some((640, 480)));
+
+        // SOI, an APP0 segment of 4 bytes, then SOF0 with height 300 and width 400
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0x2C, 0x01, 0x90];
+        assert_eq!(dimensions(&jpeg), Some((400, 300)));
+        assert_eq!(dimensions(b"RIFF....WEBP"), None);
+
+        let webp = |chunk: &[u8], data: &[u8]| [b"RIFF....WEBP", chunk, b"....", data].concat();
+        // Canvas sizes minus one, 24-bit little-endian after 4 flag bytes
+        assert_eq!(dimensions(&webp(b"VP8X", &[0, 0, 0, 0, 0x7F, 0x02, 0x00, 0xDF, 0x01, 0x00])), Some((640, 480)));
+        assert_eq!(dimensions(&webp(b"VP8 ", &[0, 0, 0, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x01])), Some((640, 480)));
+        // 639 in the low 14 bits, 479 in the next 14
+        let bits: u32 = 639 | 479 << 14;
+        assert_eq!(dimensions(&webp(b"VP8L", &[[0x2F].as_slice(), &bits.to_le_bytes()].concat())), Some((640, 480)));
+    }
+
+    #[test]
+    fn test_build_report() {
+        let image = |name: &str, dimensions| Arc::new(ImageInput { name: name.to_string(), bytes: 2048, dimensions, data: String::new() });
+        let images = [image("big.png", Some((2000, 1000))), image("small.png", Some((200, 100)))];
+        let result = |image: &str, latency_ms: u64| BenchmarkResult {
+            total_duration_ms: latency_ms,
+            image: Some(image.to_string()),
+            ..BenchmarkResult::ok("m", 0.0)
+        };
+        let results = [result("big.png", 3000), result("small.png", 1000), result("big.png", 2000)];
+
+        let report = &build_report(&[ModelSummary::from_results("m".to_string(), &[])], &images, &results)[0];
+        let rows: Vec<(&str, u64, f64)> = report.images.iter().map(|s| (s.image.as_str(), s.requests, s.avg_latency_ms)).collect();
+        assert_eq!(rows, vec![("small.png", 1, 1000.0), ("big.png", 2, 2500.0)]);
+        assert_eq!(report.latency_ms.unwrap().median, 2000.0);
+    }
+}
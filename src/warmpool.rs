@@ -0,0 +1,78 @@
+use serde::Serialize;
+
+use crate::config::SWAP_LOAD_THRESHOLD_MS;
+use crate::effect::mean;
+use crate::types::BenchmarkResult;
+
+/// Swap behaviour of one model while alternating among a pool of resident models.
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmPoolModelReport {
+    pub model: String,
+    /// Whether the model was still loaded after preloading the whole pool
+    pub resident_after_preload: bool,
+    pub requests: u64,
+    /// Requests where Ollama had to (re)load the model first
+    pub swaps: u64,
+    pub avg_latency_ms: f64,
+    /// Mean load time of the swapped requests
+    pub avg_swap_penalty_ms: f64,
+}
+
+pub fn build_report(models: &[String], resident: &[String], results: &[BenchmarkResult]) -> Vec<WarmPoolModelReport> {
+    models
+        .iter()
+        .map(|model| {
+            let successful: Vec<&BenchmarkResult> = results
+                .iter()
+                .filter(|r| &r.model == model && r.success)
+                .collect();
+            let swapped: Vec<&BenchmarkResult> = successful
+                .iter()
+                .copied()
+                .filter(|r| r.load_duration_ms >= SWAP_LOAD_THRESHOLD_MS)
+                .collect();
+
+            WarmPoolModelReport {
+                model: model.clone(),
+                resident_after_preload: resident.contains(model),
+                requests: successful.len() as u64,
+                swaps: swapped.len() as u64,
+                avg_latency_ms: mean(successful.iter().map(|r| r.total_duration_ms as f64)),
+                avg_swap_penalty_ms: mean(swapped.iter().map(|r| r.load_duration_ms as f64)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(model: &str, total_ms: u64, load_ms: u64) -> BenchmarkResult {
+        BenchmarkResult {
+            total_duration_ms: total_ms,
+            load_duration_ms: load_ms,
+            ..BenchmarkResult::ok(model, 0.0)
+        }
+    }
+
+    #[test]
+    fn test_build_report() {
+        let models = vec!["a".to_string(), "b".to_string()];
+        let resident = vec!["a".to_string()];
+        let results = vec![
+            result("a", 100, 2),
+            result("b", 2100, 2000),
+            result("a", 120, 3),
+            result("b", 300, 1),
+        ];
+
+        let report = build_report(&models, &resident, &results);
+        assert!(report[0].resident_after_preload);
+        assert_eq!(report[0].swaps, 0);
+        assert_eq!(report[0].avg_latency_ms, 110.0);
+        assert!(!report[1].resident_after_preload);
+        assert_eq!(report[1].swaps, 1);
+        assert_eq!(report[1].avg_swap_penalty_ms, 2000.0);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::types::BenchmarkResult;
+
+/// Fewer iterations than this leave no steady state to compare against.
+const MIN_ITERATIONS: usize = 4;
+
+/// Speed this far below the steady state counts as still warming up.
+const SPEED_SHORTFALL: f64 = 0.10;
+
+/// TTFT this many times the steady state counts as still warming up.
+const TTFT_FACTOR: f64 = 2.0;
+
+/// Iterations needed to compare the start of a run with its end.
+const MIN_DECLINE_ITERATIONS: usize = 6;
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Marks the leading run of iterations that are clearly slower than the
+/// steady state (median of the second half) as warm-up, at most half of
+/// them. Returns how many were marked.
+pub fn mark(results: &mut [BenchmarkResult]) -> usize {
+    let warmup = detect(results);
+    for &i in &warmup {
+        results[i].warmup = true;
+    }
+    warmup.len()
+}
+
+/// Indices of the iterations [`mark`] would mark, leaving `results` alone.
+pub fn detect(results: &[BenchmarkResult]) -> Vec<usize> {
+    let measured: Vec<usize> = (0..results.len())
+        .filter(|&i| results[i].success && results[i].anomaly.is_none())
+        .collect();
+    if measured.len() < MIN_ITERATIONS {
+        return Vec::new();
+    }
+
+    let steady = &measured[measured.len() / 2..];
+    let steady_speed = median(steady.iter().map(|&i| results[i].tokens_per_second).collect());
+    let steady_ttft = median(steady.iter().map(|&i| results[i].time_to_first_token_ms as f64).collect());
+    let is_slow = |r: &BenchmarkResult| {
+        r.tokens_per_second < steady_speed * (1.0 - SPEED_SHORTFALL)
+            || (steady_ttft > 0.0 && r.time_to_first_token_ms as f64 > steady_ttft * TTFT_FACTOR)
+    };
+
+    measured[..measured.len() / 2]
+        .iter()
+        .copied()
+        .take_while(|&i| is_slow(&results[i]))
+        .collect()
+}
+
+/// How much slower the last third of `measured` ran than the first third,
+/// by median speed, when that exceeds the warm-up shortfall: a run that
+/// gets slower as it goes is usually throttling (heat or power limits).
+pub fn decline(measured: &[&BenchmarkResult]) -> Option<f64> {
+    if measured.len() < MIN_DECLINE_ITERATIONS {
+        return None;
+    }
+    let mut order = measured.to_vec();
+    order.sort_by_key(|r| r.timestamp);
+
+    let third = order.len() / 3;
+    let first = median(order[..third].iter().map(|r| r.tokens_per_second).collect());
+    let last = median(order[order.len() - third..].iter().map(|r| r.tokens_per_second).collect());
+    let decline = 1.0 - last / first;
+    (first > 0.0 && decline > SPEED_SHORTFALL).then_some(decline)
+}
+
+/// Marks the `count` earliest-sent iterations as warm-up, leaving at least
+/// one measured. Returns how many were marked.
+pub fn mark_first(results: &mut [BenchmarkResult], count: usize) -> usize {
+    let warmup = first(results, count);
+    for &i in &warmup {
+        results[i].warmup = true;
+    }
+    warmup.len()
+}
+
+/// Indices of the iterations [`mark_first`] would mark, leaving `results`
+/// alone.
+pub fn first(results: &[BenchmarkResult], count: usize) -> Vec<usize> {
+    // Concurrent runs finish out of order, so go by send time
+    let mut order: Vec<usize> = (0..results.len()).collect();
+    order.sort_by_key(|&i| results[i].timestamp);
+    order.truncate(count.min(results.len().saturating_sub(1)));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(iterations: &[(f64, u64)]) -> Vec<BenchmarkResult> {
+        iterations
+            .iter()
+            .map(|&(tps, ttft)| BenchmarkResult {
+                time_to_first_token_ms: ttft,
+                ..BenchmarkResult::ok("m", tps)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_marks_leading_slow_iterations() {
+        let mut results = run(&[(30.0, 900), (44.0, 120), (50.0, 100), (51.0, 100), (49.0, 110), (50.0, 100)]);
+        assert_eq!(mark(&mut results), 2);
+        assert!(results[0].warmup && results[1].warmup && !results[2].warmup);
+    }
+
+    #[test]
+    fn test_leaves_steady_and_short_runs_alone() {
+        let mut steady = run(&[(49.0, 100), (50.0, 100), (51.0, 105), (50.0, 100)]);
+        assert_eq!(mark(&mut steady), 0);
+
+        // A slow iteration in the middle is noise, not warm-up
+        let mut dip = run(&[(50.0, 100), (30.0, 100), (50.0, 100), (50.0, 100)]);
+        assert_eq!(mark(&mut dip), 0);
+
+        let mut short = run(&[(10.0, 900), (50.0, 100), (50.0, 100)]);
+        assert_eq!(mark(&mut short), 0);
+    }
+
+    #[test]
+    fn test_decline() {
+        let mut results = run(&[(50.0, 100), (50.0, 100), (49.0, 100), (47.0, 100), (40.0, 100), (40.0, 100)]);
+        for (i, result) in results.iter_mut().enumerate() {
+            result.timestamp += chrono::Duration::seconds(i as i64);
+        }
+        let measured: Vec<&BenchmarkResult> = results.iter().collect();
+        assert!((decline(&measured).unwrap() - 0.2).abs() < 1e-9);
+
+        let steady = run(&[(50.0, 100), (51.0, 100), (49.0, 100), (50.0, 100), (48.0, 100), (50.0, 100)]);
+        assert_eq!(decline(&steady.iter().collect::<Vec<_>>()), None);
+        assert_eq!(decline(&measured[..5]), None);
+    }
+
+    #[test]
+    fn test_mark_first() {
+        let mut results = run(&[(50.0, 100), (50.0, 100), (50.0, 100)]);
+        results[0].timestamp += chrono::Duration::seconds(1);
+        assert_eq!(mark_first(&mut results, 1), 1);
+        assert!(!results[0].warmup && results[1].warmup);
+
+        // A time budget can cut a cell below the warm-up count
+        let mut results = run(&[(50.0, 100), (50.0, 100)]);
+        assert_eq!(mark_first(&mut results, 5), 1);
+        assert!(!results[1].warmup);
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+
+use serde::Serialize;
+
+use crate::config::CONTEXT_NEAR_FRACTION;
+use crate::precision::num;
+use crate::types::{BenchmarkResult, ModelSummary};
+
+/// What a [`Warning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    /// The request may not fit `num_ctx`, which Ollama truncates silently
+    Context,
+    /// Weights larger than GPU memory
+    Memory,
+    /// The `--gpu-filter` device could not be checked
+    Gpu,
+    /// Server settings differ from what was declared or from the baseline
+    Server,
+    /// Iterations excluded for system anomalies (suspend, OOM kills)
+    Anomaly,
+    /// The run was stopped before every iteration finished
+    Cancelled,
+    /// Fewer iterations ran than planned, to fit `--max-total-time`
+    Budget,
+    /// Part of the model ran on the CPU
+    Offload,
+    /// Leading iterations were slowed by cold caches and left out
+    Warmup,
+    /// Speed fell as the run went on
+    Throttling,
+    /// The wall clock was stepped during an iteration
+    Clock,
+}
+
+impl WarningKind {
+    fn name(&self) -> &'static str {
+        match self {
+            WarningKind::Context => "context",
+            WarningKind::Memory => "memory",
+            WarningKind::Gpu => "gpu",
+            WarningKind::Server => "server",
+            WarningKind::Anomaly => "anomaly",
+            WarningKind::Cancelled => "cancelled",
+            WarningKind::Budget => "budget",
+            WarningKind::Offload => "offload",
+            WarningKind::Warmup => "warmup",
+            WarningKind::Throttling => "throttling",
+            WarningKind::Clock => "clock",
+        }
+    }
+}
+
+/// A non-fatal problem that makes some results less trustworthy.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// Warnings raised during a run, kept so every output format reports them
+/// instead of only the terminal that happened to be watching.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(transparent)]
+pub struct Warnings(Vec<Warning>);
+
+impl Warnings {
+    /// Records a warning and returns it, for printing where it was raised.
+    pub fn push(&mut self, kind: WarningKind, model: Option<&str>, message: String) -> &Warning {
+        self.0.push(Warning { kind, model: model.map(str::to_string), message });
+        self.0.last().expect("just pushed")
+    }
+
+    /// One warning per model with iterations excluded for system anomalies.
+    pub fn add_anomalies(&mut self, results: &[BenchmarkResult]) {
+        let mut by_model: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for result in results {
+            if let Some(anomaly) = &result.anomaly {
+                by_model.entry(&result.model).or_default().push(anomaly);
+            }
+        }
+        for (model, anomalies) in by_model {
+            let message = format!(
+                "{}: {} iteration(s) excluded from statistics (first: {})",
+                model,
+                anomalies.len(),
+                anomalies[0]
+            );
+            self.push(WarningKind::Anomaly, Some(model), message);
+        }
+    }
+
+    /// One warning for the iterations whose wall clock was stepped.
+    pub fn add_clock_steps(&mut self, results: &[BenchmarkResult]) {
+        let steps = results.iter().filter(|r| r.clock_jump_ms.is_some() && r.anomaly.is_none()).count();
+        if steps > 0 {
+            let message = format!("Wall clock was adjusted during {} iteration(s); their timestamps may be skewed", steps);
+            self.push(WarningKind::Clock, None, message);
+        }
+    }
+
+    /// Warnings for what the summary's own table rows leave out: a
+    /// shortened budget, partial offload and warm-up.
+    pub fn add_summary_caveats(&mut self, summary: &ModelSummary) {
+        let label = summary.label();
+        if let Some(planned) = summary.planned_iterations {
+            let message = format!("{}: {}/{} iterations ran to fit --max-total-time", label, summary.total_tests, planned);
+            self.push(WarningKind::Budget, Some(&summary.model), message);
+        }
+        if summary.is_partial_offload() {
+            let message = format!(
+                "{}: {}% in VRAM; not comparable with fully GPU-resident models",
+                label,
+                num(summary.vram_fraction.unwrap_or_default() * 100.0, 0)
+            );
+            self.push(WarningKind::Offload, Some(&summary.model), message);
+        }
+        if summary.warmup_iterations > 0 {
+            let message = format!("{}: first {} iteration(s) excluded as warm-up (cold caches)", label, summary.warmup_iterations);
+            self.push(WarningKind::Warmup, Some(&summary.model), message);
+        }
+    }
+
+    /// A warning for a summary whose speed fell as the run went on.
+    pub fn add_throttling(&mut self, summary: &ModelSummary) {
+        if let Some(decline) = summary.speed_decline {
+            let message = format!(
+                "{}: speed fell {}% over the run; the GPU or CPU may be throttling",
+                summary.label(),
+                num(decline * 100.0, 0)
+            );
+            self.push(WarningKind::Throttling, Some(&summary.model), message);
+        }
+    }
+
+    /// A warning for a summary whose iterations filled, or nearly filled,
+    /// their context window.
+    pub fn add_context_limits(&mut self, summary: &ModelSummary) {
+        let num_ctx = summary.num_ctx.unwrap_or_default();
+        let message = if summary.context_truncated > 0 {
+            format!(
+                "{}: {} iteration(s) filled num_ctx {}; the prompt was likely truncated",
+                summary.label(),
+                summary.context_truncated,
+                num_ctx
+            )
+        } else if summary.near_context_limit > 0 {
+            format!(
+                "{}: {} iteration(s) used over {}% of num_ctx {}",
+                summary.label(),
+                summary.near_context_limit,
+                num(CONTEXT_NEAR_FRACTION * 100.0, 0),
+                num_ctx
+            )
+        } else {
+            return;
+        };
+        self.push(WarningKind::Context, Some(&summary.model), message);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.0.iter()
+    }
+
+    /// `### Warnings` section for Markdown reports.
+    pub fn markdown(&self) -> String {
+        let mut content = String::from("### Warnings\n\n");
+        for warning in &self.0 {
+            let _ = writeln!(content, "- {}", warning);
+        }
+        content.push('\n');
+        content
+    }
+
+    /// Terminal listing, for reports that have no room for a section.
+    pub fn listing(&self) -> String {
+        let mut content = String::new();
+        for warning in &self.0 {
+            let _ = writeln!(content, "⚠️  {}: {}", warning.kind.name(), warning);
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_add_anomalies() {
+        let result = |model: &str, anomaly: Option<&str>| BenchmarkResult {
+            anomaly: anomaly.map(str::to_string),
+            ..BenchmarkResult::failed(model, "p", Utc::now(), 10, String::new())
+        };
+        let results = [result("a", Some("OOM killer ran 1 time(s) (dmesg)")), result("a", Some("suspend/resume")), result("b", None)];
+
+        let mut warnings = Warnings::default();
+        warnings.add_anomalies(&results);
+        let all: Vec<&Warning> = warnings.iter().collect();
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].model.as_deref(), Some("a"));
+        assert_eq!(all[0].message, "a: 2 iteration(s) excluded from statistics (first: OOM killer ran 1 time(s) (dmesg))");
+    }
+
+    #[test]
+    fn test_add_context_limits() {
+        let mut warnings = Warnings::default();
+        warnings.add_context_limits(&ModelSummary { model: "a".to_string(), num_ctx: Some(4096), ..Default::default() });
+        assert!(warnings.is_empty());
+
+        let summary = ModelSummary { model: "a".to_string(), num_ctx: Some(4096), near_context_limit: 1, context_truncated: 2, ..Default::default() };
+        warnings.add_context_limits(&summary);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings.iter().next().unwrap().message, "a: 2 iteration(s) filled num_ctx 4096; the prompt was likely truncated");
+    }
+
+    #[test]
+    fn test_add_summary_caveats() {
+        let summary = ModelSummary {
+            model: "a".to_string(),
+            total_tests: 4,
+            planned_iterations: Some(10),
+            vram_fraction: Some(0.6),
+            speed_decline: Some(0.25),
+            ..Default::default()
+        };
+        let mut warnings = Warnings::default();
+        warnings.add_summary_caveats(&summary);
+        warnings.add_throttling(&summary);
+        let kinds: Vec<WarningKind> = warnings.iter().map(|w| w.kind).collect();
+        assert_eq!(kinds, vec![WarningKind::Budget, WarningKind::Offload, WarningKind::Throttling]);
+        assert_eq!(warnings.iter().next().unwrap().message, "a: 4/10 iterations ran to fit --max-total-time");
+    }
+
+    #[test]
+    fn test_markdown_and_listing() {
+        let mut warnings = Warnings::default();
+        warnings.push(WarningKind::Context, Some("m"), "m: prompt (~9000 tokens) exceeds num_ctx 4096".to_string());
+        assert_eq!(warnings.markdown(), "### Warnings\n\n- m: prompt (~9000 tokens) exceeds num_ctx 4096\n\n");
+        assert_eq!(warnings.listing(), "⚠️  context: m: prompt (~9000 tokens) exceeds num_ctx 4096\n");
+        assert_eq!(
+            serde_json::to_value(&warnings).unwrap(),
+            serde_json::json!([{ "kind": "context", "model": "m", "message": "m: prompt (~9000 tokens) exceeds num_ctx 4096" }])
+        );
+    }
+}